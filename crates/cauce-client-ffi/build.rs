@@ -0,0 +1,26 @@
+//! Generates the C header for the exported API.
+//!
+//! The header is written to `$OUT_DIR`. Set `CAUCE_FFI_WRITE_HEADER` to
+//! also refresh the checked-in copy in `include/`.
+
+use std::env;
+use std::path::PathBuf;
+
+fn main() {
+    let crate_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    println!("cargo:rerun-if-env-changed=CAUCE_FFI_WRITE_HEADER");
+
+    match cbindgen::generate(&crate_dir) {
+        Ok(bindings) => {
+            bindings.write_to_file(out_dir.join("cauce_client.h"));
+            if env::var_os("CAUCE_FFI_WRITE_HEADER").is_some() {
+                bindings.write_to_file(crate_dir.join("include").join("cauce_client.h"));
+            }
+        }
+        // Don't fail the build over the header; the checked-in copy still works
+        Err(e) => println!("cargo:warning=failed to generate C header: {}", e),
+    }
+}
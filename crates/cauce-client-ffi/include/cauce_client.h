@@ -0,0 +1,226 @@
+/* Cauce client C API. Generated by cbindgen from cauce-client-ffi; do not edit. */
+
+#ifndef CAUCE_CLIENT_H
+#define CAUCE_CLIENT_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Result of a fallible call.
+typedef enum CauceStatus {
+  // The call succeeded.
+  CAUCE_STATUS_OK = 0,
+  // A required pointer was null, or a string was not valid UTF-8 or JSON.
+  CAUCE_STATUS_INVALID_ARGUMENT = 1,
+  // The hub could not be reached, or the connection was lost.
+  CAUCE_STATUS_CONNECTION_FAILED = 2,
+  // The hub did not answer in time.
+  CAUCE_STATUS_TIMEOUT = 3,
+  // The hub rejected the request.
+  CAUCE_STATUS_REJECTED = 4,
+  // Any other failure, including a panic inside the library.
+  CAUCE_STATUS_INTERNAL = 5,
+} CauceStatus;
+
+// A connected client. Opaque to C.
+//
+// Created by [`cauce_client_connect`] and released with
+// [`cauce_client_free`]. Every function except [`cauce_client_free`] may be
+// called concurrently on the same handle.
+typedef struct CauceClient CauceClient;
+
+// An active subscription. Opaque to C.
+//
+// Created by [`cauce_client_subscribe`] and released with
+// [`cauce_subscription_free`].
+typedef struct CauceSubscription CauceSubscription;
+
+// Options for [`cauce_client_connect`].
+//
+// Optional fields may be NULL. All strings are borrowed for the duration of
+// the call.
+typedef struct CauceConnectOptions {
+  // Hub URL (ws://, wss://, http:// or https://). Required.
+  const char *hub_url;
+  // Unique client identifier. Required.
+  const char *client_id;
+  // Client type: "adapter", "agent" or "a2a_agent". Defaults to "agent".
+  const char *client_type;
+  // API key to authenticate with. Optional.
+  const char *api_key;
+  // Bearer token to authenticate with, if no API key is given. Optional.
+  const char *bearer_token;
+} CauceConnectOptions;
+
+// Called for each signal delivered on a subscription.
+//
+// `signal_json` is the signal serialized as JSON, valid only until the
+// callback returns. `user_data` is the pointer passed to
+// [`cauce_client_subscribe`]. May be NULL in C, which
+// [`cauce_client_subscribe`] rejects.
+typedef void (*CauceSignalCallback)(void *user_data, const char *signal_json);
+
+// Called when a subscription reports an error or stops delivering signals.
+//
+// `message` describes the error, valid only until the callback returns.
+// When `ended` is true no more signals will be delivered: either the hub
+// denied or revoked the subscription (`status` is
+// `CAUCE_STATUS_REJECTED`), or it was closed by unsubscribing or
+// disconnecting (`status` is `CAUCE_STATUS_OK` and `message` is NULL).
+// Otherwise the subscription keeps delivering, e.g. after signals were
+// dropped because the callback fell behind. `user_data` is the pointer
+// passed to [`cauce_client_subscribe`].
+typedef void (*CauceSubscriptionErrorCallback)(void *user_data,
+                                               enum CauceStatus status,
+                                               const char *message,
+                                               bool ended);
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Connect to a hub and perform the hello handshake.
+//
+// On success, `*out_client` receives a handle owned by the caller, to be
+// released with [`cauce_client_free`]. On failure it is set to NULL.
+//
+// # Safety
+//
+// `options` and `out_client` must be valid pointers, and the strings in
+// `options` must be null or NUL-terminated.
+enum CauceStatus cauce_client_connect(const struct CauceConnectOptions *options,
+                                      struct CauceClient **out_client);
+
+// Publish a signal or action, given as JSON, to a topic.
+//
+// If `out_message_id` is not NULL, it receives the hub-assigned message ID,
+// owned by the caller and released with
+// [`cauce_string_free`](crate::cauce_string_free).
+//
+// # Safety
+//
+// `client` must be a live handle, `topic` and `message_json` must be
+// NUL-terminated, and `out_message_id` must be null or valid for writes.
+enum CauceStatus cauce_client_publish(struct CauceClient *client,
+                                      const char *topic,
+                                      const char *message_json,
+                                      char **out_message_id);
+
+// Acknowledge receipt of signals on a subscription.
+//
+// # Safety
+//
+// `client` must be a live handle, `subscription_id` must be NUL-terminated,
+// and `signal_ids` must point to `signal_count` NUL-terminated strings.
+enum CauceStatus cauce_client_ack(struct CauceClient *client,
+                                  const char *subscription_id,
+                                  const char *const *signal_ids,
+                                  size_t signal_count);
+
+// Unsubscribe from a subscription at the hub.
+//
+// The subscription's callback stops being called once the hub confirms.
+// The subscription handle must still be released with
+// [`cauce_subscription_free`](crate::cauce_subscription_free).
+//
+// # Safety
+//
+// `client` must be a live handle and `subscription_id` NUL-terminated.
+enum CauceStatus cauce_client_unsubscribe(struct CauceClient *client, const char *subscription_id);
+
+// Disconnect gracefully from the hub.
+//
+// Waits for calls already in progress on other threads to finish. The
+// handle must still be released with [`cauce_client_free`].
+//
+// # Safety
+//
+// `client` must be a live handle.
+enum CauceStatus cauce_client_disconnect(struct CauceClient *client);
+
+// Release a client handle, closing its connection.
+//
+// Passing NULL is a no-op.
+//
+// # Safety
+//
+// `client` must be null or a handle from [`cauce_client_connect`] that has
+// not been released, must not be in use on another thread, and must not be
+// used afterwards.
+void cauce_client_free(struct CauceClient *client);
+
+// Returns a description of the last error on the calling thread.
+//
+// Returns NULL if the last call on this thread succeeded. The string is
+// owned by the library and valid until the next call into the library on
+// the same thread.
+const char *cauce_last_error(void);
+
+// Release a string returned by this library.
+//
+// Passing NULL is a no-op.
+//
+// # Safety
+//
+// `string` must have been returned through a `char **` out-parameter of
+// this library and not released before.
+void cauce_string_free(char *string);
+
+// Subscribe to one or more topic patterns.
+//
+// `callback` is invoked with each signal delivered on the subscription,
+// from one of the client's internal threads, until the subscription ends
+// or is released. `on_error`, which may be NULL, is invoked on the same
+// thread when the subscription reports an error, and once more when it
+// ends. Neither is called after the subscription is released.
+//
+// On success, `*out_subscription` receives a handle owned by the caller,
+// to be released with [`cauce_subscription_free`]. On failure it is set
+// to NULL.
+//
+// # Safety
+//
+// `client` must be a live handle, `topics` must point to `topic_count`
+// NUL-terminated strings, `user_data` must be safe to use from another
+// thread for the life of the subscription, and `out_subscription` must be
+// valid for writes.
+enum CauceStatus cauce_client_subscribe(struct CauceClient *client,
+                                        const char *const *topics,
+                                        size_t topic_count,
+                                        CauceSignalCallback callback,
+                                        CauceSubscriptionErrorCallback on_error,
+                                        void *user_data,
+                                        struct CauceSubscription **out_subscription);
+
+// Returns the hub-assigned ID of a subscription.
+//
+// Returns NULL if `subscription` is NULL. The string is owned by the
+// subscription and valid until it is released.
+//
+// # Safety
+//
+// `subscription` must be null or a live handle.
+const char *cauce_subscription_id(const struct CauceSubscription *subscription);
+
+// Release a subscription handle, stopping its callback.
+//
+// Once this returns, the callback is no longer running and will not be
+// called again. This does not unsubscribe at the hub; call
+// [`cauce_client_unsubscribe`](crate::cauce_client_unsubscribe) first for
+// that. Passing NULL is a no-op.
+//
+// # Safety
+//
+// `subscription` must be null or a handle from [`cauce_client_subscribe`]
+// that has not been released, and must not be used afterwards. It must not
+// be called from inside the subscription's own callback.
+void cauce_subscription_free(struct CauceSubscription *subscription);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* CAUCE_CLIENT_H */
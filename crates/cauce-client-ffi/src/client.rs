@@ -0,0 +1,260 @@
+//! Client handle: connect, publish, ack and disconnect.
+
+use std::ffi::c_char;
+use std::ptr;
+
+use cauce_client_sdk::{AuthConfig, ClientConfig};
+use cauce_core::{ClientType, PublishMessage};
+use tokio::runtime::Runtime;
+use tokio::sync::RwLock;
+
+use crate::error::{ffi_call, CauceStatus, FfiError};
+use crate::strings;
+
+/// Options for [`cauce_client_connect`].
+///
+/// Optional fields may be NULL. All strings are borrowed for the duration of
+/// the call.
+#[repr(C)]
+pub struct CauceConnectOptions {
+    /// Hub URL (ws://, wss://, http:// or https://). Required.
+    pub hub_url: *const c_char,
+
+    /// Unique client identifier. Required.
+    pub client_id: *const c_char,
+
+    /// Client type: "adapter", "agent" or "a2a_agent". Defaults to "agent".
+    pub client_type: *const c_char,
+
+    /// API key to authenticate with. Optional.
+    pub api_key: *const c_char,
+
+    /// Bearer token to authenticate with, if no API key is given. Optional.
+    pub bearer_token: *const c_char,
+}
+
+/// A connected client. Opaque to C.
+///
+/// Created by [`cauce_client_connect`] and released with
+/// [`cauce_client_free`]. Every function except [`cauce_client_free`] may be
+/// called concurrently on the same handle.
+pub struct CauceClient {
+    /// The wrapped client. Dropped before the runtime it runs on.
+    ///
+    /// Disconnecting needs exclusive access, so it takes the write lock and
+    /// waits for in-flight calls to finish.
+    pub(crate) client: RwLock<cauce_client_sdk::CauceClient>,
+
+    /// Runtime driving the connection and subscription callbacks.
+    pub(crate) runtime: Runtime,
+}
+
+impl CauceClient {
+    /// Build a client configuration from connect options.
+    ///
+    /// # Safety
+    ///
+    /// Every string in `options` must be null or valid for reads.
+    unsafe fn config(options: &CauceConnectOptions) -> Result<ClientConfig, FfiError> {
+        let hub_url = strings::required(options.hub_url, "hub_url")?;
+        let client_id = strings::required(options.client_id, "client_id")?;
+
+        let mut builder = ClientConfig::builder(hub_url, client_id);
+        if let Some(client_type) = strings::optional(options.client_type, "client_type")? {
+            let client_type: ClientType =
+                serde_json::from_value(client_type.into()).map_err(|_| {
+                    FfiError::invalid_argument(format!("unknown client_type {:?}", client_type))
+                })?;
+            builder = builder.client_type(client_type);
+        }
+        if let Some(key) = strings::optional(options.api_key, "api_key")? {
+            builder = builder.auth(AuthConfig::api_key(key));
+        } else if let Some(token) = strings::optional(options.bearer_token, "bearer_token")? {
+            builder = builder.auth(AuthConfig::bearer(token));
+        }
+
+        Ok(builder.build()?)
+    }
+}
+
+/// Borrow a client handle.
+///
+/// # Safety
+///
+/// `client` must be null or a live handle from [`cauce_client_connect`].
+unsafe fn client_ref<'a>(client: *const CauceClient) -> Result<&'a CauceClient, FfiError> {
+    client
+        .as_ref()
+        .ok_or_else(|| FfiError::invalid_argument("client is null"))
+}
+
+/// Connect to a hub and perform the hello handshake.
+///
+/// On success, `*out_client` receives a handle owned by the caller, to be
+/// released with [`cauce_client_free`]. On failure it is set to NULL.
+///
+/// # Safety
+///
+/// `options` and `out_client` must be valid pointers, and the strings in
+/// `options` must be null or NUL-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn cauce_client_connect(
+    options: *const CauceConnectOptions,
+    out_client: *mut *mut CauceClient,
+) -> CauceStatus {
+    ffi_call(|| {
+        if out_client.is_null() {
+            return Err(FfiError::invalid_argument("out_client is null"));
+        }
+        *out_client = ptr::null_mut();
+
+        let options = options
+            .as_ref()
+            .ok_or_else(|| FfiError::invalid_argument("options is null"))?;
+        let config = CauceClient::config(options)?;
+
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(2)
+            .thread_name("cauce-ffi")
+            .enable_all()
+            .build()
+            .map_err(|e| FfiError {
+                status: CauceStatus::Internal,
+                message: format!("failed to start runtime: {}", e),
+            })?;
+        let client = runtime.block_on(cauce_client_sdk::CauceClient::connect(config))?;
+
+        *out_client = Box::into_raw(Box::new(CauceClient {
+            client: RwLock::new(client),
+            runtime,
+        }));
+        Ok(())
+    })
+}
+
+/// Publish a signal or action, given as JSON, to a topic.
+///
+/// If `out_message_id` is not NULL, it receives the hub-assigned message ID,
+/// owned by the caller and released with
+/// [`cauce_string_free`](crate::cauce_string_free).
+///
+/// # Safety
+///
+/// `client` must be a live handle, `topic` and `message_json` must be
+/// NUL-terminated, and `out_message_id` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn cauce_client_publish(
+    client: *mut CauceClient,
+    topic: *const c_char,
+    message_json: *const c_char,
+    out_message_id: *mut *mut c_char,
+) -> CauceStatus {
+    ffi_call(|| {
+        let handle = client_ref(client)?;
+        let topic = strings::required(topic, "topic")?;
+        let message: PublishMessage = serde_json::from_str(strings::required(
+            message_json,
+            "message_json",
+        )?)
+        .map_err(|e| {
+            FfiError::invalid_argument(format!("message_json is not a signal or action: {}", e))
+        })?;
+
+        let response = handle
+            .runtime
+            .block_on(async { handle.client.read().await.publish(topic, message).await })?;
+
+        if !out_message_id.is_null() {
+            *out_message_id = strings::to_owned_c(response.message_id)?.into_raw();
+        }
+        Ok(())
+    })
+}
+
+/// Acknowledge receipt of signals on a subscription.
+///
+/// # Safety
+///
+/// `client` must be a live handle, `subscription_id` must be NUL-terminated,
+/// and `signal_ids` must point to `signal_count` NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn cauce_client_ack(
+    client: *mut CauceClient,
+    subscription_id: *const c_char,
+    signal_ids: *const *const c_char,
+    signal_count: usize,
+) -> CauceStatus {
+    ffi_call(|| {
+        let handle = client_ref(client)?;
+        let subscription_id = strings::required(subscription_id, "subscription_id")?;
+        let signal_ids = strings::array(signal_ids, signal_count, "signal_ids")?;
+
+        handle.runtime.block_on(async {
+            handle.client.read().await.ack(subscription_id, &signal_ids).await
+        })?;
+        Ok(())
+    })
+}
+
+/// Unsubscribe from a subscription at the hub.
+///
+/// The subscription's callback stops being called once the hub confirms.
+/// The subscription handle must still be released with
+/// [`cauce_subscription_free`](crate::cauce_subscription_free).
+///
+/// # Safety
+///
+/// `client` must be a live handle and `subscription_id` NUL-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn cauce_client_unsubscribe(
+    client: *mut CauceClient,
+    subscription_id: *const c_char,
+) -> CauceStatus {
+    ffi_call(|| {
+        let handle = client_ref(client)?;
+        let subscription_id = strings::required(subscription_id, "subscription_id")?;
+
+        handle
+            .runtime
+            .block_on(async { handle.client.read().await.unsubscribe(subscription_id).await })?;
+        Ok(())
+    })
+}
+
+/// Disconnect gracefully from the hub.
+///
+/// Waits for calls already in progress on other threads to finish. The
+/// handle must still be released with [`cauce_client_free`].
+///
+/// # Safety
+///
+/// `client` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn cauce_client_disconnect(client: *mut CauceClient) -> CauceStatus {
+    ffi_call(|| {
+        let handle = client_ref(client)?;
+        handle
+            .runtime
+            .block_on(async { handle.client.write().await.disconnect().await })?;
+        Ok(())
+    })
+}
+
+/// Release a client handle, closing its connection.
+///
+/// Passing NULL is a no-op.
+///
+/// # Safety
+///
+/// `client` must be null or a handle from [`cauce_client_connect`] that has
+/// not been released, must not be in use on another thread, and must not be
+/// used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn cauce_client_free(client: *mut CauceClient) {
+    if !client.is_null() {
+        ffi_call(|| {
+            drop(Box::from_raw(client));
+            Ok(())
+        });
+    }
+}
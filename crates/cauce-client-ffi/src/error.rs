@@ -0,0 +1,171 @@
+//! Status codes and the thread-local last error.
+
+use std::cell::RefCell;
+use std::ffi::{c_char, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr;
+
+use cauce_client_sdk::ClientError;
+
+use crate::strings;
+
+/// Result of a fallible call.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CauceStatus {
+    /// The call succeeded.
+    Ok = 0,
+
+    /// A required pointer was null, or a string was not valid UTF-8 or JSON.
+    InvalidArgument = 1,
+
+    /// The hub could not be reached, or the connection was lost.
+    ConnectionFailed = 2,
+
+    /// The hub did not answer in time.
+    Timeout = 3,
+
+    /// The hub rejected the request.
+    Rejected = 4,
+
+    /// Any other failure, including a panic inside the library.
+    Internal = 5,
+}
+
+/// An error to report across the FFI boundary.
+#[derive(Debug)]
+pub(crate) struct FfiError {
+    pub status: CauceStatus,
+    pub message: String,
+}
+
+impl FfiError {
+    /// An invalid argument error.
+    pub fn invalid_argument(message: impl Into<String>) -> Self {
+        Self {
+            status: CauceStatus::InvalidArgument,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<ClientError> for FfiError {
+    fn from(error: ClientError) -> Self {
+        let status = match &error {
+            ClientError::ConfigError { .. }
+            | ClientError::InvalidUrl { .. }
+            | ClientError::InvalidMessage { .. }
+            | ClientError::SubscriptionNotFound { .. } => CauceStatus::InvalidArgument,
+            ClientError::ConnectionTimeout { .. } | ClientError::RequestTimeout { .. } => {
+                CauceStatus::Timeout
+            }
+            ClientError::RpcError { .. }
+            | ClientError::SubscriptionDenied { .. }
+            | ClientError::SubscriptionRevoked { .. }
+            | ClientError::VersionMismatch { .. } => CauceStatus::Rejected,
+            ClientError::ConnectionFailed { .. }
+            | ClientError::ConnectionClosed { .. }
+            | ClientError::NotConnected
+            | ClientError::HandshakeFailed { .. }
+            | ClientError::ReconnectionFailed { .. }
+            | ClientError::TransportError { .. }
+            | ClientError::WebSocketError { .. } => CauceStatus::ConnectionFailed,
+            _ => CauceStatus::Internal,
+        };
+
+        Self {
+            status,
+            message: error.to_string(),
+        }
+    }
+}
+
+thread_local! {
+    /// Description of the last error on this thread.
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: &str) {
+    let message = strings::message(message);
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+fn clear_last_error() {
+    LAST_ERROR.with(|last| *last.borrow_mut() = None);
+}
+
+/// Returns a description of the last error on the calling thread.
+///
+/// Returns NULL if the last call on this thread succeeded. The string is
+/// owned by the library and valid until the next call into the library on
+/// the same thread.
+#[no_mangle]
+pub extern "C" fn cauce_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(ptr::null(), |m| m.as_ptr()))
+}
+
+/// Run `f` as an FFI entry point.
+///
+/// Records the outcome for [`cauce_last_error`] and turns panics into
+/// [`CauceStatus::Internal`] instead of unwinding into the caller.
+pub(crate) fn ffi_call(f: impl FnOnce() -> Result<(), FfiError>) -> CauceStatus {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => {
+            clear_last_error();
+            CauceStatus::Ok
+        }
+        Ok(Err(error)) => {
+            set_last_error(&error.message);
+            error.status
+        }
+        Err(_) => {
+            set_last_error("internal panic in cauce-client-ffi");
+            CauceStatus::Internal
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CStr;
+
+    #[test]
+    fn test_ffi_call_records_last_error() {
+        let status = ffi_call(|| Err(FfiError::invalid_argument("topic is null")));
+        assert_eq!(status, CauceStatus::InvalidArgument);
+        let message = unsafe { CStr::from_ptr(cauce_last_error()) };
+        assert_eq!(message.to_str().unwrap(), "topic is null");
+
+        assert_eq!(ffi_call(|| Ok(())), CauceStatus::Ok);
+        assert!(cauce_last_error().is_null());
+    }
+
+    #[test]
+    fn test_ffi_call_catches_panics() {
+        let status = ffi_call(|| panic!("boom"));
+        assert_eq!(status, CauceStatus::Internal);
+        assert!(!cauce_last_error().is_null());
+    }
+
+    #[test]
+    fn test_client_error_status() {
+        let status = |error: ClientError| FfiError::from(error).status;
+        assert_eq!(
+            status(ClientError::NotConnected),
+            CauceStatus::ConnectionFailed
+        );
+        assert_eq!(
+            status(ClientError::RequestTimeout { timeout_ms: 10 }),
+            CauceStatus::Timeout
+        );
+        assert_eq!(
+            status(ClientError::RpcError {
+                code: -32003,
+                message: "Not authorized".to_string(),
+                data: None,
+            }),
+            CauceStatus::Rejected
+        );
+    }
+}
@@ -0,0 +1,96 @@
+//! # cauce-client-ffi
+//!
+//! C ABI bindings for the Cauce client SDK.
+//!
+//! This crate exposes [`CauceClient`](cauce_client_sdk::CauceClient) to C,
+//! C++, Go and any other language with a C FFI, so adapters written in those
+//! languages get the SDK's JSON-RPC handling, reconnection and subscription
+//! restore without re-implementing them. The header is generated into
+//! `$OUT_DIR/cauce_client.h` when the crate is built; the checked-in copy
+//! at `include/cauce_client.h` is refreshed by building with
+//! `CAUCE_FFI_WRITE_HEADER=1`.
+//!
+//! ## Example (C)
+//!
+//! ```c
+//! static void on_signal(void *user_data, const char *signal_json) {
+//!     printf("signal: %s\n", signal_json);
+//! }
+//!
+//! static void on_error(void *user_data, CauceStatus status, const char *message, bool ended) {
+//!     if (ended) {
+//!         printf("subscription ended: %s\n", message ? message : "closed");
+//!     }
+//! }
+//!
+//! CauceConnectOptions options = {
+//!     .hub_url = "wss://hub.example.com",
+//!     .client_id = "my-adapter",
+//!     .client_type = "adapter",
+//!     .api_key = "secret",
+//! };
+//! CauceClient *client = NULL;
+//! if (cauce_client_connect(&options, &client) != CAUCE_STATUS_OK) {
+//!     fprintf(stderr, "connect failed: %s\n", cauce_last_error());
+//!     return 1;
+//! }
+//!
+//! const char *topics[] = {"signal.email.*"};
+//! CauceSubscription *subscription = NULL;
+//! cauce_client_subscribe(client, topics, 1, on_signal, on_error, NULL, &subscription);
+//!
+//! char *message_id = NULL;
+//! cauce_client_publish(client, "signal.email.received", signal_json, &message_id);
+//! cauce_string_free(message_id);
+//!
+//! cauce_subscription_free(subscription);
+//! cauce_client_disconnect(client);
+//! cauce_client_free(client);
+//! ```
+//!
+//! ## Memory Ownership
+//!
+//! - **Handles** (`CauceClient`, `CauceSubscription`) are created by this
+//!   library and owned by the caller, who must release each exactly once
+//!   with its `*_free` function. A subscription may outlive its client.
+//! - **Input strings** are borrowed for the duration of the call only and
+//!   must be NUL-terminated UTF-8.
+//! - **Output strings** (`char **` out-parameters) are owned by the caller
+//!   and must be released with [`cauce_string_free`].
+//! - **Borrowed strings** returned as `const char *` (such as
+//!   [`cauce_last_error`] and [`cauce_subscription_id`]) remain owned by
+//!   the library; see each function for how long they stay valid.
+//! - **Callback arguments** are valid only until the callback returns.
+//!
+//! ## Errors
+//!
+//! Fallible functions return a [`CauceStatus`]. On failure, a description
+//! of the error is available from [`cauce_last_error`] on the same thread.
+//!
+//! ## Threading
+//!
+//! Each client runs its connection on an internal runtime. Subscription
+//! callbacks are invoked from that runtime's threads, so `user_data` must be
+//! safe to use from another thread, and callbacks should return quickly.
+//! Client functions block the calling thread and must not be called from
+//! inside a callback. A client handle may be shared between threads: every
+//! function except `cauce_client_free` may be called on it concurrently.
+
+#![deny(missing_docs)]
+#![deny(rustdoc::broken_intra_doc_links)]
+
+mod client;
+mod error;
+mod strings;
+mod subscription;
+
+pub use client::{
+    cauce_client_ack, cauce_client_connect, cauce_client_disconnect, cauce_client_free,
+    cauce_client_publish, cauce_client_unsubscribe, CauceClient, CauceConnectOptions,
+};
+pub use error::{cauce_last_error, CauceStatus};
+pub use strings::cauce_string_free;
+pub use subscription::{
+    cauce_client_subscribe, cauce_subscription_free, cauce_subscription_id, CauceSignalCallback,
+    CauceSubscription, CauceSubscriptionErrorCallback,
+};
@@ -0,0 +1,82 @@
+//! Conversions between C strings and Rust strings.
+
+use std::ffi::{c_char, CStr, CString};
+
+use crate::error::FfiError;
+
+/// Borrow a required string argument.
+///
+/// # Safety
+///
+/// `ptr` must be null or point to a NUL-terminated string that outlives `'a`.
+pub(crate) unsafe fn required<'a>(ptr: *const c_char, name: &str) -> Result<&'a str, FfiError> {
+    optional(ptr, name)?.ok_or_else(|| FfiError::invalid_argument(format!("{} is null", name)))
+}
+
+/// Borrow an optional string argument; null means absent.
+///
+/// # Safety
+///
+/// `ptr` must be null or point to a NUL-terminated string that outlives `'a`.
+pub(crate) unsafe fn optional<'a>(
+    ptr: *const c_char,
+    name: &str,
+) -> Result<Option<&'a str>, FfiError> {
+    if ptr.is_null() {
+        return Ok(None);
+    }
+    CStr::from_ptr(ptr)
+        .to_str()
+        .map(Some)
+        .map_err(|_| FfiError::invalid_argument(format!("{} is not valid UTF-8", name)))
+}
+
+/// Borrow an array of `count` required strings.
+///
+/// # Safety
+///
+/// `ptr` must be null or point to `count` pointers, each valid for
+/// [`required`].
+pub(crate) unsafe fn array<'a>(
+    ptr: *const *const c_char,
+    count: usize,
+    name: &str,
+) -> Result<Vec<&'a str>, FfiError> {
+    if ptr.is_null() {
+        return Err(FfiError::invalid_argument(format!("{} is null", name)));
+    }
+    std::slice::from_raw_parts(ptr, count)
+        .iter()
+        .enumerate()
+        .map(|(i, &item)| required(item, &format!("{}[{}]", name, i)))
+        .collect()
+}
+
+/// Convert a string into one owned by the caller.
+pub(crate) fn to_owned_c(value: impl Into<String>) -> Result<CString, FfiError> {
+    CString::new(value.into()).map_err(|_| FfiError {
+        status: crate::CauceStatus::Internal,
+        message: "string contains an interior NUL".to_string(),
+    })
+}
+
+/// Convert a message for display, replacing interior NULs, which would
+/// truncate it.
+pub(crate) fn message(message: &str) -> CString {
+    CString::new(message.replace('\0', " ")).unwrap_or_default()
+}
+
+/// Release a string returned by this library.
+///
+/// Passing NULL is a no-op.
+///
+/// # Safety
+///
+/// `string` must have been returned through a `char **` out-parameter of
+/// this library and not released before.
+#[no_mangle]
+pub unsafe extern "C" fn cauce_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
@@ -0,0 +1,223 @@
+//! Subscription handle and signal callbacks.
+
+use std::ffi::{c_char, c_void, CString};
+use std::ptr;
+
+use tokio::runtime::Handle;
+use tokio::task::JoinHandle;
+
+use cauce_client_sdk::ClientError;
+
+use crate::client::CauceClient;
+use crate::error::{ffi_call, FfiError};
+use crate::strings;
+use crate::CauceStatus;
+
+/// Called for each signal delivered on a subscription.
+///
+/// `signal_json` is the signal serialized as JSON, valid only until the
+/// callback returns. `user_data` is the pointer passed to
+/// [`cauce_client_subscribe`]. May be NULL in C, which
+/// [`cauce_client_subscribe`] rejects.
+pub type CauceSignalCallback =
+    Option<extern "C" fn(user_data: *mut c_void, signal_json: *const c_char)>;
+
+/// Called when a subscription reports an error or stops delivering signals.
+///
+/// `message` describes the error, valid only until the callback returns.
+/// When `ended` is true no more signals will be delivered: either the hub
+/// denied or revoked the subscription (`status` is
+/// `CAUCE_STATUS_REJECTED`), or it was closed by unsubscribing or
+/// disconnecting (`status` is `CAUCE_STATUS_OK` and `message` is NULL).
+/// Otherwise the subscription keeps delivering, e.g. after signals were
+/// dropped because the callback fell behind. `user_data` is the pointer
+/// passed to [`cauce_client_subscribe`].
+pub type CauceSubscriptionErrorCallback = Option<
+    extern "C" fn(user_data: *mut c_void, status: CauceStatus, message: *const c_char, ended: bool),
+>;
+
+/// An active subscription. Opaque to C.
+///
+/// Created by [`cauce_client_subscribe`] and released with
+/// [`cauce_subscription_free`].
+pub struct CauceSubscription {
+    /// Hub-assigned subscription ID.
+    id: CString,
+
+    /// Task delivering signals to the callback.
+    task: Option<JoinHandle<()>>,
+
+    /// Runtime the task runs on.
+    runtime: Handle,
+}
+
+/// Callbacks and user data, moved onto the delivery task.
+struct Callback {
+    callback: extern "C" fn(*mut c_void, *const c_char),
+    on_error: CauceSubscriptionErrorCallback,
+    user_data: *mut c_void,
+}
+
+// The caller promises `user_data` may be used from the runtime's threads.
+unsafe impl Send for Callback {}
+
+impl Callback {
+    fn call(&self, signal_json: &CString) {
+        (self.callback)(self.user_data, signal_json.as_ptr());
+    }
+
+    /// Report an error, or the end of the subscription if `error` is `None`.
+    fn report(&self, error: Option<ClientError>, ended: bool) {
+        let Some(on_error) = self.on_error else {
+            return;
+        };
+        match error.map(FfiError::from) {
+            Some(error) => {
+                let message = strings::message(&error.message);
+                on_error(self.user_data, error.status, message.as_ptr(), ended);
+            }
+            None => on_error(self.user_data, CauceStatus::Ok, ptr::null(), ended),
+        }
+    }
+}
+
+/// Subscribe to one or more topic patterns.
+///
+/// `callback` is invoked with each signal delivered on the subscription,
+/// from one of the client's internal threads, until the subscription ends
+/// or is released. `on_error`, which may be NULL, is invoked on the same
+/// thread when the subscription reports an error, and once more when it
+/// ends. Neither is called after the subscription is released.
+///
+/// On success, `*out_subscription` receives a handle owned by the caller,
+/// to be released with [`cauce_subscription_free`]. On failure it is set
+/// to NULL.
+///
+/// # Safety
+///
+/// `client` must be a live handle, `topics` must point to `topic_count`
+/// NUL-terminated strings, `user_data` must be safe to use from another
+/// thread for the life of the subscription, and `out_subscription` must be
+/// valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn cauce_client_subscribe(
+    client: *mut CauceClient,
+    topics: *const *const c_char,
+    topic_count: usize,
+    callback: CauceSignalCallback,
+    on_error: CauceSubscriptionErrorCallback,
+    user_data: *mut c_void,
+    out_subscription: *mut *mut CauceSubscription,
+) -> CauceStatus {
+    ffi_call(|| {
+        if out_subscription.is_null() {
+            return Err(FfiError::invalid_argument("out_subscription is null"));
+        }
+        *out_subscription = ptr::null_mut();
+
+        let handle = client
+            .as_ref()
+            .ok_or_else(|| FfiError::invalid_argument("client is null"))?;
+        let topics = strings::array(topics, topic_count, "topics")?;
+        let callback = Callback {
+            callback: callback.ok_or_else(|| FfiError::invalid_argument("callback is null"))?,
+            on_error,
+            user_data,
+        };
+
+        let mut subscription = handle
+            .runtime
+            .block_on(async { handle.client.read().await.subscribe(&topics).await })?;
+        let id = strings::to_owned_c(subscription.subscription_id())?;
+
+        let task = handle.runtime.spawn(async move {
+            while let Some(result) = subscription.next().await {
+                let signal = match result {
+                    Ok(signal) => signal,
+                    Err(
+                        e @ (ClientError::SubscriptionDenied { .. }
+                        | ClientError::SubscriptionRevoked { .. }),
+                    ) => {
+                        tracing::info!(
+                            subscription_id = %subscription.subscription_id(),
+                            error = %e,
+                            "Subscription ended by the hub"
+                        );
+                        callback.report(Some(e), true);
+                        return;
+                    }
+                    Err(e) => {
+                        tracing::warn!(
+                            subscription_id = %subscription.subscription_id(),
+                            error = %e,
+                            "Subscription error"
+                        );
+                        callback.report(Some(e), false);
+                        continue;
+                    }
+                };
+                match serde_json::to_string(&signal).map(CString::new) {
+                    Ok(Ok(json)) => callback.call(&json),
+                    _ => tracing::warn!(
+                        signal_id = %signal.id,
+                        "Failed to serialize signal for callback"
+                    ),
+                }
+            }
+            callback.report(None, true);
+        });
+
+        *out_subscription = Box::into_raw(Box::new(CauceSubscription {
+            id,
+            task: Some(task),
+            runtime: handle.runtime.handle().clone(),
+        }));
+        Ok(())
+    })
+}
+
+/// Returns the hub-assigned ID of a subscription.
+///
+/// Returns NULL if `subscription` is NULL. The string is owned by the
+/// subscription and valid until it is released.
+///
+/// # Safety
+///
+/// `subscription` must be null or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn cauce_subscription_id(
+    subscription: *const CauceSubscription,
+) -> *const c_char {
+    subscription
+        .as_ref()
+        .map_or(ptr::null(), |subscription| subscription.id.as_ptr())
+}
+
+/// Release a subscription handle, stopping its callback.
+///
+/// Once this returns, the callback is no longer running and will not be
+/// called again. This does not unsubscribe at the hub; call
+/// [`cauce_client_unsubscribe`](crate::cauce_client_unsubscribe) first for
+/// that. Passing NULL is a no-op.
+///
+/// # Safety
+///
+/// `subscription` must be null or a handle from [`cauce_client_subscribe`]
+/// that has not been released, and must not be used afterwards. It must not
+/// be called from inside the subscription's own callback.
+#[no_mangle]
+pub unsafe extern "C" fn cauce_subscription_free(subscription: *mut CauceSubscription) {
+    if !subscription.is_null() {
+        ffi_call(|| {
+            let mut subscription = Box::from_raw(subscription);
+            if let Some(task) = subscription.task.take() {
+                task.abort();
+                // Wait for a callback in progress to return. Fails with a
+                // cancellation error, or immediately if the client's runtime
+                // is already gone.
+                let _ = subscription.runtime.block_on(task);
+            }
+            Ok(())
+        });
+    }
+}
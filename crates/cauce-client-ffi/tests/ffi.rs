@@ -0,0 +1,459 @@
+//! Tests driving the C API through its exported symbols against a mock hub.
+
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::net::SocketAddr;
+use std::ptr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use cauce_client_ffi::*;
+use cauce_core::{Payload, Signal, SignalDelivery, Source, Topic};
+use futures::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+use tokio_tungstenite::tungstenite::protocol::Message;
+
+fn make_signal(id: &str) -> Signal {
+    Signal {
+        id: id.to_string(),
+        version: "1.0".to_string(),
+        timestamp: chrono::Utc::now(),
+        source: Source::new("email", "adapter-1", "native-1"),
+        topic: Topic::new_unchecked("signal.email.received"),
+        payload: Payload::new(json!({ "subject": "hello" }), "application/json"),
+        metadata: None,
+        encrypted: None,
+        signature: None,
+    }
+}
+
+/// Answer a request from the client, returning the result and any
+/// notification to push after it.
+fn answer(method: &str, params: &Value) -> (Value, Option<Value>) {
+    match method {
+        "cauce.hello" => (
+            json!({ "session_id": "sess_ffi", "server_version": "1.0", "capabilities": [] }),
+            None,
+        ),
+        "cauce.subscribe" => {
+            let delivery = SignalDelivery::new("signal.email.received", make_signal("sig_1"))
+                .with_subscription_id("sub_ffi");
+            (
+                json!({
+                    "subscription_id": "sub_ffi",
+                    "status": "active",
+                    "topics": params["topics"],
+                    "created_at": "2024-01-01T00:00:00Z"
+                }),
+                Some(json!({ "jsonrpc": "2.0", "method": "cauce.signal", "params": delivery })),
+            )
+        }
+        "cauce.publish" => (
+            json!({ "message_id": "msg_ffi", "delivered_to": 1, "queued_for": 0 }),
+            None,
+        ),
+        "cauce.ack" => (
+            json!({ "acknowledged": params["signal_ids"], "failed": [] }),
+            None,
+        ),
+        "cauce.unsubscribe" => (json!({ "success": true }), None),
+        _ => (json!({}), None),
+    }
+}
+
+/// Answer like [`answer`], but revoke subscriptions instead of delivering
+/// a signal.
+fn answer_revoking(method: &str, params: &Value) -> (Value, Option<Value>) {
+    let (result, notification) = answer(method, params);
+    if method != "cauce.subscribe" {
+        return (result, notification);
+    }
+    let status = json!({
+        "subscription_id": "sub_ffi",
+        "status": "revoked",
+        "reason": "owner revoked access"
+    });
+    let notification = json!({
+        "jsonrpc": "2.0",
+        "method": "cauce.subscription.status",
+        "params": status
+    });
+    (result, Some(notification))
+}
+
+/// Start a mock hub on its own thread, recording the methods it receives.
+fn start_hub() -> (SocketAddr, Arc<Mutex<Vec<String>>>) {
+    start_hub_with(answer)
+}
+
+/// Start a mock hub answering requests with `answer`.
+fn start_hub_with(
+    answer: fn(&str, &Value) -> (Value, Option<Value>),
+) -> (SocketAddr, Arc<Mutex<Vec<String>>>) {
+    let methods = Arc::new(Mutex::new(Vec::new()));
+    let received = Arc::clone(&methods);
+    let (addr_tx, addr_rx) = std::sync::mpsc::channel();
+
+    std::thread::spawn(move || {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime.block_on(async move {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            addr_tx.send(listener.local_addr().unwrap()).unwrap();
+
+            let (stream, _) = listener.accept().await.unwrap();
+            let ws_stream = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let (mut write, mut read) = ws_stream.split();
+
+            while let Some(Ok(message)) = read.next().await {
+                let Message::Text(text) = message else {
+                    continue;
+                };
+                let request: Value = serde_json::from_str(&text).unwrap();
+                let method = request["method"].as_str().unwrap_or_default().to_string();
+                received.lock().unwrap().push(method.clone());
+                if request.get("id").is_none() {
+                    continue;
+                }
+
+                let (result, notification) = answer(&method, &request["params"]);
+                let response = json!({ "jsonrpc": "2.0", "id": request["id"], "result": result });
+                let mut outgoing = vec![response];
+                outgoing.extend(notification);
+                for message in outgoing {
+                    let text = message.to_string();
+                    if write.send(Message::Text(text)).await.is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+    });
+
+    (addr_rx.recv().unwrap(), methods)
+}
+
+fn last_error() -> String {
+    let error = cauce_last_error();
+    assert!(!error.is_null());
+    unsafe { CStr::from_ptr(error) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// What a subscription's callbacks received.
+#[derive(Default)]
+struct Received {
+    signals: Mutex<Vec<String>>,
+    errors: Mutex<Vec<(CauceStatus, Option<String>, bool)>>,
+}
+
+extern "C" fn collect_signal(user_data: *mut c_void, signal_json: *const c_char) {
+    let received = unsafe { &*(user_data as *const Received) };
+    let json = unsafe { CStr::from_ptr(signal_json) };
+    received
+        .signals
+        .lock()
+        .unwrap()
+        .push(json.to_str().unwrap().to_string());
+}
+
+extern "C" fn collect_error(
+    user_data: *mut c_void,
+    status: CauceStatus,
+    message: *const c_char,
+    ended: bool,
+) {
+    let received = unsafe { &*(user_data as *const Received) };
+    let message = (!message.is_null())
+        .then(|| unsafe { CStr::from_ptr(message) }.to_string_lossy().into_owned());
+    received
+        .errors
+        .lock()
+        .unwrap()
+        .push((status, message, ended));
+}
+
+/// Connect to the hub at `addr` as an adapter.
+unsafe fn connect(addr: SocketAddr) -> *mut CauceClient {
+    let hub_url = CString::new(format!("ws://{}", addr)).unwrap();
+    let client_id = CString::new("ffi-adapter").unwrap();
+    let client_type = CString::new("adapter").unwrap();
+    let options = CauceConnectOptions {
+        hub_url: hub_url.as_ptr(),
+        client_id: client_id.as_ptr(),
+        client_type: client_type.as_ptr(),
+        api_key: ptr::null(),
+        bearer_token: ptr::null(),
+    };
+    let mut client = ptr::null_mut();
+    assert_eq!(cauce_client_connect(&options, &mut client), CauceStatus::Ok);
+    client
+}
+
+fn wait_for(mut condition: impl FnMut() -> bool) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !condition() {
+        assert!(Instant::now() < deadline, "timed out waiting for condition");
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[test]
+fn test_client_lifecycle() {
+    let (addr, methods) = start_hub();
+
+    unsafe {
+        let client = connect(addr);
+        assert!(!client.is_null());
+        assert!(cauce_last_error().is_null());
+
+        // Subscribe; the hub delivers a signal right after confirming
+        let received = Received::default();
+        let signals = &received.signals;
+        let topic = CString::new("signal.email.*").unwrap();
+        let topics = [topic.as_ptr()];
+        let mut subscription = ptr::null_mut();
+        let status = cauce_client_subscribe(
+            client,
+            topics.as_ptr(),
+            topics.len(),
+            Some(collect_signal),
+            Some(collect_error),
+            &received as *const _ as *mut c_void,
+            &mut subscription,
+        );
+        assert_eq!(status, CauceStatus::Ok);
+        let subscription_id = cauce_subscription_id(subscription);
+        assert_eq!(CStr::from_ptr(subscription_id).to_str().unwrap(), "sub_ffi");
+
+        wait_for(|| !signals.lock().unwrap().is_empty());
+        let signal: Value = serde_json::from_str(&signals.lock().unwrap()[0]).unwrap();
+        assert_eq!(signal["id"], "sig_1");
+        assert_eq!(signal["payload"]["raw"]["subject"], "hello");
+
+        // Publish
+        let publish_topic = CString::new("signal.email.received").unwrap();
+        let message = CString::new(serde_json::to_string(&make_signal("sig_2")).unwrap()).unwrap();
+        let mut message_id = ptr::null_mut();
+        let status = cauce_client_publish(
+            client,
+            publish_topic.as_ptr(),
+            message.as_ptr(),
+            &mut message_id,
+        );
+        assert_eq!(status, CauceStatus::Ok);
+        assert_eq!(CStr::from_ptr(message_id).to_str().unwrap(), "msg_ffi");
+        cauce_string_free(message_id);
+
+        // Ack, then unsubscribe
+        let signal_id = CString::new("sig_1").unwrap();
+        let signal_ids = [signal_id.as_ptr()];
+        let status = cauce_client_ack(client, subscription_id, signal_ids.as_ptr(), 1);
+        assert_eq!(status, CauceStatus::Ok);
+        assert_eq!(
+            cauce_client_unsubscribe(client, subscription_id),
+            CauceStatus::Ok
+        );
+
+        // Unsubscribing ends the subscription without an error
+        wait_for(|| !received.errors.lock().unwrap().is_empty());
+        assert_eq!(
+            *received.errors.lock().unwrap(),
+            [(CauceStatus::Ok, None, true)]
+        );
+
+        cauce_subscription_free(subscription);
+        assert_eq!(cauce_client_disconnect(client), CauceStatus::Ok);
+        cauce_client_free(client);
+    }
+
+    let methods = methods.lock().unwrap();
+    for method in [
+        "cauce.hello",
+        "cauce.subscribe",
+        "cauce.publish",
+        "cauce.ack",
+        "cauce.unsubscribe",
+    ] {
+        assert!(
+            methods.iter().any(|m| m == method),
+            "hub never saw {}",
+            method
+        );
+    }
+}
+
+#[test]
+fn test_revoked_subscription_reports_end() {
+    let (addr, _) = start_hub_with(answer_revoking);
+
+    unsafe {
+        let client = connect(addr);
+        let received = Received::default();
+        let topic = CString::new("signal.email.*").unwrap();
+        let topics = [topic.as_ptr()];
+        let mut subscription = ptr::null_mut();
+        let status = cauce_client_subscribe(
+            client,
+            topics.as_ptr(),
+            topics.len(),
+            Some(collect_signal),
+            Some(collect_error),
+            &received as *const _ as *mut c_void,
+            &mut subscription,
+        );
+        assert_eq!(status, CauceStatus::Ok);
+
+        // The end is reported once, with the hub's reason
+        wait_for(|| !received.errors.lock().unwrap().is_empty());
+        std::thread::sleep(Duration::from_millis(50));
+        let errors = received.errors.lock().unwrap().clone();
+        assert_eq!(errors.len(), 1);
+        let (status, message, ended) = &errors[0];
+        assert_eq!(*status, CauceStatus::Rejected);
+        assert!(message.as_deref().unwrap().contains("owner revoked access"));
+        assert!(ended);
+        assert!(received.signals.lock().unwrap().is_empty());
+
+        cauce_subscription_free(subscription);
+        assert_eq!(cauce_client_disconnect(client), CauceStatus::Ok);
+        cauce_client_free(client);
+    }
+}
+
+#[test]
+fn test_disconnect_while_publishing_on_other_threads() {
+    let (addr, _) = start_hub();
+
+    unsafe {
+        // Raw pointers aren't Send; the handle is documented as shareable
+        let client = connect(addr) as usize;
+        let publishers: Vec<_> = (0..4)
+            .map(|i| {
+                std::thread::spawn(move || {
+                    let topic = CString::new("signal.email.received").unwrap();
+                    let signal = make_signal(&format!("sig_{}", i));
+                    let message = CString::new(serde_json::to_string(&signal).unwrap()).unwrap();
+                    let mut statuses = Vec::new();
+                    for _ in 0..10 {
+                        statuses.push(cauce_client_publish(
+                            client as *mut CauceClient,
+                            topic.as_ptr(),
+                            message.as_ptr(),
+                            ptr::null_mut(),
+                        ));
+                    }
+                    statuses
+                })
+            })
+            .collect();
+
+        assert_eq!(
+            cauce_client_disconnect(client as *mut CauceClient),
+            CauceStatus::Ok
+        );
+        for publisher in publishers {
+            for status in publisher.join().unwrap() {
+                assert!(matches!(status, CauceStatus::Ok | CauceStatus::ConnectionFailed));
+            }
+        }
+        cauce_client_free(client as *mut CauceClient);
+    }
+}
+
+#[test]
+fn test_null_arguments_are_rejected() {
+    unsafe {
+        let mut client = ptr::null_mut();
+        let status = cauce_client_connect(ptr::null(), &mut client);
+        assert_eq!(status, CauceStatus::InvalidArgument);
+        assert!(client.is_null());
+        assert_eq!(last_error(), "options is null");
+
+        let options = CauceConnectOptions {
+            hub_url: ptr::null(),
+            client_id: ptr::null(),
+            client_type: ptr::null(),
+            api_key: ptr::null(),
+            bearer_token: ptr::null(),
+        };
+        let status = cauce_client_connect(&options, &mut client);
+        assert_eq!(status, CauceStatus::InvalidArgument);
+        assert_eq!(last_error(), "hub_url is null");
+
+        let topic = CString::new("signal.email.received").unwrap();
+        let status = cauce_client_publish(
+            ptr::null_mut(),
+            topic.as_ptr(),
+            topic.as_ptr(),
+            ptr::null_mut(),
+        );
+        assert_eq!(status, CauceStatus::InvalidArgument);
+        assert_eq!(last_error(), "client is null");
+
+        // Releasing NULL handles is a no-op
+        cauce_client_free(ptr::null_mut());
+        cauce_subscription_free(ptr::null_mut());
+        cauce_string_free(ptr::null_mut());
+        assert!(cauce_subscription_id(ptr::null()).is_null());
+    }
+}
+
+#[test]
+fn test_connect_failure_reports_status() {
+    let hub_url = CString::new("not a url").unwrap();
+    let client_id = CString::new("ffi-adapter").unwrap();
+    let options = CauceConnectOptions {
+        hub_url: hub_url.as_ptr(),
+        client_id: client_id.as_ptr(),
+        client_type: ptr::null(),
+        api_key: ptr::null(),
+        bearer_token: ptr::null(),
+    };
+
+    unsafe {
+        let mut client = ptr::null_mut();
+        let status = cauce_client_connect(&options, &mut client);
+        assert_eq!(status, CauceStatus::InvalidArgument);
+        assert!(client.is_null());
+        assert!(!last_error().is_empty());
+    }
+}
+
+#[test]
+fn test_header_is_generated() {
+    let header = std::fs::read_to_string(
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("include/cauce_client.h"),
+    )
+    .unwrap();
+    for symbol in [
+        "cauce_client_connect",
+        "cauce_client_subscribe",
+        "cauce_client_publish",
+        "cauce_client_ack",
+        "cauce_client_disconnect",
+        "cauce_client_free",
+        "typedef struct CauceClient CauceClient;",
+        "CAUCE_STATUS_OK = 0",
+    ] {
+        assert!(header.contains(symbol), "header is missing {}", symbol);
+    }
+}
+
+#[test]
+fn test_checked_in_header_is_current() {
+    let generated =
+        std::fs::read_to_string(std::path::Path::new(env!("OUT_DIR")).join("cauce_client.h"))
+            .unwrap();
+    let checked_in = std::fs::read_to_string(
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("include/cauce_client.h"),
+    )
+    .unwrap();
+    assert!(
+        generated == checked_in,
+        "include/cauce_client.h is stale; rebuild with CAUCE_FFI_WRITE_HEADER=1"
+    );
+}
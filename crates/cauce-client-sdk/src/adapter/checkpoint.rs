@@ -0,0 +1,184 @@
+//! Cursor persistence for adapters.
+//!
+//! A checkpoint is an opaque cursor string (a message UID, a page token, a
+//! feed timestamp) that lets an adapter resume where it left off after a
+//! restart. The [`CheckpointStore`] trait abstracts where it is kept.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+
+use crate::error::ClientError;
+use crate::ClientResult;
+
+/// Storage backend for adapter checkpoints.
+///
+/// Checkpoints are keyed by adapter ID, so one store can be shared by
+/// several adapters.
+#[async_trait]
+pub trait CheckpointStore: Send + Sync + 'static {
+    /// Load the last saved cursor for an adapter.
+    ///
+    /// Returns `Ok(None)` if nothing has been saved yet.
+    async fn load(&self, adapter_id: &str) -> ClientResult<Option<String>>;
+
+    /// Save the cursor for an adapter, replacing any previous value.
+    async fn save(&self, adapter_id: &str, cursor: &str) -> ClientResult<()>;
+}
+
+/// In-memory checkpoint store.
+///
+/// Checkpoints are lost when the process exits. This is the default store
+/// and is useful for tests and for adapters whose source has its own
+/// read markers.
+#[derive(Debug, Default)]
+pub struct MemoryCheckpointStore {
+    cursors: Mutex<HashMap<String, String>>,
+}
+
+impl MemoryCheckpointStore {
+    /// Create an empty in-memory store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl CheckpointStore for MemoryCheckpointStore {
+    async fn load(&self, adapter_id: &str) -> ClientResult<Option<String>> {
+        Ok(self.cursors.lock().await.get(adapter_id).cloned())
+    }
+
+    async fn save(&self, adapter_id: &str, cursor: &str) -> ClientResult<()> {
+        self.cursors
+            .lock()
+            .await
+            .insert(adapter_id.to_string(), cursor.to_string());
+        Ok(())
+    }
+}
+
+/// File-backed checkpoint store.
+///
+/// Each adapter's cursor is written to `<dir>/<adapter_id>.cursor`, with
+/// characters other than ASCII letters, digits, `-`, `_` and `.`
+/// percent-encoded, so distinct adapter IDs never share a file. Writes go
+/// to a temporary file that is synced to disk and then renamed into place,
+/// so a crash never leaves a truncated cursor behind.
+///
+/// # Example
+///
+/// ```ignore
+/// let store = FileCheckpointStore::new("/var/lib/cauce/checkpoints");
+/// ```
+#[derive(Debug, Clone)]
+pub struct FileCheckpointStore {
+    dir: PathBuf,
+}
+
+impl FileCheckpointStore {
+    /// Create a store that keeps checkpoints in `dir`.
+    ///
+    /// The directory is created on the first save.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Path of the checkpoint file for an adapter.
+    fn path_for(&self, adapter_id: &str) -> PathBuf {
+        // Adapter IDs are free-form; keep them from escaping the directory
+        // and from colliding with each other
+        let mut file_name = String::with_capacity(adapter_id.len());
+        for byte in adapter_id.bytes() {
+            if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.') {
+                file_name.push(byte as char);
+            } else {
+                file_name.push_str(&format!("%{:02X}", byte));
+            }
+        }
+        self.dir.join(format!("{}.cursor", file_name))
+    }
+}
+
+#[async_trait]
+impl CheckpointStore for FileCheckpointStore {
+    async fn load(&self, adapter_id: &str) -> ClientResult<Option<String>> {
+        match tokio::fs::read_to_string(self.path_for(adapter_id)).await {
+            Ok(cursor) => Ok(Some(cursor)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(ClientError::CheckpointError {
+                message: format!("Failed to read checkpoint for {}: {}", adapter_id, e),
+            }),
+        }
+    }
+
+    async fn save(&self, adapter_id: &str, cursor: &str) -> ClientResult<()> {
+        let path = self.path_for(adapter_id);
+        let tmp = path.with_extension("cursor.tmp");
+        let to_error = |e: std::io::Error| ClientError::CheckpointError {
+            message: format!("Failed to write checkpoint for {}: {}", adapter_id, e),
+        };
+
+        tokio::fs::create_dir_all(&self.dir)
+            .await
+            .map_err(to_error)?;
+        let mut file = tokio::fs::File::create(&tmp).await.map_err(to_error)?;
+        file.write_all(cursor.as_bytes()).await.map_err(to_error)?;
+        file.sync_all().await.map_err(to_error)?;
+        drop(file);
+        tokio::fs::rename(&tmp, &path).await.map_err(to_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_memory_store_round_trip() {
+        let store = MemoryCheckpointStore::new();
+        assert_eq!(store.load("rss-1").await.unwrap(), None);
+
+        store.save("rss-1", "cursor-1").await.unwrap();
+        store.save("rss-1", "cursor-2").await.unwrap();
+        assert_eq!(
+            store.load("rss-1").await.unwrap().as_deref(),
+            Some("cursor-2")
+        );
+        assert_eq!(store.load("rss-2").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_file_store_round_trip() {
+        let dir =
+            std::env::temp_dir().join(format!("cauce-checkpoint-test-{}", std::process::id()));
+        let store = FileCheckpointStore::new(&dir);
+
+        assert_eq!(store.load("imap/alice").await.unwrap(), None);
+        store.save("imap/alice", "uid:42").await.unwrap();
+        assert_eq!(
+            store.load("imap/alice").await.unwrap().as_deref(),
+            Some("uid:42")
+        );
+
+        // The adapter ID cannot escape the checkpoint directory
+        assert_eq!(
+            store.path_for("../etc/passwd").parent(),
+            Some(dir.as_path())
+        );
+
+        // Distinct adapter IDs never share a checkpoint
+        assert_ne!(store.path_for("imap/alice"), store.path_for("imap_alice"));
+        assert_ne!(store.path_for("imap/alice"), store.path_for("imap%2Falice"));
+        store.save("imap_alice", "uid:7").await.unwrap();
+        assert_eq!(
+            store.load("imap/alice").await.unwrap().as_deref(),
+            Some("uid:42")
+        );
+
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+    }
+}
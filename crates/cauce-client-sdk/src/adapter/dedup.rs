@@ -0,0 +1,90 @@
+//! Native-ID deduplication for adapters.
+//!
+//! Sources frequently return the same message twice: overlapping poll
+//! windows, webhook retries, reconnects that replay history. The
+//! [`DedupCache`] remembers the most recent native IDs so each one is
+//! published once.
+
+use std::collections::{HashSet, VecDeque};
+
+/// Bounded set of recently seen native IDs.
+///
+/// When full, the oldest ID is forgotten first.
+#[derive(Debug)]
+pub(crate) struct DedupCache {
+    /// IDs in insertion order, oldest first.
+    order: VecDeque<String>,
+
+    /// IDs for constant-time lookup.
+    seen: HashSet<String>,
+
+    /// Maximum number of IDs remembered.
+    capacity: usize,
+}
+
+impl DedupCache {
+    /// Create a cache remembering up to `capacity` IDs.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            order: VecDeque::new(),
+            seen: HashSet::new(),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Record an ID, returning `false` if it was already present.
+    pub fn insert(&mut self, id: &str) -> bool {
+        if self.seen.contains(id) {
+            return false;
+        }
+
+        if self.order.len() >= self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.seen.remove(&oldest);
+            }
+        }
+
+        self.order.push_back(id.to_string());
+        self.seen.insert(id.to_string());
+        true
+    }
+
+    /// Forget an ID so it can be emitted again.
+    pub fn remove(&mut self, id: &str) {
+        if self.seen.remove(id) {
+            self.order.retain(|existing| existing != id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_detects_duplicates() {
+        let mut cache = DedupCache::new(10);
+        assert!(cache.insert("msg-1"));
+        assert!(!cache.insert("msg-1"));
+        assert!(cache.insert("msg-2"));
+    }
+
+    #[test]
+    fn test_oldest_evicted_when_full() {
+        let mut cache = DedupCache::new(2);
+        cache.insert("msg-1");
+        cache.insert("msg-2");
+        cache.insert("msg-3");
+
+        assert!(cache.insert("msg-1"));
+        assert!(!cache.insert("msg-3"));
+    }
+
+    #[test]
+    fn test_remove_allows_reinsert() {
+        let mut cache = DedupCache::new(10);
+        cache.insert("msg-1");
+        cache.remove("msg-1");
+        assert!(cache.insert("msg-1"));
+    }
+}
@@ -0,0 +1,1202 @@
+//! Adapter framework for bridging external platforms to a Cauce Hub.
+//!
+//! An adapter turns messages from an external source (a mailbox, a chat
+//! API, a feed) into signals, and executes the actions agents send back.
+//! Implement the [`Adapter`] trait and hand it to an [`AdapterRuntime`],
+//! which takes care of:
+//!
+//! - Lifecycle: [`start`](Adapter::start), then polling and/or push
+//!   emission, then [`shutdown`](Adapter::shutdown)
+//! - Signal construction via [`SignalBuilder`](cauce_core::SignalBuilder),
+//!   with the [`Source`] filled in from the adapter type and ID
+//! - Native-ID deduplication, so a message seen twice is published once
+//! - Cursor persistence through a [`CheckpointStore`]
+//! - Action execution: actions delivered on the adapter's action topics
+//!   (`cauce.action` notifications) are passed to
+//!   [`handle_action`](Adapter::handle_action), and the outcome is
+//!   published as an [`ActionResult`] on `signal.<adapter_type>.action_result`
+//!
+//! # Example
+//!
+//! ```ignore
+//! use std::sync::Arc;
+//! use cauce_client_sdk::adapter::{Adapter, AdapterContext, AdapterRuntime, AdapterSignal, PollBatch};
+//!
+//! struct RssAdapter { feed_url: String }
+//!
+//! #[async_trait]
+//! impl Adapter for RssAdapter {
+//!     fn adapter_type(&self) -> &str {
+//!         "rss"
+//!     }
+//!
+//!     fn poll_interval(&self) -> Option<Duration> {
+//!         Some(Duration::from_secs(300))
+//!     }
+//!
+//!     async fn poll(&self, _ctx: &AdapterContext, cursor: Option<&str>) -> Result<PollBatch, HandlerError> {
+//!         let items = fetch_items(&self.feed_url, cursor).await?;
+//!         let latest = items.last().map(|item| item.published.clone());
+//!         let signals = items
+//!             .into_iter()
+//!             .map(|item| AdapterSignal::json(item.guid.clone(), "signal.rss.item", &item))
+//!             .collect::<Result<_, _>>()?;
+//!         Ok(PollBatch::new(signals).with_cursor(latest))
+//!     }
+//! }
+//!
+//! let client = Arc::new(CauceClient::connect(config).await?);
+//! let stats = AdapterRuntime::new(client, RssAdapter { feed_url })
+//!     .with_checkpoint_store(FileCheckpointStore::new("/var/lib/cauce"))
+//!     .run_until(async { tokio::signal::ctrl_c().await.ok(); })
+//!     .await?;
+//! ```
+
+mod checkpoint;
+mod dedup;
+
+pub use checkpoint::{CheckpointStore, FileCheckpointStore, MemoryCheckpointStore};
+
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::FutureExt;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, Mutex};
+use tokio::time::{Interval, MissedTickBehavior};
+
+use cauce_core::{
+    validate_topic_pattern, Action, ActionDelivery, JsonRpcNotification, Metadata, Payload,
+    PublishResponse, Signal, Source, Topic, TopicMatcher, METHOD_ACTION,
+};
+
+#[cfg(feature = "signing")]
+use cauce_core::signing::SigningKey;
+
+use crate::client::{CauceClient, Subscription};
+use crate::dispatch::HandlerError;
+use crate::error::ClientError;
+use crate::ClientResult;
+
+use dedup::DedupCache;
+
+/// Default number of native IDs remembered for deduplication.
+const DEFAULT_DEDUP_CAPACITY: usize = 10_000;
+
+/// Lifecycle hooks implemented by an adapter.
+///
+/// Only [`adapter_type`](Self::adapter_type) is required. Poll-based
+/// adapters return an interval from [`poll_interval`](Self::poll_interval)
+/// and implement [`poll`](Self::poll). Push-based adapters spawn their
+/// listeners in [`start`](Self::start) and call
+/// [`AdapterContext::emit`] as messages arrive. Adapters that execute
+/// actions list their topics in [`action_topics`](Self::action_topics)
+/// and implement [`handle_action`](Self::handle_action).
+#[async_trait]
+pub trait Adapter: Send + Sync + 'static {
+    /// The adapter type, used as [`Source::type_`] (e.g. `"email"`).
+    ///
+    /// Must be a valid topic segment, since action results are published on
+    /// `signal.<adapter_type>.action_result`.
+    fn adapter_type(&self) -> &str;
+
+    /// How often [`poll`](Self::poll) is called; `None` disables polling.
+    fn poll_interval(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Topic patterns this adapter executes actions for.
+    fn action_topics(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Called once before polling starts and actions are accepted.
+    ///
+    /// Returning an error aborts the runtime.
+    async fn start(&self, _ctx: &AdapterContext) -> Result<(), HandlerError> {
+        Ok(())
+    }
+
+    /// Fetch new messages from the source.
+    ///
+    /// `cursor` is the last checkpoint returned in a [`PollBatch`], or
+    /// `None` on first run. The cursor is only saved once every signal in
+    /// the batch has been published.
+    async fn poll(
+        &self,
+        _ctx: &AdapterContext,
+        _cursor: Option<&str>,
+    ) -> Result<PollBatch, HandlerError> {
+        Ok(PollBatch::default())
+    }
+
+    /// Execute an action, returning optional output for the action result.
+    async fn handle_action(
+        &self,
+        _ctx: &AdapterContext,
+        action: &Action,
+    ) -> Result<Option<serde_json::Value>, HandlerError> {
+        Err(format!(
+            "{} adapter does not support {:?} actions",
+            self.adapter_type(),
+            action.action.type_
+        )
+        .into())
+    }
+
+    /// Called once when the runtime stops.
+    async fn shutdown(&self, _ctx: &AdapterContext) -> Result<(), HandlerError> {
+        Ok(())
+    }
+}
+
+/// A message from the source, before the runtime turns it into a [`Signal`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdapterSignal {
+    /// The message's ID on the source platform; used for deduplication.
+    pub native_id: String,
+
+    /// The topic to publish the signal to.
+    pub topic: String,
+
+    /// The signal payload.
+    pub payload: Payload,
+
+    /// Optional threading and priority metadata.
+    pub metadata: Option<Metadata>,
+}
+
+impl AdapterSignal {
+    /// Create a signal with an explicit payload.
+    pub fn new(native_id: impl Into<String>, topic: impl Into<String>, payload: Payload) -> Self {
+        Self {
+            native_id: native_id.into(),
+            topic: topic.into(),
+            payload,
+            metadata: None,
+        }
+    }
+
+    /// Create a signal with a JSON payload serialized from `value`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ClientError::SerializationError`] if `value` cannot be
+    /// represented as JSON.
+    pub fn json<T: Serialize + ?Sized>(
+        native_id: impl Into<String>,
+        topic: impl Into<String>,
+        value: &T,
+    ) -> ClientResult<Self> {
+        Ok(Self::new(
+            native_id,
+            topic,
+            Payload::from_serializable(value)?,
+        ))
+    }
+
+    /// Attach metadata to the signal.
+    pub fn with_metadata(mut self, metadata: Metadata) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+}
+
+/// The result of one [`Adapter::poll`] call.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PollBatch {
+    /// Messages to publish, in order.
+    pub signals: Vec<AdapterSignal>,
+
+    /// Cursor to resume from next time, if it changed.
+    pub cursor: Option<String>,
+}
+
+impl PollBatch {
+    /// Create a batch of signals without a cursor.
+    pub fn new(signals: Vec<AdapterSignal>) -> Self {
+        Self {
+            signals,
+            cursor: None,
+        }
+    }
+
+    /// Set the cursor to resume from.
+    pub fn with_cursor(mut self, cursor: Option<String>) -> Self {
+        self.cursor = cursor;
+        self
+    }
+}
+
+/// Outcome of an action, published back to the hub by the runtime.
+///
+/// Sent as the JSON payload of a signal on
+/// `signal.<adapter_type>.action_result`, with `in_reply_to` set to the
+/// action ID.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActionResult {
+    /// The ID of the executed action.
+    pub action_id: String,
+
+    /// Whether the action succeeded.
+    pub success: bool,
+
+    /// Output returned by the adapter.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<serde_json::Value>,
+
+    /// Why the action failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ActionResult {
+    /// A successful result with optional output.
+    pub fn success(action_id: impl Into<String>, output: Option<serde_json::Value>) -> Self {
+        Self {
+            action_id: action_id.into(),
+            success: true,
+            output,
+            error: None,
+        }
+    }
+
+    /// A failed result.
+    pub fn failure(action_id: impl Into<String>, error: impl Into<String>) -> Self {
+        Self {
+            action_id: action_id.into(),
+            success: false,
+            output: None,
+            error: Some(error.into()),
+        }
+    }
+}
+
+/// Configuration for an [`AdapterRuntime`].
+#[derive(Debug, Clone)]
+pub struct AdapterConfig {
+    /// The adapter instance ID, used as [`Source::adapter_id`] and as the
+    /// checkpoint key. Defaults to the client ID.
+    pub adapter_id: Option<String>,
+
+    /// Number of recent native IDs remembered for deduplication.
+    pub dedup_capacity: usize,
+
+    /// Key that signs every published signal, so the hub can verify it came
+    /// from this adapter.
+    #[cfg(feature = "signing")]
+    pub signing_key: Option<SigningKey>,
+}
+
+impl Default for AdapterConfig {
+    fn default() -> Self {
+        Self {
+            adapter_id: None,
+            dedup_capacity: DEFAULT_DEDUP_CAPACITY,
+            #[cfg(feature = "signing")]
+            signing_key: None,
+        }
+    }
+}
+
+impl AdapterConfig {
+    /// Set the adapter instance ID.
+    pub fn with_adapter_id(mut self, adapter_id: impl Into<String>) -> Self {
+        self.adapter_id = Some(adapter_id.into());
+        self
+    }
+
+    /// Set how many recent native IDs are remembered for deduplication.
+    pub fn with_dedup_capacity(mut self, capacity: usize) -> Self {
+        self.dedup_capacity = capacity;
+        self
+    }
+
+    /// Sign every published signal with `key`.
+    #[cfg(feature = "signing")]
+    pub fn with_signing_key(mut self, key: SigningKey) -> Self {
+        self.signing_key = Some(key);
+        self
+    }
+}
+
+/// Counters describing a completed adapter run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AdapterStats {
+    /// Signals published to the hub.
+    pub emitted: u64,
+    /// Signals skipped because their native ID was already published.
+    pub duplicates: u64,
+    /// Completed poll calls.
+    pub polls: u64,
+    /// Poll calls that returned an error.
+    pub poll_errors: u64,
+    /// Actions executed successfully.
+    pub actions_succeeded: u64,
+    /// Actions that failed or panicked.
+    pub actions_failed: u64,
+}
+
+/// Shared state behind an [`AdapterContext`].
+struct ContextInner {
+    client: Arc<CauceClient>,
+    adapter_type: String,
+    adapter_id: String,
+    dedup: StdMutex<DedupCache>,
+    cursor: Mutex<Option<String>>,
+    store: Arc<dyn CheckpointStore>,
+    #[cfg(feature = "signing")]
+    signing_key: Option<SigningKey>,
+    emitted: AtomicU64,
+    duplicates: AtomicU64,
+}
+
+/// Handle passed to adapter hooks for publishing signals and checkpoints.
+///
+/// Cheap to clone; push-based adapters can move clones into the tasks they
+/// spawn from [`Adapter::start`].
+#[derive(Clone)]
+pub struct AdapterContext {
+    inner: Arc<ContextInner>,
+}
+
+impl AdapterContext {
+    /// The client the adapter publishes through.
+    pub fn client(&self) -> &Arc<CauceClient> {
+        &self.inner.client
+    }
+
+    /// The adapter type.
+    pub fn adapter_type(&self) -> &str {
+        &self.inner.adapter_type
+    }
+
+    /// The adapter instance ID.
+    pub fn adapter_id(&self) -> &str {
+        &self.inner.adapter_id
+    }
+
+    /// Build the [`Source`] for a message with the given native ID.
+    pub fn source(&self, native_id: impl Into<String>) -> Source {
+        Source::new(&self.inner.adapter_type, &self.inner.adapter_id, native_id)
+    }
+
+    /// Publish a signal, skipping it if its native ID was already published.
+    ///
+    /// Returns `Ok(None)` for duplicates. A signal that fails to publish is
+    /// forgotten by the deduplication cache so it can be retried.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError::InvalidMessage`] - Invalid topic
+    /// - Any error from [`CauceClient::publish`]
+    pub async fn emit(&self, signal: AdapterSignal) -> ClientResult<Option<PublishResponse>> {
+        let native_id = signal.native_id.clone();
+        self.deduplicated(native_id, self.publish(signal)).await
+    }
+
+    /// Publish a signal encrypted end-to-end for every current subscriber,
+    /// skipping it if its native ID was already published.
+    ///
+    /// Like [`emit`](Self::emit), but published through
+    /// [`CauceClient::publish_encrypted`]. With a signing key configured,
+    /// each encrypted copy is signed after it is encrypted, so the hub can
+    /// still verify it.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError::InvalidMessage`] - Invalid topic
+    /// - Any error from [`CauceClient::publish_encrypted`]
+    #[cfg(feature = "e2e")]
+    pub async fn emit_encrypted(
+        &self,
+        signal: AdapterSignal,
+    ) -> ClientResult<Option<Vec<PublishResponse>>> {
+        let native_id = signal.native_id.clone();
+        let publish = async {
+            let topic = signal.topic.clone();
+            let built = self.build(signal)?;
+            let client = &self.inner.client;
+            #[cfg(feature = "signing")]
+            if let Some(key) = &self.inner.signing_key {
+                return client.publish_encrypted_signed(&topic, built, key).await;
+            }
+            client.publish_encrypted(&topic, built).await
+        };
+        self.deduplicated(native_id, publish).await
+    }
+
+    /// Run `publish` unless `native_id` was already published.
+    async fn deduplicated<T>(
+        &self,
+        native_id: String,
+        publish: impl Future<Output = ClientResult<T>>,
+    ) -> ClientResult<Option<T>> {
+        if !self.inner.dedup.lock().unwrap().insert(&native_id) {
+            self.inner.duplicates.fetch_add(1, Ordering::Relaxed);
+            tracing::debug!(native_id = %native_id, "Skipping duplicate signal");
+            return Ok(None);
+        }
+
+        match publish.await {
+            Ok(response) => {
+                self.inner.emitted.fetch_add(1, Ordering::Relaxed);
+                Ok(Some(response))
+            }
+            Err(e) => {
+                self.inner.dedup.lock().unwrap().remove(&native_id);
+                Err(e)
+            }
+        }
+    }
+
+    /// Publish a signal answering an action.
+    ///
+    /// The signal's metadata is linked to the action: `in_reply_to` is set
+    /// to the action ID, and the action's `thread_id` and `correlation_id`
+    /// are copied over, so a [`CauceClient::request`] waiting on the action
+    /// resolves with this signal. Responses bypass deduplication.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError::InvalidMessage`] - Invalid topic
+    /// - Any error from [`CauceClient::publish`]
+    pub async fn respond(
+        &self,
+        action: &Action,
+        mut signal: AdapterSignal,
+    ) -> ClientResult<PublishResponse> {
+        let mut metadata = signal.metadata.take().unwrap_or_default();
+        metadata.in_reply_to = Some(action.id.clone());
+        if let Some(context) = &action.context {
+            if metadata.thread_id.is_none() {
+                metadata.thread_id = context.thread_id.clone();
+            }
+            metadata.correlation_id = context.correlation_id.clone();
+        }
+
+        self.publish(signal.with_metadata(metadata)).await
+    }
+
+    /// Returns the current checkpoint cursor.
+    pub async fn cursor(&self) -> Option<String> {
+        self.inner.cursor.lock().await.clone()
+    }
+
+    /// Persist a new checkpoint cursor.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ClientError::CheckpointError`] if the store fails.
+    pub async fn save_cursor(&self, cursor: impl Into<String>) -> ClientResult<()> {
+        let cursor = cursor.into();
+        self.inner
+            .store
+            .save(&self.inner.adapter_id, &cursor)
+            .await?;
+        *self.inner.cursor.lock().await = Some(cursor);
+        Ok(())
+    }
+
+    /// Build a signal and publish it.
+    async fn publish(&self, signal: AdapterSignal) -> ClientResult<PublishResponse> {
+        let topic = signal.topic.clone();
+        let built = self.build(signal)?;
+        self.inner.client.publish(&topic, built.into()).await
+    }
+
+    /// Build a signal through [`SignalBuilder`](cauce_core::SignalBuilder),
+    /// signing it with the configured key.
+    fn build(&self, signal: AdapterSignal) -> ClientResult<Signal> {
+        let topic = Topic::new(&signal.topic).map_err(|e| ClientError::InvalidMessage {
+            message: format!("Invalid topic: {}", e),
+        })?;
+
+        let mut builder = Signal::builder()
+            .source(self.source(signal.native_id))
+            .topic(topic)
+            .payload(signal.payload);
+        if let Some(metadata) = signal.metadata {
+            builder = builder.metadata(metadata);
+        }
+        #[cfg(feature = "signing")]
+        if let Some(key) = &self.inner.signing_key {
+            builder = builder.sign_with(key);
+        }
+
+        builder.build().map_err(|e| ClientError::InvalidMessage {
+            message: format!("Failed to build signal: {}", e),
+        })
+    }
+
+    fn stats(&self) -> (u64, u64) {
+        (
+            self.inner.emitted.load(Ordering::Relaxed),
+            self.inner.duplicates.load(Ordering::Relaxed),
+        )
+    }
+}
+
+/// Drives an [`Adapter`] against a connected [`CauceClient`].
+pub struct AdapterRuntime<A: Adapter> {
+    client: Arc<CauceClient>,
+    adapter: A,
+    config: AdapterConfig,
+    store: Arc<dyn CheckpointStore>,
+}
+
+impl<A: Adapter> AdapterRuntime<A> {
+    /// Create a runtime for `adapter` that publishes through `client`.
+    ///
+    /// Checkpoints are kept in memory unless a store is set with
+    /// [`with_checkpoint_store`](Self::with_checkpoint_store).
+    pub fn new(client: Arc<CauceClient>, adapter: A) -> Self {
+        Self {
+            client,
+            adapter,
+            config: AdapterConfig::default(),
+            store: Arc::new(MemoryCheckpointStore::new()),
+        }
+    }
+
+    /// Set the runtime configuration.
+    pub fn with_config(mut self, config: AdapterConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Set where checkpoints are persisted.
+    pub fn with_checkpoint_store(mut self, store: impl CheckpointStore) -> Self {
+        self.store = Arc::new(store);
+        self
+    }
+
+    /// Run the adapter until `shutdown` completes.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError::ConfigError`] - Invalid adapter type or action topics
+    /// - [`ClientError::CheckpointError`] - The saved cursor could not be loaded
+    /// - [`ClientError::AdapterFailed`] - [`Adapter::start`] returned an error
+    /// - Any error from subscribing to the action topics
+    pub async fn run_until<F>(self, shutdown: F) -> ClientResult<AdapterStats>
+    where
+        F: Future<Output = ()>,
+    {
+        let adapter_type = self.adapter.adapter_type().to_string();
+        let result_topic = format!("signal.{}.action_result", adapter_type);
+        Topic::new(&result_topic).map_err(|e| {
+            ClientError::config_error(format!("Invalid adapter type '{}': {}", adapter_type, e))
+        })?;
+
+        let action_topics = self.adapter.action_topics();
+        for pattern in &action_topics {
+            validate_topic_pattern(pattern).map_err(|e| {
+                ClientError::config_error(format!("Invalid action topic '{}': {}", pattern, e))
+            })?;
+        }
+
+        let adapter_id = self
+            .config
+            .adapter_id
+            .clone()
+            .unwrap_or_else(|| self.client.config().client_id.clone());
+        let cursor = self.store.load(&adapter_id).await?;
+
+        let ctx = AdapterContext {
+            inner: Arc::new(ContextInner {
+                client: Arc::clone(&self.client),
+                adapter_type,
+                adapter_id,
+                dedup: StdMutex::new(DedupCache::new(self.config.dedup_capacity)),
+                cursor: Mutex::new(cursor),
+                store: Arc::clone(&self.store),
+                #[cfg(feature = "signing")]
+                signing_key: self.config.signing_key.clone(),
+                emitted: AtomicU64::new(0),
+                duplicates: AtomicU64::new(0),
+            }),
+        };
+
+        self.adapter
+            .start(&ctx)
+            .await
+            .map_err(|e| ClientError::AdapterFailed {
+                message: format!("start failed: {}", e),
+            })?;
+
+        tracing::info!(
+            adapter_type = %ctx.adapter_type(),
+            adapter_id = %ctx.adapter_id(),
+            "Adapter started"
+        );
+
+        // Listen before subscribing so no early action is missed
+        let (mut actions_rx, action_subscription) = if action_topics.is_empty() {
+            (None, None)
+        } else {
+            let actions_rx = self.client.notifications();
+            let topics: Vec<&str> = action_topics.iter().map(String::as_str).collect();
+            let subscription = self.client.subscribe(&topics).await?;
+            (Some(actions_rx), Some(subscription))
+        };
+
+        let mut ticker = self.adapter.poll_interval().map(|interval| {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            ticker
+        });
+
+        let mut stats = AdapterStats::default();
+        tokio::pin!(shutdown);
+
+        loop {
+            tokio::select! {
+                _ = &mut shutdown => break,
+                _ = next_tick(&mut ticker) => self.poll_once(&ctx, &mut stats).await,
+                notification = next_notification(&mut actions_rx) => match notification {
+                    Ok(notification) => {
+                        self.handle_notification(
+                            &ctx,
+                            notification,
+                            &action_topics,
+                            action_subscription.as_ref(),
+                            &result_topic,
+                            &mut stats,
+                        )
+                        .await;
+                    }
+                    Err(broadcast::error::RecvError::Lagged(missed)) => {
+                        tracing::warn!(missed, "Adapter fell behind on notifications");
+                    }
+                    Err(broadcast::error::RecvError::Closed) => {
+                        tracing::warn!("Notification channel closed, stopping adapter");
+                        break;
+                    }
+                },
+            }
+        }
+
+        if let Err(e) = self.adapter.shutdown(&ctx).await {
+            tracing::warn!(error = %e, "Adapter shutdown hook failed");
+        }
+        if let Some(subscription) = action_subscription {
+            let _ = self
+                .client
+                .unsubscribe(subscription.subscription_id())
+                .await;
+        }
+
+        (stats.emitted, stats.duplicates) = ctx.stats();
+        tracing::info!(?stats, "Adapter stopped");
+        Ok(stats)
+    }
+
+    /// Poll the source once and publish the results.
+    async fn poll_once(&self, ctx: &AdapterContext, stats: &mut AdapterStats) {
+        let cursor = ctx.cursor().await;
+        let batch = match self.adapter.poll(ctx, cursor.as_deref()).await {
+            Ok(batch) => batch,
+            Err(e) => {
+                stats.poll_errors += 1;
+                tracing::warn!(error = %e, "Adapter poll failed");
+                return;
+            }
+        };
+        stats.polls += 1;
+
+        let mut all_published = true;
+        for signal in batch.signals {
+            let native_id = signal.native_id.clone();
+            if let Err(e) = ctx.emit(signal).await {
+                all_published = false;
+                tracing::warn!(native_id = %native_id, error = %e, "Failed to publish signal");
+            }
+        }
+
+        match batch.cursor {
+            Some(next) if all_published && cursor.as_deref() != Some(next.as_str()) => {
+                if let Err(e) = ctx.save_cursor(next).await {
+                    tracing::warn!(error = %e, "Failed to save checkpoint");
+                }
+            }
+            Some(_) if !all_published => {
+                tracing::debug!("Not advancing checkpoint; some signals failed to publish");
+            }
+            _ => {}
+        }
+    }
+
+    /// Execute an action delivered on one of the adapter's action topics.
+    async fn handle_notification(
+        &self,
+        ctx: &AdapterContext,
+        notification: JsonRpcNotification,
+        action_topics: &[String],
+        subscription: Option<&Subscription>,
+        result_topic: &str,
+        stats: &mut AdapterStats,
+    ) {
+        if notification.method() != METHOD_ACTION {
+            return;
+        }
+
+        let delivery = match notification
+            .params()
+            .map(|params| serde_json::from_value::<ActionDelivery>(params.clone()))
+        {
+            Some(Ok(delivery)) => delivery,
+            Some(Err(e)) => {
+                tracing::warn!("Failed to parse action delivery: {}", e);
+                return;
+            }
+            None => {
+                tracing::warn!("Action notification without params");
+                return;
+            }
+        };
+
+        if !action_topics
+            .iter()
+            .any(|pattern| TopicMatcher::matches(&delivery.topic, pattern))
+        {
+            return;
+        }
+
+        let action = delivery.action;
+        let outcome = AssertUnwindSafe(self.adapter.handle_action(ctx, &action))
+            .catch_unwind()
+            .await;
+
+        let result = match outcome {
+            Ok(Ok(output)) => {
+                stats.actions_succeeded += 1;
+                ActionResult::success(&action.id, output)
+            }
+            Ok(Err(e)) => {
+                stats.actions_failed += 1;
+                tracing::warn!(action_id = %action.id, error = %e, "Action failed");
+                ActionResult::failure(&action.id, e.to_string())
+            }
+            Err(_) => {
+                stats.actions_failed += 1;
+                tracing::error!(action_id = %action.id, "Action handler panicked");
+                ActionResult::failure(&action.id, "action handler panicked")
+            }
+        };
+
+        if let Err(e) = report_result(ctx, &action, &result, result_topic).await {
+            tracing::warn!(action_id = %action.id, error = %e, "Failed to report action result");
+        }
+
+        if let Some(subscription) = subscription {
+            if let Err(e) = ctx
+                .client()
+                .ack(subscription.subscription_id(), &[&action.id])
+                .await
+            {
+                tracing::debug!(action_id = %action.id, error = %e, "Failed to ack action");
+            }
+        }
+    }
+}
+
+/// Publish an action's result as a reply signal.
+async fn report_result(
+    ctx: &AdapterContext,
+    action: &Action,
+    result: &ActionResult,
+    result_topic: &str,
+) -> ClientResult<PublishResponse> {
+    let signal = AdapterSignal::json(&action.id, result_topic, result)?;
+    ctx.respond(action, signal).await
+}
+
+/// Wait for the next poll tick, or forever if polling is disabled.
+async fn next_tick(ticker: &mut Option<Interval>) {
+    match ticker {
+        Some(ticker) => {
+            ticker.tick().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
+/// Wait for the next notification, or forever if the adapter takes no actions.
+async fn next_notification(
+    rx: &mut Option<broadcast::Receiver<JsonRpcNotification>>,
+) -> Result<JsonRpcNotification, broadcast::error::RecvError> {
+    match rx {
+        Some(rx) => rx.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ClientConfig;
+    use crate::transport::mock::{MockTransport, MockTransportHandle};
+    use crate::transport::{JsonRpcMessage, Transport};
+    use cauce_core::{
+        ActionBody, ActionContext, ActionType, JsonRpcResponse, PublishRequest, METHOD_ACK,
+        METHOD_KEYS_LIST, METHOD_PUBLISH, METHOD_SUBSCRIBE,
+    };
+
+    /// Requests the fake hub has seen, by method.
+    #[derive(Default)]
+    struct HubLog {
+        published: Vec<PublishRequest>,
+        acked: Vec<String>,
+        /// Recipients listed by `cauce.keys.list`.
+        recipients: Vec<serde_json::Value>,
+    }
+
+    fn spawn_fake_hub(handle: MockTransportHandle, log: Arc<StdMutex<HubLog>>) {
+        tokio::spawn(async move {
+            loop {
+                let Some(message) = handle.pop_sent().await else {
+                    tokio::time::sleep(Duration::from_millis(2)).await;
+                    continue;
+                };
+                let JsonRpcMessage::Request(request) = message else {
+                    continue;
+                };
+                let params = request.params().cloned().unwrap_or_default();
+                let result = match request.method() {
+                    METHOD_SUBSCRIBE => serde_json::json!({
+                        "subscription_id": "sub_actions",
+                        "status": "active",
+                        "topics": params["topics"],
+                        "created_at": "2024-01-01T00:00:00Z"
+                    }),
+                    METHOD_PUBLISH => {
+                        log.lock()
+                            .unwrap()
+                            .published
+                            .push(serde_json::from_value(params).unwrap());
+                        serde_json::json!({"message_id": "msg_1", "delivered_to": 1, "queued_for": 0})
+                    }
+                    METHOD_ACK => {
+                        let ids: Vec<String> =
+                            serde_json::from_value(params["signal_ids"].clone()).unwrap();
+                        log.lock().unwrap().acked.extend(ids.clone());
+                        serde_json::json!({ "acknowledged": ids })
+                    }
+                    METHOD_KEYS_LIST => {
+                        serde_json::json!({ "recipients": log.lock().unwrap().recipients })
+                    }
+                    _ => serde_json::json!({"success": true}),
+                };
+                let response = JsonRpcResponse::success(request.id().clone(), result);
+                handle.push_receive(response.into()).await;
+            }
+        });
+    }
+
+    async fn connect_mock() -> (Arc<CauceClient>, MockTransportHandle, Arc<StdMutex<HubLog>>) {
+        let mut transport = MockTransport::new().wait_when_empty();
+        transport.connect().await.unwrap();
+        let handle = transport.handle();
+
+        let hello = JsonRpcResponse::success(
+            cauce_core::RequestId::Number(1),
+            serde_json::json!({
+                "session_id": "sess_1",
+                "server_version": "1.0",
+                "capabilities": []
+            }),
+        );
+        handle.push_receive(hello.into()).await;
+
+        let config = ClientConfig::builder("ws://localhost:8080", "test-adapter")
+            .build()
+            .unwrap();
+        let client = CauceClient::connect_with_transport(config, Box::new(transport))
+            .await
+            .unwrap();
+        handle.pop_sent().await;
+
+        let log = Arc::new(StdMutex::new(HubLog::default()));
+        spawn_fake_hub(handle.clone(), Arc::clone(&log));
+        (Arc::new(client), handle, log)
+    }
+
+    /// Wait until `condition` holds for the hub log, or give up after 2s.
+    async fn wait_for(log: &StdMutex<HubLog>, condition: impl Fn(&HubLog) -> bool) {
+        for _ in 0..200 {
+            if condition(&log.lock().unwrap()) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+
+    fn published_signal(request: &PublishRequest) -> &Signal {
+        match &request.message {
+            cauce_core::PublishMessage::Signal(signal) => signal,
+            cauce_core::PublishMessage::Action(_) => panic!("expected a signal"),
+        }
+    }
+
+    /// Polls once, returning a duplicate and a cursor.
+    struct FeedAdapter;
+
+    #[async_trait]
+    impl Adapter for FeedAdapter {
+        fn adapter_type(&self) -> &str {
+            "feed"
+        }
+
+        fn poll_interval(&self) -> Option<Duration> {
+            Some(Duration::from_millis(20))
+        }
+
+        async fn poll(
+            &self,
+            _ctx: &AdapterContext,
+            cursor: Option<&str>,
+        ) -> Result<PollBatch, HandlerError> {
+            if cursor.is_some() {
+                return Ok(PollBatch::default());
+            }
+            let item = serde_json::json!({"title": "hello"});
+            Ok(PollBatch::new(vec![
+                AdapterSignal::json("item-1", "signal.feed.item", &item)?,
+                AdapterSignal::json("item-2", "signal.feed.item", &item)?,
+                AdapterSignal::json("item-1", "signal.feed.item", &item)?,
+            ])
+            .with_cursor(Some("page-2".to_string())))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_poll_publishes_deduplicated_signals() {
+        let (client, _handle, log) = connect_mock().await;
+        let store = Arc::new(MemoryCheckpointStore::new());
+
+        struct SharedStore(Arc<MemoryCheckpointStore>);
+
+        #[async_trait]
+        impl CheckpointStore for SharedStore {
+            async fn load(&self, adapter_id: &str) -> ClientResult<Option<String>> {
+                self.0.load(adapter_id).await
+            }
+            async fn save(&self, adapter_id: &str, cursor: &str) -> ClientResult<()> {
+                self.0.save(adapter_id, cursor).await
+            }
+        }
+
+        let runtime = AdapterRuntime::new(Arc::clone(&client), FeedAdapter)
+            .with_config(AdapterConfig::default().with_adapter_id("feed-1"))
+            .with_checkpoint_store(SharedStore(Arc::clone(&store)));
+
+        let stats = runtime
+            .run_until(async {
+                wait_for(&log, |log| log.published.len() >= 2).await;
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(stats.emitted, 2);
+        assert_eq!(stats.duplicates, 1);
+        assert!(stats.polls >= 1);
+        assert_eq!(
+            store.load("feed-1").await.unwrap().as_deref(),
+            Some("page-2")
+        );
+
+        let log = log.lock().unwrap();
+        let source = &published_signal(&log.published[0]).source;
+        assert_eq!(source.type_, "feed");
+        assert_eq!(source.adapter_id, "feed-1");
+        assert_eq!(source.native_id, "item-1");
+    }
+
+    #[cfg(feature = "signing")]
+    #[tokio::test]
+    async fn test_signing_key_signs_published_signals() {
+        let (client, _handle, log) = connect_mock().await;
+        let key = SigningKey::generate();
+
+        let runtime = AdapterRuntime::new(Arc::clone(&client), FeedAdapter).with_config(
+            AdapterConfig::default()
+                .with_adapter_id("feed-1")
+                .with_signing_key(key.clone()),
+        );
+        runtime
+            .run_until(wait_for(&log, |log| log.published.len() >= 2))
+            .await
+            .unwrap();
+
+        let log = log.lock().unwrap();
+        let signal = published_signal(&log.published[0]);
+        assert!(cauce_core::signing::verify_signal(signal, &key.verifying_key()).is_ok());
+    }
+
+    #[cfg(all(feature = "e2e", feature = "signing"))]
+    #[tokio::test]
+    async fn test_emit_encrypted_signs_each_copy() {
+        use cauce_core::e2e::{self, KeyPair};
+
+        let (client, _handle, log) = connect_mock().await;
+        let recipient = KeyPair::generate();
+        log.lock().unwrap().recipients = vec![serde_json::json!({
+            "subscription_id": "sub_1",
+            "public_key": recipient.public_key().to_base64(),
+        })];
+
+        let key = SigningKey::generate();
+        let ctx = AdapterContext {
+            inner: Arc::new(ContextInner {
+                client,
+                adapter_type: "feed".to_string(),
+                adapter_id: "feed-1".to_string(),
+                dedup: StdMutex::new(DedupCache::new(16)),
+                cursor: Mutex::new(None),
+                store: Arc::new(MemoryCheckpointStore::new()),
+                signing_key: Some(key.clone()),
+                emitted: AtomicU64::new(0),
+                duplicates: AtomicU64::new(0),
+            }),
+        };
+        let signal = AdapterSignal::new(
+            "item-1",
+            "signal.feed.item",
+            Payload::new(serde_json::json!({"title": "secret"}), "application/json"),
+        );
+        let responses = ctx.emit_encrypted(signal.clone()).await.unwrap().unwrap();
+        assert_eq!(responses.len(), 1);
+        assert!(ctx.emit_encrypted(signal).await.unwrap().is_none());
+
+        // The copy is signed over its envelope, and opens for the recipient
+        let log = log.lock().unwrap();
+        let copy = published_signal(&log.published[0]);
+        assert!(copy.is_encrypted());
+        assert!(cauce_core::signing::verify_signal(copy, &key.verifying_key()).is_ok());
+        let opened = e2e::decrypt_signal(copy, recipient.secret_key()).unwrap();
+        assert_eq!(opened.payload.raw["title"], "secret");
+    }
+
+    /// Sends messages; fails for the `"nobody"` target.
+    struct SendAdapter;
+
+    #[async_trait]
+    impl Adapter for SendAdapter {
+        fn adapter_type(&self) -> &str {
+            "chat"
+        }
+
+        fn action_topics(&self) -> Vec<String> {
+            vec!["action.chat.*".to_string()]
+        }
+
+        async fn handle_action(
+            &self,
+            _ctx: &AdapterContext,
+            action: &Action,
+        ) -> Result<Option<serde_json::Value>, HandlerError> {
+            match action.action.target.as_deref() {
+                Some("nobody") => Err("unknown recipient".into()),
+                _ => Ok(Some(serde_json::json!({"delivered": true}))),
+            }
+        }
+    }
+
+    fn action_notification(id: &str, target: &str) -> JsonRpcMessage {
+        let action = Action::builder()
+            .id(id)
+            .topic(Topic::new_unchecked("action.chat.send"))
+            .action(ActionBody::with_target(
+                ActionType::Send,
+                target,
+                serde_json::json!({"text": "hi"}),
+            ))
+            .context(
+                ActionContext::new()
+                    .with_thread_id("thread-1")
+                    .with_correlation_id(format!("corr-{}", id)),
+            )
+            .build()
+            .unwrap();
+        let delivery =
+            ActionDelivery::new("action.chat.send", action).with_subscription_id("sub_actions");
+        JsonRpcMessage::Notification(JsonRpcNotification::new(
+            METHOD_ACTION.to_string(),
+            Some(serde_json::to_value(&delivery).unwrap()),
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_actions_are_executed_and_reported() {
+        let (client, handle, log) = connect_mock().await;
+
+        let runtime = AdapterRuntime::new(Arc::clone(&client), SendAdapter);
+        let stats = runtime
+            .run_until(async {
+                // Wait for the action subscription before delivering
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                handle
+                    .push_receive(action_notification("act_ok", "alice"))
+                    .await;
+                handle
+                    .push_receive(action_notification("act_bad", "nobody"))
+                    .await;
+                wait_for(&log, |log| log.acked.len() >= 2).await;
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(stats.actions_succeeded, 1);
+        assert_eq!(stats.actions_failed, 1);
+
+        let log = log.lock().unwrap();
+        assert_eq!(log.acked, vec!["act_ok", "act_bad"]);
+
+        let results: Vec<(ActionResult, &Signal)> = log
+            .published
+            .iter()
+            .map(|request| {
+                assert_eq!(request.topic, "signal.chat.action_result");
+                let signal = published_signal(request);
+                (signal.decode_payload().unwrap(), signal)
+            })
+            .collect();
+        assert_eq!(results.len(), 2);
+
+        let (ok, ok_signal) = &results[0];
+        assert!(ok.success);
+        assert_eq!(ok.output, Some(serde_json::json!({"delivered": true})));
+        let metadata = ok_signal.metadata.as_ref().unwrap();
+        assert_eq!(metadata.in_reply_to.as_deref(), Some("act_ok"));
+        assert_eq!(metadata.thread_id.as_deref(), Some("thread-1"));
+        assert_eq!(metadata.correlation_id.as_deref(), Some("corr-act_ok"));
+
+        let (bad, _) = &results[1];
+        assert!(!bad.success);
+        assert_eq!(bad.error.as_deref(), Some("unknown recipient"));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_adapter_type_rejected() {
+        struct BadAdapter;
+
+        #[async_trait]
+        impl Adapter for BadAdapter {
+            fn adapter_type(&self) -> &str {
+                "not valid"
+            }
+        }
+
+        let (client, _handle, _log) = connect_mock().await;
+        let result = AdapterRuntime::new(client, BadAdapter)
+            .run_until(async {})
+            .await;
+        assert!(matches!(result, Err(ClientError::ConfigError { .. })));
+    }
+}
@@ -0,0 +1,371 @@
+//! Conversation grouping for agents.
+//!
+//! Signals are grouped into conversations using their threading metadata.
+//! A signal joins an existing conversation when:
+//!
+//! 1. Its `thread_id` names a known conversation, or
+//! 2. Its `in_reply_to` names a signal or reply already in a conversation, or
+//! 3. One of its `references` does.
+//!
+//! Otherwise it starts a new conversation keyed by its `thread_id`, or by
+//! its own ID if it has none. Both the number of conversations and the
+//! history kept per conversation are bounded.
+
+use std::collections::{HashMap, VecDeque};
+
+use cauce_core::Signal;
+use chrono::{DateTime, Utc};
+
+/// A group of related signals and the agent's state for them.
+///
+/// `S` is the agent's per-conversation state, created with
+/// [`Default`] when the conversation starts.
+#[derive(Debug)]
+pub struct Conversation<S> {
+    /// Key identifying the conversation.
+    thread_id: String,
+
+    /// Most recent signals, oldest first.
+    history: VecDeque<Signal>,
+
+    /// IDs linked to this conversation, oldest first.
+    linked_ids: VecDeque<String>,
+
+    /// Total number of signals received, including evicted ones.
+    signal_count: u64,
+
+    /// When the first signal arrived.
+    started_at: DateTime<Utc>,
+
+    /// When the last signal arrived.
+    last_activity: DateTime<Utc>,
+
+    /// Agent-defined state.
+    state: S,
+}
+
+impl<S: Default> Conversation<S> {
+    fn new(thread_id: String) -> Self {
+        let now = Utc::now();
+        Self {
+            thread_id,
+            history: VecDeque::new(),
+            linked_ids: VecDeque::new(),
+            signal_count: 0,
+            started_at: now,
+            last_activity: now,
+            state: S::default(),
+        }
+    }
+}
+
+impl<S> Conversation<S> {
+    /// Returns the conversation's thread ID.
+    ///
+    /// This is the `thread_id` of the signal that started the conversation,
+    /// or that signal's ID if it had none.
+    pub fn thread_id(&self) -> &str {
+        &self.thread_id
+    }
+
+    /// Returns the most recent signals, oldest first.
+    pub fn history(&self) -> impl Iterator<Item = &Signal> {
+        self.history.iter()
+    }
+
+    /// Returns the most recent signal.
+    pub fn last_signal(&self) -> Option<&Signal> {
+        self.history.back()
+    }
+
+    /// Returns the total number of signals received in this conversation.
+    pub fn signal_count(&self) -> u64 {
+        self.signal_count
+    }
+
+    /// Returns when the conversation started.
+    pub fn started_at(&self) -> DateTime<Utc> {
+        self.started_at
+    }
+
+    /// Returns when the last signal arrived.
+    pub fn last_activity(&self) -> DateTime<Utc> {
+        self.last_activity
+    }
+
+    /// Returns the agent's state for this conversation.
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    /// Returns the agent's state for this conversation mutably.
+    pub fn state_mut(&mut self) -> &mut S {
+        &mut self.state
+    }
+}
+
+/// Bounded set of conversations with an index from message ID to thread.
+#[derive(Debug)]
+pub(crate) struct ConversationTracker<S> {
+    /// Conversations by thread ID.
+    conversations: HashMap<String, Conversation<S>>,
+
+    /// Signal, reply and alias IDs to the thread they belong to.
+    index: HashMap<String, String>,
+
+    /// Maximum number of conversations kept.
+    max_conversations: usize,
+
+    /// Maximum number of signals kept per conversation.
+    max_history: usize,
+}
+
+impl<S: Default> ConversationTracker<S> {
+    /// Create a tracker with the given bounds (each at least 1).
+    pub fn new(max_conversations: usize, max_history: usize) -> Self {
+        Self {
+            conversations: HashMap::new(),
+            index: HashMap::new(),
+            max_conversations: max_conversations.max(1),
+            max_history: max_history.max(1),
+        }
+    }
+
+    /// Returns the number of tracked conversations.
+    #[cfg(test)]
+    pub fn len(&self) -> usize {
+        self.conversations.len()
+    }
+
+    /// Find the thread an incoming signal belongs to, if any.
+    pub fn resolve(&self, signal: &Signal) -> Option<String> {
+        let metadata = signal.metadata.as_ref()?;
+        let lookup = |id: &String| {
+            if self.conversations.contains_key(id) {
+                Some(id.clone())
+            } else {
+                self.index.get(id).cloned()
+            }
+        };
+
+        metadata
+            .thread_id
+            .as_ref()
+            .and_then(lookup)
+            .or_else(|| metadata.in_reply_to.as_ref().and_then(lookup))
+            .or_else(|| metadata.references.iter().flatten().find_map(lookup))
+    }
+
+    /// Add a signal to its conversation, starting one if needed.
+    ///
+    /// Returns the conversation and whether it was newly started. May evict
+    /// the least recently active conversation to stay within bounds.
+    pub fn record(&mut self, signal: &Signal) -> (&mut Conversation<S>, bool) {
+        let existing = self.resolve(signal);
+        let is_new = existing.is_none();
+        let thread_id = existing.unwrap_or_else(|| {
+            signal
+                .metadata
+                .as_ref()
+                .and_then(|m| m.thread_id.clone())
+                .unwrap_or_else(|| signal.id.clone())
+        });
+
+        if is_new {
+            self.evict_if_full();
+            self.conversations
+                .insert(thread_id.clone(), Conversation::new(thread_id.clone()));
+        }
+
+        self.link(&thread_id, &signal.id);
+        // A thread ID the conversation was found by some other way becomes an alias
+        if let Some(alias) = signal.metadata.as_ref().and_then(|m| m.thread_id.as_ref()) {
+            if *alias != thread_id {
+                self.link(&thread_id, alias);
+            }
+        }
+
+        let max_history = self.max_history;
+        let conversation = self
+            .conversations
+            .get_mut(&thread_id)
+            .expect("conversation was just inserted or resolved");
+        conversation.history.push_back(signal.clone());
+        if conversation.history.len() > max_history {
+            conversation.history.pop_front();
+        }
+        conversation.signal_count += 1;
+        conversation.last_activity = Utc::now();
+        (conversation, is_new)
+    }
+
+    /// Link an ID (such as an outgoing action) to a conversation so that
+    /// signals referencing it join the conversation.
+    pub fn link(&mut self, thread_id: &str, id: &str) {
+        // Linked IDs are bounded with some headroom for replies and aliases
+        let max_linked = self.max_history * 2;
+        let Some(conversation) = self.conversations.get_mut(thread_id) else {
+            return;
+        };
+        if self.index.contains_key(id) {
+            return;
+        }
+
+        conversation.linked_ids.push_back(id.to_string());
+        self.index.insert(id.to_string(), thread_id.to_string());
+        if conversation.linked_ids.len() > max_linked {
+            if let Some(oldest) = conversation.linked_ids.pop_front() {
+                self.index.remove(&oldest);
+            }
+        }
+    }
+
+    /// Returns a conversation by thread ID.
+    #[cfg(test)]
+    pub fn get(&self, thread_id: &str) -> Option<&Conversation<S>> {
+        self.conversations.get(thread_id)
+    }
+
+    /// Drop the least recently active conversation if at capacity.
+    fn evict_if_full(&mut self) {
+        if self.conversations.len() < self.max_conversations {
+            return;
+        }
+
+        let Some(oldest) = self
+            .conversations
+            .values()
+            .min_by_key(|c| c.last_activity)
+            .map(|c| c.thread_id.clone())
+        else {
+            return;
+        };
+
+        if let Some(conversation) = self.conversations.remove(&oldest) {
+            for id in &conversation.linked_ids {
+                self.index.remove(id);
+            }
+            tracing::debug!(thread_id = %oldest, "Evicted conversation");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cauce_core::{Metadata, Payload, Source, Topic};
+
+    fn make_signal(id: &str, metadata: Option<Metadata>) -> Signal {
+        Signal {
+            id: id.to_string(),
+            version: "1.0".to_string(),
+            timestamp: Utc::now(),
+            source: Source::new("email", "adapter-1", id),
+            topic: Topic::new_unchecked("signal.email.received"),
+            payload: Payload::new(serde_json::json!({}), "application/json"),
+            metadata,
+            encrypted: None,
+            signature: None,
+        }
+    }
+
+    #[test]
+    fn test_groups_by_thread_id() {
+        let mut tracker: ConversationTracker<u32> = ConversationTracker::new(10, 10);
+
+        let (conversation, is_new) =
+            tracker.record(&make_signal("sig_1", Some(Metadata::with_thread("t-1"))));
+        assert!(is_new);
+        assert_eq!(conversation.thread_id(), "t-1");
+        *conversation.state_mut() += 1;
+
+        let (conversation, is_new) =
+            tracker.record(&make_signal("sig_2", Some(Metadata::with_thread("t-1"))));
+        assert!(!is_new);
+        assert_eq!(*conversation.state(), 1);
+        assert_eq!(conversation.signal_count(), 2);
+        assert_eq!(conversation.last_signal().unwrap().id, "sig_2");
+    }
+
+    #[test]
+    fn test_groups_by_reply_and_references() {
+        let mut tracker: ConversationTracker<()> = ConversationTracker::new(10, 10);
+        tracker.record(&make_signal("sig_1", None));
+
+        let (conversation, _) =
+            tracker.record(&make_signal("sig_2", Some(Metadata::reply_to("sig_1"))));
+        assert_eq!(conversation.thread_id(), "sig_1");
+
+        let references = Metadata {
+            references: Some(vec!["unknown".to_string(), "sig_2".to_string()]),
+            ..Default::default()
+        };
+        let (conversation, is_new) = tracker.record(&make_signal("sig_3", Some(references)));
+        assert!(!is_new);
+        assert_eq!(conversation.thread_id(), "sig_1");
+        assert_eq!(conversation.history().count(), 3);
+        assert_eq!(tracker.len(), 1);
+    }
+
+    #[test]
+    fn test_linked_reply_joins_conversation() {
+        let mut tracker: ConversationTracker<()> = ConversationTracker::new(10, 10);
+        tracker.record(&make_signal("sig_1", None));
+        tracker.link("sig_1", "act_1");
+
+        let (conversation, is_new) =
+            tracker.record(&make_signal("sig_2", Some(Metadata::reply_to("act_1"))));
+        assert!(!is_new);
+        assert_eq!(conversation.thread_id(), "sig_1");
+    }
+
+    #[test]
+    fn test_thread_id_becomes_alias() {
+        let mut tracker: ConversationTracker<()> = ConversationTracker::new(10, 10);
+        tracker.record(&make_signal("sig_1", None));
+
+        // The platform assigned its own thread ID to the reply
+        let mut metadata = Metadata::reply_to("sig_1");
+        metadata.thread_id = Some("native-thread".to_string());
+        tracker.record(&make_signal("sig_2", Some(metadata)));
+
+        let (conversation, _) = tracker.record(&make_signal(
+            "sig_3",
+            Some(Metadata::with_thread("native-thread")),
+        ));
+        assert_eq!(conversation.thread_id(), "sig_1");
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let mut tracker: ConversationTracker<()> = ConversationTracker::new(10, 2);
+        for i in 0..5 {
+            tracker.record(&make_signal(
+                &format!("sig_{}", i),
+                Some(Metadata::with_thread("t-1")),
+            ));
+        }
+
+        let conversation = tracker.get("t-1").unwrap();
+        let ids: Vec<_> = conversation.history().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["sig_3", "sig_4"]);
+        assert_eq!(conversation.signal_count(), 5);
+    }
+
+    #[test]
+    fn test_least_recent_conversation_evicted() {
+        let mut tracker: ConversationTracker<()> = ConversationTracker::new(2, 10);
+        tracker.record(&make_signal("sig_1", Some(Metadata::with_thread("t-1"))));
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        tracker.record(&make_signal("sig_2", Some(Metadata::with_thread("t-2"))));
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        tracker.record(&make_signal("sig_3", Some(Metadata::with_thread("t-3"))));
+
+        assert_eq!(tracker.len(), 2);
+        assert!(tracker.get("t-1").is_none());
+
+        // Replies to the evicted conversation start a new one
+        let (_, is_new) = tracker.record(&make_signal("sig_4", Some(Metadata::reply_to("sig_1"))));
+        assert!(is_new);
+    }
+}
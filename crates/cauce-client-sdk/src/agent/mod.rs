@@ -0,0 +1,574 @@
+//! Agent framework with conversation state and action replies.
+//!
+//! An agent consumes signals and answers them with actions. The
+//! [`AgentRuntime`] groups incoming signals into [`Conversation`]s using
+//! their threading metadata (`thread_id`, `in_reply_to`, `references`),
+//! keeps bounded per-conversation state, and gives each handler call an
+//! [`AgentContext`] whose [`reply`](AgentContext::reply) builds a correctly
+//! linked [`Action`] and publishes it.
+//!
+//! Replies are published to `action.<platform>.<action_type>`, where the
+//! platform is the replied-to signal's [`Source::type_`](cauce_core::Source),
+//! e.g. a [`ActionType::Reply`] to an email signal goes to
+//! `action.email.reply`. The action's context links back to the signal and
+//! carries the conversation's thread ID, so replies to the action join the
+//! same conversation.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use std::sync::Arc;
+//! use cauce_client_sdk::agent::{Agent, AgentContext, AgentRuntime, Conversation};
+//!
+//! struct EchoAgent;
+//!
+//! #[async_trait]
+//! impl Agent for EchoAgent {
+//!     type State = u32;
+//!
+//!     async fn on_signal(
+//!         &self,
+//!         ctx: &AgentContext,
+//!         signal: &Signal,
+//!         conversation: &mut Conversation<u32>,
+//!     ) -> Result<(), HandlerError> {
+//!         *conversation.state_mut() += 1;
+//!         let body = ActionBody::new(
+//!             ActionType::Reply,
+//!             json!({ "text": format!("message #{}", conversation.state()) }),
+//!         );
+//!         ctx.reply(signal, body).await?;
+//!         Ok(())
+//!     }
+//! }
+//!
+//! let client = Arc::new(CauceClient::connect(config).await?);
+//! let subscription = client.subscribe(&["signal.email.*"]).await?;
+//! let stats = AgentRuntime::new(client, EchoAgent).run(subscription).await?;
+//! ```
+
+mod conversation;
+
+pub use conversation::Conversation;
+
+use std::panic::AssertUnwindSafe;
+use std::sync::{Arc, Mutex as StdMutex};
+
+use async_trait::async_trait;
+use futures::FutureExt;
+
+use cauce_core::{Action, ActionBody, ActionContext, ActionType, Signal, Topic};
+
+use crate::client::{CauceClient, Subscription};
+use crate::dispatch::{ErrorPolicy, HandlerError};
+use crate::error::ClientError;
+use crate::ClientResult;
+
+use conversation::ConversationTracker;
+
+/// Trait implemented by agents run by an [`AgentRuntime`].
+///
+/// Signals are handled one at a time, in delivery order, so a handler
+/// always sees up-to-date conversation state.
+#[async_trait]
+pub trait Agent: Send + Sync + 'static {
+    /// Per-conversation state, created with [`Default`] when a
+    /// conversation starts.
+    type State: Default + Send + 'static;
+
+    /// Handle a signal within its conversation.
+    ///
+    /// The signal has already been added to the conversation's history.
+    /// Returning `Ok(())` acknowledges the signal; an error or panic applies
+    /// the runtime's [`ErrorPolicy`].
+    async fn on_signal(
+        &self,
+        ctx: &AgentContext,
+        signal: &Signal,
+        conversation: &mut Conversation<Self::State>,
+    ) -> Result<(), HandlerError>;
+}
+
+/// Configuration for an [`AgentRuntime`].
+#[derive(Debug, Clone)]
+pub struct AgentConfig {
+    /// The agent ID set in [`ActionContext::agent_id`]. Defaults to the
+    /// client ID.
+    pub agent_id: Option<String>,
+
+    /// Maximum number of conversations kept; the least recently active
+    /// one is dropped first.
+    pub max_conversations: usize,
+
+    /// Maximum number of signals kept in each conversation's history.
+    pub max_history: usize,
+
+    /// Policy applied when a handler returns an error or panics.
+    pub on_error: ErrorPolicy,
+}
+
+impl Default for AgentConfig {
+    fn default() -> Self {
+        Self {
+            agent_id: None,
+            max_conversations: 1_000,
+            max_history: 50,
+            on_error: ErrorPolicy::LeaveUnacked,
+        }
+    }
+}
+
+impl AgentConfig {
+    /// Set the agent ID used in action contexts.
+    pub fn with_agent_id(mut self, agent_id: impl Into<String>) -> Self {
+        self.agent_id = Some(agent_id.into());
+        self
+    }
+
+    /// Set the maximum number of conversations kept.
+    pub fn with_max_conversations(mut self, max: usize) -> Self {
+        self.max_conversations = max;
+        self
+    }
+
+    /// Set the maximum number of signals kept per conversation.
+    pub fn with_max_history(mut self, max: usize) -> Self {
+        self.max_history = max;
+        self
+    }
+
+    /// Set the policy applied to failed or panicked handlers.
+    pub fn with_error_policy(mut self, policy: ErrorPolicy) -> Self {
+        self.on_error = policy;
+        self
+    }
+}
+
+/// Counters describing a completed agent run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AgentStats {
+    /// Signals whose handler returned `Ok(())`.
+    pub handled: u64,
+    /// Signals whose handler returned an error.
+    pub failed: u64,
+    /// Signals whose handler panicked.
+    pub panicked: u64,
+    /// Conversations started.
+    pub conversations: u64,
+    /// Actions published through [`AgentContext::reply`].
+    pub replies: u64,
+}
+
+/// Handle passed to [`Agent::on_signal`] for replying within a conversation.
+pub struct AgentContext {
+    client: Arc<CauceClient>,
+    agent_id: String,
+    thread_id: String,
+
+    /// IDs of actions sent during this handler call.
+    sent: StdMutex<Vec<String>>,
+}
+
+impl AgentContext {
+    /// The client the agent publishes through.
+    pub fn client(&self) -> &Arc<CauceClient> {
+        &self.client
+    }
+
+    /// The agent ID set on outgoing actions.
+    pub fn agent_id(&self) -> &str {
+        &self.agent_id
+    }
+
+    /// The thread ID set on outgoing actions.
+    pub fn thread_id(&self) -> &str {
+        &self.thread_id
+    }
+
+    /// Reply to a signal with an action.
+    ///
+    /// Builds the action through [`ActionBuilder`](cauce_core::ActionBuilder)
+    /// with a context linking it to `signal` (`in_reply_to`, `thread_id`,
+    /// `agent_id`) and publishes it to
+    /// `action.<signal source type>.<action type>`.
+    ///
+    /// # Returns
+    ///
+    /// The published action.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError::InvalidMessage`] - The signal's source type is not a
+    ///   valid topic segment
+    /// - Any error from [`CauceClient::publish`]
+    pub async fn reply(&self, signal: &Signal, body: ActionBody) -> ClientResult<Action> {
+        let topic = reply_topic(signal, body.type_);
+        let action_topic = Topic::new(&topic).map_err(|e| ClientError::InvalidMessage {
+            message: format!("Invalid reply topic: {}", e),
+        })?;
+
+        let context = ActionContext {
+            agent_id: Some(self.agent_id.clone()),
+            ..ActionContext::reply_to(&signal.id)
+        }
+        .with_thread_id(&self.thread_id);
+
+        let action = Action::builder()
+            .topic(action_topic)
+            .action(body)
+            .context(context)
+            .build()
+            .map_err(|e| ClientError::InvalidMessage {
+                message: format!("Failed to build action: {}", e),
+            })?;
+
+        self.client.publish(&topic, action.clone().into()).await?;
+        self.sent.lock().unwrap().push(action.id.clone());
+        Ok(action)
+    }
+
+    fn take_sent(&self) -> Vec<String> {
+        std::mem::take(&mut *self.sent.lock().unwrap())
+    }
+}
+
+/// Runs an [`Agent`] over a subscription.
+pub struct AgentRuntime<A: Agent> {
+    client: Arc<CauceClient>,
+    agent: A,
+    config: AgentConfig,
+}
+
+impl<A: Agent> AgentRuntime<A> {
+    /// Create a runtime for `agent` that replies and acknowledges through
+    /// `client`.
+    pub fn new(client: Arc<CauceClient>, agent: A) -> Self {
+        Self {
+            client,
+            agent,
+            config: AgentConfig::default(),
+        }
+    }
+
+    /// Set the runtime configuration.
+    pub fn with_config(mut self, config: AgentConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Handle signals from `subscription` until it closes.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError::ConfigError`] - A conversation bound is zero
+    pub async fn run(self, mut subscription: Subscription) -> ClientResult<AgentStats> {
+        if self.config.max_conversations == 0 || self.config.max_history == 0 {
+            return Err(ClientError::config_error(
+                "Agent conversation limits must be greater than 0",
+            ));
+        }
+
+        let agent_id = self
+            .config
+            .agent_id
+            .clone()
+            .unwrap_or_else(|| self.client.config().client_id.clone());
+        let mut tracker: ConversationTracker<A::State> =
+            ConversationTracker::new(self.config.max_conversations, self.config.max_history);
+        let mut stats = AgentStats::default();
+
+        while let Some(result) = subscription.next().await {
+            let signal = match result {
+                Ok(signal) => signal,
+                Err(e) => {
+                    tracing::warn!(error = %e, "Agent subscription error");
+                    continue;
+                }
+            };
+
+            let (conversation, is_new) = tracker.record(&signal);
+            if is_new {
+                stats.conversations += 1;
+            }
+            let thread_id = conversation.thread_id().to_string();
+
+            let ctx = AgentContext {
+                client: Arc::clone(&self.client),
+                agent_id: agent_id.clone(),
+                thread_id: signal
+                    .metadata
+                    .as_ref()
+                    .and_then(|m| m.thread_id.clone())
+                    .unwrap_or_else(|| thread_id.clone()),
+                sent: StdMutex::new(Vec::new()),
+            };
+
+            let outcome = AssertUnwindSafe(self.agent.on_signal(&ctx, &signal, conversation))
+                .catch_unwind()
+                .await;
+
+            // Link replies even if the handler failed afterwards
+            for action_id in ctx.take_sent() {
+                stats.replies += 1;
+                tracker.link(&thread_id, &action_id);
+            }
+
+            let ack = match outcome {
+                Ok(Ok(())) => {
+                    stats.handled += 1;
+                    true
+                }
+                Ok(Err(e)) => {
+                    stats.failed += 1;
+                    tracing::warn!(signal_id = %signal.id, error = %e, "Agent handler failed");
+                    self.config.on_error == ErrorPolicy::Ack
+                }
+                Err(_) => {
+                    stats.panicked += 1;
+                    tracing::error!(signal_id = %signal.id, "Agent handler panicked");
+                    self.config.on_error == ErrorPolicy::Ack
+                }
+            };
+
+            if ack {
+                if let Err(e) = self
+                    .client
+                    .ack(subscription.subscription_id(), &[&signal.id])
+                    .await
+                {
+                    tracing::warn!(signal_id = %signal.id, error = %e, "Failed to ack signal");
+                }
+            }
+        }
+
+        tracing::info!(?stats, "Agent stopped");
+        Ok(stats)
+    }
+}
+
+/// The topic a reply of the given type to `signal` is published to.
+fn reply_topic(signal: &Signal, action_type: ActionType) -> String {
+    let verb = match action_type {
+        ActionType::Send => "send",
+        ActionType::Reply => "reply",
+        ActionType::Forward => "forward",
+        ActionType::React => "react",
+        ActionType::Update => "update",
+        ActionType::Delete => "delete",
+    };
+    format!("action.{}.{}", signal.source.type_, verb)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ClientConfig;
+    use crate::transport::mock::{MockTransport, MockTransportHandle};
+    use crate::transport::{JsonRpcMessage, Transport};
+    use cauce_core::{
+        JsonRpcNotification, JsonRpcResponse, Metadata, Payload, PublishMessage, PublishRequest,
+        Source, METHOD_ACK, METHOD_PUBLISH, METHOD_SIGNAL, METHOD_SUBSCRIBE,
+    };
+    use std::time::Duration;
+
+    /// Requests the fake hub has seen.
+    #[derive(Default)]
+    struct HubLog {
+        published: Vec<PublishRequest>,
+        acked: Vec<String>,
+    }
+
+    /// Minimal hub stand-in: answers subscribe, publish and ack requests.
+    fn spawn_fake_hub(handle: MockTransportHandle, log: Arc<StdMutex<HubLog>>) {
+        tokio::spawn(async move {
+            loop {
+                let Some(message) = handle.pop_sent().await else {
+                    tokio::time::sleep(Duration::from_millis(2)).await;
+                    continue;
+                };
+                let JsonRpcMessage::Request(request) = message else {
+                    continue;
+                };
+                let params = request.params().cloned().unwrap_or_default();
+                let result = match request.method() {
+                    METHOD_SUBSCRIBE => serde_json::json!({
+                        "subscription_id": "sub_1",
+                        "status": "active",
+                        "topics": ["signal.**"],
+                        "created_at": "2024-01-01T00:00:00Z"
+                    }),
+                    METHOD_PUBLISH => {
+                        log.lock()
+                            .unwrap()
+                            .published
+                            .push(serde_json::from_value(params).unwrap());
+                        serde_json::json!({"message_id": "msg_1", "delivered_to": 1, "queued_for": 0})
+                    }
+                    METHOD_ACK => {
+                        let ids: Vec<String> =
+                            serde_json::from_value(params["signal_ids"].clone()).unwrap();
+                        log.lock().unwrap().acked.extend(ids.clone());
+                        serde_json::json!({ "acknowledged": ids })
+                    }
+                    _ => serde_json::json!({"success": true}),
+                };
+                let response = JsonRpcResponse::success(request.id().clone(), result);
+                handle.push_receive(response.into()).await;
+            }
+        });
+    }
+
+    async fn connect_mock() -> (Arc<CauceClient>, MockTransportHandle, Arc<StdMutex<HubLog>>) {
+        let mut transport = MockTransport::new().wait_when_empty();
+        transport.connect().await.unwrap();
+        let handle = transport.handle();
+
+        let hello = JsonRpcResponse::success(
+            cauce_core::RequestId::Number(1),
+            serde_json::json!({
+                "session_id": "sess_1",
+                "server_version": "1.0",
+                "capabilities": []
+            }),
+        );
+        handle.push_receive(hello.into()).await;
+
+        let config = ClientConfig::builder("ws://localhost:8080", "test-agent")
+            .build()
+            .unwrap();
+        let client = CauceClient::connect_with_transport(config, Box::new(transport))
+            .await
+            .unwrap();
+        handle.pop_sent().await;
+
+        let log = Arc::new(StdMutex::new(HubLog::default()));
+        spawn_fake_hub(handle.clone(), Arc::clone(&log));
+        (Arc::new(client), handle, log)
+    }
+
+    fn make_signal(id: &str, metadata: Option<Metadata>) -> Signal {
+        Signal {
+            id: id.to_string(),
+            version: "1.0".to_string(),
+            timestamp: chrono::Utc::now(),
+            source: Source::new("email", "adapter-1", id),
+            topic: Topic::new_unchecked("signal.email.received"),
+            payload: Payload::new(serde_json::json!({}), "application/json"),
+            metadata,
+            encrypted: None,
+            signature: None,
+        }
+    }
+
+    fn signal_notification(signal: &Signal) -> JsonRpcMessage {
+        JsonRpcMessage::Notification(JsonRpcNotification::new(
+            METHOD_SIGNAL.to_string(),
+            Some(serde_json::json!({ "topic": signal.topic, "signal": signal })),
+        ))
+    }
+
+    /// Replies to every signal and counts messages per conversation.
+    struct CountingAgent;
+
+    #[async_trait]
+    impl Agent for CountingAgent {
+        type State = u32;
+
+        async fn on_signal(
+            &self,
+            ctx: &AgentContext,
+            signal: &Signal,
+            conversation: &mut Conversation<u32>,
+        ) -> Result<(), HandlerError> {
+            *conversation.state_mut() += 1;
+            let body = ActionBody::new(
+                ActionType::Reply,
+                serde_json::json!({ "count": conversation.state() }),
+            );
+            ctx.reply(signal, body).await?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_reply_topic() {
+        let signal = make_signal("sig_1", None);
+        assert_eq!(
+            reply_topic(&signal, ActionType::Reply),
+            "action.email.reply"
+        );
+        assert_eq!(
+            reply_topic(&signal, ActionType::React),
+            "action.email.react"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_replies_are_linked_to_conversation() {
+        let (client, handle, log) = connect_mock().await;
+        let subscription = client.subscribe(&["signal.**"]).await.unwrap();
+
+        let runtime = AgentRuntime::new(Arc::clone(&client), CountingAgent)
+            .with_config(AgentConfig::default().with_agent_id("agent-1"));
+        let run = tokio::spawn(runtime.run(subscription));
+
+        handle
+            .push_receive(signal_notification(&make_signal("sig_1", None)))
+            .await;
+
+        // Wait for the first reply, then answer it
+        let mut first_reply = None;
+        for _ in 0..200 {
+            if let Some(request) = log.lock().unwrap().published.first() {
+                first_reply = Some(request.clone());
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let first_reply = first_reply.expect("first reply published");
+        let PublishMessage::Action(action) = &first_reply.message else {
+            panic!("expected an action");
+        };
+        assert_eq!(first_reply.topic, "action.email.reply");
+        let context = action.context.as_ref().unwrap();
+        assert_eq!(context.in_reply_to.as_deref(), Some("sig_1"));
+        assert_eq!(context.thread_id.as_deref(), Some("sig_1"));
+        assert_eq!(context.agent_id.as_deref(), Some("agent-1"));
+
+        let answer = make_signal("sig_2", Some(Metadata::reply_to(&action.id)));
+        handle.push_receive(signal_notification(&answer)).await;
+
+        for _ in 0..200 {
+            if log.lock().unwrap().acked.len() >= 2 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        {
+            let log = log.lock().unwrap();
+            assert_eq!(log.acked, vec!["sig_1", "sig_2"]);
+            let PublishMessage::Action(second) = &log.published[1].message else {
+                panic!("expected an action");
+            };
+            // Same conversation: the count carried over and the thread matches
+            assert_eq!(second.action.payload, serde_json::json!({ "count": 2 }));
+            let context = second.context.as_ref().unwrap();
+            assert_eq!(context.thread_id.as_deref(), Some("sig_1"));
+            assert_eq!(context.in_reply_to.as_deref(), Some("sig_2"));
+        }
+
+        run.abort();
+    }
+
+    #[tokio::test]
+    async fn test_zero_limits_rejected() {
+        let (client, _handle, _log) = connect_mock().await;
+        let subscription = client.subscribe(&["signal.**"]).await.unwrap();
+        let result = AgentRuntime::new(Arc::clone(&client), CountingAgent)
+            .with_config(AgentConfig::default().with_max_history(0))
+            .run(subscription)
+            .await;
+        assert!(matches!(result, Err(ClientError::ConfigError { .. })));
+    }
+}
@@ -0,0 +1,470 @@
+//! Blocking Cauce client API.
+//!
+//! This module mirrors [`CauceClient`](crate::CauceClient) and
+//! [`Subscription`](crate::Subscription) with synchronous methods, for
+//! scripts and adapters that do not run an async runtime. Each client owns
+//! a small internal Tokio runtime that drives the connection in the
+//! background; calls block the current thread until they complete.
+//!
+//! Requires the `blocking` feature.
+//!
+//! The blocking API must not be used from within an async runtime, as
+//! blocking there would stall the runtime's own threads. Calling it from
+//! async code panics.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use cauce_client_sdk::blocking::CauceClient;
+//! use cauce_client_sdk::{AuthConfig, ClientConfig};
+//!
+//! let config = ClientConfig::builder("wss://hub.example.com", "my-script")
+//!     .auth(AuthConfig::api_key("secret"))
+//!     .build()?;
+//! let client = CauceClient::connect(config)?;
+//!
+//! let subscription = client.subscribe(&["signal.email.*"])?;
+//! let id = subscription.subscription_id().to_string();
+//! for result in subscription {
+//!     let signal = result?;
+//!     println!("Received: {}", signal.id);
+//!     client.ack(&id, &[&signal.id])?;
+//! }
+//! ```
+
+mod subscription;
+
+pub use subscription::Subscription;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use cauce_core::{AckResponse, Action, PublishMessage, PublishResponse, Signal, Source};
+use serde::Serialize;
+use tokio::runtime::Runtime;
+
+use crate::client;
+use crate::config::ClientConfig;
+use crate::error::ClientError;
+use crate::router::{RequestHandler, RttStats};
+use crate::transport::Transport;
+use crate::ClientResult;
+
+/// Blocking client for connecting to and interacting with a Cauce Hub.
+///
+/// The blocking counterpart of [`client::CauceClient`]; see there for the
+/// behavior of each method.
+pub struct CauceClient {
+    /// The async client being wrapped. Dropped before the runtime.
+    inner: client::CauceClient,
+
+    /// Runtime driving the connection, shared with subscriptions.
+    runtime: Arc<Runtime>,
+}
+
+impl CauceClient {
+    /// Connect to a Cauce Hub and perform the hello handshake.
+    ///
+    /// See [`client::CauceClient::connect`].
+    pub fn connect(config: ClientConfig) -> ClientResult<Self> {
+        let runtime = Self::runtime()?;
+        let inner = runtime.block_on(client::CauceClient::connect(config))?;
+        Ok(Self { inner, runtime })
+    }
+
+    /// Perform the hello handshake over an already connected transport.
+    ///
+    /// See [`client::CauceClient::connect_with_transport`].
+    pub fn connect_with_transport(
+        config: ClientConfig,
+        transport: Box<dyn Transport>,
+    ) -> ClientResult<Self> {
+        let runtime = Self::runtime()?;
+        let inner = runtime.block_on(client::CauceClient::connect_with_transport(
+            config, transport,
+        ))?;
+        Ok(Self { inner, runtime })
+    }
+
+    /// Disconnect gracefully from the Hub.
+    pub fn disconnect(&mut self) -> ClientResult<()> {
+        self.runtime.block_on(self.inner.disconnect())
+    }
+
+    /// Subscribe to one or more topic patterns.
+    ///
+    /// Iterate the returned [`Subscription`] to receive signals.
+    pub fn subscribe(&self, topics: &[&str]) -> ClientResult<Subscription> {
+        let subscription = self.runtime.block_on(self.inner.subscribe(topics))?;
+        Ok(Subscription::new(subscription, Arc::clone(&self.runtime)))
+    }
+
+    /// Unsubscribe from a subscription.
+    pub fn unsubscribe(&self, subscription_id: &str) -> ClientResult<()> {
+        self.runtime
+            .block_on(self.inner.unsubscribe(subscription_id))
+    }
+
+    /// Publish a signal or action to a topic.
+    pub fn publish(&self, topic: &str, message: PublishMessage) -> ClientResult<PublishResponse> {
+        self.runtime.block_on(self.inner.publish(topic, message))
+    }
+
+    /// Publish a signal whose payload is serialized from `payload`.
+    pub fn publish_typed<T: Serialize + ?Sized>(
+        &self,
+        topic: &str,
+        source: Source,
+        payload: &T,
+    ) -> ClientResult<PublishResponse> {
+        self.runtime
+            .block_on(self.inner.publish_typed(topic, source, payload))
+    }
+
+    /// Publish an action and block until the correlated reply signal arrives.
+    pub fn request(&self, action: Action, timeout: Duration) -> ClientResult<Signal> {
+        self.runtime.block_on(self.inner.request(action, timeout))
+    }
+
+    /// Acknowledge receipt of signals.
+    pub fn ack(&self, subscription_id: &str, signal_ids: &[&str]) -> ClientResult<AckResponse> {
+        self.runtime
+            .block_on(self.inner.ack(subscription_id, signal_ids))
+    }
+
+    /// Returns the session ID if connected.
+    pub fn session_id(&self) -> Option<String> {
+        self.runtime.block_on(self.inner.session_id())
+    }
+
+    /// Returns the server's protocol version if connected.
+    pub fn server_version(&self) -> Option<String> {
+        self.runtime.block_on(self.inner.server_version())
+    }
+
+    /// Checks if the client is currently connected.
+    pub fn is_connected(&self) -> bool {
+        self.runtime.block_on(self.inner.is_connected())
+    }
+
+    /// Returns a list of active subscription IDs.
+    pub fn active_subscriptions(&self) -> Vec<String> {
+        self.runtime.block_on(self.inner.active_subscriptions())
+    }
+
+    /// Returns the client configuration.
+    pub fn config(&self) -> &ClientConfig {
+        self.inner.config()
+    }
+
+    /// Returns the URL of the hub currently in use.
+    pub fn hub_url(&self) -> String {
+        self.inner.hub_url()
+    }
+
+    /// Register a handler for hub-initiated requests to `method`.
+    ///
+    /// Handlers are async and run on the client's internal runtime.
+    pub fn on_request(&self, method: impl Into<String>, handler: impl RequestHandler) {
+        self.inner.on_request(method, handler);
+    }
+
+    /// Returns a snapshot of keepalive round-trip time statistics.
+    pub fn rtt_stats(&self) -> RttStats {
+        self.inner.rtt_stats()
+    }
+
+    /// Send a `cauce.ping` and return the measured round-trip time.
+    pub fn ping(&self) -> ClientResult<Duration> {
+        self.runtime.block_on(self.inner.ping())
+    }
+
+    /// Build the runtime backing a client.
+    fn runtime() -> ClientResult<Arc<Runtime>> {
+        tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("cauce-blocking")
+            .enable_all()
+            .build()
+            .map(Arc::new)
+            .map_err(|e| ClientError::TransportError {
+                message: format!("Failed to start runtime: {}", e),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::mock::{MockTransport, MockTransportHandle};
+    use crate::transport::JsonRpcMessage;
+    use cauce_core::{
+        ActionBody, ActionType, JsonRpcError, JsonRpcNotification, JsonRpcRequest,
+        JsonRpcResponse, Metadata, RequestId, SignalDelivery, METHOD_ACK, METHOD_PUBLISH,
+        METHOD_SIGNAL, METHOD_SUBSCRIBE, METHOD_UNSUBSCRIBE,
+    };
+    use futures::executor::block_on;
+
+    fn make_signal(id: &str) -> Signal {
+        Signal {
+            id: id.to_string(),
+            version: "1.0".to_string(),
+            timestamp: chrono::Utc::now(),
+            source: Source::new("email", "adapter-1", "native-1"),
+            topic: cauce_core::Topic::new_unchecked("signal.email.received"),
+            payload: cauce_core::Payload::new(serde_json::json!({}), "application/json"),
+            metadata: None,
+            encrypted: None,
+            signature: None,
+        }
+    }
+
+    /// Wait for the next request the client sends.
+    fn next_request(handle: &MockTransportHandle) -> JsonRpcRequest {
+        loop {
+            if let Some(JsonRpcMessage::Request(request)) = block_on(handle.pop_sent()) {
+                return request;
+            }
+            std::thread::sleep(Duration::from_millis(2));
+        }
+    }
+
+    /// Answer the next request the client sends with `result`.
+    fn answer_next(handle: &MockTransportHandle, result: serde_json::Value) -> String {
+        let request = next_request(handle);
+        let response = JsonRpcResponse::success(request.id().clone(), result);
+        block_on(handle.push_receive(response.into()));
+        request.method().to_string()
+    }
+
+    /// Deliver `signal` to the client as a `cauce.signal` notification.
+    fn push_signal(handle: &MockTransportHandle, delivery: &SignalDelivery) {
+        block_on(
+            handle.push_receive(JsonRpcMessage::Notification(JsonRpcNotification::new(
+                METHOD_SIGNAL.to_string(),
+                Some(serde_json::to_value(delivery).unwrap()),
+            ))),
+        );
+    }
+
+    /// Subscribe to `signal.email.*`, answered by the hub as `sub_1`.
+    fn subscribe(client: &CauceClient, handle: &MockTransportHandle) -> Subscription {
+        let hub_handle = handle.clone();
+        let hub = std::thread::spawn(move || {
+            answer_next(
+                &hub_handle,
+                serde_json::json!({
+                    "subscription_id": "sub_1",
+                    "status": "active",
+                    "topics": ["signal.email.*"],
+                    "created_at": "2024-01-01T00:00:00Z"
+                }),
+            )
+        });
+        let subscription = client.subscribe(&["signal.email.*"]).unwrap();
+        assert_eq!(hub.join().unwrap(), METHOD_SUBSCRIBE);
+        subscription
+    }
+
+    fn connect() -> (CauceClient, MockTransportHandle) {
+        let mut transport = MockTransport::new().wait_when_empty();
+        block_on(transport.connect()).unwrap();
+        let handle = transport.handle();
+        block_on(
+            handle.push_receive(
+                JsonRpcResponse::success(
+                    RequestId::Number(1),
+                    serde_json::json!({
+                        "session_id": "sess_blocking",
+                        "server_version": "1.0",
+                        "capabilities": []
+                    }),
+                )
+                .into(),
+            ),
+        );
+
+        let config = ClientConfig::builder("ws://localhost:8080", "blocking-client")
+            .build()
+            .unwrap();
+        let client = CauceClient::connect_with_transport(config, Box::new(transport)).unwrap();
+        block_on(handle.pop_sent());
+        (client, handle)
+    }
+
+    #[test]
+    fn test_connect_and_publish() {
+        let (client, handle) = connect();
+        assert_eq!(client.session_id().as_deref(), Some("sess_blocking"));
+        assert!(client.is_connected());
+
+        let hub = std::thread::spawn(move || {
+            answer_next(
+                &handle,
+                serde_json::json!({ "message_id": "msg_1", "delivered_to": 0, "queued_for": 0 }),
+            )
+        });
+        let response = client
+            .publish("signal.email.received", make_signal("sig_1").into())
+            .unwrap();
+        assert_eq!(response.message_id, "msg_1");
+        assert_eq!(hub.join().unwrap(), "cauce.publish");
+    }
+
+    #[test]
+    fn test_subscription_iterates_signals() {
+        let (client, handle) = connect();
+
+        let subscription = subscribe(&client, &handle);
+        assert_eq!(subscription.subscription_id(), "sub_1");
+
+        for id in ["sig_1", "sig_2"] {
+            let delivery = SignalDelivery::new("signal.email.received", make_signal(id))
+                .with_subscription_id("sub_1");
+            push_signal(&handle, &delivery);
+        }
+
+        let ids: Vec<String> = subscription
+            .take(2)
+            .map(|result| result.unwrap().id)
+            .collect();
+        assert_eq!(ids, ["sig_1", "sig_2"]);
+    }
+
+    #[test]
+    fn test_unsubscribe() {
+        let (client, handle) = connect();
+        let subscription = subscribe(&client, &handle);
+        assert_eq!(client.active_subscriptions(), ["sub_1"]);
+
+        let hub = std::thread::spawn(move || {
+            let request = next_request(&handle);
+            let response = JsonRpcResponse::success(
+                request.id().clone(),
+                serde_json::json!({ "success": true }),
+            );
+            block_on(handle.push_receive(response.into()));
+            request
+        });
+        client.unsubscribe(subscription.subscription_id()).unwrap();
+
+        let request = hub.join().unwrap();
+        assert_eq!(request.method(), METHOD_UNSUBSCRIBE);
+        assert_eq!(request.params().unwrap()["subscription_id"], "sub_1");
+        assert!(client.active_subscriptions().is_empty());
+    }
+
+    #[test]
+    fn test_ack() {
+        let (client, handle) = connect();
+        let subscription = subscribe(&client, &handle);
+
+        let hub = std::thread::spawn(move || {
+            let request = next_request(&handle);
+            let response = JsonRpcResponse::success(
+                request.id().clone(),
+                serde_json::json!({ "acknowledged": ["sig_1", "sig_2"], "failed": [] }),
+            );
+            block_on(handle.push_receive(response.into()));
+            request
+        });
+        let response = client
+            .ack(subscription.subscription_id(), &["sig_1", "sig_2"])
+            .unwrap();
+        assert_eq!(response.acknowledged, ["sig_1", "sig_2"]);
+        assert!(response.failed.is_empty());
+
+        let request = hub.join().unwrap();
+        assert_eq!(request.method(), METHOD_ACK);
+        assert_eq!(request.params().unwrap()["subscription_id"], "sub_1");
+    }
+
+    #[test]
+    fn test_request_returns_correlated_reply() {
+        let (client, handle) = connect();
+
+        // Answer the publish, then deliver a reply carrying its correlation ID
+        let hub = std::thread::spawn(move || {
+            let request = next_request(&handle);
+            let response = JsonRpcResponse::success(
+                request.id().clone(),
+                serde_json::json!({ "message_id": "msg_1", "delivered_to": 1, "queued_for": 0 }),
+            );
+            block_on(handle.push_receive(response.into()));
+
+            let params = request.params().unwrap();
+            let correlation_id = params["message"]["context"]["correlation_id"]
+                .as_str()
+                .unwrap();
+            let mut reply = make_signal("sig_reply");
+            reply.metadata = Some(Metadata::reply_to("act_1").correlation_id(correlation_id));
+            push_signal(
+                &handle,
+                &SignalDelivery::new("signal.email.action_result", reply),
+            );
+            request.method().to_string()
+        });
+
+        let action = Action::builder()
+            .topic(cauce_core::Topic::new_unchecked("action.email.send"))
+            .action(ActionBody::new(ActionType::Send, serde_json::json!({})))
+            .build()
+            .unwrap();
+        let reply = client.request(action, Duration::from_secs(2)).unwrap();
+        assert_eq!(reply.id, "sig_reply");
+        assert_eq!(hub.join().unwrap(), METHOD_PUBLISH);
+    }
+
+    #[test]
+    fn test_server_error_is_returned() {
+        let (client, handle) = connect();
+
+        let hub = std::thread::spawn(move || {
+            let request = next_request(&handle);
+            let response = JsonRpcResponse::error(
+                Some(request.id().clone()),
+                JsonRpcError::new(-32003, "Not authorized"),
+            );
+            block_on(handle.push_receive(response.into()));
+        });
+        let result = client.publish("signal.email.received", make_signal("sig_1").into());
+        hub.join().unwrap();
+
+        match result {
+            Err(ClientError::RpcError { code, message, .. }) => {
+                assert_eq!(code, -32003);
+                assert_eq!(message, "Not authorized");
+            }
+            other => panic!("expected RPC error, got {:?}", other),
+        }
+        assert!(client.is_connected());
+    }
+
+    #[test]
+    fn test_disconnect() {
+        let (mut client, handle) = connect();
+
+        client.disconnect().unwrap();
+        assert!(!client.is_connected());
+        assert_eq!(client.session_id(), None);
+        assert!(matches!(
+            block_on(handle.pop_sent()),
+            Some(JsonRpcMessage::Notification(n)) if n.method() == "cauce.goodbye"
+        ));
+        assert!(matches!(
+            client.publish("signal.email.received", make_signal("sig_1").into()),
+            Err(ClientError::NotConnected)
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot start a runtime from within a runtime")]
+    fn test_panics_inside_async_runtime() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            connect();
+        });
+    }
+}
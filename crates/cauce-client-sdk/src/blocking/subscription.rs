@@ -0,0 +1,108 @@
+//! Blocking subscription handle.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use cauce_core::{Signal, SignalDelivery, SubscriptionStatus};
+use tokio::runtime::Runtime;
+
+use crate::client;
+use crate::ClientResult;
+
+/// A blocking handle to an active subscription.
+///
+/// The blocking counterpart of [`client::Subscription`]. Signals are consumed
+/// by iterating: each call to [`Iterator::next`] blocks until a signal
+/// arrives, and iteration ends when the subscription is closed.
+///
+/// # Example
+///
+/// ```ignore
+/// let subscription = client.subscribe(&["signal.email.*"])?;
+/// let id = subscription.subscription_id().to_string();
+///
+/// for result in subscription {
+///     let signal = result?;
+///     println!("Received: {}", signal.id);
+///     client.ack(&id, &[&signal.id])?;
+/// }
+/// ```
+pub struct Subscription {
+    /// The async subscription being wrapped.
+    inner: client::Subscription,
+
+    /// Runtime the client's background tasks run on.
+    runtime: Arc<Runtime>,
+}
+
+impl Subscription {
+    pub(crate) fn new(inner: client::Subscription, runtime: Arc<Runtime>) -> Self {
+        Self { inner, runtime }
+    }
+
+    /// Returns the subscription ID.
+    ///
+    /// This ID is used when acknowledging signals or unsubscribing.
+    pub fn subscription_id(&self) -> &str {
+        self.inner.subscription_id()
+    }
+
+    /// Returns the topic patterns for this subscription.
+    pub fn topics(&self) -> &[String] {
+        self.inner.topics()
+    }
+
+    /// Check if a topic matches any of this subscription's patterns.
+    pub fn matches_topic(&self, topic: &str) -> bool {
+        self.inner.matches_topic(topic)
+    }
+
+    /// Returns the latest known status of the subscription.
+    pub fn status(&self) -> SubscriptionStatus {
+        self.inner.status()
+    }
+
+    /// Returns the reason given with the latest status change, if any.
+    pub fn status_reason(&self) -> Option<String> {
+        self.inner.status_reason()
+    }
+
+    /// Block until the subscription is active.
+    ///
+    /// See [`client::Subscription::wait_until_active`].
+    pub fn wait_until_active(&mut self, timeout: Duration) -> ClientResult<()> {
+        self.runtime.block_on(self.inner.wait_until_active(timeout))
+    }
+
+    /// Block until the next delivery arrives.
+    ///
+    /// See [`client::Subscription::next_delivery`].
+    pub fn next_delivery(&mut self) -> Option<ClientResult<SignalDelivery>> {
+        self.runtime.block_on(self.inner.next_delivery())
+    }
+
+    /// Receive the next signal without blocking.
+    ///
+    /// Returns `None` if no signal is available or the subscription is
+    /// closed.
+    pub fn try_next(&mut self) -> Option<ClientResult<Signal>> {
+        self.inner.try_next()
+    }
+
+    /// Returns the async subscription this handle wraps.
+    pub fn into_inner(self) -> client::Subscription {
+        self.inner
+    }
+}
+
+impl Iterator for Subscription {
+    type Item = ClientResult<Signal>;
+
+    /// Block until the next signal arrives.
+    ///
+    /// Lag, denial and revocation are reported as errors, as with
+    /// [`client::Subscription::next`].
+    fn next(&mut self) -> Option<Self::Item> {
+        self.runtime.block_on(self.inner.next())
+    }
+}
@@ -0,0 +1,246 @@
+//! Transparent end-to-end encryption for subscriptions.
+//!
+//! A `SignalDecryptor` is attached to every
+//! [`Subscription`](super::Subscription) created while [`E2eOptions`] are
+//! configured. It opens the envelope of each encrypted signal before the
+//! signal is yielded, skips copies that were encrypted for another
+//! subscriber's key, and rejects plaintext signals unless they were allowed.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use cauce_core::e2e::{self, SecretKey};
+use cauce_core::{AckRequest, Signal, METHOD_ACK};
+use tokio::sync::RwLock;
+
+use super::SubscriptionInfo;
+use crate::config::E2eOptions;
+use crate::error::ClientError;
+use crate::router::RouterHandle;
+use crate::ClientResult;
+
+/// Decrypts the signals of one subscription.
+pub(super) struct SignalDecryptor {
+    /// The secret key signals are decrypted with.
+    secret: SecretKey,
+
+    /// The matching public key, base64-encoded as it appears in envelopes.
+    public_key: String,
+
+    /// Whether plaintext signals are rejected.
+    require_encryption: bool,
+
+    /// Where to acknowledge signals that fail to decrypt, if enabled.
+    failures: Option<FailureAck>,
+}
+
+/// Acknowledges signals that failed to decrypt.
+struct FailureAck {
+    /// Router to send `cauce.ack` through.
+    router: RouterHandle,
+
+    /// Tracked subscriptions, for the hub's current subscription ID.
+    subscriptions: Arc<RwLock<HashMap<String, SubscriptionInfo>>>,
+}
+
+impl SignalDecryptor {
+    /// Create a decryptor that leaves failed signals unacknowledged.
+    pub(super) fn new(options: &E2eOptions) -> Self {
+        Self {
+            secret: options.keys().secret_key().clone(),
+            public_key: options.public_key().to_base64(),
+            require_encryption: options.require_encryption(),
+            failures: None,
+        }
+    }
+
+    /// Acknowledge signals that fail to decrypt through `router`.
+    pub(super) fn with_failure_ack(
+        mut self,
+        router: RouterHandle,
+        subscriptions: Arc<RwLock<HashMap<String, SubscriptionInfo>>>,
+    ) -> Self {
+        self.failures = Some(FailureAck {
+            router,
+            subscriptions,
+        });
+        self
+    }
+
+    /// Decrypt `signal` for the subscription `subscription_id`.
+    ///
+    /// Returns `None` for a copy encrypted to another recipient. Plaintext
+    /// signals are rejected, or passed through unchanged if allowed.
+    pub(super) fn open(
+        &self,
+        subscription_id: &str,
+        signal: Signal,
+    ) -> Option<ClientResult<Signal>> {
+        let encrypted = match &signal.encrypted {
+            None if !self.require_encryption => return Some(Ok(signal)),
+            None => {
+                tracing::warn!(
+                    subscription_id = %subscription_id,
+                    signal_id = %signal.id,
+                    "Rejecting unencrypted signal on encrypted subscription"
+                );
+                self.acknowledge(subscription_id, &signal.id);
+                return Some(Err(ClientError::UnencryptedSignal {
+                    subscription_id: subscription_id.to_string(),
+                    signal_id: signal.id,
+                }));
+            }
+            Some(encrypted) => encrypted,
+        };
+        if encrypted.recipient_public_key != self.public_key {
+            tracing::trace!(
+                subscription_id = %subscription_id,
+                signal_id = %signal.id,
+                "Skipping signal encrypted for another recipient"
+            );
+            return None;
+        }
+
+        match e2e::decrypt_signal(&signal, &self.secret) {
+            Ok(decrypted) => Some(Ok(decrypted)),
+            Err(e) => {
+                tracing::warn!(
+                    subscription_id = %subscription_id,
+                    signal_id = %signal.id,
+                    error = %e,
+                    "Failed to decrypt signal"
+                );
+                self.acknowledge(subscription_id, &signal.id);
+                Some(Err(ClientError::DecryptionFailed {
+                    subscription_id: subscription_id.to_string(),
+                    signal_id: signal.id,
+                    reason: e.to_string(),
+                }))
+            }
+        }
+    }
+
+    /// Acknowledge a rejected signal in the background, if enabled.
+    fn acknowledge(&self, subscription_id: &str, signal_id: &str) {
+        let Some(failures) = &self.failures else {
+            return;
+        };
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            tracing::warn!(signal_id = %signal_id, "No runtime to acknowledge signal on");
+            return;
+        };
+
+        let router = failures.router.clone();
+        let subscriptions = Arc::clone(&failures.subscriptions);
+        let subscription_id = subscription_id.to_string();
+        let signal_id = signal_id.to_string();
+        runtime.spawn(async move {
+            let Some(hub_id) = subscriptions
+                .read()
+                .await
+                .get(&subscription_id)
+                .map(|info| info.id.clone())
+            else {
+                return;
+            };
+            let request = AckRequest::new(hub_id, vec![signal_id.clone()]);
+            let result = match serde_json::to_value(&request) {
+                Ok(params) => router.send_request(METHOD_ACK, Some(params)).await,
+                Err(e) => Err(e.into()),
+            };
+            let result = result.and_then(|response| match response.error_obj() {
+                Some(error) => Err(ClientError::RpcError {
+                    code: error.code,
+                    message: error.message.to_string(),
+                    data: error.data.clone(),
+                }),
+                None => Ok(()),
+            });
+            if let Err(e) = result {
+                tracing::warn!(
+                    signal_id = %signal_id,
+                    error = %e,
+                    "Failed to acknowledge undecryptable signal"
+                );
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cauce_core::e2e::KeyPair;
+    use cauce_core::types::EncryptionAlgorithm;
+    use cauce_core::{Payload, Source, Topic};
+
+    fn make_signal() -> Signal {
+        Signal::builder()
+            .source(Source::new("email", "adapter-1", "msg-1"))
+            .topic(Topic::new_unchecked("signal.email.received"))
+            .payload(Payload::new(
+                serde_json::json!({"subject": "hi"}),
+                "application/json",
+            ))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_open() {
+        let options = E2eOptions::generate();
+        let decryptor = SignalDecryptor::new(&options);
+        let signal = make_signal();
+        let algorithm = EncryptionAlgorithm::X25519XSalsa20Poly1305;
+
+        let sealed = e2e::encrypt_signal(&signal, options.public_key(), algorithm).unwrap();
+        let opened = decryptor.open("sub_1", sealed.clone()).unwrap().unwrap();
+        assert_eq!(opened, signal);
+
+        // Copies for other recipients are skipped
+        let other = KeyPair::generate();
+        let foreign = e2e::encrypt_signal(&signal, other.public_key(), algorithm).unwrap();
+        assert!(decryptor.open("sub_1", foreign).is_none());
+
+        // Tampered envelopes are reported
+        let mut tampered = sealed;
+        if let Some(encrypted) = tampered.encrypted.as_mut() {
+            encrypted.nonce = "AAAA".to_string();
+        }
+        match decryptor.open("sub_1", tampered) {
+            Some(Err(ClientError::DecryptionFailed {
+                subscription_id,
+                signal_id,
+                ..
+            })) => {
+                assert_eq!(subscription_id, "sub_1");
+                assert_eq!(signal_id, signal.id);
+            }
+            other => panic!("expected decryption failure, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_open_plaintext() {
+        let signal = make_signal();
+
+        // Rejected by default
+        let decryptor = SignalDecryptor::new(&E2eOptions::generate());
+        match decryptor.open("sub_1", signal.clone()) {
+            Some(Err(ClientError::UnencryptedSignal {
+                subscription_id,
+                signal_id,
+            })) => {
+                assert_eq!(subscription_id, "sub_1");
+                assert_eq!(signal_id, signal.id);
+            }
+            other => panic!("expected unencrypted signal error, got {:?}", other),
+        }
+
+        // Passed through when allowed
+        let options = E2eOptions::generate().with_require_encryption(false);
+        let decryptor = SignalDecryptor::new(&options);
+        let opened = decryptor.open("sub_1", signal.clone()).unwrap().unwrap();
+        assert_eq!(opened, signal);
+    }
+}
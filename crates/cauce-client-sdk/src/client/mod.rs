@@ -21,7 +21,8 @@
 //! let mut subscription = client.subscribe(&["signal.email.*"]).await?;
 //!
 //! // Receive signals
-//! while let Some(signal) = subscription.next().await {
+//! while let Some(result) = subscription.next().await {
+//!     let signal = result?;
 //!     println!("Received: {}", signal.id);
 //!     client.ack(subscription.subscription_id(), &[&signal.id]).await?;
 //! }
@@ -30,42 +31,82 @@
 //! client.disconnect().await?;
 //! ```
 
+#[cfg(feature = "e2e")]
+mod encryption;
 mod subscription;
+mod typed;
 
 pub use subscription::Subscription;
+pub use typed::TypedSubscription;
 
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, watch, RwLock};
+use tokio::time::Instant;
+use tokio::task::JoinHandle;
 
 use cauce_core::{
-    AckRequest, AckResponse, Auth, HelloRequest, HelloResponse, PublishMessage, PublishRequest,
-    PublishResponse, SubscribeRequest, SubscribeResponse, SubscriptionStatus, UnsubscribeRequest,
-    UnsubscribeResponse, METHOD_ACK, METHOD_GOODBYE, METHOD_HELLO, METHOD_PUBLISH,
-    METHOD_SUBSCRIBE, METHOD_UNSUBSCRIBE,
+    generate_correlation_id, AckRequest, AckResponse, Action, ActionContext, Auth, E2eConfig,
+    HelloRequest, HelloResponse, JsonRpcNotification, JsonRpcResponse, PublishMessage,
+    PublishRequest, PublishResponse, Signal, Source, SubscribeRequest, SubscribeResponse,
+    SubscriptionStatus, Topic, UnsubscribeRequest, UnsubscribeResponse, METHOD_ACK,
+    METHOD_GOODBYE, METHOD_HELLO, METHOD_PUBLISH, METHOD_SUBSCRIBE, METHOD_UNSUBSCRIBE,
 };
-
-use crate::config::{AuthConfig, ClientConfig};
+#[cfg(feature = "e2e")]
+use cauce_core::{
+    e2e::{self, PublicKey},
+    Capability, CauceError, KeysListRequest, KeysListResponse, RecipientKey, METHOD_KEYS_LIST,
+};
+#[cfg(all(feature = "e2e", feature = "signing"))]
+use cauce_core::signing::{self, SigningKey};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+#[cfg(feature = "e2e")]
+use self::encryption::SignalDecryptor;
+#[cfg(feature = "e2e")]
+use crate::config::E2eOptions;
+use crate::config::{AuthConfig, ClientConfig, TokenCache};
 use crate::error::ClientError;
-use crate::router::{MessageRouter, RouterConfig};
+use crate::router::{
+    ConnectionEvent, HubEndpoints, MessageRouter, RequestHandler, RouterConfig, RouterHandle,
+    RttStats, StatusUpdate,
+};
 use crate::transport::{Transport, WebSocketTransport};
 use crate::ClientResult;
 
 /// Internal subscription tracking information.
 #[derive(Debug, Clone)]
 struct SubscriptionInfo {
-    /// The subscription ID.
-    #[allow(dead_code)]
+    /// The subscription ID currently known to the hub.
+    ///
+    /// Differs from the handle's ID once the subscription has been
+    /// re-created after a reconnect.
     id: String,
+    /// The hub ID before the subscription was last re-created.
+    ///
+    /// Signals delivered under it are acknowledged there.
+    previous_id: Option<String>,
     /// Topics this subscription covers.
-    #[allow(dead_code)]
     topics: Vec<String>,
-    /// Current status of the subscription.
+    /// Status from the latest subscribe response.
     #[allow(dead_code)]
     status: SubscriptionStatus,
+    /// End-to-end encryption settings sent with the subscribe request.
+    e2e: Option<E2eConfig>,
 }
 
+/// JSON-RPC error code for a rejected token (`CauceError::NotAuthorized`).
+const NOT_AUTHORIZED: i32 = -32003;
+
+/// JSON-RPC error code for an expired session (`CauceError::SessionExpired`).
+const SESSION_EXPIRED: i32 = -32013;
+
+/// How long to wait before retrying a failed proactive token refresh.
+const TOKEN_RETRY_DELAY: Duration = Duration::from_secs(5);
+
 /// High-level client for connecting to and interacting with a Cauce Hub.
 ///
 /// `CauceClient` provides a convenient API for:
@@ -105,6 +146,15 @@ pub struct CauceClient {
 
     /// Active subscriptions: subscription_id -> SubscriptionInfo.
     subscriptions: Arc<RwLock<HashMap<String, SubscriptionInfo>>>,
+
+    /// Background task restoring the session after a reconnect.
+    session_task: Option<JoinHandle<()>>,
+
+    /// Bearer tokens, if a token provider is configured.
+    tokens: Option<Arc<TokenCache>>,
+
+    /// Counts session restore attempts after reconnects.
+    restores: watch::Receiver<u64>,
 }
 
 impl CauceClient {
@@ -116,6 +166,15 @@ impl CauceClient {
     /// 3. Sends the `cauce.hello` request
     /// 4. Validates the server's response
     ///
+    /// With a [`token_provider`](crate::config::ClientConfigBuilder::token_provider),
+    /// a token is fetched before connecting and used for both the transport
+    /// and the handshake.
+    ///
+    /// If fallback hubs are configured, each endpoint is tried in turn
+    /// (in the order given by [`ReconnectConfig::hub_selection`](crate::ReconnectConfig::hub_selection))
+    /// until one accepts the connection. Later reconnects fail over in the
+    /// same way.
+    ///
     /// # Arguments
     ///
     /// * `config` - Client configuration specifying hub URL, auth, etc.
@@ -127,7 +186,7 @@ impl CauceClient {
     /// # Errors
     ///
     /// - [`ClientError::ConfigError`] - Invalid configuration
-    /// - [`ClientError::ConnectionFailed`] - Transport connection failed
+    /// - [`ClientError::ConnectionFailed`] - No hub accepted the connection
     /// - [`ClientError::HandshakeFailed`] - Hello handshake failed
     /// - [`ClientError::VersionMismatch`] - Server version incompatible
     ///
@@ -143,66 +202,112 @@ impl CauceClient {
         // Validate configuration
         config.validate()?;
 
-        // Create transport
-        let mut transport = WebSocketTransport::new(config.clone());
+        let tokens = Self::token_cache(&config);
+        let auth = match &tokens {
+            Some(tokens) => Some(AuthConfig::bearer(tokens.token().await?)),
+            None => config.auth.clone(),
+        };
 
-        // Connect transport
-        transport
-            .connect()
-            .await
-            .map_err(|e| ClientError::ConnectionFailed {
-                message: e.to_string(),
-            })?;
+        // Try each hub until one accepts the connection
+        let mut endpoints = HubEndpoints::from_config(&config);
+        let mut last_error = None;
+        for _ in 0..endpoints.len() {
+            let mut transport_config = config.clone();
+            transport_config.hub_url = endpoints.current().to_string();
+            transport_config.auth = auth.clone();
+            let mut transport = WebSocketTransport::new(transport_config);
+
+            match transport.connect().await {
+                Ok(()) => {
+                    endpoints.record_success();
+                    return Self::start(config, Box::new(transport), endpoints, tokens).await;
+                }
+                Err(e) => {
+                    tracing::warn!(url = %endpoints.current(), error = %e, "Hub unreachable");
+                    last_error = Some(e);
+                    endpoints.fail_over();
+                }
+            }
+        }
+
+        Err(ClientError::ConnectionFailed {
+            message: last_error.map_or_else(|| "no hub endpoints".to_string(), |e| e.to_string()),
+        })
+    }
+
+    /// Perform the hello handshake over an already connected transport.
+    ///
+    /// This is the transport-agnostic half of [`connect`](Self::connect): it
+    /// starts the message router on the given transport and runs the
+    /// `cauce.hello` handshake. Use it to connect over SSE, polling or a
+    /// [`MockTransport`](crate::transport::mock::MockTransport) in tests.
+    ///
+    /// The transport is assumed to be connected to `config.hub_url`. With
+    /// fallback hubs configured, reconnects fail over by pointing the
+    /// transport at another hub with [`Transport::set_hub_url`].
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Client configuration
+    /// * `transport` - A transport that has already been connected
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError::ConfigError`] - Invalid configuration
+    /// - [`ClientError::HandshakeFailed`] - Hello handshake failed
+    /// - [`ClientError::VersionMismatch`] - Server version incompatible
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let mut transport = SseTransport::new(config.clone());
+    /// transport.connect().await?;
+    ///
+    /// let client = CauceClient::connect_with_transport(config, Box::new(transport)).await?;
+    /// ```
+    pub async fn connect_with_transport(
+        config: ClientConfig,
+        transport: Box<dyn Transport>,
+    ) -> ClientResult<Self> {
+        config.validate()?;
+        let endpoints = HubEndpoints::from_config(&config);
+        let tokens = Self::token_cache(&config);
+        Self::start(config, transport, endpoints, tokens).await
+    }
 
+    /// Start the router on a transport connected to `endpoints.current()`
+    /// and run the hello handshake.
+    async fn start(
+        config: ClientConfig,
+        mut transport: Box<dyn Transport>,
+        endpoints: HubEndpoints,
+        tokens: Option<Arc<TokenCache>>,
+    ) -> ClientResult<Self> {
         // Create router config from client config
-        let router_config = RouterConfig::default().with_request_timeout(config.request_timeout);
+        let mut router_config = RouterConfig::default()
+            .with_request_timeout(config.request_timeout)
+            .with_reconnect(config.reconnect.clone());
+        if !config.keepalive_interval.is_zero() {
+            router_config =
+                router_config.with_keepalive(config.keepalive_interval, config.max_missed_pongs);
+        }
 
         // Create and start message router
-        let mut router = MessageRouter::new(Box::new(transport), router_config);
+        if let Some(tokens) = &tokens {
+            transport.set_auth(AuthConfig::bearer(tokens.token().await?));
+        }
+        let mut router = MessageRouter::new(transport, router_config)
+            .with_endpoints(endpoints)
+            .with_interceptors(config.interceptors.iter().cloned());
+        if let Some(tokens) = &tokens {
+            router = router.with_tokens(Arc::clone(tokens));
+        }
+        let events = router.connection_events();
         router.start().map_err(|e| ClientError::ConnectionFailed {
             message: format!("Failed to start message router: {}", e),
         })?;
 
-        // Build hello request
-        let hello_request = Self::build_hello_request(&config);
-
-        // Send hello request
-        let hello_params = serde_json::to_value(&hello_request).map_err(|e| {
-            ClientError::HandshakeFailed {
-                message: format!("Failed to serialize hello request: {}", e),
-            }
-        })?;
-
-        let response = router
-            .send_request(METHOD_HELLO, Some(hello_params))
-            .await
-            .map_err(|e| ClientError::HandshakeFailed {
-                message: format!("Hello request failed: {}", e),
-            })?;
-
-        // Check for RPC error
-        if let Some(error) = response.error_obj() {
-            return Err(ClientError::HandshakeFailed {
-                message: format!(
-                    "Hub rejected hello: {} (code: {})",
-                    &error.message,
-                    error.code
-                ),
-            });
-        }
-
-        // Parse hello response
-        let result = response.result().ok_or_else(|| ClientError::HandshakeFailed {
-            message: "Hello response missing result".to_string(),
-        })?;
-
-        let hello_response: HelloResponse =
-            serde_json::from_value(result.clone()).map_err(|e| ClientError::HandshakeFailed {
-                message: format!("Failed to parse hello response: {}", e),
-            })?;
-
-        // Validate server version
-        Self::validate_version(&config, &hello_response)?;
+        let hello_response = Self::hello(&router.handle(), &config, tokens.as_deref()).await?;
 
         tracing::info!(
             session_id = %hello_response.session_id,
@@ -210,12 +315,31 @@ impl CauceClient {
             "Connected to Cauce Hub"
         );
 
+        let session_id = Arc::new(RwLock::new(Some(hello_response.session_id)));
+        let server_version = Arc::new(RwLock::new(Some(hello_response.server_version)));
+        let subscriptions = Arc::new(RwLock::new(HashMap::new()));
+        let (restored_tx, restores) = watch::channel(0);
+
+        let session_task = tokio::spawn(Self::run_session_restore(
+            router.handle(),
+            config.clone(),
+            events,
+            Arc::clone(&session_id),
+            Arc::clone(&server_version),
+            Arc::clone(&subscriptions),
+            tokens.clone(),
+            restored_tx,
+        ));
+
         Ok(Self {
             router,
             config,
-            session_id: Arc::new(RwLock::new(Some(hello_response.session_id))),
-            server_version: Arc::new(RwLock::new(Some(hello_response.server_version))),
-            subscriptions: Arc::new(RwLock::new(HashMap::new())),
+            session_id,
+            server_version,
+            subscriptions,
+            session_task: Some(session_task),
+            tokens,
+            restores,
         })
     }
 
@@ -234,6 +358,10 @@ impl CauceClient {
         // Send goodbye notification (fire-and-forget)
         let _ = self.router.send_notification(METHOD_GOODBYE, None).await;
 
+        if let Some(task) = self.session_task.take() {
+            task.abort();
+        }
+
         // Stop the router
         self.router.stop().await;
 
@@ -257,11 +385,14 @@ impl CauceClient {
     ///
     /// # Returns
     ///
-    /// A [`Subscription`] handle on success.
+    /// A [`Subscription`] handle on success. If the hub requires approval the
+    /// subscription starts out [`Pending`](SubscriptionStatus::Pending); use
+    /// [`Subscription::wait_until_active`] to wait for the decision.
     ///
     /// # Errors
     ///
     /// - [`ClientError::NotConnected`] - Not connected to hub
+    /// - [`ClientError::SubscriptionDenied`] - Hub denied the subscription
     /// - [`ClientError::RpcError`] - Hub rejected subscription
     ///
     /// # Example
@@ -284,6 +415,11 @@ impl CauceClient {
         // Build subscribe request
         let request =
             SubscribeRequest::new(topics.iter().map(|t| t.to_string()).collect::<Vec<_>>());
+        #[cfg(feature = "e2e")]
+        let request = match &self.config.e2e {
+            Some(options) => request.with_e2e(options.subscribe_config()),
+            None => request,
+        };
 
         let params =
             serde_json::to_value(&request).map_err(|e| ClientError::InvalidMessage {
@@ -291,10 +427,7 @@ impl CauceClient {
             })?;
 
         // Send request
-        let response = self
-            .router
-            .send_request(METHOD_SUBSCRIBE, Some(params))
-            .await?;
+        let response = self.send_request(METHOD_SUBSCRIBE, Some(params)).await?;
 
         // Check for RPC error
         if let Some(error) = response.error_obj() {
@@ -321,10 +454,9 @@ impl CauceClient {
                 // OK - continue
             }
             SubscriptionStatus::Denied => {
-                return Err(ClientError::RpcError {
-                    code: -1,
-                    message: "Subscription denied".to_string(),
-                    data: None,
+                return Err(ClientError::SubscriptionDenied {
+                    id: subscribe_response.subscription_id,
+                    reason: "denied by hub".to_string(),
                 });
             }
             status => {
@@ -339,8 +471,10 @@ impl CauceClient {
         // Store subscription info
         let info = SubscriptionInfo {
             id: subscribe_response.subscription_id.clone(),
+            previous_id: None,
             topics: subscribe_response.topics.clone(),
             status: subscribe_response.status,
+            e2e: request.e2e,
         };
         self.subscriptions
             .write()
@@ -353,12 +487,66 @@ impl CauceClient {
             "Subscribed to topics"
         );
 
+        // Route this subscription's signals into its own channel
+        let channel = self
+            .router
+            .register_subscription(
+                &subscribe_response.subscription_id,
+                subscribe_response.topics.clone(),
+                subscribe_response.status,
+            )
+            .await;
+
         // Create subscription handle
-        Ok(Subscription::new(
+        let subscription = Subscription::new(
             subscribe_response.subscription_id,
             subscribe_response.topics,
-            self.router.subscribe_notifications(),
-        ))
+            channel,
+        );
+        #[cfg(feature = "e2e")]
+        let subscription = match &self.config.e2e {
+            Some(options) => subscription.with_decryptor(self.decryptor(options)),
+            None => subscription,
+        };
+        Ok(subscription)
+    }
+
+    /// Build the decryptor for a new subscription.
+    #[cfg(feature = "e2e")]
+    fn decryptor(&self, options: &E2eOptions) -> SignalDecryptor {
+        let decryptor = SignalDecryptor::new(options);
+        if !options.ack_on_failure() {
+            return decryptor;
+        }
+        decryptor.with_failure_ack(self.router.handle(), Arc::clone(&self.subscriptions))
+    }
+
+    /// Subscribe to the specified topics, decoding payloads into `T`.
+    ///
+    /// Equivalent to [`subscribe`](Self::subscribe) followed by
+    /// [`Subscription::typed`]. Signals whose payload does not match `T`
+    /// are reported as [`ClientError::PayloadDecode`] without ending the
+    /// stream.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`subscribe`](Self::subscribe).
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let mut emails = client.subscribe_typed::<Email>(&["signal.email.*"]).await?;
+    ///
+    /// while let Some(result) = emails.next().await {
+    ///     let (signal, email) = result?;
+    ///     println!("{} from {}", email.subject, email.from);
+    /// }
+    /// ```
+    pub async fn subscribe_typed<T: DeserializeOwned>(
+        &self,
+        topics: &[&str],
+    ) -> ClientResult<TypedSubscription<T>> {
+        Ok(self.subscribe(topics).await?.typed())
     }
 
     /// Unsubscribe from a subscription.
@@ -384,24 +572,15 @@ impl CauceClient {
             return Err(ClientError::NotConnected);
         }
 
-        // Check subscription exists
-        if !self.subscriptions.read().await.contains_key(subscription_id) {
-            return Err(ClientError::SubscriptionNotFound {
-                id: subscription_id.to_string(),
-            });
-        }
-
-        // Build unsubscribe request
-        let request = UnsubscribeRequest::new(subscription_id);
-        let params =
-            serde_json::to_value(&request).map_err(|e| ClientError::InvalidMessage {
-                message: format!("Failed to serialize unsubscribe request: {}", e),
-            })?;
-
-        // Send request
+        // Send request for the subscription's current hub ID
         let response = self
-            .router
-            .send_request(METHOD_UNSUBSCRIBE, Some(params))
+            .send_subscription_request(METHOD_UNSUBSCRIBE, subscription_id, |hub_id| {
+                serde_json::to_value(UnsubscribeRequest::new(hub_id)).map_err(|e| {
+                    ClientError::InvalidMessage {
+                        message: format!("Failed to serialize unsubscribe request: {}", e),
+                    }
+                })
+            })
             .await?;
 
         // Check for RPC error
@@ -431,8 +610,9 @@ impl CauceClient {
             });
         }
 
-        // Remove from tracking
+        // Remove from tracking and close the subscription's channel
         self.subscriptions.write().await.remove(subscription_id);
+        self.router.unregister_subscription(subscription_id).await;
 
         tracing::info!(subscription_id = %subscription_id, "Unsubscribed");
         Ok(())
@@ -482,10 +662,7 @@ impl CauceClient {
             })?;
 
         // Send request
-        let response = self
-            .router
-            .send_request(METHOD_PUBLISH, Some(params))
-            .await?;
+        let response = self.send_request(METHOD_PUBLISH, Some(params)).await?;
 
         // Check for RPC error
         if let Some(error) = response.error_obj() {
@@ -516,64 +693,77 @@ impl CauceClient {
         Ok(publish_response)
     }
 
-    /// Acknowledge receipt of signals.
+    /// Publish a signal whose payload is built from a serializable value.
     ///
-    /// Acknowledging signals informs the hub that you have successfully
-    /// processed them. This is important for reliable delivery guarantees.
+    /// The signal is built with [`SignalBuilder`](cauce_core::SignalBuilder):
+    /// the ID and timestamp are generated, and the payload's `content_type`
+    /// and `size_bytes` are filled in from the serialized JSON.
     ///
     /// # Arguments
     ///
-    /// * `subscription_id` - The subscription that received the signals
-    /// * `signal_ids` - IDs of signals to acknowledge
-    ///
-    /// # Returns
-    ///
-    /// An [`AckResponse`] containing acknowledgment results.
+    /// * `topic` - The topic to publish to
+    /// * `source` - Origin of the signal
+    /// * `payload` - The payload value
     ///
     /// # Errors
     ///
+    /// - [`ClientError::InvalidMessage`] - Invalid topic or unserializable payload
     /// - [`ClientError::NotConnected`] - Not connected to hub
-    /// - [`ClientError::SubscriptionNotFound`] - Unknown subscription ID
-    /// - [`ClientError::RpcError`] - Hub rejected acknowledgment
+    /// - [`ClientError::RpcError`] - Hub rejected publish
     ///
     /// # Example
     ///
     /// ```ignore
-    /// // Acknowledge a single signal
-    /// client.ack(subscription.subscription_id(), &[&signal.id]).await?;
+    /// let email = Email { from: "alice@example.com".into(), subject: "Hi".into() };
+    /// let source = Source::new("email", "email-adapter-1", "msg-123");
     ///
-    /// // Acknowledge multiple signals
-    /// client.ack(subscription.subscription_id(), &["sig_1", "sig_2"]).await?;
+    /// client.publish_typed("signal.email.received", source, &email).await?;
     /// ```
-    pub async fn ack(
+    pub async fn publish_typed<T: Serialize + ?Sized>(
         &self,
-        subscription_id: &str,
-        signal_ids: &[&str],
-    ) -> ClientResult<AckResponse> {
+        topic: &str,
+        source: Source,
+        payload: &T,
+    ) -> ClientResult<PublishResponse> {
+        let signal_topic = Topic::new(topic).map_err(|e| ClientError::InvalidMessage {
+            message: format!("Invalid topic: {}", e),
+        })?;
+
+        let signal = Signal::builder()
+            .source(source)
+            .topic(signal_topic)
+            .typed_payload(payload)
+            .build()
+            .map_err(|e| ClientError::InvalidMessage {
+                message: format!("Failed to build signal: {}", e),
+            })?;
+
+        self.publish(topic, signal.into()).await
+    }
+
+    /// List the public keys of the subscribers a topic will be delivered to.
+    ///
+    /// Only subscriptions with end-to-end encryption enabled are listed.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError::NotConnected`] - Not connected to hub
+    /// - [`ClientError::RpcError`] - Hub rejected the request
+    #[cfg(feature = "e2e")]
+    pub async fn recipient_keys(&self, topic: &str) -> ClientResult<Vec<RecipientKey>> {
         // Check connection
         if !self.is_connected().await {
             return Err(ClientError::NotConnected);
         }
 
-        // Check subscription exists
-        if !self.subscriptions.read().await.contains_key(subscription_id) {
-            return Err(ClientError::SubscriptionNotFound {
-                id: subscription_id.to_string(),
-            });
-        }
-
-        // Build ack request
-        let request = AckRequest::new(
-            subscription_id,
-            signal_ids.iter().map(|s| s.to_string()).collect(),
-        );
-
-        let params = serde_json::to_value(&request).map_err(|e| ClientError::InvalidMessage {
-            message: format!("Failed to serialize ack request: {}", e),
+        let params = serde_json::to_value(KeysListRequest::new(topic)).map_err(|e| {
+            ClientError::InvalidMessage {
+                message: format!("Failed to serialize keys request: {}", e),
+            }
         })?;
 
         // Send request
-        let response = self.router.send_request(METHOD_ACK, Some(params)).await?;
+        let response = self.send_request(METHOD_KEYS_LIST, Some(params)).await?;
 
         // Check for RPC error
         if let Some(error) = response.error_obj() {
@@ -586,77 +776,800 @@ impl CauceClient {
 
         // Parse response
         let result = response.result().ok_or_else(|| ClientError::InvalidMessage {
-            message: "Ack response missing result".to_string(),
+            message: "Keys response missing result".to_string(),
         })?;
 
-        let ack_response: AckResponse =
+        let keys_response: KeysListResponse =
             serde_json::from_value(result.clone()).map_err(|e| ClientError::InvalidMessage {
-                message: format!("Failed to parse ack response: {}", e),
+                message: format!("Failed to parse keys response: {}", e),
             })?;
 
-        tracing::debug!(
-            subscription_id = %subscription_id,
-            acknowledged = ack_response.acknowledged.len(),
-            "Acknowledged signals"
-        );
-
-        Ok(ack_response)
+        Ok(keys_response.recipients)
     }
 
-    /// Returns the session ID if connected.
+    /// Publish a signal encrypted end-to-end for every current subscriber.
     ///
-    /// The session ID is assigned by the hub during the hello handshake.
+    /// Fetches the subscribers' public keys with
+    /// [`recipient_keys`](Self::recipient_keys) and publishes one copy of
+    /// the signal per distinct key, each encrypted with the first algorithm
+    /// the recipient accepts. All copies share the signal's ID. The hub
+    /// delivers each copy only to the subscriptions that registered its
+    /// key, so subscribers without encryption receive none of them, and
+    /// only sees the envelopes.
     ///
-    /// # Returns
+    /// Publishing needs no key pair of its own, so this works whether or not
+    /// [`E2eOptions`] are configured.
     ///
-    /// `Some(session_id)` if connected, `None` otherwise.
-    pub async fn session_id(&self) -> Option<String> {
-        self.session_id.read().await.clone()
-    }
-
-    /// Returns the server's protocol version if connected.
+    /// The copies are unsigned, whether or not `signal` was signed: a
+    /// signature only covers the message it was made for. Adapters whose
+    /// key is registered with the hub must use
+    /// [`publish_encrypted_signed`](Self::publish_encrypted_signed).
     ///
     /// # Returns
     ///
-    /// `Some(version)` if connected, `None` otherwise.
-    pub async fn server_version(&self) -> Option<String> {
-        self.server_version.read().await.clone()
+    /// One [`PublishResponse`] per copy; empty if no subscriber has
+    /// encryption enabled, in which case nothing is published.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError::NotConnected`] - Not connected to hub
+    /// - [`ClientError::ProtocolError`] - A recipient's key is invalid or
+    ///   it accepts no supported algorithm; nothing is published
+    /// - [`ClientError::RpcError`] - Hub rejected a publish
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let responses = client.publish_encrypted("signal.email.received", signal).await?;
+    /// println!("Encrypted for {} recipients", responses.len());
+    /// ```
+    #[cfg(feature = "e2e")]
+    pub async fn publish_encrypted(
+        &self,
+        topic: &str,
+        signal: Signal,
+    ) -> ClientResult<Vec<PublishResponse>> {
+        self.publish_sealed(topic, signal, Ok).await
     }
 
-    /// Checks if the client is currently connected.
+    /// Publish a signal encrypted end-to-end for every current subscriber,
+    /// signing each copy with `key`.
     ///
-    /// # Returns
+    /// Like [`publish_encrypted`](Self::publish_encrypted), but each copy is
+    /// signed after it is encrypted, so the signature covers the envelope
+    /// and the hub can check it without reading the payload.
     ///
-    /// `true` if connected, `false` otherwise.
-    pub async fn is_connected(&self) -> bool {
-        self.session_id.read().await.is_some() && self.router.connection_state().await.is_connected()
+    /// # Errors
+    ///
+    /// As [`publish_encrypted`](Self::publish_encrypted), and
+    /// [`ClientError::InvalidMessage`] if a copy could not be signed; nothing
+    /// is published.
+    #[cfg(all(feature = "e2e", feature = "signing"))]
+    pub async fn publish_encrypted_signed(
+        &self,
+        topic: &str,
+        signal: Signal,
+        key: &SigningKey,
+    ) -> ClientResult<Vec<PublishResponse>> {
+        self.publish_sealed(topic, signal, |copy| {
+            signing::sign_signal(&copy, key).map_err(|e| ClientError::InvalidMessage {
+                message: format!("Failed to sign signal: {}", e),
+            })
+        })
+        .await
     }
 
-    /// Returns a list of active subscription IDs.
-    pub async fn active_subscriptions(&self) -> Vec<String> {
-        self.subscriptions
-            .read()
-            .await
-            .keys()
-            .cloned()
-            .collect()
+    /// Encrypt `signal` for every current subscriber, pass each copy through
+    /// `finish`, then publish the copies.
+    #[cfg(feature = "e2e")]
+    async fn publish_sealed(
+        &self,
+        topic: &str,
+        signal: Signal,
+        finish: impl Fn(Signal) -> ClientResult<Signal>,
+    ) -> ClientResult<Vec<PublishResponse>> {
+        let mut recipients = self.recipient_keys(topic).await?;
+        recipients.sort_by(|a, b| a.public_key.cmp(&b.public_key));
+        recipients.dedup_by(|a, b| a.public_key == b.public_key);
+
+        let copies = recipients
+            .iter()
+            .map(|recipient| {
+                let public_key = PublicKey::from_base64(&recipient.public_key)?;
+                let algorithm = e2e::negotiate_algorithm(&recipient.supported_algorithms)
+                    .ok_or_else(|| CauceError::InvalidEncryption {
+                        reason: format!(
+                            "subscription {} accepts no supported algorithm",
+                            recipient.subscription_id
+                        ),
+                    })?;
+                Ok(e2e::encrypt_signal(&signal, &public_key, algorithm)?)
+            })
+            .collect::<Result<Vec<_>, CauceError>>()?
+            .into_iter()
+            .map(finish)
+            .collect::<ClientResult<Vec<_>>>()?;
+
+        let mut responses = Vec::with_capacity(copies.len());
+        for copy in copies {
+            responses.push(self.publish(topic, copy.into()).await?);
+        }
+        Ok(responses)
     }
 
-    /// Returns the client configuration.
-    pub fn config(&self) -> &ClientConfig {
-        &self.config
-    }
+    /// Publish an action and wait for the signal that answers it.
+    ///
+    /// The action is given a fresh
+    /// [`correlation_id`](cauce_core::ActionContext::correlation_id); the
+    /// call resolves with the first signal whose metadata carries the same
+    /// correlation ID. Adapters answer with
+    /// [`AdapterContext::respond`](crate::adapter::AdapterContext::respond),
+    /// which copies it over.
+    ///
+    /// The reply is only delivered if this client is subscribed to the topic
+    /// it is published on, e.g. `signal.*.action_result`. It is handed to
+    /// this call rather than to the subscription, and acknowledged here.
+    ///
+    /// # Arguments
+    ///
+    /// * `action` - The action to publish; any existing correlation ID is replaced
+    /// * `timeout` - How long to wait for the reply
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError::NotConnected`] - Not connected to hub
+    /// - [`ClientError::RpcError`] - Hub rejected the publish
+    /// - [`ClientError::RequestTimeout`] - No reply arrived in time
+    /// - [`ClientError::RequestCancelled`] - The client disconnected while waiting
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let _replies = client.subscribe(&["signal.email.action_result"]).await?;
+    ///
+    /// let action = Action::builder()
+    ///     .topic(Topic::new("action.email.send")?)
+    ///     .action(ActionBody::with_target(ActionType::Send, "bob@example.com", body))
+    ///     .build()?;
+    /// let reply = client.request(action, Duration::from_secs(30)).await?;
+    /// println!("sent: {}", reply.payload.raw);
+    /// ```
+    pub async fn request(&self, mut action: Action, timeout: Duration) -> ClientResult<Signal> {
+        if !self.is_connected().await {
+            return Err(ClientError::NotConnected);
+        }
 
-    // =========================================================================
-    // Private helpers
-    // =========================================================================
+        let correlation_id = generate_correlation_id();
+        action
+            .context
+            .get_or_insert_with(ActionContext::new)
+            .correlation_id = Some(correlation_id.clone());
+        let topic = action.topic.as_str().to_string();
+
+        // Register before publishing so a fast reply is not missed
+        let reply = self.router.expect_reply(&correlation_id).await;
+        if let Err(e) = self.publish(&topic, action.into()).await {
+            self.router.cancel_reply(&correlation_id).await;
+            return Err(e);
+        }
 
-    /// Build the hello request from client config.
-    fn build_hello_request(config: &ClientConfig) -> HelloRequest {
-        let mut request = HelloRequest::new(
-            &config.protocol_version,
-            &config.client_id,
-            config.client_type,
+        let delivery = match tokio::time::timeout(timeout, reply).await {
+            Ok(Ok(delivery)) => delivery,
+            Ok(Err(_)) => return Err(ClientError::RequestCancelled),
+            Err(_) => {
+                self.router.cancel_reply(&correlation_id).await;
+                return Err(ClientError::RequestTimeout {
+                    timeout_ms: timeout.as_millis() as u64,
+                });
+            }
+        };
+
+        // The reply bypassed the subscription, so acknowledge it here
+        if let Some(hub_id) = &delivery.subscription_id {
+            let request = AckRequest::new(hub_id.clone(), vec![delivery.signal.id.clone()]);
+            let acked = match serde_json::to_value(&request) {
+                Ok(params) => self
+                    .send_request(METHOD_ACK, Some(params))
+                    .await
+                    .map(|_| ()),
+                Err(e) => Err(e.into()),
+            };
+            if let Err(e) = acked {
+                tracing::warn!(signal_id = %delivery.signal.id, error = %e, "Failed to ack reply");
+            }
+        }
+
+        Ok(delivery.signal)
+    }
+
+    /// Acknowledge receipt of signals.
+    ///
+    /// Acknowledging signals informs the hub that you have successfully
+    /// processed them. This is important for reliable delivery guarantees.
+    ///
+    /// # Arguments
+    ///
+    /// * `subscription_id` - The subscription that received the signals
+    /// * `signal_ids` - IDs of signals to acknowledge
+    ///
+    /// # Returns
+    ///
+    /// An [`AckResponse`] containing acknowledgment results.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError::NotConnected`] - Not connected to hub
+    /// - [`ClientError::SubscriptionNotFound`] - Unknown subscription ID
+    /// - [`ClientError::RpcError`] - Hub rejected acknowledgment
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // Acknowledge a single signal
+    /// client.ack(subscription.subscription_id(), &[&signal.id]).await?;
+    ///
+    /// // Acknowledge multiple signals
+    /// client.ack(subscription.subscription_id(), &["sig_1", "sig_2"]).await?;
+    /// ```
+    pub async fn ack(
+        &self,
+        subscription_id: &str,
+        signal_ids: &[&str],
+    ) -> ClientResult<AckResponse> {
+        // Check connection
+        if !self.is_connected().await {
+            return Err(ClientError::NotConnected);
+        }
+
+        let signal_ids: Vec<String> = signal_ids.iter().map(|s| s.to_string()).collect();
+        let mut ack_response = self.ack_as(subscription_id, None, &signal_ids).await?;
+
+        // Signals delivered before the subscription was re-created are
+        // tracked under its previous hub ID
+        let previous_id = self
+            .subscriptions
+            .read()
+            .await
+            .get(subscription_id)
+            .and_then(|info| info.previous_id.clone());
+        if let (Some(previous_id), false) = (previous_id, ack_response.failed.is_empty()) {
+            let failed: Vec<String> = ack_response
+                .failed
+                .iter()
+                .map(|failure| failure.signal_id.clone())
+                .collect();
+            let retried = self
+                .ack_as(subscription_id, Some(previous_id), &failed)
+                .await?;
+            ack_response.acknowledged.extend(retried.acknowledged);
+            ack_response.failed = retried.failed;
+        }
+
+        tracing::debug!(
+            subscription_id = %subscription_id,
+            acknowledged = ack_response.acknowledged.len(),
+            "Acknowledged signals"
+        );
+
+        Ok(ack_response)
+    }
+
+    /// Acknowledge signals under `hub_id`, or the subscription's current
+    /// hub ID if `None`.
+    async fn ack_as(
+        &self,
+        subscription_id: &str,
+        hub_id: Option<String>,
+        signal_ids: &[String],
+    ) -> ClientResult<AckResponse> {
+        let response = self
+            .send_subscription_request(METHOD_ACK, subscription_id, |current_id| {
+                let hub_id = hub_id.clone().unwrap_or(current_id);
+                let request = AckRequest::new(hub_id, signal_ids.to_vec());
+                serde_json::to_value(&request).map_err(|e| ClientError::InvalidMessage {
+                    message: format!("Failed to serialize ack request: {}", e),
+                })
+            })
+            .await?;
+
+        // Check for RPC error
+        if let Some(error) = response.error_obj() {
+            return Err(ClientError::RpcError {
+                code: error.code,
+                message: error.message.to_string(),
+                data: error.data.clone(),
+            });
+        }
+
+        // Parse response
+        let result = response.result().ok_or_else(|| ClientError::InvalidMessage {
+            message: "Ack response missing result".to_string(),
+        })?;
+
+        serde_json::from_value(result.clone()).map_err(|e| ClientError::InvalidMessage {
+            message: format!("Failed to parse ack response: {}", e),
+        })
+    }
+
+    /// Returns the session ID if connected.
+    ///
+    /// The session ID is assigned by the hub during the hello handshake.
+    ///
+    /// # Returns
+    ///
+    /// `Some(session_id)` if connected, `None` otherwise.
+    pub async fn session_id(&self) -> Option<String> {
+        self.session_id.read().await.clone()
+    }
+
+    /// Returns the server's protocol version if connected.
+    ///
+    /// # Returns
+    ///
+    /// `Some(version)` if connected, `None` otherwise.
+    pub async fn server_version(&self) -> Option<String> {
+        self.server_version.read().await.clone()
+    }
+
+    /// Checks if the client is currently connected.
+    ///
+    /// # Returns
+    ///
+    /// `true` if connected, `false` otherwise.
+    pub async fn is_connected(&self) -> bool {
+        self.session_id.read().await.is_some() && self.router.connection_state().await.is_connected()
+    }
+
+    /// Returns a list of active subscription IDs.
+    pub async fn active_subscriptions(&self) -> Vec<String> {
+        self.subscriptions
+            .read()
+            .await
+            .keys()
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the client configuration.
+    pub fn config(&self) -> &ClientConfig {
+        &self.config
+    }
+
+    /// Returns the URL of the hub currently in use.
+    ///
+    /// Differs from [`ClientConfig::hub_url`] after failing over to a
+    /// fallback hub.
+    pub fn hub_url(&self) -> String {
+        self.router
+            .hub_url()
+            .unwrap_or_else(|| self.config.hub_url.clone())
+    }
+
+    /// Register a handler for hub-initiated requests to `method`.
+    ///
+    /// `cauce.ping` is answered by default; other methods without a handler
+    /// are answered with a "Method not found" error. See
+    /// [`MessageRouter::on_request`].
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// client.on_request(METHOD_SUBSCRIPTION_REQUEST, |params: Option<Value>| async move {
+    ///     Ok(json!({ "approved": true }))
+    /// });
+    /// ```
+    pub fn on_request(&self, method: impl Into<String>, handler: impl RequestHandler) {
+        self.router.on_request(method, handler);
+    }
+
+    /// Returns a snapshot of keepalive round-trip time statistics.
+    ///
+    /// Statistics are only gathered when
+    /// [`keepalive_interval`](ClientConfig::keepalive_interval) is non-zero,
+    /// or when [`ping`](Self::ping) is called explicitly.
+    pub fn rtt_stats(&self) -> RttStats {
+        self.router.rtt_stats()
+    }
+
+    /// Send a `cauce.ping` and return the measured round-trip time.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError::NotConnected`] - Not connected to hub
+    /// - [`ClientError::RequestTimeout`] - No pong within the request timeout
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let rtt = client.ping().await?;
+    /// println!("Hub round trip: {:?}", rtt);
+    /// ```
+    pub async fn ping(&self) -> ClientResult<Duration> {
+        if !self.is_connected().await {
+            return Err(ClientError::NotConnected);
+        }
+
+        self.router.ping().await
+    }
+
+    /// Subscribe to connection lifecycle events.
+    ///
+    /// Events report lost connections and reconnection attempts. After a
+    /// [`ConnectionEvent::Reconnected`] the client re-runs the hello
+    /// handshake and restores its subscriptions in the background;
+    /// existing [`Subscription`] handles keep working.
+    pub fn connection_events(&self) -> broadcast::Receiver<ConnectionEvent> {
+        self.router.connection_events()
+    }
+
+    /// Subscribe to every notification the hub sends on this connection.
+    pub(crate) fn notifications(&self) -> broadcast::Receiver<JsonRpcNotification> {
+        self.router.subscribe_notifications()
+    }
+
+    // =========================================================================
+    // Private helpers
+    // =========================================================================
+
+    /// Resolve a subscription handle's ID to the ID currently used by the hub.
+    async fn hub_subscription_id(&self, subscription_id: &str) -> ClientResult<String> {
+        self.subscriptions
+            .read()
+            .await
+            .get(subscription_id)
+            .map(|info| info.id.clone())
+            .ok_or_else(|| ClientError::SubscriptionNotFound {
+                id: subscription_id.to_string(),
+            })
+    }
+
+    /// Send a request, re-authenticating and retrying once if the hub
+    /// reports the session or the bearer token expired.
+    async fn send_request(
+        &self,
+        method: &str,
+        params: Option<serde_json::Value>,
+    ) -> ClientResult<JsonRpcResponse> {
+        let response = self.router.send_request(method, params.clone()).await?;
+        if !self.reauthenticate_after(method, &response).await {
+            return Ok(response);
+        }
+        self.router.send_request(method, params).await
+    }
+
+    /// Send a request about a subscription, like
+    /// [`send_request`](Self::send_request).
+    ///
+    /// `build` makes the params from the subscription's hub ID. It is
+    /// called again for the retry, since re-authenticating re-creates the
+    /// subscription under a new hub ID.
+    async fn send_subscription_request<F>(
+        &self,
+        method: &str,
+        subscription_id: &str,
+        build: F,
+    ) -> ClientResult<JsonRpcResponse>
+    where
+        F: Fn(String) -> ClientResult<serde_json::Value>,
+    {
+        let params = build(self.hub_subscription_id(subscription_id).await?)?;
+        let response = self.router.send_request(method, Some(params)).await?;
+        if !self.reauthenticate_after(method, &response).await {
+            return Ok(response);
+        }
+        let params = build(self.hub_subscription_id(subscription_id).await?)?;
+        self.router.send_request(method, Some(params)).await
+    }
+
+    /// Re-authenticate if `response` reported the session expired, or
+    /// rejected a bearer token that has expired. Returns whether the request
+    /// should be retried.
+    ///
+    /// Other `NOT_AUTHORIZED` errors, such as ACL denials, are not retried:
+    /// a new token would not change the outcome.
+    async fn reauthenticate_after(&self, method: &str, response: &JsonRpcResponse) -> bool {
+        let Some(tokens) = &self.tokens else {
+            return false;
+        };
+        if !self.config.reconnect.enabled {
+            return false;
+        }
+        let rejected = match response.error_obj().map(|error| error.code) {
+            Some(SESSION_EXPIRED) => true,
+            Some(NOT_AUTHORIZED) => tokens.is_expired().await,
+            _ => false,
+        };
+        if !rejected {
+            return false;
+        }
+
+        tracing::info!(method = %method, "Hub rejected credentials, re-authenticating");
+        match self.reauthenticate().await {
+            Ok(()) => true,
+            Err(e) => {
+                tracing::warn!(error = %e, "Re-authentication failed");
+                false
+            }
+        }
+    }
+
+    /// Fetch a new token and reconnect with it.
+    ///
+    /// Returns once the session and its subscriptions have been restored.
+    /// The protocol has no way to re-authenticate a live session, so this
+    /// is a full reconnect; see
+    /// [`token_provider`](crate::config::ClientConfigBuilder::token_provider)
+    /// for what that means for in-flight signals.
+    async fn reauthenticate(&self) -> ClientResult<()> {
+        let tokens = self.tokens.as_ref().ok_or(ClientError::NotConnected)?;
+        tokens.refresh().await?;
+
+        let mut restores = self.restores.clone();
+        restores.borrow_and_update();
+        self.router.handle().request_reconnect();
+        restores
+            .changed()
+            .await
+            .map_err(|_| ClientError::NotConnected)?;
+
+        if self.session_id.read().await.is_none() {
+            return Err(ClientError::NotConnected);
+        }
+        Ok(())
+    }
+
+    /// Create the token cache for a configured token provider.
+    fn token_cache(config: &ClientConfig) -> Option<Arc<TokenCache>> {
+        config
+            .token_provider
+            .clone()
+            .map(|provider| Arc::new(TokenCache::new(provider, config.token_refresh_margin)))
+    }
+
+    /// Perform the `cauce.hello` handshake and validate the response.
+    ///
+    /// With `tokens`, the handshake authenticates with the current token
+    /// instead of `config.auth`.
+    async fn hello(
+        router: &RouterHandle,
+        config: &ClientConfig,
+        tokens: Option<&TokenCache>,
+    ) -> ClientResult<HelloResponse> {
+        // Build hello request
+        let mut hello_request = Self::build_hello_request(config);
+        if let Some(tokens) = tokens {
+            hello_request = hello_request.with_auth(Auth::bearer(tokens.token().await?));
+        }
+
+        // Send hello request
+        let hello_params = serde_json::to_value(&hello_request).map_err(|e| {
+            ClientError::HandshakeFailed {
+                message: format!("Failed to serialize hello request: {}", e),
+            }
+        })?;
+
+        let response = router
+            .send_request(METHOD_HELLO, Some(hello_params))
+            .await
+            .map_err(|e| ClientError::HandshakeFailed {
+                message: format!("Hello request failed: {}", e),
+            })?;
+
+        // Check for RPC error
+        if let Some(error) = response.error_obj() {
+            return Err(ClientError::HandshakeFailed {
+                message: format!(
+                    "Hub rejected hello: {} (code: {})",
+                    &error.message,
+                    error.code
+                ),
+            });
+        }
+
+        // Parse hello response
+        let result = response.result().ok_or_else(|| ClientError::HandshakeFailed {
+            message: "Hello response missing result".to_string(),
+        })?;
+
+        let hello_response: HelloResponse =
+            serde_json::from_value(result.clone()).map_err(|e| ClientError::HandshakeFailed {
+                message: format!("Failed to parse hello response: {}", e),
+            })?;
+
+        // Validate server version
+        Self::validate_version(config, &hello_response)?;
+
+        Ok(hello_response)
+    }
+
+    /// Track connection events, restoring the session after each reconnect.
+    ///
+    /// With `tokens`, the token is also refreshed shortly before it expires
+    /// and the client reconnects with the new one. Each restore attempt is
+    /// counted on `restored`.
+    #[allow(clippy::too_many_arguments)]
+    async fn run_session_restore(
+        router: RouterHandle,
+        config: ClientConfig,
+        mut events: broadcast::Receiver<ConnectionEvent>,
+        session_id: Arc<RwLock<Option<String>>>,
+        server_version: Arc<RwLock<Option<String>>>,
+        subscriptions: Arc<RwLock<HashMap<String, SubscriptionInfo>>>,
+        tokens: Option<Arc<TokenCache>>,
+        restored: watch::Sender<u64>,
+    ) {
+        // Refreshing needs a reconnect to take effect
+        let tokens = tokens.filter(|_| config.reconnect.enabled);
+        let mut retry_at: Option<Instant> = None;
+
+        loop {
+            let refresh_at = match &tokens {
+                Some(tokens) => retry_at.or(tokens.refresh_at().await),
+                None => None,
+            };
+            let refresh_due = async {
+                match refresh_at {
+                    Some(at) => tokio::time::sleep_until(at).await,
+                    None => std::future::pending().await,
+                }
+            };
+
+            let event = tokio::select! {
+                event = events.recv() => event,
+                _ = refresh_due => {
+                    let Some(tokens) = &tokens else { continue };
+                    match tokens.refresh().await {
+                        Ok(_) => {
+                            tracing::info!("Bearer token refreshed, reconnecting");
+                            retry_at = None;
+                            router.request_reconnect();
+                        }
+                        Err(e) => {
+                            tracing::warn!(error = %e, "Failed to refresh bearer token");
+                            retry_at = Some(Instant::now() + TOKEN_RETRY_DELAY);
+                        }
+                    }
+                    continue;
+                }
+            };
+
+            match event {
+                Ok(ConnectionEvent::Disconnected { .. }) => {
+                    *session_id.write().await = None;
+                }
+                Ok(ConnectionEvent::Reconnected { .. }) => {
+                    match Self::hello(&router, &config, tokens.as_deref()).await {
+                        Ok(hello) => {
+                            tracing::info!(session_id = %hello.session_id, "Session restored");
+                            *server_version.write().await = Some(hello.server_version);
+
+                            Self::restore_subscriptions(&router, &subscriptions).await;
+                            *session_id.write().await = Some(hello.session_id);
+                        }
+                        Err(e) => {
+                            tracing::error!(error = %e, "Failed to restore session after reconnect");
+                        }
+                    }
+                    restored.send_modify(|count| *count += 1);
+                }
+                Ok(ConnectionEvent::FailedOver { url }) => {
+                    tracing::info!(url = %url, "Restoring session on another hub");
+                }
+                Ok(ConnectionEvent::ReconnectFailed { .. }) => {
+                    restored.send_modify(|count| *count += 1);
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    }
+
+    /// Re-create every tracked subscription on the hub.
+    ///
+    /// The hub assigns new IDs; they are aliased onto the original channels
+    /// so existing [`Subscription`] handles keep receiving. Subscriptions the
+    /// hub has denied or revoked are dropped instead.
+    ///
+    /// A subscription the hub refuses to re-create is ended as denied, with
+    /// the hub's error as the reason, so its handle stops waiting.
+    async fn restore_subscriptions(
+        router: &RouterHandle,
+        subscriptions: &RwLock<HashMap<String, SubscriptionInfo>>,
+    ) {
+        let mut subscriptions = subscriptions.write().await;
+
+        let mut ended = Vec::new();
+        for handle_id in subscriptions.keys() {
+            if router.ended_subscription(handle_id).await.is_some() {
+                ended.push(handle_id.clone());
+            }
+        }
+        for handle_id in ended {
+            tracing::debug!(subscription_id = %handle_id, "Not restoring ended subscription");
+            subscriptions.remove(&handle_id);
+        }
+
+        let mut refused = Vec::new();
+        for (handle_id, info) in subscriptions.iter_mut() {
+            let request = SubscribeRequest {
+                e2e: info.e2e.clone(),
+                ..SubscribeRequest::new(info.topics.clone())
+            };
+            let result = match serde_json::to_value(&request) {
+                Ok(params) => router.send_request(METHOD_SUBSCRIBE, Some(params)).await,
+                Err(e) => Err(ClientError::InvalidMessage {
+                    message: format!("Failed to serialize subscribe request: {}", e),
+                }),
+            };
+
+            let restored = result.and_then(|response| {
+                if let Some(error) = response.error_obj() {
+                    return Err(ClientError::RpcError {
+                        code: error.code,
+                        message: error.message.to_string(),
+                        data: error.data.clone(),
+                    });
+                }
+                let result = response.result().ok_or_else(|| ClientError::InvalidMessage {
+                    message: "Subscribe response missing result".to_string(),
+                })?;
+                serde_json::from_value::<SubscribeResponse>(result.clone()).map_err(|e| {
+                    ClientError::InvalidMessage {
+                        message: format!("Failed to parse subscribe response: {}", e),
+                    }
+                })
+            });
+
+            match restored {
+                Ok(response) => {
+                    router
+                        .alias_subscription(&response.subscription_id, handle_id)
+                        .await;
+                    tracing::info!(
+                        subscription_id = %handle_id,
+                        hub_subscription_id = %response.subscription_id,
+                        "Subscription restored"
+                    );
+                    router
+                        .update_subscription_status(handle_id, StatusUpdate::new(response.status))
+                        .await;
+                    let previous_id = std::mem::replace(&mut info.id, response.subscription_id);
+                    info.previous_id = Some(previous_id);
+                    info.status = response.status;
+                }
+                Err(e @ ClientError::RpcError { .. }) => {
+                    tracing::warn!(
+                        subscription_id = %handle_id,
+                        error = %e,
+                        "Hub refused to restore subscription"
+                    );
+                    let update = StatusUpdate {
+                        status: SubscriptionStatus::Denied,
+                        reason: Some(e.to_string()),
+                    };
+                    router.update_subscription_status(handle_id, update).await;
+                    refused.push(handle_id.clone());
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        subscription_id = %handle_id,
+                        error = %e,
+                        "Failed to restore subscription"
+                    );
+                }
+            }
+        }
+        for handle_id in refused {
+            subscriptions.remove(&handle_id);
+        }
+    }
+
+    /// Build the hello request from client config.
+    fn build_hello_request(config: &ClientConfig) -> HelloRequest {
+        let mut request = HelloRequest::new(
+            &config.protocol_version,
+            &config.client_id,
+            config.client_type,
         );
 
         // Add auth if configured
@@ -668,6 +1581,12 @@ impl CauceClient {
             request = request.with_auth(auth);
         }
 
+        // Advertise end-to-end encryption if configured
+        #[cfg(feature = "e2e")]
+        if config.e2e.is_some() {
+            request = request.with_capability(Capability::E2eEncryption);
+        }
+
         // Add min protocol version if configured
         if config.min_protocol_version != config.protocol_version {
             request.min_protocol_version = Some(config.min_protocol_version.clone());
@@ -902,6 +1821,7 @@ mod tests {
                 payload: cauce_core::Payload::new(serde_json::json!({}), "application/json"),
                 metadata: None,
                 encrypted: None,
+                signature: None,
             }),
         };
         let params = serde_json::to_value(&request).unwrap();
@@ -938,4 +1858,590 @@ mod tests {
         let ack_response: AckResponse = serde_json::from_value(result.clone()).unwrap();
         assert_eq!(ack_response.acknowledged.len(), 2);
     }
+
+    #[tokio::test]
+    async fn test_publish_typed_fills_payload() {
+        #[derive(serde::Serialize)]
+        struct Email {
+            from: &'static str,
+        }
+
+        let mut transport = MockTransport::new().wait_when_empty();
+        transport.connect().await.unwrap();
+        let handle = transport.handle();
+        handle
+            .push_receive(
+                JsonRpcResponse::success(RequestId::Number(1), make_hello_response("sess_1")).into(),
+            )
+            .await;
+
+        let client = CauceClient::connect_with_transport(make_config(), Box::new(transport))
+            .await
+            .unwrap();
+        handle.pop_sent().await;
+
+        // Answer the publish once it has been sent
+        let hub = tokio::spawn(async move {
+            loop {
+                if let Some(crate::transport::JsonRpcMessage::Request(request)) =
+                    handle.pop_sent().await
+                {
+                    let response =
+                        JsonRpcResponse::success(request.id().clone(), make_publish_response("msg_1"));
+                    handle.push_receive(response.into()).await;
+                    return request.params().cloned().unwrap();
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(2)).await;
+            }
+        });
+
+        let source = cauce_core::Source::new("email", "email-1", "native-1");
+        let response = client
+            .publish_typed("signal.email.received", source, &Email { from: "alice" })
+            .await
+            .unwrap();
+        assert_eq!(response.message_id, "msg_1");
+
+        let params = hub.await.unwrap();
+        let payload = &params["message"]["payload"];
+        assert_eq!(payload["raw"]["from"], "alice");
+        assert_eq!(payload["content_type"], "application/json");
+        assert!(payload["size_bytes"].as_u64().unwrap() > 0);
+    }
+
+    #[tokio::test]
+    async fn test_publish_typed_rejects_invalid_topic() {
+        let mut transport = MockTransport::new().wait_when_empty();
+        transport.connect().await.unwrap();
+        transport.push_receive(
+            JsonRpcResponse::success(RequestId::Number(1), make_hello_response("sess_1")).into(),
+        );
+
+        let client = CauceClient::connect_with_transport(make_config(), Box::new(transport))
+            .await
+            .unwrap();
+        let source = cauce_core::Source::new("email", "email-1", "native-1");
+        let result = client.publish_typed("not a topic", source, &"body").await;
+        assert!(matches!(result, Err(ClientError::InvalidMessage { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_request_resolves_with_correlated_reply() {
+        use crate::transport::JsonRpcMessage;
+        use cauce_core::{ActionBody, ActionType, Metadata, METHOD_SIGNAL};
+
+        let mut transport = MockTransport::new().wait_when_empty();
+        transport.connect().await.unwrap();
+        let handle = transport.handle();
+        handle
+            .push_receive(
+                JsonRpcResponse::success(RequestId::Number(1), make_hello_response("sess_1")).into(),
+            )
+            .await;
+
+        let client = CauceClient::connect_with_transport(make_config(), Box::new(transport))
+            .await
+            .unwrap();
+        handle.pop_sent().await;
+
+        // Answer the publish, then deliver a reply carrying its correlation ID
+        let hub = tokio::spawn(async move {
+            let mut acked = None;
+            while acked.is_none() {
+                let Some(JsonRpcMessage::Request(request)) = handle.pop_sent().await else {
+                    tokio::time::sleep(std::time::Duration::from_millis(2)).await;
+                    continue;
+                };
+                let params = request.params().cloned().unwrap();
+                let result = match request.method() {
+                    METHOD_PUBLISH => make_publish_response("msg_1"),
+                    _ => {
+                        acked = Some(params.clone());
+                        serde_json::json!({ "acknowledged": params["signal_ids"] })
+                    }
+                };
+                handle
+                    .push_receive(JsonRpcResponse::success(request.id().clone(), result).into())
+                    .await;
+
+                if request.method() == METHOD_PUBLISH {
+                    let context = &params["message"]["context"];
+                    let correlation_id = context["correlation_id"].as_str().unwrap();
+                    let mut reply = Signal::builder()
+                        .id("sig_reply")
+                        .source(cauce_core::Source::new("email", "email-1", "native-1"))
+                        .topic(Topic::new_unchecked("signal.email.action_result"))
+                        .payload(cauce_core::Payload::new(serde_json::json!({}), "application/json"))
+                        .build()
+                        .unwrap();
+                    reply.metadata = Some(Metadata::reply_to("act_1").correlation_id(correlation_id));
+                    let delivery = cauce_core::SignalDelivery::new("signal.email.action_result", reply)
+                        .with_subscription_id("sub_hub");
+                    handle
+                        .push_receive(JsonRpcMessage::Notification(JsonRpcNotification::new(
+                            METHOD_SIGNAL.to_string(),
+                            Some(serde_json::to_value(&delivery).unwrap()),
+                        )))
+                        .await;
+                }
+            }
+            acked.unwrap()
+        });
+
+        let action = Action::builder()
+            .topic(Topic::new_unchecked("action.email.send"))
+            .action(ActionBody::new(ActionType::Send, serde_json::json!({})))
+            .build()
+            .unwrap();
+        let reply = client
+            .request(action, std::time::Duration::from_secs(2))
+            .await
+            .unwrap();
+        assert_eq!(reply.id, "sig_reply");
+
+        let ack = hub.await.unwrap();
+        assert_eq!(ack["subscription_id"], "sub_hub");
+        assert_eq!(ack["signal_ids"], serde_json::json!(["sig_reply"]));
+    }
+
+    #[tokio::test]
+    async fn test_refused_restore_ends_subscription() {
+        use crate::transport::JsonRpcMessage;
+        use cauce_core::JsonRpcError;
+
+        let mut transport = MockTransport::new().wait_when_empty();
+        transport.connect().await.unwrap();
+        let handle = transport.handle();
+        handle
+            .push_receive(
+                JsonRpcResponse::success(RequestId::Number(1), make_hello_response("sess_1")).into(),
+            )
+            .await;
+
+        let client = CauceClient::connect_with_transport(make_config(), Box::new(transport))
+            .await
+            .unwrap();
+        handle.pop_sent().await;
+
+        // Accept the first subscribe and refuse to re-create it
+        let hub = tokio::spawn(async move {
+            let mut subscribes = 0;
+            while subscribes < 2 {
+                let Some(JsonRpcMessage::Request(request)) = handle.pop_sent().await else {
+                    tokio::time::sleep(std::time::Duration::from_millis(2)).await;
+                    continue;
+                };
+                subscribes += 1;
+                let response = if subscribes == 1 {
+                    JsonRpcResponse::success(
+                        request.id().clone(),
+                        make_subscribe_response("sub_1", &["signal.email.*"]),
+                    )
+                } else {
+                    JsonRpcResponse::error(
+                        Some(request.id().clone()),
+                        JsonRpcError::new(-32003, "Topic not allowed"),
+                    )
+                };
+                handle.push_receive(response.into()).await;
+            }
+        });
+
+        let mut subscription = client.subscribe(&["signal.email.*"]).await.unwrap();
+        CauceClient::restore_subscriptions(&client.router.handle(), &client.subscriptions).await;
+        hub.await.unwrap();
+
+        match subscription.next().await {
+            Some(Err(ClientError::SubscriptionDenied { id, reason })) => {
+                assert_eq!(id, "sub_1");
+                assert!(reason.contains("Topic not allowed"), "{}", reason);
+            }
+            other => panic!("expected denial, got {:?}", other.map(|r| r.map(|s| s.id))),
+        }
+        assert!(client.subscriptions.read().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_request_times_out_without_reply() {
+        use cauce_core::{ActionBody, ActionType};
+
+        let mut transport = MockTransport::new().wait_when_empty();
+        transport.connect().await.unwrap();
+        let handle = transport.handle();
+        handle
+            .push_receive(
+                JsonRpcResponse::success(RequestId::Number(1), make_hello_response("sess_1")).into(),
+            )
+            .await;
+        let client = CauceClient::connect_with_transport(make_config(), Box::new(transport))
+            .await
+            .unwrap();
+        handle.pop_sent().await;
+
+        tokio::spawn(async move {
+            loop {
+                if let Some(crate::transport::JsonRpcMessage::Request(request)) =
+                    handle.pop_sent().await
+                {
+                    let response =
+                        JsonRpcResponse::success(request.id().clone(), make_publish_response("msg_1"));
+                    handle.push_receive(response.into()).await;
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(2)).await;
+            }
+        });
+
+        let action = Action::builder()
+            .topic(Topic::new_unchecked("action.email.send"))
+            .action(ActionBody::new(ActionType::Send, serde_json::json!({})))
+            .build()
+            .unwrap();
+        let result = client
+            .request(action, std::time::Duration::from_millis(300))
+            .await;
+        assert!(matches!(result, Err(ClientError::RequestTimeout { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_pending_subscription_tracks_status() {
+        use crate::transport::JsonRpcMessage;
+        use cauce_core::{SubscriptionStatusNotification, METHOD_SUBSCRIPTION_STATUS};
+
+        let mut transport = MockTransport::new().wait_when_empty();
+        transport.connect().await.unwrap();
+        let handle = transport.handle();
+        handle
+            .push_receive(
+                JsonRpcResponse::success(RequestId::Number(1), make_hello_response("sess_1")).into(),
+            )
+            .await;
+        let client = CauceClient::connect_with_transport(make_config(), Box::new(transport))
+            .await
+            .unwrap();
+        handle.pop_sent().await;
+
+        let status_notification = |status, reason: Option<&str>| {
+            let mut update = SubscriptionStatusNotification::new("sub_pending", status);
+            update.reason = reason.map(str::to_string);
+            JsonRpcMessage::Notification(JsonRpcNotification::new(
+                METHOD_SUBSCRIPTION_STATUS.to_string(),
+                Some(serde_json::to_value(&update).unwrap()),
+            ))
+        };
+
+        // Answer the subscribe as pending, then approve it
+        let hub_handle = handle.clone();
+        let hub = tokio::spawn(async move {
+            let request = loop {
+                if let Some(JsonRpcMessage::Request(request)) = hub_handle.pop_sent().await {
+                    break request;
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(2)).await;
+            };
+            let mut result = make_subscribe_response("sub_pending", &["signal.email.*"]);
+            result["status"] = serde_json::json!("pending");
+            hub_handle
+                .push_receive(JsonRpcResponse::success(request.id().clone(), result).into())
+                .await;
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            hub_handle
+                .push_receive(status_notification(SubscriptionStatus::Active, None))
+                .await;
+        });
+
+        let mut subscription = client.subscribe(&["signal.email.*"]).await.unwrap();
+        subscription
+            .wait_until_active(std::time::Duration::from_secs(2))
+            .await
+            .unwrap();
+        hub.await.unwrap();
+        assert_eq!(subscription.status(), SubscriptionStatus::Active);
+
+        handle
+            .push_receive(status_notification(
+                SubscriptionStatus::Revoked,
+                Some("owner revoked access"),
+            ))
+            .await;
+        match subscription.next().await {
+            Some(Err(ClientError::SubscriptionRevoked { id, reason })) => {
+                assert_eq!(id, "sub_pending");
+                assert_eq!(reason, "owner revoked access");
+            }
+            other => panic!("expected revocation, got {:?}", other.map(|r| r.is_ok())),
+        }
+        assert!(subscription.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_expired_session_reauthenticates_and_retries() {
+        use crate::transport::JsonRpcMessage;
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        let fetches = Arc::new(AtomicU32::new(0));
+        let counter = Arc::clone(&fetches);
+        let config = ClientConfig::builder("ws://localhost:8080", "test-client")
+            .token_provider(move || {
+                let n = counter.fetch_add(1, Ordering::SeqCst);
+                async move { Ok(format!("token-{}", n)) }
+            })
+            .reconnect(
+                crate::ReconnectConfig::default().with_initial_delay(Duration::from_millis(10)),
+            )
+            .build()
+            .unwrap();
+
+        let mut transport = MockTransport::new().wait_when_empty();
+        transport.connect().await.unwrap();
+        let handle = transport.handle();
+        handle
+            .push_receive(
+                JsonRpcResponse::success(RequestId::Number(1), make_hello_response("sess_1")).into(),
+            )
+            .await;
+        let client = CauceClient::connect_with_transport(config, Box::new(transport))
+            .await
+            .unwrap();
+        let Some(JsonRpcMessage::Request(hello)) = handle.pop_sent().await else {
+            panic!("expected hello");
+        };
+        assert_eq!(hello.params().unwrap()["auth"]["token"], "token-0");
+
+        // Reject the first publish, then answer everything
+        let hub_handle = handle.clone();
+        let hub = tokio::spawn(async move {
+            let mut calls = Vec::new();
+            while calls.len() < 5 {
+                let Some(JsonRpcMessage::Request(request)) = hub_handle.pop_sent().await else {
+                    tokio::time::sleep(Duration::from_millis(2)).await;
+                    continue;
+                };
+                let id = request.id().clone();
+                let response = match request.method() {
+                    METHOD_HELLO => {
+                        let token = request.params().unwrap()["auth"]["token"].clone();
+                        calls.push(format!("hello:{}", token.as_str().unwrap()));
+                        JsonRpcResponse::success(id, make_hello_response("sess_2"))
+                    }
+                    METHOD_SUBSCRIBE => {
+                        calls.push("subscribe".to_string());
+                        JsonRpcResponse::success(
+                            id,
+                            make_subscribe_response("sub_1", &["signal.email.*"]),
+                        )
+                    }
+                    METHOD_PUBLISH if !calls.contains(&"publish".to_string()) => {
+                        calls.push("publish".to_string());
+                        JsonRpcResponse::error(
+                            Some(id),
+                            cauce_core::JsonRpcError::new(SESSION_EXPIRED, "Session expired"),
+                        )
+                    }
+                    method => {
+                        calls.push(method.to_string());
+                        JsonRpcResponse::success(id, make_publish_response("msg_001"))
+                    }
+                };
+                hub_handle.push_receive(response.into()).await;
+            }
+            calls
+        });
+
+        let _subscription = client.subscribe(&["signal.email.*"]).await.unwrap();
+        let signal = cauce_core::Signal {
+            id: "sig_001".to_string(),
+            version: "1.0".to_string(),
+            timestamp: chrono::Utc::now(),
+            source: Source::new("email", "adapter-1", "native-1"),
+            topic: Topic::new_unchecked("signal.email.received"),
+            payload: cauce_core::Payload::new(serde_json::json!({}), "application/json"),
+            metadata: None,
+            encrypted: None,
+            signature: None,
+        };
+        let published = client
+            .publish("signal.email.received", PublishMessage::Signal(signal))
+            .await
+            .unwrap();
+        assert_eq!(published.message_id, "msg_001");
+
+        assert_eq!(
+            hub.await.unwrap(),
+            ["subscribe", "publish", "hello:token-1", "subscribe", METHOD_PUBLISH]
+        );
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+        assert!(matches!(
+            handle.auth(),
+            Some(AuthConfig::Bearer { token }) if token == "token-1"
+        ));
+        assert_eq!(client.session_id().await.as_deref(), Some("sess_2"));
+    }
+
+    #[tokio::test]
+    async fn test_acl_denial_does_not_reauthenticate() {
+        use crate::transport::JsonRpcMessage;
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        let fetches = Arc::new(AtomicU32::new(0));
+        let counter = Arc::clone(&fetches);
+        let config = ClientConfig::builder("ws://localhost:8080", "test-client")
+            .token_provider(move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+                async { Ok("token".to_string()) }
+            })
+            .reconnect(
+                crate::ReconnectConfig::default().with_initial_delay(Duration::from_millis(10)),
+            )
+            .build()
+            .unwrap();
+
+        let mut transport = MockTransport::new().wait_when_empty();
+        transport.connect().await.unwrap();
+        let handle = transport.handle();
+        handle
+            .push_receive(
+                JsonRpcResponse::success(RequestId::Number(1), make_hello_response("sess_1")).into(),
+            )
+            .await;
+        let client = CauceClient::connect_with_transport(config, Box::new(transport))
+            .await
+            .unwrap();
+        handle.pop_sent().await;
+
+        // The hub denies the publish by ACL; the token is still valid
+        let hub_handle = handle.clone();
+        let hub = tokio::spawn(async move {
+            loop {
+                let Some(JsonRpcMessage::Request(request)) = hub_handle.pop_sent().await else {
+                    tokio::time::sleep(Duration::from_millis(2)).await;
+                    continue;
+                };
+                let response = JsonRpcResponse::error(
+                    Some(request.id().clone()),
+                    cauce_core::JsonRpcError::new(NOT_AUTHORIZED, "Not authorized"),
+                );
+                hub_handle.push_receive(response.into()).await;
+                return request.method().to_string();
+            }
+        });
+
+        let signal = cauce_core::Signal {
+            id: "sig_001".to_string(),
+            version: "1.0".to_string(),
+            timestamp: chrono::Utc::now(),
+            source: Source::new("email", "adapter-1", "native-1"),
+            topic: Topic::new_unchecked("signal.email.received"),
+            payload: cauce_core::Payload::new(serde_json::json!({}), "application/json"),
+            metadata: None,
+            encrypted: None,
+            signature: None,
+        };
+        let result = client
+            .publish("signal.email.received", PublishMessage::Signal(signal))
+            .await;
+        assert!(matches!(
+            result,
+            Err(ClientError::RpcError { code: NOT_AUTHORIZED, .. })
+        ));
+        assert_eq!(hub.await.unwrap(), METHOD_PUBLISH);
+
+        // No new token, no reconnect, no retry
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+        assert!(handle.pop_sent().await.is_none());
+        assert_eq!(client.session_id().await.as_deref(), Some("sess_1"));
+    }
+
+    #[tokio::test]
+    async fn test_ack_after_reauthentication_uses_restored_id() {
+        use crate::transport::JsonRpcMessage;
+
+        let config = ClientConfig::builder("ws://localhost:8080", "test-client")
+            .token_provider(|| async { Ok("token".to_string()) })
+            .reconnect(
+                crate::ReconnectConfig::default().with_initial_delay(Duration::from_millis(10)),
+            )
+            .build()
+            .unwrap();
+
+        let mut transport = MockTransport::new().wait_when_empty();
+        transport.connect().await.unwrap();
+        let handle = transport.handle();
+        handle
+            .push_receive(
+                JsonRpcResponse::success(RequestId::Number(1), make_hello_response("sess_1")).into(),
+            )
+            .await;
+        let client = CauceClient::connect_with_transport(config, Box::new(transport))
+            .await
+            .unwrap();
+        handle.pop_sent().await;
+
+        // Reject the first ack; the restored subscription does not know
+        // the signal, its predecessor does
+        let hub_handle = handle.clone();
+        let hub = tokio::spawn(async move {
+            let mut calls = Vec::new();
+            let mut subscribes = 0;
+            while calls.len() < 6 {
+                let Some(JsonRpcMessage::Request(request)) = hub_handle.pop_sent().await else {
+                    tokio::time::sleep(Duration::from_millis(2)).await;
+                    continue;
+                };
+                let id = request.id().clone();
+                let params = request.params().cloned().unwrap_or_default();
+                let response = match request.method() {
+                    METHOD_HELLO => {
+                        calls.push("hello".to_string());
+                        JsonRpcResponse::success(id, make_hello_response("sess_2"))
+                    }
+                    METHOD_SUBSCRIBE => {
+                        subscribes += 1;
+                        let hub_id = format!("sub_{}", subscribes);
+                        calls.push(format!("subscribe:{}", hub_id));
+                        JsonRpcResponse::success(
+                            id,
+                            make_subscribe_response(&hub_id, &["signal.email.*"]),
+                        )
+                    }
+                    _ => {
+                        let hub_id = params["subscription_id"].as_str().unwrap().to_string();
+                        calls.push(format!("ack:{}", hub_id));
+                        if calls.len() == 2 {
+                            JsonRpcResponse::error(
+                                Some(id),
+                                cauce_core::JsonRpcError::new(SESSION_EXPIRED, "Session expired"),
+                            )
+                        } else if hub_id == "sub_2" {
+                            let failed = [serde_json::json!({
+                                "signal_id": "sig_old",
+                                "reason": "unknown signal"
+                            })];
+                            JsonRpcResponse::success(
+                                id,
+                                serde_json::json!({ "acknowledged": [], "failed": failed }),
+                            )
+                        } else {
+                            JsonRpcResponse::success(id, make_ack_response(&["sig_old"]))
+                        }
+                    }
+                };
+                hub_handle.push_receive(response.into()).await;
+            }
+            calls
+        });
+
+        let subscription = client.subscribe(&["signal.email.*"]).await.unwrap();
+        let response = client
+            .ack(subscription.subscription_id(), &["sig_old"])
+            .await
+            .unwrap();
+        assert_eq!(response.acknowledged, ["sig_old"]);
+        assert!(response.failed.is_empty());
+
+        assert_eq!(
+            hub.await.unwrap(),
+            ["subscribe:sub_1", "ack:sub_1", "hello", "subscribe:sub_2", "ack:sub_2", "ack:sub_1"]
+        );
+    }
 }
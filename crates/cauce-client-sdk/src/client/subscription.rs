@@ -2,44 +2,84 @@
 //!
 //! A [`Subscription`] is returned when you subscribe to topics via
 //! [`CauceClient::subscribe`](super::CauceClient::subscribe).
-//! It provides an async stream interface for receiving signals that match
-//! the subscribed topic patterns.
+//! The message router delivers each `cauce.signal` notification into the
+//! bounded channel of the subscription it was addressed to, so subscriptions
+//! never see each other's traffic and a slow consumer only affects itself.
+//!
+//! `Subscription` implements [`futures::Stream`], so it composes with
+//! stream combinators.
+//!
+//! Subscriptions that need approval start out
+//! [`Pending`](SubscriptionStatus::Pending). The hub reports approval,
+//! denial and revocation with `cauce.subscription.status` notifications,
+//! which the handle tracks; see [`Subscription::status`] and
+//! [`Subscription::wait_until_active`].
+//!
+//! With end-to-end encryption configured (the `e2e` feature), each
+//! subscription decrypts [`Signal::encrypted`] before yielding the signal
+//! and skips copies encrypted for other subscribers.
+//!
+//! Signals are yielded without their delivery envelope. Use
+//! [`Subscription::next_delivery`] to also see whether the hub verified the
+//! signal's provenance signature.
 //!
 //! # Example
 //!
 //! ```ignore
 //! let mut subscription = client.subscribe(&["signal.email.*"]).await?;
 //!
-//! while let Some(signal) = subscription.next().await {
+//! while let Some(result) = subscription.next().await {
+//!     let signal = result?;
 //!     println!("Received: {}", signal.id);
 //!     client.ack(subscription.subscription_id(), &[&signal.id]).await?;
 //! }
 //! ```
 
-use cauce_core::{JsonRpcNotification, Signal, SignalDelivery, TopicMatcher, METHOD_SIGNAL};
-use tokio::sync::broadcast;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use cauce_core::{Signal, SignalDelivery, SubscriptionStatus, TopicMatcher};
+use futures::Stream;
+use tokio::sync::{mpsc, watch};
+
+#[cfg(feature = "e2e")]
+use super::encryption::SignalDecryptor;
+use super::TypedSubscription;
+use crate::error::ClientError;
+use crate::router::{StatusUpdate, SubscriptionChannel};
+use crate::ClientResult;
 
 /// A handle to an active subscription.
 ///
-/// Provides an async stream of signals matching the subscription's topic patterns.
-/// Use [`next()`](Self::next) to receive the next matching signal.
+/// Yields the signals the hub delivers for this subscription. Use
+/// [`next()`](Self::next) to receive the next signal, or consume it as a
+/// [`Stream`] of `ClientResult<Signal>`.
 ///
-/// # Topic Matching
+/// # Lag
 ///
-/// Signals are filtered based on the subscription's topic patterns:
-/// - Exact match: `signal.email.received` matches `signal.email.received`
-/// - Single wildcard: `signal.email.*` matches `signal.email.received`
-/// - Multi wildcard: `signal.**` matches `signal.email.received.urgent`
+/// Each subscription has a bounded channel (see
+/// [`RouterConfig::subscription_channel_capacity`](crate::RouterConfig::subscription_channel_capacity)).
+/// If it fills up because the consumer is too slow, further signals are
+/// dropped and the next receive returns [`ClientError::SubscriptionLagged`]
+/// with the number of dropped signals. Dropped signals are not acknowledged,
+/// so the hub will redeliver them.
 ///
 /// # Example
 ///
 /// ```ignore
-/// let mut subscription = client.subscribe(&["signal.email.*", "signal.slack.**"]).await?;
+/// use futures::StreamExt;
 ///
-/// // Receive next matching signal
-/// if let Some(signal) = subscription.next().await {
+/// let subscription = client.subscribe(&["signal.email.*", "signal.slack.**"]).await?;
+///
+/// let mut urgent = subscription
+///     .filter_map(|result| async move { result.ok() })
+///     .filter(|signal| futures::future::ready(signal.topic.as_str().ends_with("urgent")));
+///
+/// while let Some(signal) = urgent.next().await {
 ///     println!("Signal ID: {}", signal.id);
-///     println!("Topic: {:?}", signal.topic);
 /// }
 /// ```
 pub struct Subscription {
@@ -49,8 +89,21 @@ pub struct Subscription {
     /// Topic patterns this subscription covers.
     topics: Vec<String>,
 
-    /// Broadcast receiver for JSON-RPC notifications.
-    notification_rx: broadcast::Receiver<JsonRpcNotification>,
+    /// Receiver for deliveries routed to this subscription.
+    signal_rx: mpsc::Receiver<SignalDelivery>,
+
+    /// Count of signals dropped because the channel was full.
+    dropped: Arc<AtomicU64>,
+
+    /// Status updates from the hub.
+    status: watch::Receiver<StatusUpdate>,
+
+    /// Whether the end of the subscription has been reported.
+    end_reported: bool,
+
+    /// Decrypts end-to-end encrypted signals, if configured.
+    #[cfg(feature = "e2e")]
+    decryptor: Option<SignalDecryptor>,
 }
 
 impl Subscription {
@@ -60,19 +113,27 @@ impl Subscription {
     ///
     /// * `id` - The subscription ID from the hub
     /// * `topics` - The topic patterns subscribed to
-    /// * `notification_rx` - Receiver for incoming notifications
-    pub(crate) fn new(
-        id: String,
-        topics: Vec<String>,
-        notification_rx: broadcast::Receiver<JsonRpcNotification>,
-    ) -> Self {
+    /// * `channel` - The router channel for this subscription's signals
+    pub(crate) fn new(id: String, topics: Vec<String>, channel: SubscriptionChannel) -> Self {
         Self {
             id,
             topics,
-            notification_rx,
+            signal_rx: channel.rx,
+            dropped: channel.dropped,
+            status: channel.status,
+            end_reported: false,
+            #[cfg(feature = "e2e")]
+            decryptor: None,
         }
     }
 
+    /// Decrypt signals with `decryptor` before yielding them.
+    #[cfg(feature = "e2e")]
+    pub(super) fn with_decryptor(mut self, decryptor: SignalDecryptor) -> Self {
+        self.decryptor = Some(decryptor);
+        self
+    }
+
     /// Returns the subscription ID.
     ///
     /// This ID is used when acknowledging signals or unsubscribing.
@@ -123,104 +184,266 @@ impl Subscription {
             .any(|pattern| TopicMatcher::matches(topic, pattern))
     }
 
-    /// Returns the next signal matching this subscription.
+    /// Returns the current status of the subscription.
+    pub fn status(&self) -> SubscriptionStatus {
+        self.status.borrow().status
+    }
+
+    /// Returns the reason the hub gave for the current status, if any.
+    pub fn status_reason(&self) -> Option<String> {
+        self.status.borrow().reason.clone()
+    }
+
+    /// Waits for the next status change and returns the new status.
     ///
-    /// This method will block until a matching signal is received or
-    /// the subscription is closed.
+    /// Returns `None` once no further changes can arrive: the subscription
+    /// was denied, revoked or expired, was unsubscribed, or the client
+    /// disconnected.
+    pub async fn status_changed(&mut self) -> Option<SubscriptionStatus> {
+        if self.status.borrow().is_terminal() {
+            return None;
+        }
+        self.status.changed().await.ok()?;
+        Some(self.status())
+    }
+
+    /// Waits until the hub approves the subscription.
     ///
-    /// # Returns
+    /// Returns immediately if the subscription is already active.
+    ///
+    /// # Errors
     ///
-    /// - `Some(Signal)` - The next matching signal
-    /// - `None` - If the subscription is closed or the connection is lost
+    /// - [`ClientError::SubscriptionDenied`] - The hub denied the subscription
+    /// - [`ClientError::SubscriptionRevoked`] - The subscription was revoked
+    ///   or expired
+    /// - [`ClientError::RequestTimeout`] - Still pending after `timeout`
+    /// - [`ClientError::SubscriptionNotActive`] - No further updates can
+    ///   arrive (unsubscribed or disconnected) while pending
     ///
     /// # Example
     ///
     /// ```ignore
-    /// while let Some(signal) = subscription.next().await {
-    ///     println!("Received signal: {}", signal.id);
-    ///
-    ///     // Process the signal...
-    ///
-    ///     // Acknowledge receipt
-    ///     client.ack(subscription.subscription_id(), &[&signal.id]).await?;
-    /// }
+    /// let mut subscription = client.subscribe(&["signal.email.*"]).await?;
+    /// subscription.wait_until_active(Duration::from_secs(300)).await?;
     /// ```
-    pub async fn next(&mut self) -> Option<Signal> {
-        loop {
-            match self.notification_rx.recv().await {
-                Ok(notification) => {
-                    // Check if this is a signal notification
-                    if notification.method() != METHOD_SIGNAL {
-                        continue;
-                    }
-
-                    // Parse the signal delivery
-                    if let Some(params) = notification.params() {
-                        match serde_json::from_value::<SignalDelivery>(params.clone()) {
-                            Ok(delivery) => {
-                                // Check if the topic matches our subscription
-                                if self.matches_topic(&delivery.topic) {
-                                    return Some(delivery.signal);
-                                }
-                                // Topic doesn't match, continue waiting
-                            }
-                            Err(e) => {
-                                tracing::warn!("Failed to parse signal delivery: {}", e);
-                                continue;
-                            }
-                        }
-                    }
-                }
-                Err(broadcast::error::RecvError::Closed) => {
-                    // Channel closed, subscription is done
-                    return None;
+    pub async fn wait_until_active(&mut self, timeout: Duration) -> ClientResult<()> {
+        let wait = async {
+            loop {
+                if let Some(result) = self.settled() {
+                    return result;
                 }
-                Err(broadcast::error::RecvError::Lagged(n)) => {
-                    // We missed some messages, log and continue
-                    tracing::warn!("Subscription lagged behind by {} messages", n);
-                    continue;
+                if self.status.changed().await.is_err() {
+                    return self.settled().unwrap_or_else(|| {
+                        Err(ClientError::SubscriptionNotActive {
+                            id: self.id.clone(),
+                            status: "pending".to_string(),
+                        })
+                    });
                 }
             }
+        };
+
+        match tokio::time::timeout(timeout, wait).await {
+            Ok(result) => result,
+            Err(_) => Err(ClientError::RequestTimeout {
+                timeout_ms: timeout.as_millis() as u64,
+            }),
+        }
+    }
+
+    /// Returns the outcome of waiting for approval, or `None` while pending.
+    fn settled(&self) -> Option<ClientResult<()>> {
+        match self.status() {
+            SubscriptionStatus::Pending => None,
+            SubscriptionStatus::Active => Some(Ok(())),
+            _ => Some(Err(self.end_error())),
+        }
+    }
+
+    /// Build the error describing why the hub ended this subscription.
+    fn end_error(&self) -> ClientError {
+        let update = self.status.borrow();
+        let id = self.id.clone();
+        match update.status {
+            SubscriptionStatus::Denied => ClientError::SubscriptionDenied {
+                id,
+                reason: update
+                    .reason
+                    .clone()
+                    .unwrap_or_else(|| "no reason given".to_string()),
+            },
+            SubscriptionStatus::Expired => ClientError::SubscriptionRevoked {
+                id,
+                reason: update
+                    .reason
+                    .clone()
+                    .unwrap_or_else(|| "subscription expired".to_string()),
+            },
+            _ => ClientError::SubscriptionRevoked {
+                id,
+                reason: update
+                    .reason
+                    .clone()
+                    .unwrap_or_else(|| "no reason given".to_string()),
+            },
         }
     }
 
-    /// Attempts to receive the next signal without blocking.
+    /// Converts into a subscription that decodes payloads into `T`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let mut emails = subscription.typed::<Email>();
+    /// while let Some(Ok((signal, email))) = emails.next().await {
+    ///     println!("{}: {}", signal.id, email.subject);
+    /// }
+    /// ```
+    pub fn typed<T: serde::de::DeserializeOwned>(self) -> TypedSubscription<T> {
+        TypedSubscription::new(self)
+    }
+
+    /// Returns the next signal for this subscription.
+    ///
+    /// Waits until a signal is received or the subscription is closed.
     ///
     /// # Returns
     ///
-    /// - `Some(signal)` - A matching signal was available
-    /// - `None` - No matching signal available or subscription is closed
-    pub fn try_next(&mut self) -> Option<Signal> {
-        loop {
-            match self.notification_rx.try_recv() {
-                Ok(notification) => {
-                    if notification.method() != METHOD_SIGNAL {
-                        continue;
-                    }
+    /// - `Some(Ok(Signal))` - The next signal
+    /// - `Some(Err(ClientError::SubscriptionLagged { .. }))` - Signals were
+    ///   dropped because this subscription fell behind
+    /// - `Some(Err(ClientError::DecryptionFailed { .. }))` - An encrypted
+    ///   signal could not be decrypted
+    /// - `Some(Err(ClientError::UnencryptedSignal { .. }))` - A plaintext
+    ///   signal arrived while end-to-end encryption is required
+    /// - `Some(Err(ClientError::SubscriptionDenied { .. }))` or
+    ///   `Some(Err(ClientError::SubscriptionRevoked { .. }))` - The hub ended
+    ///   the subscription; the next call returns `None`
+    /// - `None` - The subscription was closed (unsubscribed or disconnected)
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// while let Some(result) = subscription.next().await {
+    ///     match result {
+    ///         Ok(signal) => {
+    ///             println!("Received signal: {}", signal.id);
+    ///             client.ack(subscription.subscription_id(), &[&signal.id]).await?;
+    ///         }
+    ///         Err(e) => eprintln!("Subscription error: {}", e),
+    ///     }
+    /// }
+    /// ```
+    pub async fn next(&mut self) -> Option<ClientResult<Signal>> {
+        std::future::poll_fn(|cx| self.poll_signal(cx)).await
+    }
 
-                    if let Some(params) = notification.params() {
-                        if let Ok(delivery) =
-                            serde_json::from_value::<SignalDelivery>(params.clone())
-                        {
-                            if self.matches_topic(&delivery.topic) {
-                                return Some(delivery.signal);
-                            }
-                        }
-                    }
-                }
-                Err(broadcast::error::TryRecvError::Empty) => {
-                    return None;
-                }
-                Err(broadcast::error::TryRecvError::Closed) => {
-                    return None;
+    /// Returns the next delivery for this subscription.
+    ///
+    /// Like [`next()`](Self::next), but yields the whole
+    /// [`SignalDelivery`], including
+    /// [`verified`](SignalDelivery::verified): whether the hub checked the
+    /// signal's provenance signature against a key registered for its
+    /// source adapter. Encrypted signals are decrypted in place.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// while let Some(result) = subscription.next_delivery().await {
+    ///     let delivery = result?;
+    ///     if !delivery.verified {
+    ///         continue;
+    ///     }
+    ///     println!("Verified signal: {}", delivery.signal.id);
+    /// }
+    /// ```
+    pub async fn next_delivery(&mut self) -> Option<ClientResult<SignalDelivery>> {
+        std::future::poll_fn(|cx| self.poll_delivery(cx)).await
+    }
+
+    /// Attempts to receive the next signal without waiting.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(Ok(signal))` - A signal was available
+    /// - `Some(Err(ClientError::SubscriptionLagged { .. }))` - Signals were dropped
+    /// - `Some(Err(ClientError::DecryptionFailed { .. }))` - An encrypted
+    ///   signal could not be decrypted
+    /// - `Some(Err(ClientError::UnencryptedSignal { .. }))` - A plaintext
+    ///   signal arrived while end-to-end encryption is required
+    /// - `None` - No signal available or the subscription is closed
+    pub fn try_next(&mut self) -> Option<ClientResult<Signal>> {
+        if let Some(err) = self.take_lagged() {
+            return Some(Err(err));
+        }
+        while let Ok(delivery) = self.signal_rx.try_recv() {
+            if let Some(result) = self.open(delivery) {
+                return Some(result.map(|delivery| delivery.signal));
+            }
+        }
+        None
+    }
+
+    /// Decrypt a received delivery's signal, or return `None` to skip a
+    /// copy that was encrypted for another subscriber.
+    fn open(&self, delivery: SignalDelivery) -> Option<ClientResult<SignalDelivery>> {
+        #[cfg(feature = "e2e")]
+        if let Some(decryptor) = &self.decryptor {
+            return decryptor
+                .open(&self.id, delivery.signal)
+                .map(|result| result.map(|signal| SignalDelivery { signal, ..delivery }));
+        }
+        Some(Ok(delivery))
+    }
+
+    /// Returns a lag error if signals were dropped since the last check.
+    fn take_lagged(&self) -> Option<ClientError> {
+        let missed = self.dropped.swap(0, Ordering::Relaxed);
+        (missed > 0).then(|| ClientError::SubscriptionLagged {
+            id: self.id.clone(),
+            missed,
+        })
+    }
+
+    /// Poll for the next signal.
+    fn poll_signal(&mut self, cx: &mut Context<'_>) -> Poll<Option<ClientResult<Signal>>> {
+        self.poll_delivery(cx)
+            .map(|item| item.map(|result| result.map(|delivery| delivery.signal)))
+    }
+
+    /// Poll for the next delivery, reporting lag first and a denial or
+    /// revocation last.
+    fn poll_delivery(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<ClientResult<SignalDelivery>>> {
+        if let Some(err) = self.take_lagged() {
+            return Poll::Ready(Some(Err(err)));
+        }
+        loop {
+            match self.signal_rx.poll_recv(cx) {
+                Poll::Ready(None) if !self.end_reported && self.status.borrow().is_terminal() => {
+                    self.end_reported = true;
+                    return Poll::Ready(Some(Err(self.end_error())));
                 }
-                Err(broadcast::error::TryRecvError::Lagged(_)) => {
-                    continue;
+                Poll::Ready(Some(delivery)) => {
+                    if let Some(result) = self.open(delivery) {
+                        return Poll::Ready(Some(result));
+                    }
                 }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
             }
         }
     }
+}
 
+impl Stream for Subscription {
+    type Item = ClientResult<Signal>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().poll_signal(cx)
+    }
 }
 
 #[cfg(test)]
@@ -228,10 +451,10 @@ mod tests {
     use super::*;
     use cauce_core::{Payload, Signal, Source, Topic};
     use chrono::Utc;
-    use tokio::sync::broadcast;
+    use futures::StreamExt;
 
-    fn make_signal(id: &str, topic: &str) -> Signal {
-        Signal {
+    fn make_delivery(id: &str, topic: &str) -> SignalDelivery {
+        let signal = Signal {
             id: id.to_string(),
             version: "1.0".to_string(),
             timestamp: Utc::now(),
@@ -240,195 +463,292 @@ mod tests {
             payload: Payload::new(serde_json::json!({}), "application/json"),
             metadata: None,
             encrypted: None,
-        }
+            signature: None,
+        };
+        SignalDelivery::new(topic, signal)
     }
 
-    fn make_signal_notification(topic: &str, signal: Signal) -> JsonRpcNotification {
-        let delivery = SignalDelivery::new(topic, signal);
-        JsonRpcNotification::new(
-            METHOD_SIGNAL.to_string(),
-            Some(serde_json::to_value(&delivery).unwrap()),
-        )
+    fn make_channel(
+        capacity: usize,
+    ) -> (
+        mpsc::Sender<SignalDelivery>,
+        Arc<AtomicU64>,
+        SubscriptionChannel,
+    ) {
+        let (tx, rx) = mpsc::channel(capacity);
+        let dropped = Arc::new(AtomicU64::new(0));
+        let (_status_tx, status) = watch::channel(StatusUpdate::new(SubscriptionStatus::Active));
+        let channel = SubscriptionChannel {
+            rx,
+            dropped: Arc::clone(&dropped),
+            status,
+        };
+        (tx, dropped, channel)
+    }
+
+    fn make_pending_subscription() -> (
+        mpsc::Sender<SignalDelivery>,
+        watch::Sender<StatusUpdate>,
+        Subscription,
+    ) {
+        let (tx, rx) = mpsc::channel(10);
+        let (status_tx, status) = watch::channel(StatusUpdate::new(SubscriptionStatus::Pending));
+        let channel = SubscriptionChannel {
+            rx,
+            dropped: Arc::new(AtomicU64::new(0)),
+            status,
+        };
+        let sub = Subscription::new("sub_123".to_string(), vec!["signal.**".to_string()], channel);
+        (tx, status_tx, sub)
+    }
+
+    fn make_subscription(
+        topics: &[&str],
+    ) -> (mpsc::Sender<SignalDelivery>, Arc<AtomicU64>, Subscription) {
+        let (tx, dropped, channel) = make_channel(10);
+        let sub = Subscription::new(
+            "sub_123".to_string(),
+            topics.iter().map(|t| t.to_string()).collect(),
+            channel,
+        );
+        (tx, dropped, sub)
     }
 
     #[test]
     fn test_subscription_id() {
-        let (tx, rx) = broadcast::channel(10);
-        let sub = Subscription::new("sub_123".to_string(), vec!["signal.*".to_string()], rx);
-
+        let (_tx, _dropped, sub) = make_subscription(&["signal.*"]);
         assert_eq!(sub.subscription_id(), "sub_123");
-        drop(tx);
     }
 
     #[test]
     fn test_subscription_topics() {
-        let (tx, rx) = broadcast::channel(10);
-        let sub = Subscription::new(
-            "sub_123".to_string(),
-            vec!["signal.email.*".to_string(), "signal.slack.**".to_string()],
-            rx,
-        );
+        let (_tx, _dropped, sub) = make_subscription(&["signal.email.*", "signal.slack.**"]);
 
         assert_eq!(sub.topics().len(), 2);
         assert_eq!(sub.topics()[0], "signal.email.*");
         assert_eq!(sub.topics()[1], "signal.slack.**");
-        drop(tx);
     }
 
     #[test]
     fn test_matches_topic_exact() {
-        let (tx, rx) = broadcast::channel(10);
-        let sub = Subscription::new(
-            "sub_123".to_string(),
-            vec!["signal.email.received".to_string()],
-            rx,
-        );
+        let (_tx, _dropped, sub) = make_subscription(&["signal.email.received"]);
 
         assert!(sub.matches_topic("signal.email.received"));
         assert!(!sub.matches_topic("signal.email.sent"));
-        drop(tx);
     }
 
     #[test]
     fn test_matches_topic_single_wildcard() {
-        let (tx, rx) = broadcast::channel(10);
-        let sub = Subscription::new("sub_123".to_string(), vec!["signal.email.*".to_string()], rx);
+        let (_tx, _dropped, sub) = make_subscription(&["signal.email.*"]);
 
         assert!(sub.matches_topic("signal.email.received"));
         assert!(sub.matches_topic("signal.email.sent"));
         assert!(!sub.matches_topic("signal.email.inbox.unread"));
         assert!(!sub.matches_topic("signal.slack.message"));
-        drop(tx);
     }
 
     #[test]
     fn test_matches_topic_multi_wildcard() {
-        let (tx, rx) = broadcast::channel(10);
-        let sub = Subscription::new("sub_123".to_string(), vec!["signal.**".to_string()], rx);
+        let (_tx, _dropped, sub) = make_subscription(&["signal.**"]);
 
         assert!(sub.matches_topic("signal.email"));
         assert!(sub.matches_topic("signal.email.received"));
         assert!(sub.matches_topic("signal.email.inbox.unread"));
         assert!(!sub.matches_topic("action.email.send"));
-        drop(tx);
     }
 
     #[test]
     fn test_matches_topic_multiple_patterns() {
-        let (tx, rx) = broadcast::channel(10);
-        let sub = Subscription::new(
-            "sub_123".to_string(),
-            vec!["signal.email.*".to_string(), "signal.slack.**".to_string()],
-            rx,
-        );
+        let (_tx, _dropped, sub) = make_subscription(&["signal.email.*", "signal.slack.**"]);
 
         assert!(sub.matches_topic("signal.email.received"));
         assert!(sub.matches_topic("signal.slack.message"));
         assert!(sub.matches_topic("signal.slack.channel.join"));
         assert!(!sub.matches_topic("signal.teams.message"));
-        drop(tx);
     }
 
     #[tokio::test]
-    async fn test_next_receives_matching_signal() {
-        let (tx, rx) = broadcast::channel(10);
-        let mut sub =
-            Subscription::new("sub_123".to_string(), vec!["signal.email.*".to_string()], rx);
+    async fn test_next_receives_signal() {
+        let (tx, _dropped, mut sub) = make_subscription(&["signal.email.*"]);
 
-        // Send a matching signal
-        let signal = make_signal("sig_001", "signal.email.received");
-        let notification = make_signal_notification("signal.email.received", signal.clone());
-        tx.send(notification).unwrap();
+        tx.send(make_delivery("sig_001", "signal.email.received"))
+            .await
+            .unwrap();
 
-        // Should receive the signal
-        let received = sub.next().await;
-        assert!(received.is_some());
-        assert_eq!(received.unwrap().id, "sig_001");
+        let received = sub.next().await.unwrap().unwrap();
+        assert_eq!(received.id, "sig_001");
     }
 
     #[tokio::test]
-    async fn test_next_filters_non_matching() {
-        let (tx, rx) = broadcast::channel(10);
-        let mut sub =
-            Subscription::new("sub_123".to_string(), vec!["signal.email.*".to_string()], rx);
-
-        // Send non-matching signal first
-        let non_matching = make_signal("sig_001", "signal.slack.message");
-        tx.send(make_signal_notification("signal.slack.message", non_matching))
-            .unwrap();
+    async fn test_next_delivery_reports_verified() {
+        let (tx, _dropped, mut sub) = make_subscription(&["signal.email.*"]);
 
-        // Send matching signal
-        let matching = make_signal("sig_002", "signal.email.received");
-        tx.send(make_signal_notification(
-            "signal.email.received",
-            matching.clone(),
-        ))
-        .unwrap();
+        tx.send(make_delivery("sig_001", "signal.email.received").with_verified(true))
+            .await
+            .unwrap();
+        tx.send(make_delivery("sig_002", "signal.email.received"))
+            .await
+            .unwrap();
 
-        // Should only receive the matching one
-        let received = sub.next().await;
-        assert!(received.is_some());
-        assert_eq!(received.unwrap().id, "sig_002");
+        let verified = sub.next_delivery().await.unwrap().unwrap();
+        assert_eq!(verified.signal.id, "sig_001");
+        assert!(verified.verified);
+        let unverified = sub.next_delivery().await.unwrap().unwrap();
+        assert_eq!(unverified.signal.id, "sig_002");
+        assert!(!unverified.verified);
     }
 
     #[tokio::test]
-    async fn test_next_ignores_non_signal_notifications() {
-        let (tx, rx) = broadcast::channel(10);
-        let mut sub =
-            Subscription::new("sub_123".to_string(), vec!["signal.email.*".to_string()], rx);
-
-        // Send a non-signal notification
-        let other = JsonRpcNotification::new("cauce.ping".to_string(), None);
-        tx.send(other).unwrap();
-
-        // Send matching signal
-        let signal = make_signal("sig_001", "signal.email.received");
-        tx.send(make_signal_notification("signal.email.received", signal))
+    async fn test_next_reports_lag_before_signals() {
+        let (tx, dropped, mut sub) = make_subscription(&["signal.email.*"]);
+
+        tx.send(make_delivery("sig_001", "signal.email.received"))
+            .await
             .unwrap();
+        dropped.fetch_add(3, Ordering::Relaxed);
 
-        // Should receive the signal
-        let received = sub.next().await;
-        assert!(received.is_some());
-        assert_eq!(received.unwrap().id, "sig_001");
+        match sub.next().await {
+            Some(Err(ClientError::SubscriptionLagged { id, missed })) => {
+                assert_eq!(id, "sub_123");
+                assert_eq!(missed, 3);
+            }
+            other => panic!("expected lag error, got {:?}", other.map(|r| r.is_ok())),
+        }
+
+        // Lag is reported once, then delivery continues
+        assert_eq!(sub.next().await.unwrap().unwrap().id, "sig_001");
     }
 
     #[tokio::test]
     async fn test_next_returns_none_on_close() {
-        let (tx, rx) = broadcast::channel::<JsonRpcNotification>(10);
-        let mut sub =
-            Subscription::new("sub_123".to_string(), vec!["signal.email.*".to_string()], rx);
+        let (tx, _dropped, mut sub) = make_subscription(&["signal.email.*"]);
 
         // Drop sender to close channel
         drop(tx);
 
-        // Should return None
-        let received = sub.next().await;
-        assert!(received.is_none());
+        assert!(sub.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_stream_combinators() {
+        let (tx, _dropped, sub) = make_subscription(&["signal.**"]);
+
+        for i in 0..3 {
+            tx.send(make_delivery(
+                &format!("sig_{}", i),
+                "signal.email.received",
+            ))
+            .await
+            .unwrap();
+        }
+        drop(tx);
+
+        let ids: Vec<String> = sub
+            .filter_map(|result| async move { result.ok() })
+            .map(|signal| signal.id)
+            .collect()
+            .await;
+        assert_eq!(ids, vec!["sig_0", "sig_1", "sig_2"]);
     }
 
     #[test]
     fn test_try_next_empty() {
-        let (tx, rx) = broadcast::channel(10);
-        let mut sub =
-            Subscription::new("sub_123".to_string(), vec!["signal.email.*".to_string()], rx);
+        let (_tx, _dropped, mut sub) = make_subscription(&["signal.email.*"]);
 
         // No messages available
-        let result = sub.try_next();
-        assert!(result.is_none());
-        drop(tx);
+        assert!(sub.try_next().is_none());
+    }
+
+    #[test]
+    fn test_try_next_available() {
+        let (tx, _dropped, mut sub) = make_subscription(&["signal.email.*"]);
+        tx.try_send(make_delivery("sig_001", "signal.email.received"))
+            .unwrap();
+
+        assert_eq!(sub.try_next().unwrap().unwrap().id, "sig_001");
     }
 
     #[test]
     fn test_try_next_closed() {
-        let (tx, rx) = broadcast::channel::<JsonRpcNotification>(10);
-        let mut sub =
-            Subscription::new("sub_123".to_string(), vec!["signal.email.*".to_string()], rx);
+        let (tx, _dropped, mut sub) = make_subscription(&["signal.email.*"]);
 
         // Close channel
         drop(tx);
 
         // Should return None when closed
-        let result = sub.try_next();
-        assert!(result.is_none());
+        assert!(sub.try_next().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_active() {
+        let (_tx, status_tx, mut sub) = make_pending_subscription();
+        assert_eq!(sub.status(), SubscriptionStatus::Pending);
+
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            status_tx.send_replace(StatusUpdate::new(SubscriptionStatus::Active));
+            // Keep the sender alive until the waiter has seen the update
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        });
+
+        sub.wait_until_active(Duration::from_secs(2)).await.unwrap();
+        assert_eq!(sub.status(), SubscriptionStatus::Active);
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_active_timeout() {
+        let (_tx, _status_tx, mut sub) = make_pending_subscription();
+
+        let result = sub.wait_until_active(Duration::from_millis(10)).await;
+        assert!(matches!(
+            result,
+            Err(ClientError::RequestTimeout { timeout_ms: 10 })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_denied_surfaces_reason() {
+        let (tx, status_tx, mut sub) = make_pending_subscription();
+
+        status_tx.send_replace(StatusUpdate {
+            status: SubscriptionStatus::Denied,
+            reason: Some("not on the allow list".to_string()),
+        });
+        drop(tx);
+
+        match sub.wait_until_active(Duration::from_secs(1)).await {
+            Err(ClientError::SubscriptionDenied { id, reason }) => {
+                assert_eq!(id, "sub_123");
+                assert_eq!(reason, "not on the allow list");
+            }
+            other => panic!("expected denial, got {:?}", other),
+        }
+        assert_eq!(sub.status_reason().as_deref(), Some("not on the allow list"));
+        assert!(sub.status_changed().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_revocation_ends_stream_with_error() {
+        let (tx, status_tx, mut sub) = make_pending_subscription();
+        status_tx.send_replace(StatusUpdate::new(SubscriptionStatus::Active));
+        tx.send(make_delivery("sig_001", "signal.email.received"))
+            .await
+            .unwrap();
+
+        status_tx.send_replace(StatusUpdate {
+            status: SubscriptionStatus::Revoked,
+            reason: Some("owner revoked access".to_string()),
+        });
+        drop(tx);
+
+        // Signals already delivered are still received first
+        assert_eq!(sub.next().await.unwrap().unwrap().id, "sig_001");
+        match sub.next().await {
+            Some(Err(ClientError::SubscriptionRevoked { reason, .. })) => {
+                assert_eq!(reason, "owner revoked access");
+            }
+            other => panic!("expected revocation, got {:?}", other.map(|r| r.is_ok())),
+        }
+        assert!(sub.next().await.is_none());
     }
 }
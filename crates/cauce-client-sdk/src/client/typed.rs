@@ -0,0 +1,213 @@
+//! Typed subscription handle.
+//!
+//! A [`TypedSubscription`] wraps a [`Subscription`] and decodes each signal's
+//! payload into a caller-chosen type. Decoding failures are reported per
+//! signal as [`ClientError::PayloadDecode`]; the stream keeps going.
+//!
+//! # Example
+//!
+//! ```ignore
+//! #[derive(Deserialize)]
+//! struct Email {
+//!     from: String,
+//!     subject: String,
+//! }
+//!
+//! let mut emails = client.subscribe_typed::<Email>(&["signal.email.*"]).await?;
+//!
+//! while let Some(result) = emails.next().await {
+//!     match result {
+//!         Ok((signal, email)) => {
+//!             println!("{} from {}", email.subject, email.from);
+//!             client.ack(emails.subscription_id(), &[&signal.id]).await?;
+//!         }
+//!         Err(e) => eprintln!("Skipping signal: {}", e),
+//!     }
+//! }
+//! ```
+
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use cauce_core::{Signal, SubscriptionStatus};
+use futures::Stream;
+use serde::de::DeserializeOwned;
+
+use super::Subscription;
+use crate::error::ClientError;
+use crate::ClientResult;
+
+/// A subscription whose signal payloads are decoded into `T`.
+///
+/// Created with [`CauceClient::subscribe_typed`](super::CauceClient::subscribe_typed)
+/// or [`Subscription::typed`]. Yields `(Signal, T)` pairs; the signal is
+/// kept so it can still be acknowledged.
+pub struct TypedSubscription<T> {
+    /// The underlying untyped subscription.
+    inner: Subscription,
+
+    /// The payload type; `fn() -> T` keeps the handle `Send` and `Sync`
+    /// regardless of `T`.
+    _payload: PhantomData<fn() -> T>,
+}
+
+impl<T: DeserializeOwned> TypedSubscription<T> {
+    /// Wrap an untyped subscription.
+    pub(crate) fn new(inner: Subscription) -> Self {
+        Self {
+            inner,
+            _payload: PhantomData,
+        }
+    }
+
+    /// Returns the subscription ID.
+    pub fn subscription_id(&self) -> &str {
+        self.inner.subscription_id()
+    }
+
+    /// Returns the topic patterns this subscription covers.
+    pub fn topics(&self) -> &[String] {
+        self.inner.topics()
+    }
+
+    /// Returns the current status of the subscription.
+    pub fn status(&self) -> SubscriptionStatus {
+        self.inner.status()
+    }
+
+    /// Waits until the hub approves the subscription.
+    ///
+    /// See [`Subscription::wait_until_active`].
+    pub async fn wait_until_active(&mut self, timeout: Duration) -> ClientResult<()> {
+        self.inner.wait_until_active(timeout).await
+    }
+
+    /// Returns the next signal together with its decoded payload.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(Ok((signal, payload)))` - The next signal
+    /// - `Some(Err(ClientError::PayloadDecode { .. }))` - The payload did not
+    ///   match `T`; the signal is skipped and left unacknowledged
+    /// - `Some(Err(ClientError::SubscriptionLagged { .. }))` - Signals were dropped
+    /// - `None` - The subscription was closed
+    pub async fn next(&mut self) -> Option<ClientResult<(Signal, T)>> {
+        self.inner.next().await.map(|result| result.and_then(decode))
+    }
+
+    /// Unwrap into the underlying untyped subscription.
+    pub fn into_inner(self) -> Subscription {
+        self.inner
+    }
+}
+
+impl<T: DeserializeOwned> Stream for TypedSubscription<T> {
+    type Item = ClientResult<(Signal, T)>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.get_mut().inner)
+            .poll_next(cx)
+            .map(|item| item.map(|result| result.and_then(decode)))
+    }
+}
+
+/// Decode a signal's payload into `T`.
+fn decode<T: DeserializeOwned>(signal: Signal) -> ClientResult<(Signal, T)> {
+    match signal.decode_payload() {
+        Ok(payload) => Ok((signal, payload)),
+        Err(e) => Err(ClientError::PayloadDecode {
+            signal_id: signal.id,
+            message: e.to_string(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router::{StatusUpdate, SubscriptionChannel};
+    use cauce_core::{Payload, SignalDelivery, Source, SubscriptionStatus, Topic};
+    use futures::StreamExt;
+    use serde::Deserialize;
+    use std::sync::atomic::AtomicU64;
+    use std::sync::Arc;
+    use tokio::sync::{mpsc, watch};
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Email {
+        from: String,
+    }
+
+    fn make_delivery(id: &str, raw: serde_json::Value) -> SignalDelivery {
+        let signal = Signal {
+            id: id.to_string(),
+            version: "1.0".to_string(),
+            timestamp: chrono::Utc::now(),
+            source: Source::new("email", "adapter-1", "native-123"),
+            topic: Topic::new_unchecked("signal.email.received"),
+            payload: Payload::new(raw, "application/json"),
+            metadata: None,
+            encrypted: None,
+            signature: None,
+        };
+        SignalDelivery::new("signal.email.received", signal)
+    }
+
+    fn make_typed() -> (mpsc::Sender<SignalDelivery>, TypedSubscription<Email>) {
+        let (tx, rx) = mpsc::channel(10);
+        let (_status_tx, status) = watch::channel(StatusUpdate::new(SubscriptionStatus::Active));
+        let channel = SubscriptionChannel {
+            rx,
+            dropped: Arc::new(AtomicU64::new(0)),
+            status,
+        };
+        let sub = Subscription::new(
+            "sub_123".to_string(),
+            vec!["signal.email.*".to_string()],
+            channel,
+        );
+        (tx, sub.typed())
+    }
+
+    #[tokio::test]
+    async fn test_next_decodes_payload() {
+        let (tx, mut typed) = make_typed();
+        tx.send(make_delivery("sig_1", serde_json::json!({"from": "alice"})))
+            .await
+            .unwrap();
+
+        let (signal, email) = typed.next().await.unwrap().unwrap();
+        assert_eq!(signal.id, "sig_1");
+        assert_eq!(email.from, "alice");
+        assert_eq!(typed.subscription_id(), "sub_123");
+    }
+
+    #[tokio::test]
+    async fn test_decode_failure_does_not_end_stream() {
+        let (tx, typed) = make_typed();
+        tx.send(make_delivery(
+            "sig_bad",
+            serde_json::json!({"subject": "no sender"}),
+        ))
+        .await
+        .unwrap();
+        tx.send(make_delivery(
+            "sig_good",
+            serde_json::json!({"from": "bob"}),
+        ))
+        .await
+        .unwrap();
+        drop(tx);
+
+        let results: Vec<_> = typed.collect().await;
+        assert_eq!(results.len(), 2);
+        match &results[0] {
+            Err(ClientError::PayloadDecode { signal_id, .. }) => assert_eq!(signal_id, "sig_bad"),
+            other => panic!("expected decode error, got {:?}", other.as_ref().map(|(s, _)| &s.id)),
+        }
+        let (_, email) = results[1].as_ref().unwrap();
+        assert_eq!(email.from, "bob");
+    }
+}
@@ -0,0 +1,145 @@
+//! End-to-end encryption configuration for the Cauce Client SDK.
+//!
+//! This module provides [`E2eOptions`], which holds the key pair a client
+//! decrypts its subscriptions with and the algorithms it accepts.
+
+use cauce_core::e2e::{KeyPair, PublicKey, SUPPORTED_ALGORITHMS};
+use cauce_core::types::EncryptionAlgorithm;
+use cauce_core::E2eConfig;
+
+/// End-to-end encryption settings.
+///
+/// With these set, every subscription registers the public key with the
+/// hub and decrypts [`Signal::encrypted`](cauce_core::Signal::encrypted)
+/// before yielding the signal. Signals that arrive unencrypted are rejected
+/// unless [`with_require_encryption(false)`](Self::with_require_encryption)
+/// is set, and
+/// [`CauceClient::publish_encrypted`](crate::CauceClient::publish_encrypted)
+/// can be used to publish.
+///
+/// # Example
+///
+/// ```rust
+/// use cauce_client_sdk::{ClientConfig, E2eOptions};
+/// use cauce_core::e2e::KeyPair;
+///
+/// let config = ClientConfig::builder("wss://hub.example.com", "my-agent")
+///     .e2e(E2eOptions::new(KeyPair::generate()).with_ack_on_failure(true))
+///     .build()
+///     .expect("valid config");
+/// ```
+#[derive(Debug, Clone)]
+pub struct E2eOptions {
+    /// The key pair signals are decrypted with.
+    keys: KeyPair,
+
+    /// Algorithms to accept, in order of preference.
+    algorithms: Vec<EncryptionAlgorithm>,
+
+    /// Acknowledge signals that fail to decrypt instead of leaving them for
+    /// redelivery.
+    ack_on_failure: bool,
+
+    /// Reject signals that arrive unencrypted.
+    require_encryption: bool,
+}
+
+impl E2eOptions {
+    /// Create options that decrypt with `keys` and accept every supported
+    /// algorithm.
+    pub fn new(keys: KeyPair) -> Self {
+        Self {
+            keys,
+            algorithms: SUPPORTED_ALGORITHMS.to_vec(),
+            ack_on_failure: false,
+            require_encryption: true,
+        }
+    }
+
+    /// Create options with a freshly generated key pair.
+    pub fn generate() -> Self {
+        Self::new(KeyPair::generate())
+    }
+
+    /// Set the algorithms to accept, in order of preference.
+    pub fn with_algorithms(mut self, algorithms: Vec<EncryptionAlgorithm>) -> Self {
+        self.algorithms = algorithms;
+        self
+    }
+
+    /// Set whether signals that fail to decrypt are acknowledged.
+    ///
+    /// By default they are left unacknowledged, so the hub redelivers them,
+    /// e.g. after the key is rotated back. Acknowledging them drops them for
+    /// good.
+    pub fn with_ack_on_failure(mut self, ack: bool) -> Self {
+        self.ack_on_failure = ack;
+        self
+    }
+
+    /// Set whether signals that arrive unencrypted are rejected.
+    ///
+    /// On by default: a plaintext signal on an encrypted subscription is
+    /// reported as [`ClientError::UnencryptedSignal`](crate::ClientError::UnencryptedSignal)
+    /// and handled like one that failed to decrypt, so a publisher or hub
+    /// can't silently downgrade the subscription. Turn it off to accept a
+    /// mix of encrypted and plaintext signals.
+    pub fn with_require_encryption(mut self, require: bool) -> Self {
+        self.require_encryption = require;
+        self
+    }
+
+    /// Returns the key pair.
+    pub fn keys(&self) -> &KeyPair {
+        &self.keys
+    }
+
+    /// Returns the public key registered with subscriptions.
+    pub fn public_key(&self) -> &PublicKey {
+        self.keys.public_key()
+    }
+
+    /// Returns the accepted algorithms, in order of preference.
+    pub fn algorithms(&self) -> &[EncryptionAlgorithm] {
+        &self.algorithms
+    }
+
+    /// Returns whether signals that fail to decrypt are acknowledged.
+    pub fn ack_on_failure(&self) -> bool {
+        self.ack_on_failure
+    }
+
+    /// Returns whether signals that arrive unencrypted are rejected.
+    pub fn require_encryption(&self) -> bool {
+        self.require_encryption
+    }
+
+    /// Build the `e2e` section of a subscribe request.
+    pub(crate) fn subscribe_config(&self) -> E2eConfig {
+        self.algorithms.iter().fold(
+            E2eConfig::enabled(self.public_key().to_base64()),
+            |config, algorithm| config.with_algorithm(*algorithm),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subscribe_config() {
+        let options =
+            E2eOptions::generate().with_algorithms(vec![EncryptionAlgorithm::XChaCha20Poly1305]);
+        assert!(!options.ack_on_failure());
+        assert!(options.require_encryption());
+
+        let config = options.subscribe_config();
+        assert!(config.enabled);
+        assert_eq!(config.public_key, Some(options.public_key().to_base64()));
+        assert_eq!(
+            config.supported_algorithms,
+            vec![EncryptionAlgorithm::XChaCha20Poly1305]
+        );
+    }
+}
@@ -22,16 +22,26 @@
 //! ```
 
 mod auth;
+#[cfg(feature = "e2e")]
+mod e2e;
 mod reconnect;
 mod tls;
+mod token;
 
 pub use auth::AuthConfig;
-pub use reconnect::ReconnectConfig;
+#[cfg(feature = "e2e")]
+pub use e2e::E2eOptions;
+pub(crate) use reconnect::random_index;
+pub use reconnect::{HubSelection, ReconnectConfig};
 pub use tls::TlsConfig;
+pub(crate) use token::TokenCache;
+pub use token::TokenProvider;
 
 use crate::error::ClientError;
+use crate::router::Interceptor;
 use cauce_core::{ClientType, Transport as TransportType};
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use std::time::Duration;
 
 /// Configuration for connecting to a Cauce Hub.
@@ -55,6 +65,10 @@ pub struct ClientConfig {
     /// Hub URL (ws://, wss://, http://, or https://).
     pub hub_url: String,
 
+    /// Additional hub URLs to fail over to, in preference order.
+    #[serde(default)]
+    pub fallback_urls: Vec<String>,
+
     /// Unique client identifier.
     pub client_id: String,
 
@@ -64,6 +78,14 @@ pub struct ClientConfig {
     /// Authentication configuration.
     pub auth: Option<AuthConfig>,
 
+    /// Source of short-lived bearer tokens, used instead of a static `auth`.
+    #[serde(skip)]
+    pub token_provider: Option<Arc<dyn TokenProvider>>,
+
+    /// How long before a token's `exp` claim it is refreshed.
+    #[serde(default = "default_token_refresh_margin", with = "duration_secs")]
+    pub token_refresh_margin: Duration,
+
     /// Preferred transport type.
     pub transport: TransportType,
 
@@ -81,15 +103,29 @@ pub struct ClientConfig {
     #[serde(with = "duration_secs")]
     pub request_timeout: Duration,
 
-    /// Keepalive ping interval.
+    /// Keepalive ping interval. Zero disables the heartbeat.
     #[serde(with = "duration_secs")]
     pub keepalive_interval: Duration,
 
+    /// Consecutive unanswered keepalive pings before the connection is
+    /// considered dead.
+    #[serde(default = "default_max_missed_pongs")]
+    pub max_missed_pongs: u32,
+
     /// Protocol version to use.
     pub protocol_version: String,
 
     /// Minimum protocol version to accept from server.
     pub min_protocol_version: String,
+
+    /// Interceptors around requests and notifications, outermost first.
+    #[serde(skip)]
+    pub interceptors: Vec<Arc<dyn Interceptor>>,
+
+    /// End-to-end encryption settings.
+    #[cfg(feature = "e2e")]
+    #[serde(skip)]
+    pub e2e: Option<E2eOptions>,
 }
 
 impl ClientConfig {
@@ -133,12 +169,36 @@ impl ClientConfig {
                 valid_schemes.join(", ")
             )));
         }
+        if let Some(url) = self
+            .fallback_urls
+            .iter()
+            .find(|url| !valid_schemes.iter().any(|s| url.starts_with(s)))
+        {
+            return Err(ClientError::config_error(format!(
+                "fallback URL {:?} must start with one of: {}",
+                url,
+                valid_schemes.join(", ")
+            )));
+        }
 
         // Validate client_id
         if self.client_id.is_empty() {
             return Err(ClientError::config_error("client_id cannot be empty"));
         }
 
+        // A heartbeat that tolerates no misses could never declare a dead link
+        if !self.keepalive_interval.is_zero() && self.max_missed_pongs == 0 {
+            return Err(ClientError::config_error(
+                "max_missed_pongs must be at least 1 when keepalive is enabled",
+            ));
+        }
+
+        if self.auth.is_some() && self.token_provider.is_some() {
+            return Err(ClientError::config_error(
+                "auth and token_provider cannot both be set",
+            ));
+        }
+
         // Validate TLS config if present
         if let Some(ref tls) = self.tls {
             tls.validate()
@@ -148,6 +208,14 @@ impl ClientConfig {
         Ok(())
     }
 
+    /// Returns every hub endpoint: `hub_url` followed by the fallback URLs.
+    pub fn endpoints(&self) -> Vec<String> {
+        std::iter::once(&self.hub_url)
+            .chain(&self.fallback_urls)
+            .cloned()
+            .collect()
+    }
+
     /// Returns the WebSocket URL for this configuration.
     ///
     /// Converts http:// to ws:// and https:// to wss:// if necessary.
@@ -186,17 +254,24 @@ impl ClientConfig {
 #[derive(Debug, Clone)]
 pub struct ClientConfigBuilder {
     hub_url: String,
+    fallback_urls: Vec<String>,
     client_id: String,
     client_type: ClientType,
     auth: Option<AuthConfig>,
+    token_provider: Option<Arc<dyn TokenProvider>>,
+    token_refresh_margin: Duration,
     transport: TransportType,
     reconnect: ReconnectConfig,
     tls: Option<TlsConfig>,
     connect_timeout: Duration,
     request_timeout: Duration,
     keepalive_interval: Duration,
+    max_missed_pongs: u32,
     protocol_version: String,
     min_protocol_version: String,
+    interceptors: Vec<Arc<dyn Interceptor>>,
+    #[cfg(feature = "e2e")]
+    e2e: Option<E2eOptions>,
 }
 
 impl ClientConfigBuilder {
@@ -204,20 +279,36 @@ impl ClientConfigBuilder {
     fn new(hub_url: impl Into<String>, client_id: impl Into<String>) -> Self {
         Self {
             hub_url: hub_url.into(),
+            fallback_urls: Vec::new(),
             client_id: client_id.into(),
             client_type: ClientType::Agent,
             auth: None,
+            token_provider: None,
+            token_refresh_margin: default_token_refresh_margin(),
             transport: TransportType::WebSocket,
             reconnect: ReconnectConfig::default(),
             tls: None,
             connect_timeout: Duration::from_secs(30),
             request_timeout: Duration::from_secs(60),
             keepalive_interval: Duration::from_secs(30),
+            max_missed_pongs: default_max_missed_pongs(),
             protocol_version: "1.0".to_string(),
             min_protocol_version: "1.0".to_string(),
+            interceptors: Vec::new(),
+            #[cfg(feature = "e2e")]
+            e2e: None,
         }
     }
 
+    /// Add a hub to fail over to when the preferred ones are unreachable.
+    ///
+    /// Fallbacks are tried in the order they are added; see
+    /// [`ReconnectConfig::hub_selection`] for how endpoints are chosen.
+    pub fn fallback_url(mut self, url: impl Into<String>) -> Self {
+        self.fallback_urls.push(url.into());
+        self
+    }
+
     /// Set the client type.
     pub fn client_type(mut self, client_type: ClientType) -> Self {
         self.client_type = client_type;
@@ -230,6 +321,30 @@ impl ClientConfigBuilder {
         self
     }
 
+    /// Fetch bearer tokens from `provider` instead of a static `auth`.
+    ///
+    /// The provider is called before connecting, shortly before a JWT's
+    /// `exp` claim, and whenever the hub reports the session as expired or
+    /// rejects a token whose `exp` has passed. The client then reconnects
+    /// with the new token and restores its subscriptions. Other
+    /// authorization errors, such as an ACL denial, are returned as they
+    /// are.
+    ///
+    /// Restored subscriptions get new hub IDs, as after any reconnect.
+    /// Signals already delivered can still be acknowledged, but signals the
+    /// hub queued for the old session and had not delivered yet are not
+    /// redelivered to the restored subscriptions.
+    pub fn token_provider(mut self, provider: impl TokenProvider) -> Self {
+        self.token_provider = Some(Arc::new(provider));
+        self
+    }
+
+    /// Set how long before a token expires it is refreshed.
+    pub fn token_refresh_margin(mut self, margin: Duration) -> Self {
+        self.token_refresh_margin = margin;
+        self
+    }
+
     /// Set the preferred transport type.
     pub fn transport(mut self, transport: TransportType) -> Self {
         self.transport = transport;
@@ -261,11 +376,20 @@ impl ClientConfigBuilder {
     }
 
     /// Set the keepalive ping interval.
+    ///
+    /// A zero interval disables the heartbeat.
     pub fn keepalive_interval(mut self, interval: Duration) -> Self {
         self.keepalive_interval = interval;
         self
     }
 
+    /// Set how many consecutive keepalive pings may go unanswered before
+    /// the connection is considered dead.
+    pub fn max_missed_pongs(mut self, max_missed: u32) -> Self {
+        self.max_missed_pongs = max_missed;
+        self
+    }
+
     /// Set the protocol version.
     pub fn protocol_version(mut self, version: impl Into<String>) -> Self {
         self.protocol_version = version.into();
@@ -278,23 +402,49 @@ impl ClientConfigBuilder {
         self
     }
 
+    /// Add an interceptor around requests and notifications.
+    ///
+    /// Interceptors see requests and notifications in the order they are
+    /// added, and responses in reverse order.
+    pub fn interceptor(mut self, interceptor: impl Interceptor) -> Self {
+        self.interceptors.push(Arc::new(interceptor));
+        self
+    }
+
+    /// Enable end-to-end encryption.
+    ///
+    /// Subscriptions register the public key and decrypt signals with the
+    /// secret key; see [`E2eOptions`].
+    #[cfg(feature = "e2e")]
+    pub fn e2e(mut self, options: E2eOptions) -> Self {
+        self.e2e = Some(options);
+        self
+    }
+
     /// Build the configuration.
     ///
     /// Returns an error if the configuration is invalid.
     pub fn build(self) -> Result<ClientConfig, ClientError> {
         let config = ClientConfig {
             hub_url: self.hub_url,
+            fallback_urls: self.fallback_urls,
             client_id: self.client_id,
             client_type: self.client_type,
             auth: self.auth,
+            token_provider: self.token_provider,
+            token_refresh_margin: self.token_refresh_margin,
             transport: self.transport,
             reconnect: self.reconnect,
             tls: self.tls,
             connect_timeout: self.connect_timeout,
             request_timeout: self.request_timeout,
             keepalive_interval: self.keepalive_interval,
+            max_missed_pongs: self.max_missed_pongs,
             protocol_version: self.protocol_version,
             min_protocol_version: self.min_protocol_version,
+            interceptors: self.interceptors,
+            #[cfg(feature = "e2e")]
+            e2e: self.e2e,
         };
 
         config.validate()?;
@@ -302,6 +452,16 @@ impl ClientConfigBuilder {
     }
 }
 
+/// Default number of missed pongs before a connection is declared dead.
+fn default_max_missed_pongs() -> u32 {
+    3
+}
+
+/// Default time before expiry that bearer tokens are refreshed.
+fn default_token_refresh_margin() -> Duration {
+    Duration::from_secs(60)
+}
+
 /// Serde helper for serializing Duration as seconds.
 mod duration_secs {
     use serde::{Deserialize, Deserializer, Serializer};
@@ -350,6 +510,7 @@ mod tests {
             .connect_timeout(Duration::from_secs(60))
             .request_timeout(Duration::from_secs(120))
             .keepalive_interval(Duration::from_secs(45))
+            .max_missed_pongs(5)
             .protocol_version("1.1")
             .build()
             .expect("should build");
@@ -359,6 +520,7 @@ mod tests {
         assert_eq!(config.connect_timeout, Duration::from_secs(60));
         assert_eq!(config.request_timeout, Duration::from_secs(120));
         assert_eq!(config.keepalive_interval, Duration::from_secs(45));
+        assert_eq!(config.max_missed_pongs, 5);
         assert_eq!(config.protocol_version, "1.1");
     }
 
@@ -374,6 +536,23 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_fallback_urls() {
+        let config = ClientConfig::builder("wss://primary.example.com", "my-agent")
+            .fallback_url("wss://standby.example.com")
+            .build()
+            .unwrap();
+        assert_eq!(
+            config.endpoints(),
+            vec!["wss://primary.example.com", "wss://standby.example.com"]
+        );
+
+        let result = ClientConfig::builder("wss://primary.example.com", "my-agent")
+            .fallback_url("standby.example.com")
+            .build();
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_validate_empty_client_id() {
         let result = ClientConfig::builder("wss://hub.example.com", "").build();
@@ -469,5 +648,80 @@ mod tests {
         assert_eq!(config.hub_url, "wss://hub.example.com");
         assert_eq!(config.client_id, "test-agent");
         assert_eq!(config.client_type, ClientType::Agent);
+        assert_eq!(config.max_missed_pongs, 3);
+    }
+
+    #[test]
+    fn test_zero_missed_pongs_rejected() {
+        let result = ClientConfig::builder("wss://hub.example.com", "agent")
+            .max_missed_pongs(0)
+            .build();
+        assert!(result.is_err());
+
+        // Allowed when the heartbeat is disabled
+        let result = ClientConfig::builder("wss://hub.example.com", "agent")
+            .keepalive_interval(Duration::ZERO)
+            .max_missed_pongs(0)
+            .build();
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_token_provider() {
+        let config = ClientConfig::builder("wss://hub.example.com", "agent")
+            .token_provider(|| async { Ok("token".to_string()) })
+            .token_refresh_margin(Duration::from_secs(30))
+            .build()
+            .expect("should build");
+        assert!(config.token_provider.is_some());
+        assert_eq!(config.token_refresh_margin, Duration::from_secs(30));
+
+        // Providers are not serialized
+        let json = serde_json::to_string(&config).unwrap();
+        let parsed: ClientConfig = serde_json::from_str(&json).unwrap();
+        assert!(parsed.token_provider.is_none());
+
+        let result = ClientConfig::builder("wss://hub.example.com", "agent")
+            .auth(AuthConfig::bearer("static"))
+            .token_provider(|| async { Ok("token".to_string()) })
+            .build();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_interceptors() {
+        struct Noop;
+        impl Interceptor for Noop {}
+
+        let config = ClientConfig::builder("wss://hub.example.com", "agent")
+            .interceptor(Noop)
+            .interceptor(Noop)
+            .build()
+            .expect("should build");
+        assert_eq!(config.interceptors.len(), 2);
+
+        // Interceptors are not serialized
+        let json = serde_json::to_string(&config).unwrap();
+        let parsed: ClientConfig = serde_json::from_str(&json).unwrap();
+        assert!(parsed.interceptors.is_empty());
+    }
+
+    #[cfg(feature = "e2e")]
+    #[test]
+    fn test_e2e() {
+        let options = E2eOptions::generate();
+        let config = ClientConfig::builder("wss://hub.example.com", "agent")
+            .e2e(options.clone())
+            .build()
+            .expect("should build");
+        assert_eq!(
+            config.e2e.as_ref().map(E2eOptions::public_key),
+            Some(options.public_key())
+        );
+
+        // Keys are not serialized
+        let json = serde_json::to_string(&config).unwrap();
+        let parsed: ClientConfig = serde_json::from_str(&json).unwrap();
+        assert!(parsed.e2e.is_none());
     }
 }
@@ -1,7 +1,8 @@
 //! Reconnection configuration for the Cauce Client SDK.
 //!
 //! This module provides types for configuring automatic reconnection
-//! behavior when the connection to a Hub is lost.
+//! behavior when the connection to a Hub is lost, including failover
+//! between several hub endpoints.
 
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
@@ -11,6 +12,12 @@ use std::time::Duration;
 /// When the connection to the Hub is lost, the client will automatically
 /// attempt to reconnect using exponential backoff.
 ///
+/// When the client has fallback hubs (see
+/// [`ClientConfigBuilder::fallback_url`](crate::config::ClientConfigBuilder::fallback_url)),
+/// an endpoint that fails `failover_after` consecutive attempts is marked
+/// unhealthy for `unhealthy_cooldown` and the next endpoint is chosen
+/// according to `hub_selection`.
+///
 /// # Example
 ///
 /// ```rust
@@ -51,6 +58,33 @@ pub struct ReconnectConfig {
     /// Jitter helps prevent the "thundering herd" problem when many
     /// clients try to reconnect at the same time.
     pub jitter: bool,
+
+    /// How the next hub is chosen when several are configured.
+    #[serde(default)]
+    pub hub_selection: HubSelection,
+
+    /// Consecutive failed attempts against a hub before failing over to
+    /// another one.
+    #[serde(default = "default_failover_after")]
+    pub failover_after: u32,
+
+    /// How long a hub that triggered a failover is avoided.
+    #[serde(with = "duration_millis", default = "default_unhealthy_cooldown")]
+    pub unhealthy_cooldown: Duration,
+}
+
+/// Policy for choosing among several hub endpoints.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HubSelection {
+    /// Prefer endpoints in the order they were configured, failing back to
+    /// earlier ones once they are healthy again.
+    #[default]
+    Ordered,
+
+    /// Start on a random endpoint and stay on it until it becomes
+    /// unhealthy, then move to another random healthy endpoint.
+    RandomSticky,
 }
 
 impl Default for ReconnectConfig {
@@ -62,6 +96,9 @@ impl Default for ReconnectConfig {
             backoff_multiplier: 2.0,
             max_attempts: None,
             jitter: true,
+            hub_selection: HubSelection::Ordered,
+            failover_after: default_failover_after(),
+            unhealthy_cooldown: default_unhealthy_cooldown(),
         }
     }
 }
@@ -116,6 +153,25 @@ impl ReconnectConfig {
         self
     }
 
+    /// Set how the next hub is chosen when several are configured.
+    pub fn with_hub_selection(mut self, selection: HubSelection) -> Self {
+        self.hub_selection = selection;
+        self
+    }
+
+    /// Set how many consecutive failed attempts against a hub trigger a
+    /// failover (at least 1).
+    pub fn with_failover_after(mut self, attempts: u32) -> Self {
+        self.failover_after = attempts.max(1);
+        self
+    }
+
+    /// Set how long a hub that triggered a failover is avoided.
+    pub fn with_unhealthy_cooldown(mut self, cooldown: Duration) -> Self {
+        self.unhealthy_cooldown = cooldown;
+        self
+    }
+
     /// Calculate the delay for a given attempt number (0-indexed).
     ///
     /// The delay follows exponential backoff: `initial_delay * multiplier^attempt`,
@@ -149,6 +205,21 @@ impl ReconnectConfig {
     }
 }
 
+/// Default number of failed attempts before failing over.
+fn default_failover_after() -> u32 {
+    2
+}
+
+/// Default time an unhealthy hub is avoided.
+fn default_unhealthy_cooldown() -> Duration {
+    Duration::from_secs(60)
+}
+
+/// Pick a pseudo-random index below `len` (which must be non-zero).
+pub(crate) fn random_index(len: usize) -> usize {
+    ((rand_jitter() * len as f64) as usize).min(len - 1)
+}
+
 /// Generate a pseudo-random value between 0.0 and 1.0 for jitter.
 ///
 /// This uses a simple approach based on system time to avoid
@@ -310,5 +381,31 @@ mod tests {
         assert_eq!(config.backoff_multiplier, 1.5);
         assert_eq!(config.max_attempts, Some(5));
         assert!(!config.jitter);
+        assert_eq!(config.hub_selection, HubSelection::Ordered);
+        assert_eq!(config.failover_after, 2);
+        assert_eq!(config.unhealthy_cooldown, Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_failover_settings() {
+        let config = ReconnectConfig::new()
+            .with_hub_selection(HubSelection::RandomSticky)
+            .with_failover_after(0)
+            .with_unhealthy_cooldown(Duration::from_secs(5));
+        assert_eq!(config.failover_after, 1);
+
+        let json = serde_json::to_value(&config).unwrap();
+        assert_eq!(json["hub_selection"], "random_sticky");
+        assert_eq!(json["unhealthy_cooldown"], 5000);
+
+        let restored: ReconnectConfig = serde_json::from_value(json).unwrap();
+        assert_eq!(restored.hub_selection, HubSelection::RandomSticky);
+    }
+
+    #[test]
+    fn test_random_index_in_range() {
+        for len in 1..10 {
+            assert!(random_index(len) < len);
+        }
     }
 }
@@ -0,0 +1,327 @@
+//! Bearer token providers for the Cauce Client SDK.
+//!
+//! Short-lived OAuth or JWT tokens cannot be configured statically with
+//! [`AuthConfig::bearer`](super::AuthConfig::bearer). A [`TokenProvider`]
+//! supplies a fresh token whenever the client connects, and again when the
+//! token is about to expire or has expired and the hub rejects it.
+
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+use crate::error::ClientError;
+
+/// Trait for sources of bearer tokens.
+///
+/// Implemented automatically for async closures of the form
+/// `Fn() -> impl Future<Output = Result<String, ClientError>>`.
+///
+/// If the token is a JWT, its `exp` claim is used to refresh it before it
+/// expires.
+///
+/// # Example
+///
+/// ```ignore
+/// let config = ClientConfig::builder("wss://hub.example.com", "my-agent")
+///     .token_provider(|| async {
+///         let token = oauth.fetch_access_token().await?;
+///         Ok(token.secret)
+///     })
+///     .build()?;
+/// ```
+#[async_trait]
+pub trait TokenProvider: Send + Sync + 'static {
+    /// Fetch a fresh token.
+    async fn token(&self) -> Result<String, ClientError>;
+}
+
+#[async_trait]
+impl<F, Fut> TokenProvider for F
+where
+    F: Fn() -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<String, ClientError>> + Send + 'static,
+{
+    async fn token(&self) -> Result<String, ClientError> {
+        (self)().await
+    }
+}
+
+impl fmt::Debug for dyn TokenProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("TokenProvider")
+    }
+}
+
+/// A token and when it should be replaced.
+struct CachedToken {
+    token: String,
+
+    /// When to fetch a new token, if the token's expiry is known.
+    refresh_at: Option<Instant>,
+
+    /// When the token expires, if known.
+    expires_at: Option<SystemTime>,
+}
+
+/// Caches the token from a [`TokenProvider`] until it is due for refresh.
+pub(crate) struct TokenCache {
+    provider: Arc<dyn TokenProvider>,
+
+    /// How long before expiry a token is refreshed.
+    margin: Duration,
+
+    /// The current token. Held across fetches so concurrent callers share one.
+    current: Mutex<Option<CachedToken>>,
+}
+
+impl TokenCache {
+    /// Create a cache refreshing tokens `margin` before they expire.
+    pub fn new(provider: Arc<dyn TokenProvider>, margin: Duration) -> Self {
+        Self {
+            provider,
+            margin,
+            current: Mutex::new(None),
+        }
+    }
+
+    /// Returns the cached token, fetching a new one if none is cached or
+    /// the cached one is due for refresh.
+    pub async fn token(&self) -> Result<String, ClientError> {
+        let mut current = self.current.lock().await;
+        match current.as_ref() {
+            Some(cached) if !cached.refresh_at.is_some_and(|at| Instant::now() >= at) => {
+                Ok(cached.token.clone())
+            }
+            _ => self.fetch(&mut current).await,
+        }
+    }
+
+    /// Fetch a new token regardless of the cached one.
+    pub async fn refresh(&self) -> Result<String, ClientError> {
+        let mut current = self.current.lock().await;
+        self.fetch(&mut current).await
+    }
+
+    /// Returns when the cached token should be refreshed, if known.
+    pub async fn refresh_at(&self) -> Option<Instant> {
+        self.current.lock().await.as_ref()?.refresh_at
+    }
+
+    /// Whether the cached token is known to have expired.
+    ///
+    /// Tokens whose expiry is unknown are never considered expired.
+    pub async fn is_expired(&self) -> bool {
+        self.current
+            .lock()
+            .await
+            .as_ref()
+            .and_then(|cached| cached.expires_at)
+            .is_some_and(|exp| SystemTime::now() >= exp)
+    }
+
+    async fn fetch(&self, current: &mut Option<CachedToken>) -> Result<String, ClientError> {
+        let token = self.provider.token().await?;
+
+        // Refresh `margin` before expiry, but never before half the lifetime
+        // has passed so short-lived tokens are not fetched in a tight loop
+        let expires_at = jwt_expiry(&token);
+        let refresh_at = expires_at.map(|exp| {
+            let lifetime = exp.duration_since(SystemTime::now()).unwrap_or_default();
+            Instant::now() + lifetime.saturating_sub(self.margin).max(lifetime / 2)
+        });
+        tracing::debug!(
+            refresh_in_secs = ?refresh_at.map(|at| (at - Instant::now()).as_secs()),
+            "Fetched bearer token"
+        );
+
+        *current = Some(CachedToken {
+            token: token.clone(),
+            refresh_at,
+            expires_at,
+        });
+        Ok(token)
+    }
+}
+
+/// Returns the expiry time from a JWT's `exp` claim.
+///
+/// Returns `None` if the token is not a JWT or has no `exp` claim. The
+/// signature is not verified; the hub does that.
+fn jwt_expiry(token: &str) -> Option<SystemTime> {
+    let payload = token.split('.').nth(1)?;
+    let claims: serde_json::Value = serde_json::from_slice(&base64url_decode(payload)?).ok()?;
+    let exp = claims.get("exp")?.as_f64()?;
+    (exp >= 0.0).then(|| UNIX_EPOCH + Duration::from_secs_f64(exp))
+}
+
+/// Decode unpadded base64url, as used in JWT segments.
+fn base64url_decode(input: &str) -> Option<Vec<u8>> {
+    let mut output = Vec::with_capacity(input.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+
+    for byte in input.trim_end_matches('=').bytes() {
+        let value = match byte {
+            b'A'..=b'Z' => byte - b'A',
+            b'a'..=b'z' => byte - b'a' + 26,
+            b'0'..=b'9' => byte - b'0' + 52,
+            b'-' | b'+' => 62,
+            b'_' | b'/' => 63,
+            _ => return None,
+        };
+        buffer = (buffer << 6) | u32::from(value);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+
+    Some(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Build an unsigned JWT with the given `exp` claim.
+    fn jwt(exp: u64) -> String {
+        const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+        let encode = |bytes: &[u8]| {
+            let mut output = String::new();
+            for chunk in bytes.chunks(3) {
+                let n = chunk
+                    .iter()
+                    .enumerate()
+                    .fold(0u32, |n, (i, &b)| n | (u32::from(b) << (16 - 8 * i)));
+                for i in 0..=chunk.len() {
+                    output.push(ALPHABET[((n >> (18 - 6 * i)) & 63) as usize] as char);
+                }
+            }
+            output
+        };
+        let claims = serde_json::json!({ "sub": "agent", "exp": exp }).to_string();
+        format!(
+            "{}.{}.sig",
+            encode(br#"{"alg":"none"}"#),
+            encode(claims.as_bytes())
+        )
+    }
+
+    fn unix_now() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    }
+
+    #[test]
+    fn test_base64url_decode() {
+        assert_eq!(base64url_decode("aGVsbG8").unwrap(), b"hello");
+        assert_eq!(base64url_decode("aGk=").unwrap(), b"hi");
+        assert_eq!(base64url_decode("-_8").unwrap(), [0xfb, 0xff]);
+        assert!(base64url_decode("not base64!").is_none());
+    }
+
+    #[test]
+    fn test_jwt_expiry() {
+        let exp = unix_now() + 600;
+        let expiry = jwt_expiry(&jwt(exp)).unwrap();
+        assert_eq!(expiry.duration_since(UNIX_EPOCH).unwrap().as_secs(), exp);
+
+        assert!(jwt_expiry("opaque-token").is_none());
+        assert!(jwt_expiry("a.e30.c").is_none()); // {} has no exp
+    }
+
+    #[tokio::test]
+    async fn test_cache_reuses_token_until_refresh() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let counter = Arc::clone(&calls);
+        let provider = move || {
+            let n = counter.fetch_add(1, Ordering::SeqCst);
+            async move { Ok(format!("token-{}", n)) }
+        };
+        let cache = TokenCache::new(Arc::new(provider), Duration::from_secs(60));
+
+        assert_eq!(cache.token().await.unwrap(), "token-0");
+        assert_eq!(cache.token().await.unwrap(), "token-0");
+        assert!(cache.refresh_at().await.is_none());
+
+        assert_eq!(cache.refresh().await.unwrap(), "token-1");
+        assert_eq!(cache.token().await.unwrap(), "token-1");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_cache_schedules_refresh_before_expiry() {
+        let token = jwt(unix_now() + 600);
+        let cache = TokenCache::new(
+            Arc::new(move || {
+                let token = token.clone();
+                async move { Ok(token) }
+            }),
+            Duration::from_secs(60),
+        );
+
+        cache.token().await.unwrap();
+        let refresh_in = cache.refresh_at().await.unwrap() - Instant::now();
+        assert!(refresh_in > Duration::from_secs(530) && refresh_in <= Duration::from_secs(540));
+    }
+
+    #[tokio::test]
+    async fn test_cache_short_lived_token_refreshes_at_half_life() {
+        let token = jwt(unix_now() + 40);
+        let cache = TokenCache::new(
+            Arc::new(move || {
+                let token = token.clone();
+                async move { Ok(token) }
+            }),
+            Duration::from_secs(60),
+        );
+
+        cache.token().await.unwrap();
+        let refresh_in = cache.refresh_at().await.unwrap() - Instant::now();
+        assert!(refresh_in > Duration::from_secs(15) && refresh_in <= Duration::from_secs(20));
+    }
+
+    #[tokio::test]
+    async fn test_is_expired() {
+        let tokens = Arc::new(Mutex::new(vec![
+            jwt(unix_now() + 600),
+            jwt(unix_now() - 1),
+            "opaque".to_string(),
+        ]));
+        let cache = TokenCache::new(
+            Arc::new(move || {
+                let tokens = Arc::clone(&tokens);
+                async move { Ok(tokens.lock().await.remove(0)) }
+            }),
+            Duration::from_secs(60),
+        );
+        assert!(!cache.is_expired().await);
+
+        cache.token().await.unwrap();
+        assert!(!cache.is_expired().await);
+        cache.refresh().await.unwrap();
+        assert!(cache.is_expired().await);
+        cache.refresh().await.unwrap();
+        assert!(!cache.is_expired().await);
+    }
+
+    #[tokio::test]
+    async fn test_provider_error_is_returned() {
+        let cache = TokenCache::new(
+            Arc::new(|| async { Err(ClientError::config_error("no credentials")) }),
+            Duration::from_secs(60),
+        );
+        assert!(cache.token().await.is_err());
+        assert!(cache.refresh_at().await.is_none());
+    }
+}
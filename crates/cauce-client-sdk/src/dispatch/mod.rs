@@ -0,0 +1,665 @@
+//! Handler-based signal dispatch with automatic acknowledgement.
+//!
+//! The [`Dispatcher`] consumes a [`Subscription`] and routes each signal to the
+//! first registered handler whose topic pattern matches. Handlers run
+//! concurrently up to a configurable limit, and signals are acknowledged in
+//! batches once their handler succeeds.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use std::sync::Arc;
+//! use cauce_client_sdk::{CauceClient, Dispatcher, DispatcherConfig, ErrorPolicy};
+//!
+//! let client = Arc::new(CauceClient::connect(config).await?);
+//! let subscription = client.subscribe(&["signal.email.*", "signal.slack.*"]).await?;
+//!
+//! let stats = Dispatcher::new(Arc::clone(&client))
+//!     .with_config(DispatcherConfig::default().with_concurrency(8))
+//!     .on("signal.email.*", |signal: Signal| async move {
+//!         println!("email: {}", signal.id);
+//!         Ok(())
+//!     })
+//!     .on("signal.slack.*", |signal: Signal| async move {
+//!         println!("slack: {}", signal.id);
+//!         Ok(())
+//!     })
+//!     .run(subscription)
+//!     .await?;
+//! ```
+
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::FutureExt;
+use tokio::sync::{mpsc, Semaphore};
+
+use cauce_core::{validate_topic_pattern, Signal, TopicMatcher, MAX_SIGNALS_PER_BATCH};
+
+use crate::client::{CauceClient, Subscription};
+use crate::error::ClientError;
+use crate::ClientResult;
+
+/// Error type returned by signal handlers.
+pub type HandlerError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Trait for asynchronous signal handlers.
+///
+/// Implemented automatically for async closures of the form
+/// `Fn(Signal) -> impl Future<Output = Result<(), HandlerError>>`.
+///
+/// # Example
+///
+/// ```ignore
+/// struct EmailHandler;
+///
+/// #[async_trait]
+/// impl SignalHandler for EmailHandler {
+///     async fn handle(&self, signal: Signal) -> Result<(), HandlerError> {
+///         println!("email: {}", signal.id);
+///         Ok(())
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait SignalHandler: Send + Sync + 'static {
+    /// Handle a single signal.
+    ///
+    /// Returning `Ok(())` marks the signal for acknowledgement. Returning an
+    /// error (or panicking) applies the dispatcher's [`ErrorPolicy`].
+    async fn handle(&self, signal: Signal) -> Result<(), HandlerError>;
+}
+
+#[async_trait]
+impl<F, Fut> SignalHandler for F
+where
+    F: Fn(Signal) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<(), HandlerError>> + Send + 'static,
+{
+    async fn handle(&self, signal: Signal) -> Result<(), HandlerError> {
+        (self)(signal).await
+    }
+}
+
+/// What to do with a signal whose handler failed or panicked.
+///
+/// The protocol has no negative acknowledgement, so a signal that should be
+/// retried is simply left unacknowledged and the hub redelivers it according
+/// to its redelivery policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ErrorPolicy {
+    /// Leave the signal unacknowledged so the hub redelivers it.
+    #[default]
+    LeaveUnacked,
+    /// Acknowledge the signal anyway, dropping it.
+    Ack,
+}
+
+/// Configuration for a [`Dispatcher`].
+#[derive(Debug, Clone)]
+pub struct DispatcherConfig {
+    /// Maximum number of handlers running at the same time.
+    pub concurrency: usize,
+
+    /// Policy applied when a handler returns an error or panics.
+    pub on_error: ErrorPolicy,
+
+    /// Maximum number of signal IDs sent in a single `cauce.ack` request.
+    ///
+    /// Clamped to [`MAX_SIGNALS_PER_BATCH`].
+    pub ack_batch_size: usize,
+
+    /// How long to wait for a batch to fill before flushing pending acks.
+    pub ack_flush_interval: Duration,
+}
+
+impl Default for DispatcherConfig {
+    fn default() -> Self {
+        Self {
+            concurrency: 16,
+            on_error: ErrorPolicy::LeaveUnacked,
+            ack_batch_size: MAX_SIGNALS_PER_BATCH,
+            ack_flush_interval: Duration::from_millis(100),
+        }
+    }
+}
+
+impl DispatcherConfig {
+    /// Set the maximum number of concurrently running handlers.
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+
+    /// Set the policy applied to failed or panicked handlers.
+    pub fn with_error_policy(mut self, policy: ErrorPolicy) -> Self {
+        self.on_error = policy;
+        self
+    }
+
+    /// Set the maximum number of signal IDs per ack request.
+    pub fn with_ack_batch_size(mut self, size: usize) -> Self {
+        self.ack_batch_size = size;
+        self
+    }
+
+    /// Set how long pending acks may wait before being flushed.
+    pub fn with_ack_flush_interval(mut self, interval: Duration) -> Self {
+        self.ack_flush_interval = interval;
+        self
+    }
+
+    /// Returns the effective ack batch size (between 1 and [`MAX_SIGNALS_PER_BATCH`]).
+    fn effective_batch_size(&self) -> usize {
+        self.ack_batch_size.clamp(1, MAX_SIGNALS_PER_BATCH)
+    }
+}
+
+/// Counters describing a completed dispatch run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DispatchStats {
+    /// Signals whose handler returned `Ok(())`.
+    pub handled: u64,
+    /// Signals whose handler returned an error.
+    pub failed: u64,
+    /// Signals whose handler panicked.
+    pub panicked: u64,
+    /// Signals that matched no registered handler (left unacknowledged).
+    pub unmatched: u64,
+    /// Signals acknowledged to the hub.
+    pub acked: u64,
+}
+
+/// Shared counters updated by handler and ack tasks.
+#[derive(Default)]
+struct StatsCounters {
+    handled: AtomicU64,
+    failed: AtomicU64,
+    panicked: AtomicU64,
+    unmatched: AtomicU64,
+    acked: AtomicU64,
+}
+
+impl StatsCounters {
+    fn snapshot(&self) -> DispatchStats {
+        DispatchStats {
+            handled: self.handled.load(Ordering::Relaxed),
+            failed: self.failed.load(Ordering::Relaxed),
+            panicked: self.panicked.load(Ordering::Relaxed),
+            unmatched: self.unmatched.load(Ordering::Relaxed),
+            acked: self.acked.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// A topic pattern and the handler registered for it.
+struct Route {
+    pattern: String,
+    handler: Arc<dyn SignalHandler>,
+}
+
+/// Routes signals from a subscription to topic-pattern handlers.
+///
+/// Handlers are matched in registration order; the first matching pattern
+/// wins. Each handler invocation runs in its own task, so a panic in one
+/// handler does not affect others or the dispatcher itself.
+pub struct Dispatcher {
+    client: Arc<CauceClient>,
+    routes: Vec<Route>,
+    config: DispatcherConfig,
+}
+
+impl Dispatcher {
+    /// Create a dispatcher that acknowledges signals through `client`.
+    pub fn new(client: Arc<CauceClient>) -> Self {
+        Self {
+            client,
+            routes: Vec::new(),
+            config: DispatcherConfig::default(),
+        }
+    }
+
+    /// Set the dispatcher configuration.
+    pub fn with_config(mut self, config: DispatcherConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Register a handler for signals whose topic matches `pattern`.
+    ///
+    /// Patterns support the usual `*` and `**` wildcards and are validated
+    /// when [`run`](Self::run) is called.
+    pub fn on(mut self, pattern: impl Into<String>, handler: impl SignalHandler) -> Self {
+        self.routes.push(Route {
+            pattern: pattern.into(),
+            handler: Arc::new(handler),
+        });
+        self
+    }
+
+    /// Returns the number of registered handlers.
+    pub fn handler_count(&self) -> usize {
+        self.routes.len()
+    }
+
+    /// Find the handler for a topic, if any.
+    fn handler_for(&self, topic: &str) -> Option<Arc<dyn SignalHandler>> {
+        self.routes
+            .iter()
+            .find(|route| TopicMatcher::matches(topic, &route.pattern))
+            .map(|route| Arc::clone(&route.handler))
+    }
+
+    /// Dispatch signals from `subscription` until it closes.
+    ///
+    /// Waits for in-flight handlers and flushes pending acks before
+    /// returning.
+    ///
+    /// # Errors
+    ///
+    /// - [`ClientError::ConfigError`] - A registered pattern is invalid or
+    ///   the concurrency limit is zero
+    pub async fn run(self, mut subscription: Subscription) -> ClientResult<DispatchStats> {
+        for route in &self.routes {
+            validate_topic_pattern(&route.pattern).map_err(|e| {
+                ClientError::config_error(format!(
+                    "Invalid handler pattern '{}': {}",
+                    route.pattern, e
+                ))
+            })?;
+        }
+        if self.config.concurrency == 0 {
+            return Err(ClientError::config_error(
+                "Dispatcher concurrency must be greater than 0",
+            ));
+        }
+
+        let stats = Arc::new(StatsCounters::default());
+        let semaphore = Arc::new(Semaphore::new(self.config.concurrency));
+        let (ack_tx, ack_rx) = mpsc::unbounded_channel();
+        let ack_task = tokio::spawn(run_acker(
+            Arc::clone(&self.client),
+            subscription.subscription_id().to_string(),
+            ack_rx,
+            self.config.clone(),
+            Arc::clone(&stats),
+        ));
+
+        while let Some(result) = subscription.next().await {
+            let signal = match result {
+                Ok(signal) => signal,
+                Err(e) => {
+                    // Dropped signals stay unacked and will be redelivered
+                    tracing::warn!(error = %e, "Dispatcher subscription error");
+                    continue;
+                }
+            };
+
+            let Some(handler) = self.handler_for(signal.topic.as_str()) else {
+                tracing::warn!(
+                    signal_id = %signal.id,
+                    topic = %signal.topic,
+                    "No handler registered for signal topic, leaving unacknowledged"
+                );
+                stats.unmatched.fetch_add(1, Ordering::Relaxed);
+                continue;
+            };
+
+            let permit = Arc::clone(&semaphore)
+                .acquire_owned()
+                .await
+                .expect("dispatcher semaphore is never closed");
+            let ack_tx = ack_tx.clone();
+            let stats = Arc::clone(&stats);
+            let on_error = self.config.on_error;
+
+            tokio::spawn(async move {
+                let _permit = permit;
+                let signal_id = signal.id.clone();
+                let outcome = AssertUnwindSafe(handler.handle(signal)).catch_unwind().await;
+
+                let ack = match outcome {
+                    Ok(Ok(())) => {
+                        stats.handled.fetch_add(1, Ordering::Relaxed);
+                        true
+                    }
+                    Ok(Err(e)) => {
+                        tracing::warn!(signal_id = %signal_id, error = %e, "Signal handler failed");
+                        stats.failed.fetch_add(1, Ordering::Relaxed);
+                        on_error == ErrorPolicy::Ack
+                    }
+                    Err(_) => {
+                        tracing::error!(signal_id = %signal_id, "Signal handler panicked");
+                        stats.panicked.fetch_add(1, Ordering::Relaxed);
+                        on_error == ErrorPolicy::Ack
+                    }
+                };
+
+                if ack {
+                    let _ = ack_tx.send(signal_id);
+                }
+            });
+        }
+
+        // Wait for all in-flight handlers to release their permits
+        let _ = semaphore
+            .acquire_many(self.config.concurrency as u32)
+            .await;
+
+        drop(ack_tx);
+        let _ = ack_task.await;
+
+        Ok(stats.snapshot())
+    }
+}
+
+/// Collect acknowledged signal IDs and flush them in batches.
+async fn run_acker(
+    client: Arc<CauceClient>,
+    subscription_id: String,
+    mut rx: mpsc::UnboundedReceiver<String>,
+    config: DispatcherConfig,
+    stats: Arc<StatsCounters>,
+) {
+    let batch_size = config.effective_batch_size();
+    let mut pending: Vec<String> = Vec::with_capacity(batch_size);
+    let mut flush_timer = tokio::time::interval(config.ack_flush_interval);
+    flush_timer.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        let closed = tokio::select! {
+            received = rx.recv() => match received {
+                Some(id) => {
+                    pending.push(id);
+                    if pending.len() < batch_size {
+                        continue;
+                    }
+                    false
+                }
+                None => true,
+            },
+            _ = flush_timer.tick() => false,
+        };
+
+        if !pending.is_empty() {
+            flush_acks(&client, &subscription_id, &pending, batch_size, &stats).await;
+            pending.clear();
+        }
+
+        if closed {
+            break;
+        }
+    }
+}
+
+/// Send `cauce.ack` requests for `ids`, at most `batch_size` per request.
+async fn flush_acks(
+    client: &CauceClient,
+    subscription_id: &str,
+    ids: &[String],
+    batch_size: usize,
+    stats: &StatsCounters,
+) {
+    for chunk in ids.chunks(batch_size) {
+        let ids: Vec<&str> = chunk.iter().map(String::as_str).collect();
+        match client.ack(subscription_id, &ids).await {
+            Ok(response) => {
+                stats
+                    .acked
+                    .fetch_add(response.acknowledged.len() as u64, Ordering::Relaxed);
+            }
+            Err(e) => {
+                tracing::warn!(
+                    subscription_id = %subscription_id,
+                    count = ids.len(),
+                    error = %e,
+                    "Failed to acknowledge signals"
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ClientConfig;
+    use crate::transport::mock::{MockTransport, MockTransportHandle};
+    use crate::transport::{JsonRpcMessage, Transport};
+    use cauce_core::{
+        JsonRpcNotification, JsonRpcResponse, Payload, Source, Topic, METHOD_ACK, METHOD_SIGNAL,
+        METHOD_SUBSCRIBE,
+    };
+    use std::sync::Mutex as StdMutex;
+
+    /// Minimal hub stand-in: answers subscribe and ack requests and records acked IDs.
+    fn spawn_fake_hub(handle: MockTransportHandle, acks: Arc<StdMutex<Vec<Vec<String>>>>) {
+        tokio::spawn(async move {
+            loop {
+                let Some(message) = handle.pop_sent().await else {
+                    tokio::time::sleep(Duration::from_millis(2)).await;
+                    continue;
+                };
+                let JsonRpcMessage::Request(request) = message else {
+                    continue;
+                };
+                let result = match request.method() {
+                    METHOD_SUBSCRIBE => serde_json::json!({
+                        "subscription_id": "sub_1",
+                        "status": "active",
+                        "topics": ["signal.**"],
+                        "created_at": "2024-01-01T00:00:00Z"
+                    }),
+                    METHOD_ACK => {
+                        let ids: Vec<String> = serde_json::from_value(
+                            request.params().unwrap()["signal_ids"].clone(),
+                        )
+                        .unwrap();
+                        acks.lock().unwrap().push(ids.clone());
+                        serde_json::json!({ "acknowledged": ids })
+                    }
+                    _ => serde_json::json!({}),
+                };
+                let response = JsonRpcResponse::success(request.id().clone(), result);
+                handle.push_receive(response.into()).await;
+            }
+        });
+    }
+
+    /// Wait until at least `count` signal IDs have been acked, or give up after 2s.
+    async fn wait_for_acks(acks: &StdMutex<Vec<Vec<String>>>, count: usize) {
+        for _ in 0..200 {
+            if acks.lock().unwrap().iter().map(Vec::len).sum::<usize>() >= count {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+
+    async fn connect_mock() -> (Arc<CauceClient>, MockTransportHandle) {
+        let mut transport = MockTransport::new().wait_when_empty();
+        transport.connect().await.unwrap();
+        let handle = transport.handle();
+
+        let hello = JsonRpcResponse::success(
+            cauce_core::RequestId::Number(1),
+            serde_json::json!({
+                "session_id": "sess_1",
+                "server_version": "1.0",
+                "capabilities": []
+            }),
+        );
+        handle.push_receive(hello.into()).await;
+
+        let config = ClientConfig::builder("ws://localhost:8080", "test-client")
+            .build()
+            .unwrap();
+        let client = CauceClient::connect_with_transport(config, Box::new(transport))
+            .await
+            .unwrap();
+        // Drop the hello request so the fake hub only sees later traffic
+        handle.pop_sent().await;
+        (Arc::new(client), handle)
+    }
+
+    fn signal_notification(id: &str, topic: &str) -> JsonRpcMessage {
+        let signal = Signal {
+            id: id.to_string(),
+            version: "1.0".to_string(),
+            timestamp: chrono::Utc::now(),
+            source: Source::new("test", "adapter-1", "native-1"),
+            topic: Topic::new_unchecked(topic),
+            payload: Payload::new(serde_json::json!({}), "application/json"),
+            metadata: None,
+            encrypted: None,
+            signature: None,
+        };
+        JsonRpcMessage::Notification(JsonRpcNotification::new(
+            METHOD_SIGNAL.to_string(),
+            Some(serde_json::json!({ "topic": topic, "signal": signal })),
+        ))
+    }
+
+    #[test]
+    fn test_dispatcher_config_default() {
+        let config = DispatcherConfig::default();
+        assert_eq!(config.concurrency, 16);
+        assert_eq!(config.on_error, ErrorPolicy::LeaveUnacked);
+        assert_eq!(config.ack_batch_size, MAX_SIGNALS_PER_BATCH);
+    }
+
+    #[test]
+    fn test_effective_batch_size_is_clamped() {
+        let config = DispatcherConfig::default().with_ack_batch_size(500);
+        assert_eq!(config.effective_batch_size(), MAX_SIGNALS_PER_BATCH);
+
+        let config = DispatcherConfig::default().with_ack_batch_size(0);
+        assert_eq!(config.effective_batch_size(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_handler_matching_order() {
+        let (client, _handle) = connect_mock().await;
+        let dispatcher = Dispatcher::new(client)
+            .on("signal.email.*", |_s: Signal| async { Ok(()) })
+            .on("signal.**", |_s: Signal| async { Ok(()) });
+
+        assert_eq!(dispatcher.handler_count(), 2);
+        assert!(dispatcher.handler_for("signal.email.received").is_some());
+        assert!(dispatcher.handler_for("signal.slack.message").is_some());
+        assert!(dispatcher.handler_for("action.reply").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_run_rejects_invalid_pattern() {
+        let (client, handle) = connect_mock().await;
+        let acks = Arc::new(StdMutex::new(Vec::new()));
+        spawn_fake_hub(handle, Arc::clone(&acks));
+
+        let subscription = client.subscribe(&["signal.**"]).await.unwrap();
+        let result = Dispatcher::new(Arc::clone(&client))
+            .on("signal..bad", |_s: Signal| async { Ok(()) })
+            .run(subscription)
+            .await;
+
+        assert!(matches!(result, Err(ClientError::ConfigError { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_run_acks_successes_and_isolates_failures() {
+        let (client, handle) = connect_mock().await;
+        let acks = Arc::new(StdMutex::new(Vec::new()));
+        spawn_fake_hub(handle.clone(), Arc::clone(&acks));
+
+        let subscription = client.subscribe(&["signal.**"]).await.unwrap();
+
+        handle.push_receive(signal_notification("sig_ok", "signal.email.received")).await;
+        handle.push_receive(signal_notification("sig_err", "signal.slack.message")).await;
+        handle.push_receive(signal_notification("sig_panic", "signal.sms.received")).await;
+        handle.push_receive(signal_notification("sig_none", "signal.other.thing")).await;
+
+        let dispatcher = Dispatcher::new(Arc::clone(&client))
+            .with_config(DispatcherConfig::default().with_ack_flush_interval(Duration::from_millis(10)))
+            .on("signal.email.*", |_s: Signal| async { Ok(()) })
+            .on("signal.slack.*", |_s: Signal| async {
+                Err::<(), HandlerError>("boom".into())
+            })
+            .on("signal.sms.*", |s: Signal| async move {
+                if s.id == "sig_panic" {
+                    panic!("handler panic");
+                }
+                Ok(())
+            });
+
+        // The subscription only ends when the router shuts down, so bound the run
+        let run = tokio::spawn(dispatcher.run(subscription));
+        wait_for_acks(&acks, 1).await;
+        // Give failed handlers a chance to (wrongly) ack before checking
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        run.abort();
+
+        let acked: Vec<String> = acks.lock().unwrap().iter().flatten().cloned().collect();
+        assert_eq!(acked, vec!["sig_ok".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_error_policy_ack() {
+        let (client, handle) = connect_mock().await;
+        let acks = Arc::new(StdMutex::new(Vec::new()));
+        spawn_fake_hub(handle.clone(), Arc::clone(&acks));
+
+        let subscription = client.subscribe(&["signal.**"]).await.unwrap();
+        handle.push_receive(signal_notification("sig_err", "signal.slack.message")).await;
+
+        let dispatcher = Dispatcher::new(Arc::clone(&client))
+            .with_config(
+                DispatcherConfig::default()
+                    .with_error_policy(ErrorPolicy::Ack)
+                    .with_ack_flush_interval(Duration::from_millis(10)),
+            )
+            .on("signal.**", |_s: Signal| async {
+                Err::<(), HandlerError>("boom".into())
+            });
+
+        let run = tokio::spawn(dispatcher.run(subscription));
+        wait_for_acks(&acks, 1).await;
+        run.abort();
+
+        let acked: Vec<String> = acks.lock().unwrap().iter().flatten().cloned().collect();
+        assert_eq!(acked, vec!["sig_err".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_acks_are_batched() {
+        let (client, handle) = connect_mock().await;
+        let acks = Arc::new(StdMutex::new(Vec::new()));
+        spawn_fake_hub(handle.clone(), Arc::clone(&acks));
+
+        let subscription = client.subscribe(&["signal.**"]).await.unwrap();
+        for i in 0..5 {
+            handle
+                .push_receive(signal_notification(&format!("sig_{}", i), "signal.email.received"))
+                .await;
+        }
+
+        let dispatcher = Dispatcher::new(Arc::clone(&client))
+            .with_config(
+                DispatcherConfig::default()
+                    .with_ack_batch_size(2)
+                    .with_ack_flush_interval(Duration::from_millis(50)),
+            )
+            .on("signal.**", |_s: Signal| async { Ok(()) });
+
+        let run = tokio::spawn(dispatcher.run(subscription));
+        wait_for_acks(&acks, 5).await;
+        run.abort();
+
+        let batches = acks.lock().unwrap().clone();
+        assert!(batches.iter().all(|batch| batch.len() <= 2));
+        assert_eq!(batches.iter().map(Vec::len).sum::<usize>(), 5);
+    }
+}
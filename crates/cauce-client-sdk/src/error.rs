@@ -157,6 +157,15 @@ pub enum ClientError {
         message: String,
     },
 
+    /// A signal payload did not match the expected type.
+    #[error("failed to decode payload of signal {signal_id}: {message}")]
+    PayloadDecode {
+        /// ID of the signal whose payload could not be decoded.
+        signal_id: String,
+        /// Details about the decoding failure.
+        message: String,
+    },
+
     // =========================================================================
     // Configuration Errors
     // =========================================================================
@@ -219,6 +228,80 @@ pub enum ClientError {
         /// The current status.
         status: String,
     },
+
+    /// Subscription was denied by the hub.
+    #[error("subscription denied: {id} ({reason})")]
+    SubscriptionDenied {
+        /// The subscription ID.
+        id: String,
+        /// The reason given by the hub.
+        reason: String,
+    },
+
+    /// Subscription was revoked by the hub or expired.
+    #[error("subscription revoked: {id} ({reason})")]
+    SubscriptionRevoked {
+        /// The subscription ID.
+        id: String,
+        /// The reason given by the hub.
+        reason: String,
+    },
+
+    /// Subscription fell behind and signals were dropped.
+    ///
+    /// Raised when a subscription's delivery channel is full; the dropped
+    /// signals stay unacknowledged and will be redelivered by the hub.
+    #[error("subscription lagged: {id} dropped {missed} signals")]
+    SubscriptionLagged {
+        /// The subscription ID.
+        id: String,
+        /// Number of signals dropped since the last successful receive.
+        missed: u64,
+    },
+
+    /// An end-to-end encrypted signal could not be decrypted.
+    ///
+    /// The signal is left unacknowledged unless acknowledging failures was
+    /// enabled, so the hub will redeliver it.
+    #[error("failed to decrypt signal {signal_id} on subscription {subscription_id}: {reason}")]
+    DecryptionFailed {
+        /// The subscription ID.
+        subscription_id: String,
+        /// ID of the signal that could not be decrypted.
+        signal_id: String,
+        /// Why decryption failed.
+        reason: String,
+    },
+
+    /// A plaintext signal arrived on an end-to-end encrypted subscription.
+    ///
+    /// Handled like [`DecryptionFailed`](Self::DecryptionFailed): the
+    /// signal is not yielded, and is left unacknowledged unless
+    /// acknowledging failures was enabled.
+    #[error("signal {signal_id} on encrypted subscription {subscription_id} is not encrypted")]
+    UnencryptedSignal {
+        /// The subscription ID.
+        subscription_id: String,
+        /// ID of the plaintext signal.
+        signal_id: String,
+    },
+
+    // =========================================================================
+    // Adapter Errors
+    // =========================================================================
+    /// An adapter lifecycle hook failed.
+    #[error("adapter failed: {message}")]
+    AdapterFailed {
+        /// Details about the failure.
+        message: String,
+    },
+
+    /// Loading or saving an adapter checkpoint failed.
+    #[error("checkpoint error: {message}")]
+    CheckpointError {
+        /// Details about the failure.
+        message: String,
+    },
 }
 
 impl From<JsonRpcError> for ClientError {
@@ -382,4 +465,73 @@ mod tests {
         };
         assert_eq!(err.to_string(), "subscription not found: sub_123");
     }
+
+    #[test]
+    fn test_subscription_denied_and_revoked() {
+        let err = ClientError::SubscriptionDenied {
+            id: "sub_123".to_string(),
+            reason: "not allowed".to_string(),
+        };
+        assert_eq!(err.to_string(), "subscription denied: sub_123 (not allowed)");
+
+        let err = ClientError::SubscriptionRevoked {
+            id: "sub_123".to_string(),
+            reason: "expired".to_string(),
+        };
+        assert_eq!(err.to_string(), "subscription revoked: sub_123 (expired)");
+    }
+
+    #[test]
+    fn test_subscription_lagged() {
+        let err = ClientError::SubscriptionLagged {
+            id: "sub_123".to_string(),
+            missed: 7,
+        };
+        assert_eq!(err.to_string(), "subscription lagged: sub_123 dropped 7 signals");
+    }
+
+    #[test]
+    fn test_decryption_failed() {
+        let err = ClientError::DecryptionFailed {
+            subscription_id: "sub_123".to_string(),
+            signal_id: "sig_1".to_string(),
+            reason: "wrong key".to_string(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "failed to decrypt signal sig_1 on subscription sub_123: wrong key"
+        );
+    }
+
+    #[test]
+    fn test_unencrypted_signal() {
+        let err = ClientError::UnencryptedSignal {
+            subscription_id: "sub_123".to_string(),
+            signal_id: "sig_1".to_string(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "signal sig_1 on encrypted subscription sub_123 is not encrypted"
+        );
+    }
+
+    #[test]
+    fn test_payload_decode() {
+        let err = ClientError::PayloadDecode {
+            signal_id: "sig_1".to_string(),
+            message: "missing field `from`".to_string(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "failed to decode payload of signal sig_1: missing field `from`"
+        );
+    }
+
+    #[test]
+    fn test_checkpoint_error() {
+        let err = ClientError::CheckpointError {
+            message: "disk full".to_string(),
+        };
+        assert_eq!(err.to_string(), "checkpoint error: disk full");
+    }
 }
@@ -28,7 +28,8 @@
 //!         .await?;
 //!
 //!     // Receive signals
-//!     while let Some(signal) = subscription.next().await {
+//!     while let Some(result) = subscription.next().await {
+//!         let signal = result?;
 //!         println!("Received signal: {}", signal.id);
 //!
 //!         // Acknowledge the signal
@@ -48,35 +49,66 @@
 //! - **Automatic Reconnection**: Exponential backoff with configurable parameters
 //! - **Local Queuing**: Buffer messages when Hub is unavailable
 //! - **Type-Safe API**: Leverages cauce-core types for protocol compliance
+//! - **End-to-End Encryption**: Transparent payload encryption for
+//!   subscriptions and publishing (requires the `e2e` feature)
+//! - **Signal Provenance**: Adapters can sign the signals they publish
+//!   (requires the `signing` feature)
 //!
 //! ## Modules
 //!
+//! - [`adapter`] - Adapter framework for bridging external platforms
+//! - [`agent`] - Agent framework with conversation state and action replies
+//! - `blocking` - Synchronous client API (requires the `blocking` feature)
 //! - [`client`] - High-level CauceClient API
 //! - [`config`] - Client configuration types
+//! - [`dispatch`] - Handler-based signal dispatch with automatic acknowledgement
 //! - [`transport`] - Transport trait and implementations
 //! - [`router`] - Message routing and request-response correlation
 //! - [`queue`] - Local message queue for resilience
+//! - `testing` - In-memory hub for tests (requires the `test-support` feature)
 //! - [`error`] - Client error types
 
 #![deny(missing_docs)]
 #![deny(rustdoc::broken_intra_doc_links)]
 
+pub mod adapter;
+pub mod agent;
+#[cfg(feature = "blocking")]
+pub mod blocking;
 pub mod client;
 pub mod config;
+pub mod dispatch;
 pub mod error;
 pub mod queue;
 pub mod router;
+#[cfg(any(test, feature = "test-support"))]
+pub mod testing;
 pub mod transport;
 
 // =============================================================================
 // Public API Re-exports
 // =============================================================================
 
-pub use client::{CauceClient, Subscription};
-pub use config::{AuthConfig, ClientConfig, ClientConfigBuilder, ReconnectConfig, TlsConfig};
+pub use adapter::{
+    ActionResult, Adapter, AdapterConfig, AdapterContext, AdapterRuntime, AdapterSignal,
+    AdapterStats, CheckpointStore, FileCheckpointStore, MemoryCheckpointStore, PollBatch,
+};
+pub use agent::{Agent, AgentConfig, AgentContext, AgentRuntime, AgentStats, Conversation};
+pub use client::{CauceClient, Subscription, TypedSubscription};
+#[cfg(feature = "e2e")]
+pub use config::E2eOptions;
+pub use config::{
+    AuthConfig, ClientConfig, ClientConfigBuilder, HubSelection, ReconnectConfig, TlsConfig,
+    TokenProvider,
+};
+pub use dispatch::{
+    DispatchStats, Dispatcher, DispatcherConfig, ErrorPolicy, HandlerError, SignalHandler,
+};
 pub use error::ClientError;
 pub use queue::{LocalQueue, QueueConfig, QueueStats};
-pub use router::{MessageRouter, RouterConfig};
+pub use router::{
+    ConnectionEvent, Interceptor, MessageRouter, RequestHandler, RouterConfig, RttStats,
+};
 pub use transport::{
     ConnectionState, JsonRpcMessage, LongPollingTransport, PollingTransport, SseTransport,
     Transport, WebSocketTransport, WebhookTransport,
@@ -126,10 +158,20 @@ mod tests {
         // Verify that key types are accessible
         let _ = std::any::type_name::<CauceClient>();
         let _ = std::any::type_name::<Subscription>();
+        let _ = std::any::type_name::<TypedSubscription<serde_json::Value>>();
         let _ = std::any::type_name::<ClientConfig>();
         let _ = std::any::type_name::<ClientError>();
         let _ = std::any::type_name::<ConnectionState>();
         let _ = std::any::type_name::<MessageRouter>();
         let _ = std::any::type_name::<RouterConfig>();
+        let _ = std::any::type_name::<ConnectionEvent>();
+        let _ = std::any::type_name::<RttStats>();
+        let _ = std::any::type_name::<AdapterConfig>();
+        let _ = std::any::type_name::<AdapterContext>();
+        let _ = std::any::type_name::<ActionResult>();
+        let _ = std::any::type_name::<MemoryCheckpointStore>();
+        let _ = std::any::type_name::<AgentConfig>();
+        let _ = std::any::type_name::<Conversation<()>>();
+        let _ = std::any::type_name::<Dispatcher>();
     }
 }
@@ -0,0 +1,608 @@
+//! Per-subscription signal demultiplexing for the message router.
+//!
+//! This module provides the internal [`SubscriptionDemux`] that routes
+//! `cauce.signal` deliveries into bounded per-subscription channels keyed by
+//! the delivery's `subscription_id`, and tracks each subscription's status
+//! as reported by `cauce.subscription.status` notifications.
+
+use cauce_core::{SignalDelivery, SubscriptionStatus, TopicMatcher};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::sync::{mpsc, oneshot, watch, Mutex};
+
+/// Maximum number of subscription IDs buffered before they are registered.
+const MAX_UNCLAIMED_SUBSCRIPTIONS: usize = 16;
+
+/// Latest status the hub reported for a subscription.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct StatusUpdate {
+    /// The subscription status.
+    pub status: SubscriptionStatus,
+
+    /// Reason given by the hub, if any.
+    pub reason: Option<String>,
+}
+
+impl StatusUpdate {
+    /// Create an update without a reason.
+    pub fn new(status: SubscriptionStatus) -> Self {
+        Self {
+            status,
+            reason: None,
+        }
+    }
+
+    /// Whether the subscription will not deliver any more signals.
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self.status,
+            SubscriptionStatus::Denied | SubscriptionStatus::Revoked | SubscriptionStatus::Expired
+        )
+    }
+}
+
+/// Receiving half of a registered subscription channel.
+pub(crate) struct SubscriptionChannel {
+    /// Receiver for deliveries routed to this subscription.
+    pub rx: mpsc::Receiver<SignalDelivery>,
+
+    /// Count of signals dropped because the channel was full.
+    pub dropped: Arc<AtomicU64>,
+
+    /// Status updates for this subscription.
+    pub status: watch::Receiver<StatusUpdate>,
+}
+
+/// Sending half of a registered subscription channel.
+struct SubscriptionSender {
+    /// Topic patterns, used for deliveries without a subscription ID.
+    topics: Vec<String>,
+
+    /// Bounded sender into the subscription's channel.
+    tx: mpsc::Sender<SignalDelivery>,
+
+    /// Count of signals dropped because the channel was full.
+    dropped: Arc<AtomicU64>,
+
+    /// Publishes status updates to the subscription handle.
+    status: watch::Sender<StatusUpdate>,
+}
+
+impl SubscriptionSender {
+    /// Try to deliver a signal, recording a drop if the channel is full.
+    ///
+    /// Returns `false` if the receiver has been dropped.
+    fn deliver(&self, id: &str, delivery: SignalDelivery) -> bool {
+        match self.tx.try_send(delivery) {
+            Ok(()) => true,
+            Err(mpsc::error::TrySendError::Full(delivery)) => {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+                tracing::warn!(
+                    subscription_id = %id,
+                    signal_id = %delivery.signal.id,
+                    "Subscription channel full, dropping signal"
+                );
+                true
+            }
+            Err(mpsc::error::TrySendError::Closed(_)) => false,
+        }
+    }
+}
+
+/// Routes signal deliveries to per-subscription channels.
+///
+/// Deliveries that arrive for a subscription ID before it is registered
+/// (the hub may push signals before the subscribe response has been
+/// processed) are buffered, up to the channel capacity, and handed over on
+/// registration.
+///
+/// When a subscription is re-created on the hub after a reconnect it gets a
+/// new ID; an alias maps the new ID onto the channel registered under the
+/// original one so existing [`Subscription`](crate::Subscription) handles
+/// keep receiving.
+///
+/// Deliveries whose `correlation_id` matches a pending
+/// [`request`](crate::CauceClient::request) are handed to that request
+/// instead of any subscription channel.
+///
+/// Status notifications update the subscription's status. A subscription
+/// that is denied, revoked or expires has its channel closed; its final
+/// status is kept until it is unregistered.
+pub(crate) struct SubscriptionDemux {
+    /// Registered subscriptions by ID.
+    senders: Mutex<HashMap<String, SubscriptionSender>>,
+
+    /// Deliveries for subscription IDs that are not registered yet.
+    unclaimed: Mutex<HashMap<String, VecDeque<SignalDelivery>>>,
+
+    /// Hub subscription ID -> registered subscription ID.
+    aliases: Mutex<HashMap<String, String>>,
+
+    /// Pending request/reply exchanges by correlation ID.
+    replies: Mutex<HashMap<String, oneshot::Sender<SignalDelivery>>>,
+
+    /// Status updates for subscription IDs that are not registered yet.
+    unclaimed_status: Mutex<HashMap<String, StatusUpdate>>,
+
+    /// Final status of subscriptions the hub has ended.
+    ended: Mutex<HashMap<String, StatusUpdate>>,
+
+    /// Capacity of each subscription channel.
+    capacity: usize,
+}
+
+impl SubscriptionDemux {
+    /// Create a new demultiplexer with the given per-subscription capacity.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            senders: Mutex::new(HashMap::new()),
+            unclaimed: Mutex::new(HashMap::new()),
+            aliases: Mutex::new(HashMap::new()),
+            replies: Mutex::new(HashMap::new()),
+            unclaimed_status: Mutex::new(HashMap::new()),
+            ended: Mutex::new(HashMap::new()),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Register a subscription and return its receiving channel.
+    ///
+    /// `status` is the status from the subscribe response. Any deliveries
+    /// or status updates buffered for this subscription ID are applied.
+    /// Registering an ID twice replaces the previous channel.
+    pub async fn register(
+        &self,
+        id: &str,
+        topics: Vec<String>,
+        status: SubscriptionStatus,
+    ) -> SubscriptionChannel {
+        let (tx, rx) = mpsc::channel(self.capacity);
+        let dropped = Arc::new(AtomicU64::new(0));
+        let (status_tx, status_rx) = watch::channel(StatusUpdate::new(status));
+        let sender = SubscriptionSender {
+            topics,
+            tx,
+            dropped: Arc::clone(&dropped),
+            status: status_tx,
+        };
+
+        if let Some(buffered) = self.unclaimed.lock().await.remove(id) {
+            for delivery in buffered {
+                sender.deliver(id, delivery);
+            }
+        }
+
+        self.ended.lock().await.remove(id);
+        self.senders.lock().await.insert(id.to_string(), sender);
+
+        let early_status = self.unclaimed_status.lock().await.remove(id);
+        if let Some(update) = early_status {
+            self.set_status(id, update).await;
+        }
+
+        SubscriptionChannel {
+            rx,
+            dropped,
+            status: status_rx,
+        }
+    }
+
+    /// Unregister a subscription, closing its channel.
+    ///
+    /// Returns `true` if the subscription was registered.
+    pub async fn unregister(&self, id: &str) -> bool {
+        self.unclaimed.lock().await.remove(id);
+        self.unclaimed_status.lock().await.remove(id);
+        self.ended.lock().await.remove(id);
+        self.aliases.lock().await.retain(|_, target| target != id);
+        self.senders.lock().await.remove(id).is_some()
+    }
+
+    /// Route deliveries for `hub_id` to the channel registered as `id`.
+    ///
+    /// Buffered deliveries for `hub_id` are moved into that channel.
+    pub async fn alias(&self, hub_id: &str, id: &str) {
+        if hub_id == id {
+            return;
+        }
+        self.aliases
+            .lock()
+            .await
+            .insert(hub_id.to_string(), id.to_string());
+
+        if let Some(buffered) = self.unclaimed.lock().await.remove(hub_id) {
+            if let Some(sender) = self.senders.lock().await.get(id) {
+                for delivery in buffered {
+                    sender.deliver(id, delivery);
+                }
+            }
+        }
+    }
+
+    /// Apply a status update from the hub.
+    ///
+    /// `hub_id` may be an alias. Updates for IDs that are not registered
+    /// yet are kept and applied on registration.
+    pub async fn update_status(&self, hub_id: &str, update: StatusUpdate) {
+        let id = self
+            .aliases
+            .lock()
+            .await
+            .get(hub_id)
+            .cloned()
+            .unwrap_or_else(|| hub_id.to_string());
+
+        if self.senders.lock().await.contains_key(&id) {
+            self.set_status(&id, update).await;
+            return;
+        }
+
+        let mut unclaimed = self.unclaimed_status.lock().await;
+        if unclaimed.contains_key(&id) || unclaimed.len() < MAX_UNCLAIMED_SUBSCRIPTIONS {
+            unclaimed.insert(id, update);
+        } else {
+            tracing::warn!(
+                subscription_id = %id,
+                "Dropping status update for unknown subscription"
+            );
+        }
+    }
+
+    /// Returns the final status of a subscription the hub has ended.
+    pub async fn ended(&self, id: &str) -> Option<StatusUpdate> {
+        self.ended.lock().await.get(id).cloned()
+    }
+
+    /// Publish a status update to a registered subscription, closing its
+    /// channel if the update is terminal.
+    async fn set_status(&self, id: &str, update: StatusUpdate) {
+        let mut senders = self.senders.lock().await;
+        let Some(sender) = senders.get(id) else {
+            return;
+        };
+
+        tracing::debug!(
+            subscription_id = %id,
+            status = ?update.status,
+            "Subscription status changed"
+        );
+        sender.status.send_replace(update.clone());
+
+        if update.is_terminal() {
+            senders.remove(id);
+            drop(senders);
+            self.aliases.lock().await.retain(|_, target| target != id);
+            self.ended.lock().await.insert(id.to_string(), update);
+        }
+    }
+
+    /// Wait for the delivery of a signal carrying `correlation_id`.
+    ///
+    /// The receiver completes with the first matching delivery, or errors
+    /// if the exchange is cancelled or the demux is cleared.
+    pub async fn expect_reply(&self, correlation_id: &str) -> oneshot::Receiver<SignalDelivery> {
+        let (tx, rx) = oneshot::channel();
+        self.replies
+            .lock()
+            .await
+            .insert(correlation_id.to_string(), tx);
+        rx
+    }
+
+    /// Stop waiting for a reply.
+    pub async fn cancel_reply(&self, correlation_id: &str) {
+        self.replies.lock().await.remove(correlation_id);
+    }
+
+    /// Route a signal delivery to its subscription channel(s).
+    ///
+    /// Deliveries that answer a pending request go to that request only.
+    /// Deliveries with a `subscription_id` go to that subscription only.
+    /// Deliveries without one (from older hubs) go to every subscription
+    /// whose topic patterns match the delivery topic.
+    pub async fn route(&self, delivery: SignalDelivery) {
+        let correlation_id = delivery
+            .signal
+            .metadata
+            .as_ref()
+            .and_then(|m| m.correlation_id.as_deref());
+        if let Some(correlation_id) = correlation_id {
+            let waiter = self.replies.lock().await.remove(correlation_id);
+            if let Some(waiter) = waiter {
+                let _ = waiter.send(delivery);
+                return;
+            }
+        }
+
+        let subscription_id = match delivery.subscription_id.clone() {
+            Some(id) => Some(self.aliases.lock().await.get(&id).cloned().unwrap_or(id)),
+            None => None,
+        };
+        let mut senders = self.senders.lock().await;
+
+        match subscription_id {
+            Some(id) => match senders.get(&id) {
+                Some(sender) => {
+                    if !sender.deliver(&id, delivery) {
+                        senders.remove(&id);
+                    }
+                }
+                None => {
+                    drop(senders);
+                    self.buffer_unclaimed(id, delivery).await;
+                }
+            },
+            None => {
+                let mut closed = Vec::new();
+                for (id, sender) in senders.iter() {
+                    let matches = sender
+                        .topics
+                        .iter()
+                        .any(|pattern| TopicMatcher::matches(&delivery.topic, pattern));
+                    if matches && !sender.deliver(id, delivery.clone()) {
+                        closed.push(id.clone());
+                    }
+                }
+                for id in closed {
+                    senders.remove(&id);
+                }
+            }
+        }
+    }
+
+    /// Buffer a delivery for a subscription that is not registered yet.
+    async fn buffer_unclaimed(&self, id: String, delivery: SignalDelivery) {
+        let mut unclaimed = self.unclaimed.lock().await;
+        if !unclaimed.contains_key(&id) && unclaimed.len() >= MAX_UNCLAIMED_SUBSCRIPTIONS {
+            tracing::warn!(
+                subscription_id = %id,
+                "Dropping signal for unknown subscription"
+            );
+            return;
+        }
+
+        let buffer = unclaimed.entry(id).or_default();
+        if buffer.len() >= self.capacity {
+            buffer.pop_front();
+        }
+        buffer.push_back(delivery);
+    }
+
+    /// Get the number of registered subscriptions.
+    pub async fn subscription_count(&self) -> usize {
+        self.senders.lock().await.len()
+    }
+
+    /// Close all subscription channels and drop buffered deliveries.
+    pub async fn clear(&self) {
+        self.senders.lock().await.clear();
+        self.unclaimed.lock().await.clear();
+        self.aliases.lock().await.clear();
+        self.replies.lock().await.clear();
+        self.unclaimed_status.lock().await.clear();
+        self.ended.lock().await.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cauce_core::{Metadata, Payload, Signal, Source, Topic};
+
+    fn make_delivery(id: &str, topic: &str, subscription_id: Option<&str>) -> SignalDelivery {
+        let signal = Signal {
+            id: id.to_string(),
+            version: "1.0".to_string(),
+            timestamp: chrono::Utc::now(),
+            source: Source::new("test", "adapter-1", "native-1"),
+            topic: Topic::new_unchecked(topic),
+            payload: Payload::new(serde_json::json!({}), "application/json"),
+            metadata: None,
+            encrypted: None,
+            signature: None,
+        };
+        let delivery = SignalDelivery::new(topic, signal);
+        match subscription_id {
+            Some(sub) => delivery.with_subscription_id(sub),
+            None => delivery,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_route_by_subscription_id() {
+        let demux = SubscriptionDemux::new(10);
+        let mut a = demux
+            .register("sub_a", vec!["signal.**".to_string()], SubscriptionStatus::Active)
+            .await;
+        let mut b = demux
+            .register("sub_b", vec!["signal.**".to_string()], SubscriptionStatus::Active)
+            .await;
+
+        demux
+            .route(make_delivery("sig_1", "signal.email.received", Some("sub_a")))
+            .await;
+
+        assert_eq!(a.rx.try_recv().unwrap().signal.id, "sig_1");
+        assert!(b.rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_route_without_subscription_id_matches_topics() {
+        let demux = SubscriptionDemux::new(10);
+        let mut email = demux
+            .register("sub_a", vec!["signal.email.*".to_string()], SubscriptionStatus::Active)
+            .await;
+        let mut slack = demux
+            .register("sub_b", vec!["signal.slack.*".to_string()], SubscriptionStatus::Active)
+            .await;
+
+        demux
+            .route(make_delivery("sig_1", "signal.email.received", None))
+            .await;
+
+        assert_eq!(email.rx.try_recv().unwrap().signal.id, "sig_1");
+        assert!(slack.rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_route_keeps_verified_flag() {
+        let demux = SubscriptionDemux::new(10);
+        demux
+            .route(make_delivery("sig_early", "signal.a", Some("sub_a")).with_verified(true))
+            .await;
+        let mut channel = demux
+            .register("sub_a", vec!["signal.**".to_string()], SubscriptionStatus::Active)
+            .await;
+        demux
+            .route(make_delivery("sig_1", "signal.a", Some("sub_a")))
+            .await;
+
+        assert!(channel.rx.try_recv().unwrap().verified);
+        assert!(!channel.rx.try_recv().unwrap().verified);
+    }
+
+    #[tokio::test]
+    async fn test_overflow_counts_dropped() {
+        let demux = SubscriptionDemux::new(2);
+        let channel = demux
+            .register("sub_a", vec!["signal.**".to_string()], SubscriptionStatus::Active)
+            .await;
+
+        for i in 0..5 {
+            demux
+                .route(make_delivery(&format!("sig_{}", i), "signal.a", Some("sub_a")))
+                .await;
+        }
+
+        assert_eq!(channel.dropped.load(Ordering::Relaxed), 3);
+    }
+
+    #[tokio::test]
+    async fn test_unclaimed_deliveries_buffered_until_register() {
+        let demux = SubscriptionDemux::new(10);
+        demux
+            .route(make_delivery("sig_early", "signal.a", Some("sub_late")))
+            .await;
+
+        let mut channel = demux
+
+            .register("sub_late", vec!["signal.**".to_string()], SubscriptionStatus::Active)
+
+            .await;
+        assert_eq!(channel.rx.try_recv().unwrap().signal.id, "sig_early");
+    }
+
+    #[tokio::test]
+    async fn test_alias_routes_new_hub_id() {
+        let demux = SubscriptionDemux::new(10);
+        let mut channel = demux
+            .register("sub_old", vec!["signal.**".to_string()], SubscriptionStatus::Active)
+            .await;
+
+        // A delivery for the new ID arriving before the alias is buffered
+        demux
+            .route(make_delivery("sig_1", "signal.a", Some("sub_new")))
+            .await;
+        demux.alias("sub_new", "sub_old").await;
+        demux
+            .route(make_delivery("sig_2", "signal.a", Some("sub_new")))
+            .await;
+
+        assert_eq!(channel.rx.try_recv().unwrap().signal.id, "sig_1");
+        assert_eq!(channel.rx.try_recv().unwrap().signal.id, "sig_2");
+    }
+
+    #[tokio::test]
+    async fn test_closed_receiver_is_removed() {
+        let demux = SubscriptionDemux::new(10);
+        let channel = demux
+            .register("sub_a", vec!["signal.**".to_string()], SubscriptionStatus::Active)
+            .await;
+        drop(channel);
+
+        demux
+            .route(make_delivery("sig_1", "signal.a", Some("sub_a")))
+            .await;
+        assert_eq!(demux.subscription_count().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_unregister_and_clear() {
+        let demux = SubscriptionDemux::new(10);
+        let mut channel = demux
+            .register("sub_a", vec!["signal.**".to_string()], SubscriptionStatus::Active)
+            .await;
+
+        assert!(demux.unregister("sub_a").await);
+        assert!(!demux.unregister("sub_a").await);
+        assert!(channel.rx.recv().await.is_none());
+
+        let mut channel = demux
+
+            .register("sub_b", vec!["signal.**".to_string()], SubscriptionStatus::Active)
+
+            .await;
+        demux.clear().await;
+        assert!(channel.rx.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_correlated_reply_goes_to_waiter() {
+        let demux = SubscriptionDemux::new(10);
+        let mut channel = demux
+            .register("sub_a", vec!["signal.**".to_string()], SubscriptionStatus::Active)
+            .await;
+        let reply = demux.expect_reply("corr-1").await;
+
+        let mut delivery = make_delivery("sig_1", "signal.email.sent", Some("sub_a"));
+        delivery.signal.metadata = Some(Metadata::reply_to("act_1").correlation_id("corr-1"));
+        demux.route(delivery.clone()).await;
+        assert_eq!(reply.await.unwrap().signal.id, "sig_1");
+        assert!(channel.rx.try_recv().is_err());
+
+        // Once answered, the same correlation ID is routed normally
+        demux.route(delivery).await;
+        assert_eq!(channel.rx.try_recv().unwrap().signal.id, "sig_1");
+    }
+
+    #[tokio::test]
+    async fn test_status_update_and_terminal_close() {
+        let demux = SubscriptionDemux::new(10);
+        let mut channel = demux
+            .register("sub_a", vec!["signal.**".to_string()], SubscriptionStatus::Pending)
+            .await;
+        demux.alias("sub_new", "sub_a").await;
+
+        demux
+            .update_status("sub_new", StatusUpdate::new(SubscriptionStatus::Active))
+            .await;
+        assert_eq!(channel.status.borrow().status, SubscriptionStatus::Active);
+
+        let revoked = StatusUpdate {
+            status: SubscriptionStatus::Revoked,
+            reason: Some("owner revoked access".to_string()),
+        };
+        demux.update_status("sub_a", revoked.clone()).await;
+        assert_eq!(*channel.status.borrow(), revoked);
+        assert!(channel.rx.recv().await.is_none());
+        assert_eq!(demux.ended("sub_a").await, Some(revoked));
+        assert_eq!(demux.subscription_count().await, 0);
+
+        assert!(!demux.unregister("sub_a").await);
+        assert!(demux.ended("sub_a").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_early_status_applied_on_register() {
+        let demux = SubscriptionDemux::new(10);
+        demux
+            .update_status("sub_late", StatusUpdate::new(SubscriptionStatus::Active))
+            .await;
+
+        let channel = demux
+            .register("sub_late", vec!["signal.**".to_string()], SubscriptionStatus::Pending)
+            .await;
+        assert_eq!(channel.status.borrow().status, SubscriptionStatus::Active);
+    }
+}
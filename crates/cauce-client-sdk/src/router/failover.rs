@@ -0,0 +1,248 @@
+//! Hub endpoint selection for failover.
+//!
+//! [`HubEndpoints`] tracks the health of each configured hub and decides
+//! which one the next connection attempt goes to, following the
+//! [`HubSelection`] policy from [`ReconnectConfig`].
+
+use std::time::{Duration, Instant};
+
+use crate::config::{random_index, ClientConfig, HubSelection, ReconnectConfig};
+
+/// Recent connection outcomes for one endpoint.
+#[derive(Debug, Clone, Default)]
+struct EndpointHealth {
+    /// Consecutive failed connection attempts.
+    failures: u32,
+
+    /// Until when the endpoint is avoided after triggering a failover.
+    unhealthy_until: Option<Instant>,
+}
+
+impl EndpointHealth {
+    fn is_healthy(&self, now: Instant) -> bool {
+        !matches!(self.unhealthy_until, Some(until) if now < until)
+    }
+}
+
+/// The set of hub endpoints and which one is in use.
+#[derive(Debug, Clone)]
+pub(crate) struct HubEndpoints {
+    /// Endpoint URLs in preference order.
+    urls: Vec<String>,
+
+    /// Health of each endpoint, parallel to `urls`.
+    health: Vec<EndpointHealth>,
+
+    /// Index of the endpoint in use.
+    current: usize,
+
+    /// How the next endpoint is chosen.
+    selection: HubSelection,
+
+    /// Consecutive failures before failing over.
+    failover_after: u32,
+
+    /// How long an endpoint that triggered a failover is avoided.
+    cooldown: Duration,
+}
+
+impl HubEndpoints {
+    /// Create the endpoint set for a client configuration.
+    ///
+    /// Starts on the first endpoint, or a random one for
+    /// [`HubSelection::RandomSticky`].
+    pub fn from_config(config: &ClientConfig) -> Self {
+        Self::new(config.endpoints(), &config.reconnect)
+    }
+
+    /// Create an endpoint set from URLs in preference order (at least one).
+    pub fn new(urls: Vec<String>, reconnect: &ReconnectConfig) -> Self {
+        assert!(!urls.is_empty(), "at least one hub endpoint is required");
+        let current = match reconnect.hub_selection {
+            HubSelection::Ordered => 0,
+            HubSelection::RandomSticky => random_index(urls.len()),
+        };
+
+        Self {
+            health: vec![EndpointHealth::default(); urls.len()],
+            urls,
+            current,
+            selection: reconnect.hub_selection,
+            failover_after: reconnect.failover_after.max(1),
+            cooldown: reconnect.unhealthy_cooldown,
+        }
+    }
+
+    /// Returns the number of endpoints.
+    pub fn len(&self) -> usize {
+        self.urls.len()
+    }
+
+    /// Returns the URL of the endpoint in use.
+    pub fn current(&self) -> &str {
+        &self.urls[self.current]
+    }
+
+    /// Record a successful connection to the current endpoint.
+    pub fn record_success(&mut self) {
+        self.health[self.current] = EndpointHealth::default();
+    }
+
+    /// Record a failed connection attempt to the current endpoint.
+    ///
+    /// After `failover_after` consecutive failures the endpoint is marked
+    /// unhealthy and another one is selected. Returns `true` if the current
+    /// endpoint changed.
+    pub fn record_failure(&mut self) -> bool {
+        let health = &mut self.health[self.current];
+        health.failures += 1;
+        if health.failures < self.failover_after {
+            return false;
+        }
+        self.fail_over()
+    }
+
+    /// Mark the current endpoint unhealthy and select another one.
+    ///
+    /// Returns `true` if the current endpoint changed.
+    pub fn fail_over(&mut self) -> bool {
+        let now = Instant::now();
+        self.health[self.current] = EndpointHealth {
+            failures: 0,
+            unhealthy_until: Some(now + self.cooldown),
+        };
+
+        let previous = self.current;
+        self.current = self.pick(now, Some(previous));
+        self.current != previous
+    }
+
+    /// Choose the endpoint for a new round of reconnection attempts.
+    ///
+    /// [`HubSelection::Ordered`] fails back to the most preferred healthy
+    /// endpoint; [`HubSelection::RandomSticky`] stays put while the current
+    /// endpoint is healthy.
+    pub fn reselect(&mut self) {
+        let now = Instant::now();
+        let sticky = self.selection == HubSelection::RandomSticky
+            && self.health[self.current].is_healthy(now);
+        if !sticky {
+            self.current = self.pick(now, None);
+        }
+    }
+
+    /// Pick a healthy endpoint, avoiding `exclude` if possible.
+    ///
+    /// When every candidate is unhealthy, the one whose cooldown ends first
+    /// is chosen.
+    fn pick(&self, now: Instant, exclude: Option<usize>) -> usize {
+        let candidates: Vec<usize> = (0..self.urls.len())
+            .filter(|&i| Some(i) != exclude || self.urls.len() == 1)
+            .collect();
+        let healthy: Vec<usize> = candidates
+            .iter()
+            .copied()
+            .filter(|&i| self.health[i].is_healthy(now))
+            .collect();
+
+        if healthy.is_empty() {
+            return candidates
+                .into_iter()
+                .min_by_key(|&i| self.health[i].unhealthy_until)
+                .unwrap_or(self.current);
+        }
+
+        match self.selection {
+            HubSelection::Ordered => healthy[0],
+            HubSelection::RandomSticky => healthy[random_index(healthy.len())],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoints(selection: HubSelection, failover_after: u32) -> HubEndpoints {
+        let reconnect = ReconnectConfig::default()
+            .with_hub_selection(selection)
+            .with_failover_after(failover_after)
+            .with_unhealthy_cooldown(Duration::from_secs(60));
+        HubEndpoints::new(
+            vec![
+                "wss://primary".to_string(),
+                "wss://standby".to_string(),
+                "wss://backup".to_string(),
+            ],
+            &reconnect,
+        )
+    }
+
+    #[test]
+    fn test_ordered_failover_after_consecutive_failures() {
+        let mut hubs = endpoints(HubSelection::Ordered, 2);
+        assert_eq!(hubs.current(), "wss://primary");
+
+        assert!(!hubs.record_failure());
+        assert!(hubs.record_failure());
+        assert_eq!(hubs.current(), "wss://standby");
+
+        // The primary stays avoided during its cooldown
+        hubs.reselect();
+        assert_eq!(hubs.current(), "wss://standby");
+    }
+
+    #[test]
+    fn test_success_resets_failures() {
+        let mut hubs = endpoints(HubSelection::Ordered, 2);
+        hubs.record_failure();
+        hubs.record_success();
+        assert!(!hubs.record_failure());
+        assert_eq!(hubs.current(), "wss://primary");
+    }
+
+    #[test]
+    fn test_ordered_fails_back_after_cooldown() {
+        let mut hubs = endpoints(HubSelection::Ordered, 1);
+        hubs.cooldown = Duration::ZERO;
+        hubs.record_failure();
+        assert_eq!(hubs.current(), "wss://standby");
+
+        hubs.reselect();
+        assert_eq!(hubs.current(), "wss://primary");
+    }
+
+    #[test]
+    fn test_all_unhealthy_picks_earliest_recovery() {
+        let mut hubs = endpoints(HubSelection::Ordered, 1);
+        hubs.fail_over();
+        hubs.fail_over();
+        assert_eq!(hubs.current(), "wss://backup");
+
+        // Everything is unhealthy; the primary recovers first
+        hubs.fail_over();
+        assert_eq!(hubs.current(), "wss://primary");
+    }
+
+    #[test]
+    fn test_random_sticky_stays_while_healthy() {
+        let mut hubs = endpoints(HubSelection::RandomSticky, 1);
+        let start = hubs.current().to_string();
+        hubs.reselect();
+        assert_eq!(hubs.current(), start);
+
+        assert!(hubs.record_failure());
+        assert_ne!(hubs.current(), start);
+    }
+
+    #[test]
+    fn test_single_endpoint() {
+        let mut hubs = HubEndpoints::new(
+            vec!["wss://only".to_string()],
+            &ReconnectConfig::default().with_failover_after(1),
+        );
+        assert!(!hubs.record_failure());
+        assert_eq!(hubs.current(), "wss://only");
+        assert_eq!(hubs.len(), 1);
+    }
+}
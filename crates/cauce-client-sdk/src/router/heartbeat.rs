@@ -0,0 +1,146 @@
+//! Keepalive heartbeat for the message router.
+//!
+//! The heartbeat periodically sends `cauce.ping` requests, records round-trip
+//! times in [`RttStats`], and flags the connection as dead after too many
+//! consecutive pings go unanswered.
+
+use cauce_core::{PingParams, METHOD_PING};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, Mutex};
+
+use super::tracker::RequestTracker;
+use super::RouterResult;
+use crate::transport::Transport;
+
+/// Round-trip time statistics gathered by the keepalive heartbeat.
+///
+/// Obtain a snapshot with [`MessageRouter::rtt_stats`](super::MessageRouter::rtt_stats).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RttStats {
+    /// Round-trip time of the most recent pong.
+    pub last: Option<Duration>,
+    /// Smallest observed round-trip time.
+    pub min: Option<Duration>,
+    /// Largest observed round-trip time.
+    pub max: Option<Duration>,
+    /// Mean round-trip time over all samples.
+    pub average: Option<Duration>,
+    /// Number of pongs received.
+    pub samples: u64,
+    /// Number of consecutive pings that went unanswered.
+    pub missed_pongs: u32,
+}
+
+impl RttStats {
+    /// Record a successful ping round trip.
+    pub(crate) fn record(&mut self, rtt: Duration) {
+        let total = self.average.unwrap_or_default() * self.samples as u32 + rtt;
+        self.samples += 1;
+        self.average = Some(total / self.samples as u32);
+        self.last = Some(rtt);
+        self.min = Some(self.min.map_or(rtt, |min| min.min(rtt)));
+        self.max = Some(self.max.map_or(rtt, |max| max.max(rtt)));
+        self.missed_pongs = 0;
+    }
+
+    /// Record an unanswered ping, returning the consecutive miss count.
+    pub(crate) fn record_miss(&mut self) -> u32 {
+        self.missed_pongs += 1;
+        self.missed_pongs
+    }
+}
+
+/// Send a single ping and return its round-trip time.
+///
+/// Any response counts as a pong, including an error response: it proves the
+/// hub is reading from the connection.
+pub(super) async fn ping(
+    tracker: &RequestTracker,
+    transport: &Mutex<Box<dyn Transport>>,
+    timeout: Duration,
+) -> RouterResult<Duration> {
+    let params = serde_json::to_value(PingParams::now())?;
+    let started = Instant::now();
+    super::send_request_via(tracker, transport, METHOD_PING, Some(params), timeout).await?;
+    Ok(started.elapsed())
+}
+
+/// Run the heartbeat loop until shutdown.
+///
+/// Pings are skipped while the connection is flagged as dead, so the receive
+/// task can reconnect without the heartbeat racing it.
+pub(super) async fn run(
+    tracker: Arc<RequestTracker>,
+    transport: Arc<Mutex<Box<dyn Transport>>>,
+    stats: Arc<StdMutex<RttStats>>,
+    dead: Arc<AtomicBool>,
+    interval: Duration,
+    max_missed: u32,
+    mut shutdown_rx: broadcast::Receiver<()>,
+) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    // The first tick completes immediately; the connection was just established
+    ticker.tick().await;
+
+    loop {
+        tokio::select! {
+            _ = shutdown_rx.recv() => break,
+            _ = ticker.tick() => {}
+        }
+
+        if dead.load(Ordering::SeqCst) {
+            continue;
+        }
+
+        match ping(&tracker, &transport, interval).await {
+            Ok(rtt) => {
+                tracing::trace!(rtt_ms = rtt.as_millis() as u64, "Received pong");
+                stats.lock().unwrap().record(rtt);
+            }
+            Err(e) => {
+                let missed = stats.lock().unwrap().record_miss();
+                tracing::warn!(missed, max_missed, error = %e, "Keepalive ping unanswered");
+                if missed >= max_missed {
+                    tracing::error!(
+                        missed,
+                        "Connection declared dead after missed keepalive pings"
+                    );
+                    dead.store(true, Ordering::SeqCst);
+                }
+            }
+        }
+    }
+
+    tracing::debug!("Heartbeat task stopped");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rtt_stats_record() {
+        let mut stats = RttStats::default();
+        stats.record(Duration::from_millis(10));
+        stats.record(Duration::from_millis(30));
+
+        assert_eq!(stats.samples, 2);
+        assert_eq!(stats.last, Some(Duration::from_millis(30)));
+        assert_eq!(stats.min, Some(Duration::from_millis(10)));
+        assert_eq!(stats.max, Some(Duration::from_millis(30)));
+        assert_eq!(stats.average, Some(Duration::from_millis(20)));
+    }
+
+    #[test]
+    fn test_rtt_stats_miss_resets_on_pong() {
+        let mut stats = RttStats::default();
+        assert_eq!(stats.record_miss(), 1);
+        assert_eq!(stats.record_miss(), 2);
+
+        stats.record(Duration::from_millis(5));
+        assert_eq!(stats.missed_pongs, 0);
+    }
+}
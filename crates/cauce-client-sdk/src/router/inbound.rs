@@ -0,0 +1,192 @@
+//! Handling of hub-initiated JSON-RPC requests.
+//!
+//! Hubs occasionally call methods on the client, such as
+//! `cauce.subscription.request` asking a user agent to approve a
+//! subscription, or `cauce.ping` to check liveness. Applications answer
+//! these by registering a [`RequestHandler`] per method. `cauce.ping` is
+//! answered by default, and methods without a handler get a
+//! "Method not found" error.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+use cauce_core::{JsonRpcError, JsonRpcRequest, JsonRpcResponse, PongParams, METHOD_PING};
+use futures::FutureExt;
+use serde_json::Value;
+use tokio::sync::Mutex;
+
+use crate::transport::{JsonRpcMessage, Transport};
+
+/// Trait for handlers of hub-initiated requests.
+///
+/// Implemented automatically for async closures of the form
+/// `Fn(Option<Value>) -> impl Future<Output = Result<Value, JsonRpcError>>`.
+///
+/// # Example
+///
+/// ```ignore
+/// client.on_request(METHOD_SUBSCRIPTION_REQUEST, |params: Option<Value>| async move {
+///     let request: SubscriptionRequest = serde_json::from_value(params.unwrap_or_default())
+///         .map_err(|_| JsonRpcError::invalid_params())?;
+///     println!("{} wants {:?}", request.client_id, request.topics);
+///     Ok(json!({ "approved": true }))
+/// });
+/// ```
+#[async_trait]
+pub trait RequestHandler: Send + Sync + 'static {
+    /// Handle a request, returning its result or a JSON-RPC error.
+    async fn handle(&self, params: Option<Value>) -> Result<Value, JsonRpcError>;
+}
+
+#[async_trait]
+impl<F, Fut> RequestHandler for F
+where
+    F: Fn(Option<Value>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<Value, JsonRpcError>> + Send + 'static,
+{
+    async fn handle(&self, params: Option<Value>) -> Result<Value, JsonRpcError> {
+        (self)(params).await
+    }
+}
+
+/// Default `cauce.ping` handler, answering with the current time.
+struct PingHandler;
+
+#[async_trait]
+impl RequestHandler for PingHandler {
+    async fn handle(&self, _params: Option<Value>) -> Result<Value, JsonRpcError> {
+        serde_json::to_value(PongParams::now()).map_err(|_| JsonRpcError::internal_error())
+    }
+}
+
+/// Registry of request handlers and the transport used to answer.
+pub(crate) struct RequestHandlers {
+    /// Handlers by method name.
+    handlers: RwLock<HashMap<String, Arc<dyn RequestHandler>>>,
+
+    /// Transport responses are sent over.
+    transport: Arc<Mutex<Box<dyn Transport>>>,
+}
+
+impl RequestHandlers {
+    /// Create a registry with the default `cauce.ping` handler.
+    pub fn new(transport: Arc<Mutex<Box<dyn Transport>>>) -> Self {
+        let mut handlers: HashMap<String, Arc<dyn RequestHandler>> = HashMap::new();
+        handlers.insert(METHOD_PING.to_string(), Arc::new(PingHandler));
+        Self {
+            handlers: RwLock::new(handlers),
+            transport,
+        }
+    }
+
+    /// Register a handler, replacing any previous one for the method.
+    pub fn insert(&self, method: String, handler: Arc<dyn RequestHandler>) {
+        self.handlers.write().unwrap().insert(method, handler);
+    }
+
+    /// Remove the handler for a method.
+    ///
+    /// Returns `true` if a handler was registered.
+    pub fn remove(&self, method: &str) -> bool {
+        self.handlers.write().unwrap().remove(method).is_some()
+    }
+
+    /// Answer a request in the background.
+    ///
+    /// The handler runs in its own task so a slow handler does not hold up
+    /// the receive loop.
+    pub fn dispatch(self: &Arc<Self>, request: JsonRpcRequest) {
+        let handler = self.handlers.read().unwrap().get(request.method()).cloned();
+        let this = Arc::clone(self);
+
+        tokio::spawn(async move {
+            let response = Self::answer(handler, &request).await;
+            let mut transport = this.transport.lock().await;
+            if let Err(e) = transport.send(JsonRpcMessage::Response(response)).await {
+                tracing::warn!(
+                    method = %request.method(),
+                    error = %e,
+                    "Failed to answer hub request"
+                );
+            }
+        });
+    }
+
+    /// Run the handler (if any) and build the response.
+    async fn answer(
+        handler: Option<Arc<dyn RequestHandler>>,
+        request: &JsonRpcRequest,
+    ) -> JsonRpcResponse {
+        let id = request.id().clone();
+        let Some(handler) = handler else {
+            tracing::debug!(method = %request.method(), "No handler for hub request");
+            return JsonRpcResponse::error(
+                Some(id),
+                JsonRpcError::with_data(
+                    -32601,
+                    "Method not found",
+                    serde_json::json!({ "method": request.method() }),
+                ),
+            );
+        };
+
+        let outcome = AssertUnwindSafe(handler.handle(request.params().cloned()))
+            .catch_unwind()
+            .await;
+
+        match outcome {
+            Ok(Ok(result)) => JsonRpcResponse::success(id, result),
+            Ok(Err(error)) => JsonRpcResponse::error(Some(id), error),
+            Err(_) => {
+                tracing::error!(method = %request.method(), "Request handler panicked");
+                JsonRpcResponse::error(Some(id), JsonRpcError::internal_error())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cauce_core::RequestId;
+
+    fn request(method: &str) -> JsonRpcRequest {
+        JsonRpcRequest::new(
+            RequestId::Number(7),
+            method,
+            Some(serde_json::json!({"x": 1})),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_default_ping_handler() {
+        let handler: Arc<dyn RequestHandler> = Arc::new(PingHandler);
+        let response = RequestHandlers::answer(Some(handler), &request(METHOD_PING)).await;
+        assert_eq!(response.id(), Some(&RequestId::Number(7)));
+        assert!(response.result().unwrap()["timestamp"].is_string());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_method() {
+        let response = RequestHandlers::answer(None, &request("cauce.unknown")).await;
+        let error = response.error_obj().unwrap();
+        assert_eq!(error.code, -32601);
+        assert_eq!(error.data.as_ref().unwrap()["method"], "cauce.unknown");
+    }
+
+    #[tokio::test]
+    async fn test_handler_result_and_error() {
+        let echo: Arc<dyn RequestHandler> =
+            Arc::new(|params: Option<Value>| async move { Ok(params.unwrap_or_default()) });
+        let response = RequestHandlers::answer(Some(echo), &request("app.echo")).await;
+        assert_eq!(response.result().unwrap()["x"], 1);
+
+        let failing: Arc<dyn RequestHandler> =
+            Arc::new(|_params: Option<Value>| async { Err(JsonRpcError::invalid_params()) });
+        let response = RequestHandlers::answer(Some(failing), &request("app.fail")).await;
+        assert_eq!(response.error_obj().unwrap().code, -32602);
+    }
+}
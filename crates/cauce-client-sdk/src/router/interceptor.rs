@@ -0,0 +1,279 @@
+//! Interceptors around outgoing requests and incoming notifications.
+//!
+//! An [`Interceptor`] sees every JSON-RPC request the client sends and every
+//! notification it receives, so cross-cutting concerns such as tracing,
+//! metrics, payload encryption or PII redaction can be added without
+//! changing the client itself. Interceptors run in the order they were
+//! added for requests and notifications, and in reverse order for
+//! responses, so the first interceptor added is the outermost.
+
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use cauce_core::{JsonRpcNotification, JsonRpcRequest, JsonRpcResponse};
+
+use crate::error::ClientError;
+
+use super::RouterResult;
+
+/// Hooks around the messages exchanged with the hub.
+///
+/// All methods have no-op defaults, so implementations only override the
+/// hooks they need. Keepalive pings bypass interceptors.
+///
+/// # Example
+///
+/// ```ignore
+/// struct Timing;
+///
+/// #[async_trait]
+/// impl Interceptor for Timing {
+///     async fn on_response(
+///         &self,
+///         request: &JsonRpcRequest,
+///         _result: &mut Result<JsonRpcResponse, ClientError>,
+///         elapsed: Duration,
+///     ) {
+///         tracing::info!(method = %request.method, ?elapsed, "request completed");
+///     }
+/// }
+///
+/// let config = ClientConfig::builder("wss://hub.example.com", "my-agent")
+///     .interceptor(Timing)
+///     .build()?;
+/// ```
+#[async_trait]
+pub trait Interceptor: Send + Sync + 'static {
+    /// Called before a request is sent.
+    ///
+    /// The request may be modified. Returning an error fails the request
+    /// with that error without sending it; interceptors added later are
+    /// skipped.
+    async fn on_request(&self, _request: &mut JsonRpcRequest) -> Result<(), ClientError> {
+        Ok(())
+    }
+
+    /// Called when a request completes, with its outcome and the time since
+    /// it entered the chain.
+    ///
+    /// The outcome may be modified. Only interceptors whose
+    /// [`on_request`](Self::on_request) ran are called.
+    async fn on_response(
+        &self,
+        _request: &JsonRpcRequest,
+        _result: &mut Result<JsonRpcResponse, ClientError>,
+        _elapsed: Duration,
+    ) {
+    }
+
+    /// Called for each notification received from the hub, before it is
+    /// delivered to subscriptions.
+    ///
+    /// The notification may be modified. Returning an error drops it.
+    async fn on_notification(
+        &self,
+        _notification: &mut JsonRpcNotification,
+    ) -> Result<(), ClientError> {
+        Ok(())
+    }
+}
+
+impl fmt::Debug for dyn Interceptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Interceptor")
+    }
+}
+
+/// An ordered list of interceptors, cheap to clone into background tasks.
+#[derive(Clone, Default)]
+pub(crate) struct InterceptorChain {
+    interceptors: Arc<Vec<Arc<dyn Interceptor>>>,
+}
+
+impl InterceptorChain {
+    /// Append `interceptors` to the chain.
+    pub fn extend(&mut self, interceptors: impl IntoIterator<Item = Arc<dyn Interceptor>>) {
+        Arc::make_mut(&mut self.interceptors).extend(interceptors);
+    }
+
+    /// Run `request` through the chain, sending it with `send` unless an
+    /// interceptor rejects it.
+    pub async fn send<F, Fut>(
+        &self,
+        mut request: JsonRpcRequest,
+        send: F,
+    ) -> RouterResult<JsonRpcResponse>
+    where
+        F: FnOnce(JsonRpcRequest) -> Fut,
+        Fut: Future<Output = RouterResult<JsonRpcResponse>>,
+    {
+        if self.interceptors.is_empty() {
+            return send(request).await;
+        }
+
+        let started = Instant::now();
+        let mut entered = 0;
+        let mut rejected = None;
+        for interceptor in self.interceptors.iter() {
+            entered += 1;
+            if let Err(e) = interceptor.on_request(&mut request).await {
+                tracing::debug!(
+                    method = %request.method,
+                    error = %e,
+                    "Request rejected by interceptor"
+                );
+                rejected = Some(e);
+                break;
+            }
+        }
+
+        let mut result = match rejected {
+            Some(e) => Err(e),
+            None => send(request.clone()).await,
+        };
+        for interceptor in self.interceptors[..entered].iter().rev() {
+            interceptor
+                .on_response(&request, &mut result, started.elapsed())
+                .await;
+        }
+        result
+    }
+
+    /// Run an incoming notification through the chain.
+    ///
+    /// Returns `false` if an interceptor dropped it.
+    pub async fn receive(&self, notification: &mut JsonRpcNotification) -> bool {
+        for interceptor in self.interceptors.iter() {
+            if let Err(e) = interceptor.on_notification(notification).await {
+                tracing::debug!(
+                    method = %notification.method,
+                    error = %e,
+                    "Notification dropped by interceptor"
+                );
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cauce_core::RequestId;
+    use std::sync::Mutex;
+
+    /// Records the hooks it sees, tagging requests with its name.
+    struct Recorder {
+        name: &'static str,
+        log: Arc<Mutex<Vec<String>>>,
+        reject: bool,
+    }
+
+    #[async_trait]
+    impl Interceptor for Recorder {
+        async fn on_request(&self, request: &mut JsonRpcRequest) -> Result<(), ClientError> {
+            self.log
+                .lock()
+                .unwrap()
+                .push(format!("{} request", self.name));
+            if self.reject {
+                return Err(ClientError::config_error("rejected"));
+            }
+            request.method.push_str(&format!(".{}", self.name));
+            Ok(())
+        }
+
+        async fn on_response(
+            &self,
+            _request: &JsonRpcRequest,
+            _result: &mut Result<JsonRpcResponse, ClientError>,
+            _elapsed: Duration,
+        ) {
+            self.log
+                .lock()
+                .unwrap()
+                .push(format!("{} response", self.name));
+        }
+
+        async fn on_notification(
+            &self,
+            notification: &mut JsonRpcNotification,
+        ) -> Result<(), ClientError> {
+            if self.reject {
+                return Err(ClientError::config_error("rejected"));
+            }
+            notification.method.push_str(&format!(".{}", self.name));
+            Ok(())
+        }
+    }
+
+    fn chain(log: &Arc<Mutex<Vec<String>>>, rejecting: Option<&str>) -> InterceptorChain {
+        let mut chain = InterceptorChain::default();
+        chain.extend(["a", "b", "c"].map(|name| {
+            Arc::new(Recorder {
+                name,
+                log: Arc::clone(log),
+                reject: rejecting == Some(name),
+            }) as Arc<dyn Interceptor>
+        }));
+        chain
+    }
+
+    #[tokio::test]
+    async fn test_requests_pass_through_in_order() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let request = JsonRpcRequest::new(RequestId::Number(1), "test".to_string(), None);
+
+        let result = chain(&log, None)
+            .send(request, |request| async move {
+                assert_eq!(request.method, "test.a.b.c");
+                Ok(JsonRpcResponse::success(request.id, serde_json::json!({})))
+            })
+            .await;
+
+        assert!(result.is_ok());
+        assert_eq!(
+            *log.lock().unwrap(),
+            [
+                "a request",
+                "b request",
+                "c request",
+                "c response",
+                "b response",
+                "a response"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rejected_request_is_not_sent() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let request = JsonRpcRequest::new(RequestId::Number(1), "test".to_string(), None);
+
+        let result = chain(&log, Some("b"))
+            .send(request, |_| async { panic!("request should not be sent") })
+            .await;
+
+        assert!(matches!(result, Err(ClientError::ConfigError { .. })));
+        assert_eq!(
+            *log.lock().unwrap(),
+            ["a request", "b request", "b response", "a response"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_notifications_modified_or_dropped() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+
+        let mut notification = JsonRpcNotification::new("test".to_string(), None);
+        assert!(chain(&log, None).receive(&mut notification).await);
+        assert_eq!(notification.method, "test.a.b.c");
+
+        let mut notification = JsonRpcNotification::new("test".to_string(), None);
+        assert!(!chain(&log, Some("b")).receive(&mut notification).await);
+    }
+}
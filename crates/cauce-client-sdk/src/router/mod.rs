@@ -5,7 +5,17 @@
 //!
 //! - **Request-response correlation**: Match response IDs to pending requests
 //! - **Notification routing**: Broadcast incoming notifications to subscribers
+//! - **Inbound requests**: Answer hub-initiated requests with registered
+//!   [`RequestHandler`]s
+//! - **Signal demultiplexing**: Route `cauce.signal` deliveries into bounded
+//!   per-subscription channels
 //! - **Timeout management**: Cancel requests that exceed their timeout
+//! - **Keepalive**: Ping the hub periodically, track round-trip times, and
+//!   reconnect when pongs stop arriving
+//! - **Token refresh**: Reconnect with a fresh bearer token from a
+//!   [`TokenProvider`](crate::TokenProvider)
+//! - **Interceptors**: Pass outgoing requests and incoming notifications
+//!   through a chain of [`Interceptor`]s
 //!
 //! ## Example
 //!
@@ -33,17 +43,36 @@
 //! }
 //! ```
 
+mod demux;
+mod failover;
+mod heartbeat;
+mod inbound;
+mod interceptor;
 mod tracker;
 
+use crate::config::{AuthConfig, ReconnectConfig, TokenCache};
 use crate::error::ClientError;
 use crate::transport::{ConnectionState, JsonRpcMessage, Transport};
 
-use cauce_core::{JsonRpcNotification, JsonRpcRequest, JsonRpcResponse};
-use std::sync::Arc;
+use cauce_core::{
+    JsonRpcNotification, JsonRpcRequest, JsonRpcResponse, SignalDelivery,
+    SubscriptionStatus, SubscriptionStatusNotification, METHOD_SIGNAL,
+    METHOD_SUBSCRIPTION_STATUS,
+};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex as StdMutex};
 use std::time::Duration;
 use tokio::sync::{broadcast, oneshot, Mutex};
 use tokio::task::JoinHandle;
 
+pub(crate) use demux::{StatusUpdate, SubscriptionChannel};
+pub(crate) use failover::HubEndpoints;
+pub use heartbeat::RttStats;
+pub use inbound::RequestHandler;
+pub use interceptor::Interceptor;
+use demux::SubscriptionDemux;
+use inbound::RequestHandlers;
+use interceptor::InterceptorChain;
 use tracker::RequestTracker;
 
 /// Result type for router operations.
@@ -63,6 +92,23 @@ pub struct RouterConfig {
     /// When this capacity is exceeded, the oldest notifications
     /// are dropped for lagging subscribers.
     pub notification_channel_capacity: usize,
+
+    /// Capacity of each subscription's signal channel.
+    ///
+    /// When a subscription's channel is full, further signals for it are
+    /// dropped and reported as [`ClientError::SubscriptionLagged`].
+    pub subscription_channel_capacity: usize,
+
+    /// Interval between keepalive pings, or `None` to disable the heartbeat.
+    pub keepalive_interval: Option<Duration>,
+
+    /// Number of consecutive unanswered pings before the connection is
+    /// declared dead.
+    pub max_missed_pongs: u32,
+
+    /// Reconnection policy applied when the connection is lost, or `None`
+    /// to stop the router instead.
+    pub reconnect: Option<ReconnectConfig>,
 }
 
 impl Default for RouterConfig {
@@ -70,6 +116,10 @@ impl Default for RouterConfig {
         Self {
             request_timeout: Duration::from_secs(60),
             notification_channel_capacity: 1000,
+            subscription_channel_capacity: 1000,
+            keepalive_interval: None,
+            max_missed_pongs: 3,
+            reconnect: None,
         }
     }
 }
@@ -86,6 +136,57 @@ impl RouterConfig {
         self.notification_channel_capacity = capacity;
         self
     }
+
+    /// Create a new configuration with custom per-subscription channel capacity.
+    pub fn with_subscription_capacity(mut self, capacity: usize) -> Self {
+        self.subscription_channel_capacity = capacity;
+        self
+    }
+
+    /// Enable the keepalive heartbeat.
+    ///
+    /// A `cauce.ping` is sent every `interval`; after `max_missed_pongs`
+    /// consecutive pings go unanswered the connection is declared dead.
+    pub fn with_keepalive(mut self, interval: Duration, max_missed_pongs: u32) -> Self {
+        self.keepalive_interval = Some(interval);
+        self.max_missed_pongs = max_missed_pongs.max(1);
+        self
+    }
+
+    /// Reconnect the transport with the given policy when the connection is lost.
+    pub fn with_reconnect(mut self, reconnect: ReconnectConfig) -> Self {
+        self.reconnect = Some(reconnect);
+        self
+    }
+}
+
+/// Connection lifecycle events emitted by the [`MessageRouter`].
+///
+/// Obtain a receiver with [`MessageRouter::connection_events`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConnectionEvent {
+    /// The connection was lost.
+    Disconnected {
+        /// Why the connection was considered lost.
+        reason: String,
+    },
+    /// The transport reconnected. Session state (hello, subscriptions) must
+    /// be re-established by the caller.
+    Reconnected {
+        /// The reconnection attempt that succeeded (1-based).
+        attempt: u32,
+    },
+    /// Reconnection moved to a different hub endpoint. A
+    /// [`Reconnected`](Self::Reconnected) event follows once connected.
+    FailedOver {
+        /// The hub URL now in use.
+        url: String,
+    },
+    /// Reconnection was abandoned; the router has stopped.
+    ReconnectFailed {
+        /// Number of reconnection attempts made.
+        attempts: u32,
+    },
 }
 
 /// Message router for request-response correlation and notification routing.
@@ -113,12 +214,42 @@ pub struct MessageRouter {
     /// Channel for broadcasting notifications to subscribers.
     notification_tx: broadcast::Sender<JsonRpcNotification>,
 
+    /// Per-subscription signal channels.
+    demux: Arc<SubscriptionDemux>,
+
+    /// Handlers for hub-initiated requests.
+    request_handlers: Arc<RequestHandlers>,
+
+    /// Round-trip time statistics from the keepalive heartbeat.
+    rtt_stats: Arc<StdMutex<RttStats>>,
+
+    /// Set when the heartbeat declares the connection dead.
+    connection_dead: Arc<AtomicBool>,
+
+    /// Set to reconnect on purpose, such as to re-authenticate.
+    reconnect_requested: Arc<AtomicBool>,
+
+    /// Channel for connection lifecycle events.
+    events_tx: broadcast::Sender<ConnectionEvent>,
+
     /// Handle to the background receive task.
     receive_task: Option<JoinHandle<()>>,
 
+    /// Handle to the keepalive heartbeat task.
+    heartbeat_task: Option<JoinHandle<()>>,
+
     /// Shutdown signal sender.
     shutdown_tx: Option<broadcast::Sender<()>>,
 
+    /// Hub endpoints to reconnect to, if failover is configured.
+    endpoints: Option<Arc<StdMutex<HubEndpoints>>>,
+
+    /// Bearer tokens to reconnect with, if a token provider is configured.
+    tokens: Option<Arc<TokenCache>>,
+
+    /// Interceptors around requests and notifications.
+    interceptors: InterceptorChain,
+
     /// Router configuration.
     config: RouterConfig,
 }
@@ -138,16 +269,63 @@ impl MessageRouter {
     pub fn new(transport: Box<dyn Transport>, config: RouterConfig) -> Self {
         let (notification_tx, _) = broadcast::channel(config.notification_channel_capacity);
 
+        let transport = Arc::new(Mutex::new(transport));
+
         Self {
             tracker: Arc::new(RequestTracker::new()),
-            transport: Arc::new(Mutex::new(transport)),
+            request_handlers: Arc::new(RequestHandlers::new(Arc::clone(&transport))),
+            transport,
             notification_tx,
+            demux: Arc::new(SubscriptionDemux::new(config.subscription_channel_capacity)),
+            rtt_stats: Arc::new(StdMutex::new(RttStats::default())),
+            connection_dead: Arc::new(AtomicBool::new(false)),
+            reconnect_requested: Arc::new(AtomicBool::new(false)),
+            events_tx: broadcast::channel(16).0,
             receive_task: None,
+            heartbeat_task: None,
             shutdown_tx: None,
+            endpoints: None,
+            tokens: None,
+            interceptors: InterceptorChain::default(),
             config,
         }
     }
 
+    /// Reconnect across `endpoints`, failing over between them.
+    ///
+    /// The transport must currently be connected to `endpoints.current()`.
+    pub(crate) fn with_endpoints(mut self, endpoints: HubEndpoints) -> Self {
+        self.endpoints = Some(Arc::new(StdMutex::new(endpoints)));
+        self
+    }
+
+    /// Reconnect with a bearer token from `tokens`.
+    ///
+    /// The transport must already be connected with the current token.
+    pub(crate) fn with_tokens(mut self, tokens: Arc<TokenCache>) -> Self {
+        self.tokens = Some(tokens);
+        self
+    }
+
+    /// Pass requests and notifications through `interceptors`, after any
+    /// added before.
+    ///
+    /// Must be called before [`start`](Self::start).
+    pub fn with_interceptors(
+        mut self,
+        interceptors: impl IntoIterator<Item = Arc<dyn Interceptor>>,
+    ) -> Self {
+        self.interceptors.extend(interceptors);
+        self
+    }
+
+    /// Returns the URL of the hub in use, if failover is configured.
+    pub fn hub_url(&self) -> Option<String> {
+        self.endpoints
+            .as_ref()
+            .map(|endpoints| endpoints.lock().unwrap().current().to_string())
+    }
+
     /// Start the router's background receive task.
     ///
     /// This begins continuously receiving messages from the transport and
@@ -162,7 +340,19 @@ impl MessageRouter {
         }
 
         let (shutdown_tx, shutdown_rx) = broadcast::channel(1);
-        self.shutdown_tx = Some(shutdown_tx);
+        self.shutdown_tx = Some(shutdown_tx.clone());
+
+        if let Some(interval) = self.config.keepalive_interval {
+            self.heartbeat_task = Some(tokio::spawn(heartbeat::run(
+                Arc::clone(&self.tracker),
+                Arc::clone(&self.transport),
+                Arc::clone(&self.rtt_stats),
+                Arc::clone(&self.connection_dead),
+                interval,
+                self.config.max_missed_pongs,
+                shutdown_tx.subscribe(),
+            )));
+        }
 
         let task_handle = self.spawn_receive_task(shutdown_rx);
         self.receive_task = Some(task_handle);
@@ -184,14 +374,19 @@ impl MessageRouter {
             let _ = tx.send(());
         }
 
-        // Wait for background task to complete
+        // Wait for background tasks to complete
+        if let Some(handle) = self.heartbeat_task.take() {
+            handle.abort();
+            let _ = handle.await;
+        }
         if let Some(handle) = self.receive_task.take() {
             handle.abort();
             let _ = handle.await;
         }
 
-        // Clear all pending requests
+        // Clear all pending requests and close subscription channels
         self.tracker.clear().await;
+        self.demux.clear().await;
 
         tracing::info!("Message router stopped");
     }
@@ -250,61 +445,12 @@ impl MessageRouter {
         params: Option<serde_json::Value>,
         timeout: Duration,
     ) -> RouterResult<JsonRpcResponse> {
-        let method = method.into();
-
-        // Generate unique request ID
-        let request_id = self.tracker.next_id();
-
-        // Create the request
-        let request = JsonRpcRequest::new(request_id.clone(), method.clone(), params);
-        let message = JsonRpcMessage::Request(request);
-
-        // Create response channel
-        let (tx, rx) = oneshot::channel();
-
-        // Register as pending before sending
-        self.tracker.register(request_id.clone(), tx).await;
-
-        tracing::debug!("Sending request: method={}, id={:?}", method, request_id);
-
-        // Send via transport
-        let send_result = {
-            let mut transport = self.transport.lock().await;
-            transport.send(message).await
-        };
-
-        if let Err(e) = send_result {
-            // Remove pending request on send failure
-            self.tracker.cancel(&request_id).await;
-            tracing::error!("Failed to send request: {}", e);
-            return Err(e);
-        }
-
-        // Wait for response with timeout
-        match tokio::time::timeout(timeout, rx).await {
-            Ok(Ok(response)) => {
-                tracing::debug!("Received response for id={:?}", request_id);
-                Ok(response)
-            }
-            Ok(Err(_)) => {
-                // Channel closed (request was cancelled during shutdown)
-                tracing::debug!("Request cancelled: id={:?}", request_id);
-                Err(ClientError::RequestCancelled)
-            }
-            Err(_) => {
-                // Timeout - remove from pending
-                self.tracker.cancel(&request_id).await;
-                tracing::warn!(
-                    "Request timeout after {:?}: method={}, id={:?}",
-                    timeout,
-                    method,
-                    request_id
-                );
-                Err(ClientError::RequestTimeout {
-                    timeout_ms: timeout.as_millis() as u64,
-                })
-            }
-        }
+        let request = JsonRpcRequest::new(self.tracker.next_id(), method.into(), params);
+        self.interceptors
+            .send(request, |request| {
+                send_built_request(&self.tracker, &self.transport, request, timeout)
+            })
+            .await
     }
 
     /// Send a notification (fire-and-forget).
@@ -361,6 +507,63 @@ impl MessageRouter {
         self.notification_tx.subscribe()
     }
 
+    /// Register a handler for hub-initiated requests to `method`.
+    ///
+    /// Replaces any previous handler for the method, including the default
+    /// `cauce.ping` handler. Requests for methods without a handler are
+    /// answered with a "Method not found" error.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// router.on_request("cauce.subscription.request", |params: Option<Value>| async move {
+    ///     Ok(json!({ "approved": true }))
+    /// });
+    /// ```
+    pub fn on_request(&self, method: impl Into<String>, handler: impl RequestHandler) {
+        self.request_handlers.insert(method.into(), Arc::new(handler));
+    }
+
+    /// Remove the handler for hub-initiated requests to `method`.
+    ///
+    /// Returns `true` if a handler was registered.
+    pub fn remove_request_handler(&self, method: &str) -> bool {
+        self.request_handlers.remove(method)
+    }
+
+    /// Register a subscription for signal delivery.
+    ///
+    /// Returns the bounded channel that `cauce.signal` deliveries for this
+    /// subscription are routed into, starting at `status`.
+    pub(crate) async fn register_subscription(
+        &self,
+        subscription_id: &str,
+        topics: Vec<String>,
+        status: SubscriptionStatus,
+    ) -> SubscriptionChannel {
+        self.demux.register(subscription_id, topics, status).await
+    }
+
+    /// Unregister a subscription, closing its signal channel.
+    pub(crate) async fn unregister_subscription(&self, subscription_id: &str) -> bool {
+        self.demux.unregister(subscription_id).await
+    }
+
+    /// Wait for the delivery of a signal carrying `correlation_id`.
+    ///
+    /// The matching delivery bypasses subscription channels.
+    pub(crate) async fn expect_reply(
+        &self,
+        correlation_id: &str,
+    ) -> oneshot::Receiver<SignalDelivery> {
+        self.demux.expect_reply(correlation_id).await
+    }
+
+    /// Stop waiting for a correlated reply.
+    pub(crate) async fn cancel_reply(&self, correlation_id: &str) {
+        self.demux.cancel_reply(correlation_id).await
+    }
+
     /// Get the current connection state from the transport.
     pub async fn connection_state(&self) -> ConnectionState {
         self.transport.lock().await.state()
@@ -373,6 +576,59 @@ impl MessageRouter {
         self.tracker.pending_count().await
     }
 
+    /// Send a `cauce.ping` and return the round-trip time.
+    ///
+    /// The measurement is also recorded in [`rtt_stats`](Self::rtt_stats).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the ping could not be sent or timed out.
+    pub async fn ping(&self) -> RouterResult<Duration> {
+        let rtt = heartbeat::ping(&self.tracker, &self.transport, self.config.request_timeout).await?;
+        self.rtt_stats.lock().unwrap().record(rtt);
+        Ok(rtt)
+    }
+
+    /// Get a snapshot of the keepalive round-trip time statistics.
+    pub fn rtt_stats(&self) -> RttStats {
+        self.rtt_stats.lock().unwrap().clone()
+    }
+
+    /// Subscribe to connection lifecycle events.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let mut events = router.connection_events();
+    /// while let Ok(event) = events.recv().await {
+    ///     if let ConnectionEvent::Reconnected { .. } = event {
+    ///         // re-run the hello handshake
+    ///     }
+    /// }
+    /// ```
+    pub fn connection_events(&self) -> broadcast::Receiver<ConnectionEvent> {
+        self.events_tx.subscribe()
+    }
+
+    /// Get a cloneable handle for sending requests from background tasks.
+    pub(crate) fn handle(&self) -> RouterHandle {
+        RouterHandle {
+            tracker: Arc::clone(&self.tracker),
+            transport: Arc::clone(&self.transport),
+            demux: Arc::clone(&self.demux),
+            reconnect_requested: Arc::clone(&self.reconnect_requested),
+            interceptors: self.interceptors.clone(),
+            request_timeout: self.config.request_timeout,
+        }
+    }
+
+    /// Get the number of subscriptions with an open signal channel.
+    ///
+    /// Useful for debugging and metrics.
+    pub async fn subscription_channels(&self) -> usize {
+        self.demux.subscription_count().await
+    }
+
     /// Get mutable access to the underlying transport.
     ///
     /// This can be used to connect/disconnect the transport.
@@ -386,6 +642,16 @@ impl MessageRouter {
         let transport = Arc::clone(&self.transport);
         let tracker = Arc::clone(&self.tracker);
         let notification_tx = self.notification_tx.clone();
+        let demux = Arc::clone(&self.demux);
+        let request_handlers = Arc::clone(&self.request_handlers);
+        let connection_dead = Arc::clone(&self.connection_dead);
+        let reconnect_requested = Arc::clone(&self.reconnect_requested);
+        let rtt_stats = Arc::clone(&self.rtt_stats);
+        let events_tx = self.events_tx.clone();
+        let reconnect = self.config.reconnect.clone();
+        let endpoints = self.endpoints.clone();
+        let tokens = self.tokens.clone();
+        let interceptors = self.interceptors.clone();
 
         tokio::spawn(async move {
             tracing::debug!("Message router receive task started");
@@ -397,45 +663,170 @@ impl MessageRouter {
                     break;
                 }
 
-                // Receive next message from transport
-                let message_result = {
-                    let mut transport = transport.lock().await;
+                // The heartbeat flags half-open connections that never error out
+                let lost_reason = if connection_dead.load(Ordering::SeqCst) {
+                    let _ = transport.lock().await.disconnect().await;
+                    Some("keepalive pings unanswered".to_string())
+                } else if reconnect_requested.swap(false, Ordering::SeqCst) {
+                    let _ = transport.lock().await.disconnect().await;
+                    Some("reconnect requested".to_string())
+                } else {
+                    // Receive next message from transport
+                    let message_result = {
+                        let mut transport = transport.lock().await;
+
+                        // Use a short timeout to allow checking shutdown signal periodically
+                        tokio::time::timeout(Duration::from_millis(100), transport.receive()).await
+                    };
+
+                    match message_result {
+                        Ok(Ok(Some(message))) => {
+                            // Route the message
+                            Self::route_message(
+                                message,
+                                &tracker,
+                                &notification_tx,
+                                &demux,
+                                &request_handlers,
+                                &interceptors,
+                            )
+                            .await;
+                            None
+                        }
+                        Ok(Ok(None)) => {
+                            // Connection closed
+                            tracing::info!("Transport connection closed");
+                            Some("transport connection closed".to_string())
+                        }
+                        Ok(Err(e)) => {
+                            // Transport error
+                            tracing::error!("Transport receive error: {}", e);
 
-                    // Use a short timeout to allow checking shutdown signal periodically
-                    tokio::time::timeout(Duration::from_millis(100), transport.receive()).await
+                            // Only connection errors end the connection
+                            e.should_reconnect().then(|| e.to_string())
+                        }
+                        Err(_) => {
+                            // Timeout - continue loop to check shutdown
+                            continue;
+                        }
+                    }
+                };
+
+                let Some(reason) = lost_reason else {
+                    continue;
                 };
 
-                match message_result {
-                    Ok(Ok(Some(message))) => {
-                        // Route the message
-                        Self::route_message(message, &tracker, &notification_tx).await;
+                let _ = events_tx.send(ConnectionEvent::Disconnected {
+                    reason: reason.clone(),
+                });
+
+                let Some(reconnect) = reconnect.as_ref().filter(|r| r.enabled) else {
+                    tracing::info!("Stopping receive task: {}", reason);
+                    break;
+                };
+
+                // Pending requests will never be answered on a new connection
+                tracker.clear().await;
+
+                if !Self::reconnect(
+                    &transport,
+                    reconnect,
+                    endpoints.as_deref(),
+                    tokens.as_deref(),
+                    &events_tx,
+                    &mut shutdown_rx,
+                )
+                .await
+                {
+                    break;
+                }
+                connection_dead.store(false, Ordering::SeqCst);
+                rtt_stats.lock().unwrap().missed_pongs = 0;
+            }
+
+            tracing::debug!("Message router receive task stopped");
+        })
+    }
+
+    /// Reconnect the transport using the given backoff policy.
+    ///
+    /// With `endpoints`, each attempt goes to the currently selected hub and
+    /// repeated failures fail over to another one. With `tokens`, each
+    /// attempt authenticates with the current bearer token.
+    ///
+    /// Returns `true` once the transport is connected again, or `false` if
+    /// attempts were exhausted or shutdown was requested.
+    async fn reconnect(
+        transport: &Mutex<Box<dyn Transport>>,
+        config: &ReconnectConfig,
+        endpoints: Option<&StdMutex<HubEndpoints>>,
+        tokens: Option<&TokenCache>,
+        events_tx: &broadcast::Sender<ConnectionEvent>,
+        shutdown_rx: &mut broadcast::Receiver<()>,
+    ) -> bool {
+        let mut attempt = 0;
+        let previous_url = endpoints.map(|endpoints| {
+            let mut endpoints = endpoints.lock().unwrap();
+            let previous = endpoints.current().to_string();
+            endpoints.reselect();
+            previous
+        });
+
+        while config.should_attempt(attempt) {
+            let delay = config.delay_for_attempt(attempt);
+            attempt += 1;
+            let url = endpoints.map(|endpoints| endpoints.lock().unwrap().current().to_string());
+            tracing::info!(attempt, delay_ms = delay.as_millis() as u64, url = ?url, "Reconnecting");
+
+            tokio::select! {
+                _ = shutdown_rx.recv() => return false,
+                _ = tokio::time::sleep(delay) => {}
+            }
+
+            let auth = match tokens {
+                Some(tokens) => match tokens.token().await {
+                    Ok(token) => Some(AuthConfig::bearer(token)),
+                    Err(e) => {
+                        tracing::warn!(attempt, error = %e, "Failed to fetch bearer token");
+                        continue;
                     }
-                    Ok(Ok(None)) => {
-                        // Connection closed
-                        tracing::info!("Transport connection closed, stopping receive task");
-                        break;
+                },
+                None => None,
+            };
+
+            let mut transport = transport.lock().await;
+            let _ = transport.disconnect().await;
+            if let Some(url) = &url {
+                transport.set_hub_url(url);
+            }
+            if let Some(auth) = auth {
+                transport.set_auth(auth);
+            }
+            match transport.connect().await {
+                Ok(()) => {
+                    if let Some(endpoints) = endpoints {
+                        endpoints.lock().unwrap().record_success();
                     }
-                    Ok(Err(e)) => {
-                        // Transport error
-                        tracing::error!("Transport receive error: {}", e);
-
-                        // On connection errors, break the loop
-                        if e.should_reconnect() {
-                            tracing::info!(
-                                "Stopping receive task due to transport error (reconnectable)"
-                            );
-                            break;
-                        }
+                    if let Some(url) = url.filter(|url| Some(url) != previous_url.as_ref()) {
+                        tracing::info!(url = %url, "Failed over to another hub");
+                        let _ = events_tx.send(ConnectionEvent::FailedOver { url });
                     }
-                    Err(_) => {
-                        // Timeout - continue loop to check shutdown
-                        continue;
+                    tracing::info!(attempt, "Reconnected");
+                    let _ = events_tx.send(ConnectionEvent::Reconnected { attempt });
+                    return true;
+                }
+                Err(e) => {
+                    tracing::warn!(attempt, error = %e, "Reconnection attempt failed");
+                    if let Some(endpoints) = endpoints {
+                        endpoints.lock().unwrap().record_failure();
                     }
                 }
             }
+        }
 
-            tracing::debug!("Message router receive task stopped");
-        })
+        tracing::error!(attempts = attempt, "Giving up on reconnection");
+        let _ = events_tx.send(ConnectionEvent::ReconnectFailed { attempts: attempt });
+        false
     }
 
     /// Route an incoming message to the appropriate handler.
@@ -443,6 +834,9 @@ impl MessageRouter {
         message: JsonRpcMessage,
         tracker: &Arc<RequestTracker>,
         notification_tx: &broadcast::Sender<JsonRpcNotification>,
+        demux: &SubscriptionDemux,
+        request_handlers: &Arc<RequestHandlers>,
+        interceptors: &InterceptorChain,
     ) {
         match message {
             JsonRpcMessage::Response(response) => {
@@ -456,26 +850,190 @@ impl MessageRouter {
                 }
             }
 
-            JsonRpcMessage::Notification(notification) => {
+            JsonRpcMessage::Notification(mut notification) => {
                 // Broadcast notification to all subscribers
                 tracing::debug!("Routing notification: method={}", notification.method());
 
+                if !interceptors.receive(&mut notification).await {
+                    return;
+                }
+
+                // Demultiplex signal deliveries into per-subscription channels
+                if notification.method() == METHOD_SIGNAL {
+                    match notification
+                        .params()
+                        .map(|params| serde_json::from_value::<SignalDelivery>(params.clone()))
+                    {
+                        Some(Ok(delivery)) => demux.route(delivery).await,
+                        Some(Err(e)) => tracing::warn!("Failed to parse signal delivery: {}", e),
+                        None => tracing::warn!("Signal notification without params"),
+                    }
+                }
+
+                // Track approval, denial and revocation of subscriptions
+                if notification.method() == METHOD_SUBSCRIPTION_STATUS {
+                    match notification.params().map(|params| {
+                        serde_json::from_value::<SubscriptionStatusNotification>(params.clone())
+                    }) {
+                        Some(Ok(update)) => {
+                            demux
+                                .update_status(
+                                    &update.subscription_id,
+                                    StatusUpdate {
+                                        status: update.status,
+                                        reason: update.reason,
+                                    },
+                                )
+                                .await
+                        }
+                        Some(Err(e)) => {
+                            tracing::warn!("Failed to parse subscription status: {}", e)
+                        }
+                        None => tracing::warn!("Subscription status notification without params"),
+                    }
+                }
+
                 // Ignore error if no subscribers (or all have lagged too far behind)
                 let _ = notification_tx.send(notification);
             }
 
             JsonRpcMessage::Request(request) => {
-                // Unexpected: clients don't normally receive requests from the hub
-                tracing::warn!(
-                    "Received unexpected request from hub: method={}, id={:?}",
-                    request.method(),
-                    request.id()
-                );
+                tracing::debug!("Routing hub request: method={}", request.method());
+                request_handlers.dispatch(request);
             }
         }
     }
 }
 
+/// A cloneable, non-owning handle to a running [`MessageRouter`].
+///
+/// Lets background tasks (such as session restoration after a reconnect)
+/// send requests without borrowing the router itself.
+#[derive(Clone)]
+pub(crate) struct RouterHandle {
+    tracker: Arc<RequestTracker>,
+    transport: Arc<Mutex<Box<dyn Transport>>>,
+    demux: Arc<SubscriptionDemux>,
+    reconnect_requested: Arc<AtomicBool>,
+    interceptors: InterceptorChain,
+    request_timeout: Duration,
+}
+
+impl RouterHandle {
+    /// Send a request with the router's default timeout.
+    pub async fn send_request(
+        &self,
+        method: impl Into<String>,
+        params: Option<serde_json::Value>,
+    ) -> RouterResult<JsonRpcResponse> {
+        let request = JsonRpcRequest::new(self.tracker.next_id(), method.into(), params);
+        self.interceptors
+            .send(request, |request| {
+                send_built_request(&self.tracker, &self.transport, request, self.request_timeout)
+            })
+            .await
+    }
+
+    /// Route deliveries addressed to `hub_id` into the channel registered
+    /// as `subscription_id`.
+    pub async fn alias_subscription(&self, hub_id: &str, subscription_id: &str) {
+        self.demux.alias(hub_id, subscription_id).await;
+    }
+
+    /// Apply a subscription status reported outside a status notification,
+    /// such as in a subscribe response.
+    pub async fn update_subscription_status(&self, subscription_id: &str, update: StatusUpdate) {
+        self.demux.update_status(subscription_id, update).await;
+    }
+
+    /// Returns the final status of a subscription the hub has ended.
+    pub async fn ended_subscription(&self, subscription_id: &str) -> Option<StatusUpdate> {
+        self.demux.ended(subscription_id).await
+    }
+
+    /// Drop the connection and reconnect under the reconnection policy.
+    ///
+    /// Emits the usual [`ConnectionEvent::Disconnected`] and
+    /// [`ConnectionEvent::Reconnected`] events.
+    pub fn request_reconnect(&self) {
+        self.reconnect_requested.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Send a request over `transport` and wait for the correlated response.
+///
+/// Used by the keepalive heartbeat, which runs without access to the router
+/// itself and bypasses interceptors.
+async fn send_request_via(
+    tracker: &RequestTracker,
+    transport: &Mutex<Box<dyn Transport>>,
+    method: impl Into<String>,
+    params: Option<serde_json::Value>,
+    timeout: Duration,
+) -> RouterResult<JsonRpcResponse> {
+    let request = JsonRpcRequest::new(tracker.next_id(), method.into(), params);
+    send_built_request(tracker, transport, request, timeout).await
+}
+
+/// Send `request` over `transport` and wait for the correlated response.
+async fn send_built_request(
+    tracker: &RequestTracker,
+    transport: &Mutex<Box<dyn Transport>>,
+    request: JsonRpcRequest,
+    timeout: Duration,
+) -> RouterResult<JsonRpcResponse> {
+    let method = request.method.clone();
+    let request_id = request.id.clone();
+    let message = JsonRpcMessage::Request(request);
+
+    // Create response channel
+    let (tx, rx) = oneshot::channel();
+
+    // Register as pending before sending
+    tracker.register(request_id.clone(), tx).await;
+
+    tracing::debug!("Sending request: method={}, id={:?}", method, request_id);
+
+    // Send via transport
+    let send_result = {
+        let mut transport = transport.lock().await;
+        transport.send(message).await
+    };
+
+    if let Err(e) = send_result {
+        // Remove pending request on send failure
+        tracker.cancel(&request_id).await;
+        tracing::error!("Failed to send request: {}", e);
+        return Err(e);
+    }
+
+    // Wait for response with timeout
+    match tokio::time::timeout(timeout, rx).await {
+        Ok(Ok(response)) => {
+            tracing::debug!("Received response for id={:?}", request_id);
+            Ok(response)
+        }
+        Ok(Err(_)) => {
+            // Channel closed (request was cancelled during shutdown)
+            tracing::debug!("Request cancelled: id={:?}", request_id);
+            Err(ClientError::RequestCancelled)
+        }
+        Err(_) => {
+            // Timeout - remove from pending
+            tracker.cancel(&request_id).await;
+            tracing::warn!(
+                "Request timeout after {:?}: method={}, id={:?}",
+                timeout,
+                method,
+                request_id
+            );
+            Err(ClientError::RequestTimeout {
+                timeout_ms: timeout.as_millis() as u64,
+            })
+        }
+    }
+}
+
 impl Drop for MessageRouter {
     fn drop(&mut self) {
         // Signal shutdown
@@ -483,7 +1041,10 @@ impl Drop for MessageRouter {
             let _ = tx.send(());
         }
 
-        // Abort receive task
+        // Abort background tasks
+        if let Some(handle) = &self.heartbeat_task {
+            handle.abort();
+        }
         if let Some(handle) = &self.receive_task {
             handle.abort();
         }
@@ -505,16 +1066,19 @@ mod tests {
         let config = RouterConfig::default();
         assert_eq!(config.request_timeout, Duration::from_secs(60));
         assert_eq!(config.notification_channel_capacity, 1000);
+        assert_eq!(config.subscription_channel_capacity, 1000);
     }
 
     #[test]
     fn test_router_config_builder() {
         let config = RouterConfig::default()
             .with_request_timeout(Duration::from_secs(30))
-            .with_notification_capacity(500);
+            .with_notification_capacity(500)
+            .with_subscription_capacity(50);
 
         assert_eq!(config.request_timeout, Duration::from_secs(30));
         assert_eq!(config.notification_channel_capacity, 500);
+        assert_eq!(config.subscription_channel_capacity, 50);
     }
 
     #[test]
@@ -611,10 +1175,39 @@ mod tests {
         assert_eq!(router.pending_requests().await, 0);
     }
 
+    #[tokio::test]
+    async fn test_register_unregister_subscription() {
+        let mut router = make_router();
+        let mut channel = router
+            .register_subscription(
+                "sub_1",
+                vec!["signal.**".to_string()],
+                SubscriptionStatus::Active,
+            )
+            .await;
+        assert_eq!(router.subscription_channels().await, 1);
+
+        assert!(router.unregister_subscription("sub_1").await);
+        assert_eq!(router.subscription_channels().await, 0);
+        assert!(channel.rx.recv().await.is_none());
+
+        let mut channel = router
+            .register_subscription(
+                "sub_2",
+                vec!["signal.**".to_string()],
+                SubscriptionStatus::Active,
+            )
+            .await;
+        router.stop().await;
+        assert!(channel.rx.recv().await.is_none());
+    }
+
     #[tokio::test]
     async fn test_route_response_to_pending() {
         let tracker = Arc::new(RequestTracker::new());
         let (notification_tx, _) = broadcast::channel(10);
+        let demux = SubscriptionDemux::new(10);
+        let handlers = test_request_handlers();
 
         let request_id = tracker.next_id();
         let (tx, rx) = oneshot::channel();
@@ -625,7 +1218,15 @@ mod tests {
         let message = JsonRpcMessage::Response(response);
 
         // Route the message
-        MessageRouter::route_message(message, &tracker, &notification_tx).await;
+        MessageRouter::route_message(
+            message,
+            &tracker,
+            &notification_tx,
+            &demux,
+            &handlers,
+            &InterceptorChain::default(),
+        )
+        .await;
 
         // Should have completed the pending request
         let received = rx.await.expect("should receive response");
@@ -636,15 +1237,376 @@ mod tests {
     async fn test_route_notification_to_subscribers() {
         let tracker = Arc::new(RequestTracker::new());
         let (notification_tx, mut rx) = broadcast::channel(10);
+        let demux = SubscriptionDemux::new(10);
+        let handlers = test_request_handlers();
 
         let notification = JsonRpcNotification::new("cauce.signal".to_string(), None);
         let message = JsonRpcMessage::Notification(notification);
 
         // Route the message
-        MessageRouter::route_message(message, &tracker, &notification_tx).await;
+        MessageRouter::route_message(
+            message,
+            &tracker,
+            &notification_tx,
+            &demux,
+            &handlers,
+            &InterceptorChain::default(),
+        )
+        .await;
 
         // Subscriber should receive it
         let received = rx.recv().await.expect("should receive notification");
         assert_eq!(received.method(), "cauce.signal");
     }
+
+    #[tokio::test]
+    async fn test_route_signal_to_subscription_channel() {
+        let tracker = Arc::new(RequestTracker::new());
+        let (notification_tx, _) = broadcast::channel(10);
+        let demux = SubscriptionDemux::new(10);
+        let handlers = test_request_handlers();
+        let mut channel = demux
+            .register("sub_1", vec!["signal.**".to_string()], SubscriptionStatus::Active)
+            .await;
+
+        let signal = cauce_core::Signal {
+            id: "sig_1".to_string(),
+            version: "1.0".to_string(),
+            timestamp: chrono::Utc::now(),
+            source: cauce_core::Source::new("test", "adapter-1", "native-1"),
+            topic: cauce_core::Topic::new_unchecked("signal.test"),
+            payload: cauce_core::Payload::new(serde_json::json!({}), "application/json"),
+            metadata: None,
+            encrypted: None,
+            signature: None,
+        };
+        let delivery = SignalDelivery::new("signal.test", signal).with_subscription_id("sub_1");
+        let notification = JsonRpcNotification::new(
+            METHOD_SIGNAL.to_string(),
+            Some(serde_json::to_value(&delivery).unwrap()),
+        );
+
+        MessageRouter::route_message(
+            JsonRpcMessage::Notification(notification),
+            &tracker,
+            &notification_tx,
+            &demux,
+            &handlers,
+            &InterceptorChain::default(),
+        )
+        .await;
+
+        assert_eq!(channel.rx.try_recv().unwrap().signal.id, "sig_1");
+    }
+
+    fn test_request_handlers() -> Arc<RequestHandlers> {
+        let transport: Box<dyn Transport> = Box::new(MockTransport::new());
+        Arc::new(RequestHandlers::new(Arc::new(Mutex::new(transport))))
+    }
+
+    /// Start a router whose transport blocks until messages are pushed.
+    async fn start_waiting_router() -> (MessageRouter, crate::transport::mock::MockTransportHandle)
+    {
+        let mut transport = MockTransport::new().wait_when_empty();
+        transport.connect().await.expect("connect");
+        let handle = transport.handle();
+        let mut router = MessageRouter::new(Box::new(transport), RouterConfig::default());
+        router.start().expect("should start");
+        (router, handle)
+    }
+
+    async fn answer_hub_request(
+        handle: &crate::transport::mock::MockTransportHandle,
+        method: &str,
+    ) -> JsonRpcResponse {
+        let request = JsonRpcRequest::new(
+            cauce_core::RequestId::from_string("hub-1"),
+            method,
+            Some(serde_json::json!({"client_id": "client-1"})),
+        );
+        handle.push_receive(JsonRpcMessage::Request(request)).await;
+
+        let sent = tokio::time::timeout(Duration::from_secs(2), async {
+            loop {
+                if let Some(message) = handle.pop_sent().await {
+                    return message;
+                }
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .expect("response sent");
+        match sent {
+            JsonRpcMessage::Response(response) => response,
+            other => panic!("expected response, got {:?}", other),
+        }
+    }
+
+    /// Tags outgoing requests and drops `test.private` notifications.
+    struct Tagger;
+
+    #[async_trait::async_trait]
+    impl Interceptor for Tagger {
+        async fn on_request(&self, request: &mut JsonRpcRequest) -> RouterResult<()> {
+            request.params = Some(serde_json::json!({ "tagged": true }));
+            Ok(())
+        }
+
+        async fn on_notification(
+            &self,
+            notification: &mut JsonRpcNotification,
+        ) -> RouterResult<()> {
+            if notification.method == "test.private" {
+                return Err(ClientError::config_error("private"));
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_interceptors_wrap_requests_and_notifications() {
+        let mut transport = MockTransport::new().wait_when_empty();
+        transport.connect().await.expect("connect");
+        let handle = transport.handle();
+        let mut router = MessageRouter::new(Box::new(transport), RouterConfig::default())
+            .with_interceptors([Arc::new(Tagger) as Arc<dyn Interceptor>]);
+        let mut notifications = router.subscribe_notifications();
+        router.start().expect("should start");
+
+        let _ = router
+            .send_request_with_timeout("test.method", None, Duration::from_millis(10))
+            .await;
+        match handle.pop_sent().await {
+            Some(JsonRpcMessage::Request(request)) => {
+                assert_eq!(request.params, Some(serde_json::json!({ "tagged": true })));
+            }
+            other => panic!("expected request, got {:?}", other),
+        }
+
+        for method in ["test.private", "test.public"] {
+            let notification = JsonRpcNotification::new(method.to_string(), None);
+            handle.push_receive(JsonRpcMessage::Notification(notification)).await;
+        }
+        let received = tokio::time::timeout(Duration::from_secs(2), notifications.recv())
+            .await
+            .expect("notification delivered")
+            .unwrap();
+        assert_eq!(received.method(), "test.public");
+        router.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_hub_ping_answered_by_default() {
+        let (mut router, handle) = start_waiting_router().await;
+
+        let response = answer_hub_request(&handle, cauce_core::METHOD_PING).await;
+        assert!(response.is_success());
+        assert!(response.result().unwrap()["timestamp"].is_string());
+        router.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_hub_request_unknown_method() {
+        let (mut router, handle) = start_waiting_router().await;
+
+        let response = answer_hub_request(&handle, "cauce.subscription.request").await;
+        assert_eq!(response.error_obj().unwrap().code, -32601);
+        router.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_hub_request_custom_handler() {
+        let (mut router, handle) = start_waiting_router().await;
+        router.on_request("cauce.subscription.request", |params: Option<serde_json::Value>| async move {
+            let client_id = params.unwrap_or_default()["client_id"].clone();
+            Ok(serde_json::json!({"approved": true, "client_id": client_id}))
+        });
+
+        let response = answer_hub_request(&handle, "cauce.subscription.request").await;
+        let result = response.result().unwrap();
+        assert_eq!(result["approved"], true);
+        assert_eq!(result["client_id"], "client-1");
+
+        assert!(router.remove_request_handler("cauce.subscription.request"));
+        assert!(!router.remove_request_handler("cauce.subscription.request"));
+        router.stop().await;
+    }
+
+    /// Answer every ping sent through `handle` until the test ends.
+    fn spawn_pong_responder(handle: crate::transport::mock::MockTransportHandle) {
+        tokio::spawn(async move {
+            loop {
+                match handle.pop_sent().await {
+                    Some(JsonRpcMessage::Request(request)) => {
+                        let response = JsonRpcResponse::success(
+                            request.id().clone(),
+                            serde_json::json!({"timestamp": chrono::Utc::now()}),
+                        );
+                        handle.push_receive(response.into()).await;
+                    }
+                    Some(_) => {}
+                    None => tokio::time::sleep(Duration::from_millis(5)).await,
+                }
+            }
+        });
+    }
+
+    async fn next_event(events: &mut broadcast::Receiver<ConnectionEvent>) -> ConnectionEvent {
+        tokio::time::timeout(Duration::from_secs(2), events.recv())
+            .await
+            .expect("event within timeout")
+            .expect("event channel open")
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_records_rtt() {
+        let mut transport = MockTransport::new().wait_when_empty();
+        transport.connect().await.unwrap();
+        spawn_pong_responder(transport.handle());
+
+        let config = RouterConfig::default().with_keepalive(Duration::from_millis(20), 3);
+        let mut router = MessageRouter::new(Box::new(transport), config);
+        router.start().unwrap();
+
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(2);
+        while router.rtt_stats().samples < 2 && tokio::time::Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        let stats = router.rtt_stats();
+        assert!(stats.samples >= 2);
+        assert!(stats.last.is_some());
+        assert_eq!(stats.missed_pongs, 0);
+
+        router.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_missed_pongs_disconnect() {
+        let mut transport = MockTransport::new().wait_when_empty();
+        transport.connect().await.unwrap();
+
+        let config = RouterConfig::default().with_keepalive(Duration::from_millis(20), 2);
+        let mut router = MessageRouter::new(Box::new(transport), config);
+        let mut events = router.connection_events();
+        router.start().unwrap();
+
+        assert!(matches!(
+            next_event(&mut events).await,
+            ConnectionEvent::Disconnected { .. }
+        ));
+        assert!(router.rtt_stats().missed_pongs >= 2);
+        assert!(!router.connection_state().await.is_connected());
+
+        router.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_after_missed_pongs() {
+        let mut transport = MockTransport::new().wait_when_empty();
+        transport.connect().await.unwrap();
+
+        let config = RouterConfig::default()
+            .with_keepalive(Duration::from_millis(20), 1)
+            .with_reconnect(
+                ReconnectConfig::default()
+                    .with_initial_delay(Duration::from_millis(10))
+                    .with_jitter(false),
+            );
+        let mut router = MessageRouter::new(Box::new(transport), config);
+        let mut events = router.connection_events();
+        router.start().unwrap();
+
+        assert!(matches!(
+            next_event(&mut events).await,
+            ConnectionEvent::Disconnected { .. }
+        ));
+        assert_eq!(
+            next_event(&mut events).await,
+            ConnectionEvent::Reconnected { attempt: 1 }
+        );
+        assert!(router.connection_state().await.is_connected());
+
+        router.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_fails_over_to_standby() {
+        let mut transport = MockTransport::new().wait_when_empty();
+        transport.connect().await.unwrap();
+        let handle = transport.handle();
+        handle.set_unreachable("ws://primary", true).await;
+
+        let reconnect = ReconnectConfig::default()
+            .with_initial_delay(Duration::from_millis(10))
+            .with_jitter(false)
+            .with_failover_after(1);
+        let endpoints = HubEndpoints::new(
+            vec!["ws://primary".to_string(), "ws://standby".to_string()],
+            &reconnect,
+        );
+        let config = RouterConfig::default()
+            .with_keepalive(Duration::from_millis(20), 1)
+            .with_reconnect(reconnect);
+        let mut router = MessageRouter::new(Box::new(transport), config).with_endpoints(endpoints);
+        let mut events = router.connection_events();
+        router.start().unwrap();
+        assert_eq!(router.hub_url().as_deref(), Some("ws://primary"));
+
+        assert!(matches!(
+            next_event(&mut events).await,
+            ConnectionEvent::Disconnected { .. }
+        ));
+        assert_eq!(
+            next_event(&mut events).await,
+            ConnectionEvent::FailedOver {
+                url: "ws://standby".to_string()
+            }
+        );
+        assert_eq!(
+            next_event(&mut events).await,
+            ConnectionEvent::Reconnected { attempt: 2 }
+        );
+        assert_eq!(handle.connected_urls().await[0], "ws://standby");
+
+        router.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_requested_reconnect_uses_fresh_token() {
+        let mut transport = MockTransport::new().wait_when_empty();
+        transport.connect().await.unwrap();
+        let handle = transport.handle();
+
+        let tokens = Arc::new(TokenCache::new(
+            Arc::new(|| async { Ok("fresh-token".to_string()) }),
+            Duration::from_secs(60),
+        ));
+        let config = RouterConfig::default().with_reconnect(
+            ReconnectConfig::default()
+                .with_initial_delay(Duration::from_millis(10))
+                .with_jitter(false),
+        );
+        let mut router = MessageRouter::new(Box::new(transport), config).with_tokens(tokens);
+        let mut events = router.connection_events();
+        router.start().unwrap();
+
+        router.handle().request_reconnect();
+        assert_eq!(
+            next_event(&mut events).await,
+            ConnectionEvent::Disconnected {
+                reason: "reconnect requested".to_string()
+            }
+        );
+        assert_eq!(
+            next_event(&mut events).await,
+            ConnectionEvent::Reconnected { attempt: 1 }
+        );
+        assert!(matches!(
+            handle.auth(),
+            Some(AuthConfig::Bearer { token }) if token == "fresh-token"
+        ));
+
+        router.stop().await;
+    }
 }
@@ -0,0 +1,705 @@
+//! An in-memory hub speaking the Cauce protocol.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use cauce_core::{
+    AckRequest, AckResponse, CauceError, E2eConfig, HelloResponse, JsonRpcError,
+    JsonRpcNotification, JsonRpcRequest, JsonRpcResponse, KeysListRequest, KeysListResponse,
+    PongParams, PublishMessage, PublishRequest, PublishResponse, RecipientKey, Signal,
+    SignalDelivery, SubscribeRequest, SubscribeResponse, SubscriptionStatus, TopicMatcher,
+    UnsubscribeRequest, UnsubscribeResponse, METHOD_ACK, METHOD_HELLO, METHOD_KEYS_LIST,
+    METHOD_PING, METHOD_PUBLISH, METHOD_SIGNAL, METHOD_SUBSCRIBE, METHOD_UNSUBSCRIBE,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::ClientError;
+use crate::transport::{ConnectionState, JsonRpcMessage, Transport, TransportResult};
+
+/// A message published to the hub.
+#[derive(Debug, Clone)]
+pub struct PublishedMessage {
+    /// Hub-assigned message ID.
+    pub message_id: String,
+
+    /// Topic the message was published to.
+    pub topic: String,
+
+    /// The published signal or action.
+    pub message: PublishMessage,
+}
+
+/// A subscription held by the hub.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HubSubscription {
+    /// Hub-assigned subscription ID.
+    pub subscription_id: String,
+
+    /// Topic patterns subscribed to.
+    pub topics: Vec<String>,
+}
+
+/// State shared between a [`MockHub`] and its handles.
+#[derive(Default)]
+struct HubState {
+    /// Messages waiting to be received by the client.
+    outbox: VecDeque<JsonRpcMessage>,
+
+    /// Every request the client has sent, in order.
+    requests: Vec<JsonRpcRequest>,
+
+    /// Active subscriptions by ID.
+    subscriptions: HashMap<String, Vec<String>>,
+
+    /// End-to-end encryption settings of subscriptions that enabled it.
+    e2e: HashMap<String, E2eConfig>,
+
+    /// Published messages, in order.
+    published: Vec<PublishedMessage>,
+
+    /// Acknowledged signal IDs, in order.
+    acked: Vec<String>,
+
+    /// Errors to answer upcoming requests with, by method.
+    failures: HashMap<String, VecDeque<CauceError>>,
+
+    /// Set to close the connection on the next receive.
+    closing: bool,
+
+    /// Counter for generated IDs.
+    next_id: u64,
+}
+
+impl HubState {
+    fn next_id(&mut self, prefix: &str) -> String {
+        self.next_id += 1;
+        format!("{}_{}", prefix, self.next_id)
+    }
+
+    /// Queue `signal` for every subscription matching `topic`.
+    fn deliver(&mut self, topic: &str, signal: &Signal) -> usize {
+        self.deliver_if(topic, signal, |_| true)
+    }
+
+    /// Queue a published `signal` for every subscription matching `topic`
+    /// that it is addressed to.
+    ///
+    /// Like a hub, an encrypted signal only goes to the subscription that
+    /// registered its recipient key.
+    fn publish(&mut self, topic: &str, signal: &Signal) -> usize {
+        let Some(recipient) = signal.encrypted.as_ref().map(|e| e.recipient_public_key.clone())
+        else {
+            return self.deliver(topic, signal);
+        };
+        let addressed: Vec<String> = self
+            .e2e
+            .iter()
+            .filter(|(_, e2e)| e2e.public_key.as_ref() == Some(&recipient))
+            .map(|(id, _)| id.clone())
+            .collect();
+        self.deliver_if(topic, signal, |id| addressed.iter().any(|a| a == id))
+    }
+
+    /// Queue `signal` for every subscription matching `topic` that passes
+    /// `filter`.
+    fn deliver_if(
+        &mut self,
+        topic: &str,
+        signal: &Signal,
+        filter: impl Fn(&str) -> bool,
+    ) -> usize {
+        let mut matching: Vec<String> = self
+            .subscriptions
+            .iter()
+            .filter(|(_, patterns)| patterns.iter().any(|p| TopicMatcher::matches(topic, p)))
+            .filter(|(id, _)| filter(id))
+            .map(|(id, _)| id.clone())
+            .collect();
+        matching.sort();
+
+        for subscription_id in &matching {
+            let delivery = SignalDelivery::new(topic, signal.clone())
+                .with_subscription_id(subscription_id.clone());
+            let notification =
+                JsonRpcNotification::new(METHOD_SIGNAL.to_string(), to_value(&delivery));
+            self.outbox
+                .push_back(JsonRpcMessage::Notification(notification));
+        }
+        matching.len()
+    }
+
+    /// Public keys of the subscriptions matching `topic` with encryption
+    /// enabled.
+    fn recipients(&self, topic: &str) -> Vec<RecipientKey> {
+        let mut recipients: Vec<RecipientKey> = self
+            .subscriptions
+            .iter()
+            .filter(|(_, patterns)| patterns.iter().any(|p| TopicMatcher::matches(topic, p)))
+            .filter_map(|(id, _)| {
+                let e2e = self.e2e.get(id)?;
+                Some(RecipientKey {
+                    subscription_id: id.clone(),
+                    public_key: e2e.public_key.clone()?,
+                    supported_algorithms: e2e.supported_algorithms.clone(),
+                })
+            })
+            .collect();
+        recipients.sort_by(|a, b| a.subscription_id.cmp(&b.subscription_id));
+        recipients
+    }
+
+    /// Answer a request from the client.
+    fn answer(&mut self, request: &JsonRpcRequest) -> Result<Value, JsonRpcError> {
+        if let Some(error) = self
+            .failures
+            .get_mut(request.method())
+            .and_then(VecDeque::pop_front)
+        {
+            return Err(error.into());
+        }
+
+        match request.method() {
+            METHOD_HELLO => {
+                let session_id = self.next_id("session");
+                ok(&HelloResponse::new(session_id, "1.0"))
+            }
+            METHOD_PING => ok(&PongParams::now()),
+            METHOD_SUBSCRIBE => {
+                let params: SubscribeRequest = params(request)?;
+                let subscription_id = self.next_id("sub");
+                self.subscriptions
+                    .insert(subscription_id.clone(), params.topics.clone());
+                if let Some(e2e) = params.e2e.filter(|e2e| e2e.enabled) {
+                    self.e2e.insert(subscription_id.clone(), e2e);
+                }
+                ok(&SubscribeResponse::new(
+                    subscription_id,
+                    SubscriptionStatus::Active,
+                    params.topics,
+                ))
+            }
+            METHOD_UNSUBSCRIBE => {
+                let params: UnsubscribeRequest = params(request)?;
+                if self.subscriptions.remove(&params.subscription_id).is_none() {
+                    return Err(CauceError::SubscriptionNotFound {
+                        id: params.subscription_id,
+                    }
+                    .into());
+                }
+                self.e2e.remove(&params.subscription_id);
+                ok(&UnsubscribeResponse::success())
+            }
+            METHOD_PUBLISH => {
+                let params: PublishRequest = params(request)?;
+                let message_id = self.next_id("msg");
+                let delivered_to = match &params.message {
+                    PublishMessage::Signal(signal) => self.publish(&params.topic, signal),
+                    PublishMessage::Action(_) => 0,
+                };
+                self.published.push(PublishedMessage {
+                    message_id: message_id.clone(),
+                    topic: params.topic,
+                    message: params.message,
+                });
+                ok(&PublishResponse::new(message_id, delivered_to as u32, 0))
+            }
+            METHOD_ACK => {
+                let params: AckRequest = params(request)?;
+                self.acked.extend(params.signal_ids.iter().cloned());
+                ok(&AckResponse::all_acknowledged(params.signal_ids))
+            }
+            METHOD_KEYS_LIST => {
+                let params: KeysListRequest = params(request)?;
+                ok(&KeysListResponse::new(self.recipients(&params.topic)))
+            }
+            method => Err(CauceError::MethodNotFound {
+                method: method.to_string(),
+            }
+            .into()),
+        }
+    }
+}
+
+fn to_value(value: &impl Serialize) -> Option<Value> {
+    Some(serde_json::to_value(value).expect("protocol types serialize"))
+}
+
+fn ok(value: &impl Serialize) -> Result<Value, JsonRpcError> {
+    Ok(serde_json::to_value(value).expect("protocol types serialize"))
+}
+
+fn params<T: DeserializeOwned>(request: &JsonRpcRequest) -> Result<T, JsonRpcError> {
+    serde_json::from_value(request.params().cloned().unwrap_or_default())
+        .map_err(|_| JsonRpcError::invalid_params())
+}
+
+/// An in-memory hub for testing clients without sockets.
+///
+/// `MockHub` implements [`Transport`] and answers the client's requests the
+/// way a hub would: `cauce.hello` opens a session, `cauce.subscribe` and
+/// `cauce.unsubscribe` manage subscriptions, `cauce.publish` records the
+/// message and delivers signals to matching subscriptions, `cauce.ack`
+/// records acknowledgements, and `cauce.keys.list` lists the public keys of
+/// subscriptions that enabled end-to-end encryption. Use a [`MockHubHandle`] to inject signals,
+/// inspect what the client did, and simulate errors.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use cauce_client_sdk::testing::MockHub;
+/// use cauce_client_sdk::{CauceClient, ClientConfig, Transport};
+///
+/// let mut hub = MockHub::new();
+/// hub.connect().await?;
+/// let handle = hub.handle();
+///
+/// let config = ClientConfig::builder("ws://mock", "my-adapter").build()?;
+/// let client = CauceClient::connect_with_transport(config, Box::new(hub)).await?;
+///
+/// let mut subscription = client.subscribe(&["signal.email.*"]).await?;
+/// handle.inject_signal("signal.email.received", signal);
+/// let received = subscription.next().await;
+/// ```
+pub struct MockHub {
+    /// Current connection state.
+    state: ConnectionState,
+
+    /// Hub state, shared with handles.
+    shared: Arc<Mutex<HubState>>,
+}
+
+/// A cloneable handle to a [`MockHub`].
+///
+/// Once the hub has been handed to a client, the handle lets tests keep
+/// injecting signals and inspecting what the client sent.
+#[derive(Clone)]
+pub struct MockHubHandle {
+    /// Hub state, shared with the hub.
+    shared: Arc<Mutex<HubState>>,
+}
+
+impl MockHub {
+    /// Create a new hub in the disconnected state.
+    pub fn new() -> Self {
+        Self {
+            state: ConnectionState::Disconnected,
+            shared: Arc::new(Mutex::new(HubState::default())),
+        }
+    }
+
+    /// Get a handle to this hub.
+    pub fn handle(&self) -> MockHubHandle {
+        MockHubHandle {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl Default for MockHub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockHubHandle {
+    /// Deliver `signal` to every subscription matching `topic`.
+    ///
+    /// Returns the number of subscriptions it was delivered to.
+    pub fn inject_signal(&self, topic: &str, signal: Signal) -> usize {
+        self.shared.lock().unwrap().deliver(topic, &signal)
+    }
+
+    /// Send an arbitrary message to the client.
+    pub fn inject(&self, message: JsonRpcMessage) {
+        self.shared.lock().unwrap().outbox.push_back(message);
+    }
+
+    /// Answer the next request for `method` with `error` instead of
+    /// handling it.
+    ///
+    /// Queued errors are used in order, one per request.
+    pub fn fail_next(&self, method: &str, error: CauceError) {
+        self.shared
+            .lock()
+            .unwrap()
+            .failures
+            .entry(method.to_string())
+            .or_default()
+            .push_back(error);
+    }
+
+    /// Close the connection, as if the hub went away.
+    ///
+    /// The client sees the connection close once it has received any
+    /// messages already sent to it.
+    pub fn close(&self) {
+        self.shared.lock().unwrap().closing = true;
+    }
+
+    /// Every request the client has sent, in order.
+    pub fn requests(&self) -> Vec<JsonRpcRequest> {
+        self.shared.lock().unwrap().requests.clone()
+    }
+
+    /// Every message the client has published, in order.
+    pub fn published(&self) -> Vec<PublishedMessage> {
+        self.shared.lock().unwrap().published.clone()
+    }
+
+    /// Every signal ID the client has acknowledged, in order.
+    pub fn acked(&self) -> Vec<String> {
+        self.shared.lock().unwrap().acked.clone()
+    }
+
+    /// The client's active subscriptions, ordered by ID.
+    pub fn subscriptions(&self) -> Vec<HubSubscription> {
+        let mut subscriptions: Vec<HubSubscription> = self
+            .shared
+            .lock()
+            .unwrap()
+            .subscriptions
+            .iter()
+            .map(|(id, topics)| HubSubscription {
+                subscription_id: id.clone(),
+                topics: topics.clone(),
+            })
+            .collect();
+        subscriptions.sort_by(|a, b| a.subscription_id.cmp(&b.subscription_id));
+        subscriptions
+    }
+}
+
+#[async_trait]
+impl Transport for MockHub {
+    async fn connect(&mut self) -> TransportResult<()> {
+        self.shared.lock().unwrap().closing = false;
+        self.state = ConnectionState::Connected;
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> TransportResult<()> {
+        self.state = ConnectionState::Disconnected;
+        Ok(())
+    }
+
+    async fn send(&mut self, message: JsonRpcMessage) -> TransportResult<()> {
+        if self.state != ConnectionState::Connected {
+            return Err(ClientError::NotConnected);
+        }
+
+        // Notifications and responses from the client need no answer
+        let JsonRpcMessage::Request(request) = message else {
+            return Ok(());
+        };
+
+        let mut state = self.shared.lock().unwrap();
+        let queued = state.outbox.len();
+        let response = match state.answer(&request) {
+            Ok(result) => JsonRpcResponse::success(request.id().clone(), result),
+            Err(error) => JsonRpcResponse::error(Some(request.id().clone()), error),
+        };
+        state.requests.push(request);
+
+        // The response goes ahead of any signals the request caused
+        state
+            .outbox
+            .insert(queued, JsonRpcMessage::Response(response));
+        Ok(())
+    }
+
+    async fn receive(&mut self) -> TransportResult<Option<JsonRpcMessage>> {
+        if self.state != ConnectionState::Connected {
+            return Err(ClientError::NotConnected);
+        }
+
+        loop {
+            {
+                let mut state = self.shared.lock().unwrap();
+                if let Some(message) = state.outbox.pop_front() {
+                    return Ok(Some(message));
+                }
+                if state.closing {
+                    state.closing = false;
+                    self.state = ConnectionState::Disconnected;
+                    return Ok(None);
+                }
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    }
+
+    fn state(&self) -> ConnectionState {
+        self.state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::CauceClient;
+    use crate::config::ClientConfig;
+    use cauce_core::{Payload, Source, Topic};
+
+    fn make_signal(id: &str) -> Signal {
+        Signal {
+            id: id.to_string(),
+            version: "1.0".to_string(),
+            timestamp: chrono::Utc::now(),
+            source: Source::new("email", "adapter-1", "native-1"),
+            topic: Topic::new_unchecked("signal.email.received"),
+            payload: Payload::new(serde_json::json!({}), "application/json"),
+            metadata: None,
+            encrypted: None,
+            signature: None,
+        }
+    }
+
+    async fn connect() -> (CauceClient, MockHubHandle) {
+        let mut hub = MockHub::new();
+        hub.connect().await.unwrap();
+        let handle = hub.handle();
+        let config = ClientConfig::builder("ws://mock", "test-adapter")
+            .keepalive_interval(Duration::ZERO)
+            .build()
+            .unwrap();
+        let client = CauceClient::connect_with_transport(config, Box::new(hub))
+            .await
+            .unwrap();
+        (client, handle)
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_and_receive_injected_signal() {
+        let (client, handle) = connect().await;
+        assert_eq!(client.session_id().await.as_deref(), Some("session_1"));
+
+        let mut subscription = client.subscribe(&["signal.email.*"]).await.unwrap();
+        assert_eq!(
+            handle.subscriptions(),
+            [HubSubscription {
+                subscription_id: subscription.subscription_id().to_string(),
+                topics: vec!["signal.email.*".to_string()],
+            }]
+        );
+
+        assert_eq!(
+            handle.inject_signal("signal.slack.message", make_signal("sig_0")),
+            0
+        );
+        assert_eq!(
+            handle.inject_signal("signal.email.received", make_signal("sig_1")),
+            1
+        );
+        let signal = subscription.next().await.unwrap().unwrap();
+        assert_eq!(signal.id, "sig_1");
+
+        let subscription_id = subscription.subscription_id().to_string();
+        client.ack(&subscription_id, &["sig_1"]).await.unwrap();
+        assert_eq!(handle.acked(), ["sig_1"]);
+
+        client.unsubscribe(&subscription_id).await.unwrap();
+        assert!(handle.subscriptions().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_publish_is_recorded_and_delivered() {
+        let (client, handle) = connect().await;
+        let mut subscription = client.subscribe(&["signal.email.*"]).await.unwrap();
+
+        let response = client
+            .publish("signal.email.received", make_signal("sig_1").into())
+            .await
+            .unwrap();
+        assert_eq!(response.delivered_to, 1);
+
+        let published = handle.published();
+        assert_eq!(published.len(), 1);
+        assert_eq!(published[0].message_id, response.message_id);
+        assert_eq!(published[0].topic, "signal.email.received");
+        assert_eq!(subscription.next().await.unwrap().unwrap().id, "sig_1");
+    }
+
+    #[tokio::test]
+    async fn test_fail_next_simulates_errors() {
+        let (client, handle) = connect().await;
+        handle.fail_next(
+            METHOD_PUBLISH,
+            CauceError::RateLimited {
+                retry_after_ms: 1000,
+            },
+        );
+
+        let result = client
+            .publish("signal.email.received", make_signal("sig_1").into())
+            .await;
+        assert!(matches!(
+            result,
+            Err(ClientError::RpcError { code: -32006, .. })
+        ));
+        assert!(handle.published().is_empty());
+
+        // Only the next request fails
+        client
+            .publish("signal.email.received", make_signal("sig_1").into())
+            .await
+            .unwrap();
+        assert_eq!(handle.published().len(), 1);
+    }
+
+    #[cfg(feature = "e2e")]
+    async fn connect_with_e2e(options: crate::E2eOptions) -> (CauceClient, MockHubHandle) {
+        let mut hub = MockHub::new();
+        hub.connect().await.unwrap();
+        let handle = hub.handle();
+        let config = ClientConfig::builder("ws://mock", "test-adapter")
+            .keepalive_interval(Duration::ZERO)
+            .e2e(options)
+            .build()
+            .unwrap();
+        let client = CauceClient::connect_with_transport(config, Box::new(hub))
+            .await
+            .unwrap();
+        (client, handle)
+    }
+
+    #[cfg(feature = "e2e")]
+    #[tokio::test]
+    async fn test_publish_encrypted_round_trip() {
+        let options = crate::E2eOptions::generate();
+        let (client, handle) = connect_with_e2e(options.clone()).await;
+
+        let hello: cauce_core::HelloRequest = params(&handle.requests()[0]).unwrap();
+        assert!(hello
+            .capabilities
+            .contains(&cauce_core::Capability::E2eEncryption));
+
+        // Nothing is published while no subscriber has a key
+        let signal = Signal {
+            payload: Payload::new(serde_json::json!({"subject": "secret"}), "application/json"),
+            ..make_signal("sig_1")
+        };
+        let responses = client
+            .publish_encrypted("signal.email.received", signal.clone())
+            .await
+            .unwrap();
+        assert!(responses.is_empty());
+
+        let mut subscription = client.subscribe(&["signal.email.*"]).await.unwrap();
+        let keys = client
+            .recipient_keys("signal.email.received")
+            .await
+            .unwrap();
+        assert_eq!(keys.len(), 1);
+        assert_eq!(keys[0].public_key, options.public_key().to_base64());
+
+        let responses = client
+            .publish_encrypted("signal.email.received", signal.clone())
+            .await
+            .unwrap();
+        assert_eq!(responses.len(), 1);
+
+        // The hub only sees the envelope
+        match &handle.published()[0].message {
+            PublishMessage::Signal(published) => {
+                assert!(published.is_encrypted());
+                assert_eq!(published.payload.raw, Value::Null);
+            }
+            other => panic!("expected a signal, got {:?}", other),
+        }
+        assert_eq!(subscription.next().await.unwrap().unwrap(), signal);
+    }
+
+    #[cfg(feature = "e2e")]
+    #[tokio::test]
+    async fn test_undecryptable_signal_is_reported_and_acked() {
+        use cauce_core::e2e::{self, KeyPair};
+        use cauce_core::types::EncryptionAlgorithm;
+
+        let options = crate::E2eOptions::generate().with_ack_on_failure(true);
+        let (client, handle) = connect_with_e2e(options.clone()).await;
+        let mut subscription = client.subscribe(&["signal.email.*"]).await.unwrap();
+        let algorithm = EncryptionAlgorithm::A256Gcm;
+
+        // A copy for another recipient is skipped
+        let other = KeyPair::generate();
+        let foreign = e2e::encrypt_signal(&make_signal("sig_1"), other.public_key(), algorithm);
+        handle.inject_signal("signal.email.received", foreign.unwrap());
+
+        let mut tampered =
+            e2e::encrypt_signal(&make_signal("sig_2"), options.public_key(), algorithm).unwrap();
+        if let Some(encrypted) = tampered.encrypted.as_mut() {
+            encrypted.nonce = "AAAAAAAAAAAAAAAA".to_string();
+        }
+        handle.inject_signal("signal.email.received", tampered);
+
+        match subscription.next().await {
+            Some(Err(ClientError::DecryptionFailed {
+                subscription_id,
+                signal_id,
+                ..
+            })) => {
+                assert_eq!(subscription_id, subscription.subscription_id());
+                assert_eq!(signal_id, "sig_2");
+            }
+            other => panic!("expected decryption failure, got {:?}", other),
+        }
+
+        // The failed signal is acknowledged in the background
+        for _ in 0..100 {
+            if !handle.acked().is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(handle.acked(), ["sig_2"]);
+    }
+
+    #[cfg(feature = "e2e")]
+    #[tokio::test]
+    async fn test_plaintext_signal_is_rejected_on_encrypted_subscription() {
+        let options = crate::E2eOptions::generate().with_ack_on_failure(true);
+        let (client, handle) = connect_with_e2e(options).await;
+        let mut subscription = client.subscribe(&["signal.email.*"]).await.unwrap();
+
+        handle.inject_signal("signal.email.received", make_signal("sig_1"));
+        match subscription.next().await {
+            Some(Err(ClientError::UnencryptedSignal {
+                subscription_id,
+                signal_id,
+            })) => {
+                assert_eq!(subscription_id, subscription.subscription_id());
+                assert_eq!(signal_id, "sig_1");
+            }
+            other => panic!("expected unencrypted signal error, got {:?}", other),
+        }
+
+        for _ in 0..100 {
+            if !handle.acked().is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(handle.acked(), ["sig_1"]);
+    }
+
+    #[tokio::test]
+    async fn test_close_ends_connection() {
+        let mut hub = MockHub::new();
+        hub.connect().await.unwrap();
+        let handle = hub.handle();
+
+        handle.inject(JsonRpcMessage::Notification(JsonRpcNotification::new(
+            "test.event".to_string(),
+            None,
+        )));
+        handle.close();
+
+        assert!(hub.receive().await.unwrap().is_some());
+        assert!(hub.receive().await.unwrap().is_none());
+        assert_eq!(hub.state(), ConnectionState::Disconnected);
+    }
+}
@@ -0,0 +1,17 @@
+//! Test support for code built on the client SDK.
+//!
+//! [`MockHub`] is an in-memory hub that implements
+//! [`Transport`](crate::Transport), so adapters and agents can be tested
+//! against the real [`CauceClient`](crate::CauceClient) without sockets or
+//! hand-scripted JSON-RPC responses.
+//!
+//! Requires the `test-support` feature, typically enabled only for tests:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! cauce-client-sdk = { version = "0.1", features = ["test-support"] }
+//! ```
+
+mod hub;
+
+pub use hub::{HubSubscription, MockHub, MockHubHandle, PublishedMessage};
@@ -3,11 +3,12 @@
 //! This module provides [`MockTransport`], a simple transport implementation
 //! that can be used for unit testing without real network connections.
 
+use crate::config::AuthConfig;
 use crate::error::ClientError;
 use crate::transport::{ConnectionState, JsonRpcMessage, Transport, TransportResult};
 use async_trait::async_trait;
-use std::collections::VecDeque;
-use std::sync::Arc;
+use std::collections::{HashSet, VecDeque};
+use std::sync::{Arc, Mutex as StdMutex};
 use tokio::sync::Mutex;
 
 /// A mock transport for testing purposes.
@@ -48,6 +49,82 @@ pub struct MockTransport {
 
     /// If true, receive() will return a connection closed error.
     should_fail_receive: bool,
+
+    /// If true, receive() waits for a message instead of reporting a closed
+    /// connection when the receive queue is empty.
+    wait_when_empty: bool,
+
+    /// Hub URL set through `set_hub_url`, if any.
+    hub_url: Option<String>,
+
+    /// Hub URLs that connect() fails for.
+    unreachable: Arc<Mutex<HashSet<String>>>,
+
+    /// Hub URLs successfully connected to, in order.
+    connected_urls: Arc<Mutex<Vec<String>>>,
+
+    /// Credentials set through `set_auth`, if any.
+    auth: Arc<StdMutex<Option<AuthConfig>>>,
+}
+
+/// A cloneable handle to a [`MockTransport`]'s message queues.
+///
+/// Once a transport has been boxed and handed to a
+/// [`MessageRouter`](crate::router::MessageRouter), the handle lets tests keep
+/// injecting messages and inspecting what was sent.
+#[derive(Clone)]
+pub struct MockTransportHandle {
+    /// Queue of messages that have been sent.
+    sent: Arc<Mutex<VecDeque<JsonRpcMessage>>>,
+
+    /// Queue of messages to be received.
+    receive_queue: Arc<Mutex<VecDeque<JsonRpcMessage>>>,
+
+    /// Hub URLs that connect() fails for.
+    unreachable: Arc<Mutex<HashSet<String>>>,
+
+    /// Hub URLs successfully connected to, in order.
+    connected_urls: Arc<Mutex<Vec<String>>>,
+
+    /// Credentials set through `set_auth`, if any.
+    auth: Arc<StdMutex<Option<AuthConfig>>>,
+}
+
+impl MockTransportHandle {
+    /// Push a message onto the transport's receive queue.
+    pub async fn push_receive(&self, message: JsonRpcMessage) {
+        self.receive_queue.lock().await.push_back(message);
+    }
+
+    /// Pop the oldest message sent through the transport.
+    pub async fn pop_sent(&self) -> Option<JsonRpcMessage> {
+        self.sent.lock().await.pop_front()
+    }
+
+    /// Get the number of messages in the sent queue.
+    pub async fn sent_count(&self) -> usize {
+        self.sent.lock().await.len()
+    }
+
+    /// Make `connect()` fail (or succeed again) while pointed at `url`.
+    pub async fn set_unreachable(&self, url: &str, unreachable: bool) {
+        let mut urls = self.unreachable.lock().await;
+        if unreachable {
+            urls.insert(url.to_string());
+        } else {
+            urls.remove(url);
+        }
+    }
+
+    /// Hub URLs the transport has connected to after `set_hub_url`, in order.
+    pub async fn connected_urls(&self) -> Vec<String> {
+        self.connected_urls.lock().await.clone()
+    }
+
+    /// The credentials most recently set through `set_auth`, if any.
+    pub fn auth(&self) -> Option<AuthConfig> {
+        self.auth.lock().unwrap().clone()
+    }
 }
 
 impl MockTransport {
@@ -58,6 +135,33 @@ impl MockTransport {
             sent: Arc::new(Mutex::new(VecDeque::new())),
             receive_queue: Arc::new(Mutex::new(VecDeque::new())),
             should_fail_receive: false,
+            wait_when_empty: false,
+            hub_url: None,
+            unreachable: Arc::new(Mutex::new(HashSet::new())),
+            connected_urls: Arc::new(Mutex::new(Vec::new())),
+            auth: Arc::new(StdMutex::new(None)),
+        }
+    }
+
+    /// Make `receive()` wait for messages instead of returning `Ok(None)`.
+    ///
+    /// By default an empty receive queue is reported as a closed connection,
+    /// which stops a [`MessageRouter`](crate::router::MessageRouter) after the
+    /// queued messages are drained. Enable this for long-running tests that
+    /// inject messages through a [`MockTransportHandle`].
+    pub fn wait_when_empty(mut self) -> Self {
+        self.wait_when_empty = true;
+        self
+    }
+
+    /// Get a handle to this transport's message queues.
+    pub fn handle(&self) -> MockTransportHandle {
+        MockTransportHandle {
+            sent: Arc::clone(&self.sent),
+            receive_queue: Arc::clone(&self.receive_queue),
+            unreachable: Arc::clone(&self.unreachable),
+            connected_urls: Arc::clone(&self.connected_urls),
+            auth: Arc::clone(&self.auth),
         }
     }
 
@@ -127,6 +231,14 @@ impl Default for MockTransport {
 #[async_trait]
 impl Transport for MockTransport {
     async fn connect(&mut self) -> TransportResult<()> {
+        if let Some(url) = &self.hub_url {
+            if self.unreachable.lock().await.contains(url) {
+                return Err(ClientError::ConnectionFailed {
+                    message: format!("{} unreachable", url),
+                });
+            }
+            self.connected_urls.lock().await.push(url.clone());
+        }
         self.state = ConnectionState::Connected;
         Ok(())
     }
@@ -155,12 +267,26 @@ impl Transport for MockTransport {
             });
         }
 
-        Ok(self.receive_queue.lock().await.pop_front())
+        loop {
+            let message = self.receive_queue.lock().await.pop_front();
+            if message.is_some() || !self.wait_when_empty {
+                return Ok(message);
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        }
     }
 
     fn state(&self) -> ConnectionState {
         self.state
     }
+
+    fn set_hub_url(&mut self, url: &str) {
+        self.hub_url = Some(url.to_string());
+    }
+
+    fn set_auth(&mut self, auth: AuthConfig) {
+        *self.auth.lock().unwrap() = Some(auth);
+    }
 }
 
 #[cfg(test)]
@@ -236,6 +362,37 @@ mod tests {
         assert!(received.is_none());
     }
 
+    #[tokio::test]
+    async fn test_mock_transport_wait_when_empty() {
+        let mut transport = MockTransport::new().wait_when_empty();
+        transport.connect().await.unwrap();
+        let handle = transport.handle();
+
+        let pending =
+            tokio::time::timeout(std::time::Duration::from_millis(20), transport.receive()).await;
+        assert!(pending.is_err());
+
+        let notification = JsonRpcNotification::new("test".to_string(), None);
+        handle
+            .push_receive(JsonRpcMessage::Notification(notification))
+            .await;
+        let received = transport.receive().await.unwrap();
+        assert!(received.unwrap().is_notification());
+    }
+
+    #[tokio::test]
+    async fn test_mock_transport_handle_sees_sent() {
+        let mut transport = MockTransport::new();
+        transport.connect().await.unwrap();
+        let handle = transport.handle();
+
+        let request = JsonRpcRequest::new(1.into(), "test".to_string(), None);
+        transport.send(JsonRpcMessage::Request(request)).await.unwrap();
+
+        assert_eq!(handle.sent_count().await, 1);
+        assert!(handle.pop_sent().await.unwrap().is_request());
+    }
+
     #[tokio::test]
     async fn test_mock_transport_receive_not_connected() {
         let mut transport = MockTransport::new();
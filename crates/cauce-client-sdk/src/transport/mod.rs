@@ -51,6 +51,7 @@ pub use sse::SseTransport;
 pub use webhook::WebhookTransport;
 pub use websocket::WebSocketTransport;
 
+use crate::config::AuthConfig;
 use crate::error::ClientError;
 use async_trait::async_trait;
 
@@ -178,6 +179,18 @@ pub trait Transport: Send + Sync {
     /// Returns the current connection state.
     fn state(&self) -> ConnectionState;
 
+    /// Point the transport at a different hub for the next `connect()`.
+    ///
+    /// Used for failover between hubs. The default implementation ignores
+    /// the URL, so transports that cannot switch keep their endpoint.
+    fn set_hub_url(&mut self, _url: &str) {}
+
+    /// Replace the credentials used from the next `connect()` or request on.
+    ///
+    /// Used to swap in a refreshed bearer token. The default implementation
+    /// ignores the credentials.
+    fn set_auth(&mut self, _auth: AuthConfig) {}
+
     /// Returns true if currently connected.
     ///
     /// This is a convenience method equivalent to `state().is_connected()`.
@@ -24,7 +24,7 @@ use futures::StreamExt;
 use tokio::sync::Mutex;
 use tokio::task::JoinHandle;
 
-use crate::config::ClientConfig;
+use crate::config::{AuthConfig, ClientConfig};
 use crate::error::ClientError;
 use crate::transport::{ConnectionState, JsonRpcMessage, Transport, TransportResult};
 
@@ -363,6 +363,14 @@ impl Transport for SseTransport {
         self.state
     }
 
+    fn set_hub_url(&mut self, url: &str) {
+        self.config.hub_url = url.to_string();
+    }
+
+    fn set_auth(&mut self, auth: AuthConfig) {
+        self.config.auth = Some(auth);
+    }
+
     fn is_connected(&self) -> bool {
         self.state == ConnectionState::Connected
     }
@@ -25,7 +25,7 @@ use async_trait::async_trait;
 use tokio::sync::Mutex;
 use tokio::task::JoinHandle;
 
-use crate::config::ClientConfig;
+use crate::config::{AuthConfig, ClientConfig};
 use crate::error::ClientError;
 use crate::transport::{ConnectionState, JsonRpcMessage, Transport, TransportResult};
 
@@ -334,6 +334,14 @@ impl Transport for WebhookTransport {
         self.state
     }
 
+    fn set_hub_url(&mut self, url: &str) {
+        self.config.hub_url = url.to_string();
+    }
+
+    fn set_auth(&mut self, auth: AuthConfig) {
+        self.config.auth = Some(auth);
+    }
+
     fn is_connected(&self) -> bool {
         self.state == ConnectionState::Connected
     }
@@ -31,7 +31,7 @@
 //! }
 //! ```
 
-use crate::config::ClientConfig;
+use crate::config::{AuthConfig, ClientConfig};
 use crate::error::ClientError;
 use crate::transport::{ConnectionState, JsonRpcMessage, TransportResult};
 
@@ -444,6 +444,14 @@ impl Transport for WebSocketTransport {
     fn state(&self) -> ConnectionState {
         self.state
     }
+
+    fn set_hub_url(&mut self, url: &str) {
+        self.config.hub_url = url.to_string();
+    }
+
+    fn set_auth(&mut self, auth: AuthConfig) {
+        self.config.auth = Some(auth);
+    }
 }
 
 impl Drop for WebSocketTransport {
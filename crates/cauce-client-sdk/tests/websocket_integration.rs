@@ -27,19 +27,14 @@ async fn start_echo_server() -> (SocketAddr, oneshot::Sender<()>) {
 
                         // Echo messages back
                         while let Some(Ok(msg)) = read.next().await {
-                            match msg {
-                                Message::Text(text) => {
-                                    if write.send(Message::Text(text)).await.is_err() {
-                                        break;
-                                    }
-                                }
-                                Message::Ping(data) => {
-                                    if write.send(Message::Pong(data)).await.is_err() {
-                                        break;
-                                    }
-                                }
+                            let reply = match msg {
+                                Message::Text(text) => Message::Text(text),
+                                Message::Ping(data) => Message::Pong(data),
                                 Message::Close(_) => break,
-                                _ => {}
+                                _ => continue,
+                            };
+                            if write.send(reply).await.is_err() {
+                                break;
                             }
                         }
                     }
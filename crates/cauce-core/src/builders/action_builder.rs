@@ -5,7 +5,9 @@ use uuid::Uuid;
 
 use crate::constants::PROTOCOL_VERSION;
 use crate::errors::BuilderError;
-use crate::types::{Action, ActionBody, ActionContext, Encrypted, Topic};
+#[cfg(feature = "signing")]
+use crate::signing::{self, SigningKey};
+use crate::types::{Action, ActionBody, ActionContext, Encrypted, Signature, Topic};
 
 /// Fluent builder for creating [`Action`] instances.
 ///
@@ -53,6 +55,9 @@ pub struct ActionBuilder {
     action: Option<ActionBody>,
     context: Option<ActionContext>,
     encrypted: Option<Encrypted>,
+    signature: Option<Signature>,
+    #[cfg(feature = "signing")]
+    signing_key: Option<SigningKey>,
 }
 
 impl ActionBuilder {
@@ -189,6 +194,25 @@ impl ActionBuilder {
         self
     }
 
+    /// Sets a precomputed provenance signature (optional).
+    ///
+    /// The signature must cover the action exactly as built, so this is
+    /// mainly useful when an ID and timestamp are also set. Prefer
+    /// `sign_with` (requires the `signing` feature).
+    pub fn signature(mut self, signature: Signature) -> Self {
+        self.signature = Some(signature);
+        self
+    }
+
+    /// Signs the action with `key` when it is built (optional).
+    ///
+    /// Overrides any signature set with [`signature`](Self::signature).
+    #[cfg(feature = "signing")]
+    pub fn sign_with(mut self, key: &SigningKey) -> Self {
+        self.signing_key = Some(key.clone());
+        self
+    }
+
     /// Builds the Action, returning an error if required fields are missing.
     ///
     /// # Returns
@@ -233,7 +257,7 @@ impl ActionBuilder {
         // Use current timestamp if not provided
         let timestamp = self.timestamp.unwrap_or_else(Utc::now);
 
-        Ok(Action {
+        let action = Action {
             id,
             version,
             timestamp,
@@ -241,7 +265,18 @@ impl ActionBuilder {
             action: self.action.unwrap(),
             context: self.context,
             encrypted: self.encrypted,
-        })
+            signature: self.signature,
+        };
+
+        #[cfg(feature = "signing")]
+        if let Some(key) = &self.signing_key {
+            return signing::sign_action(&action, key)
+                .map_err(|e| BuilderError::InvalidPayload {
+                    reason: e.to_string(),
+                });
+        }
+
+        Ok(action)
     }
 }
 
@@ -5,7 +5,9 @@ use uuid::Uuid;
 
 use crate::constants::PROTOCOL_VERSION;
 use crate::errors::BuilderError;
-use crate::types::{Encrypted, Metadata, Payload, Signal, Source, Topic};
+#[cfg(feature = "signing")]
+use crate::signing::{self, SigningKey};
+use crate::types::{Encrypted, Metadata, Payload, Signal, Signature, Source, Topic};
 
 /// Fluent builder for creating [`Signal`] instances.
 ///
@@ -50,8 +52,12 @@ pub struct SignalBuilder {
     source: Option<Source>,
     topic: Option<Topic>,
     payload: Option<Payload>,
+    payload_error: Option<String>,
     metadata: Option<Metadata>,
     encrypted: Option<Encrypted>,
+    signature: Option<Signature>,
+    #[cfg(feature = "signing")]
+    signing_key: Option<SigningKey>,
 }
 
 impl SignalBuilder {
@@ -164,6 +170,36 @@ impl SignalBuilder {
     /// ```
     pub fn payload(mut self, payload: Payload) -> Self {
         self.payload = Some(payload);
+        self.payload_error = None;
+        self
+    }
+
+    /// Sets the payload from a serializable value (required).
+    ///
+    /// The value is serialized to JSON; `content_type` and `size_bytes` are
+    /// filled in automatically. A serialization failure is reported by
+    /// [`build`](Self::build).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cauce_core::builders::SignalBuilder;
+    /// use serde_json::json;
+    ///
+    /// let builder = SignalBuilder::new()
+    ///     .typed_payload(&json!({"from": "alice@example.com"}));
+    /// ```
+    pub fn typed_payload<T: serde::Serialize + ?Sized>(mut self, value: &T) -> Self {
+        match Payload::from_serializable(value) {
+            Ok(payload) => {
+                self.payload = Some(payload);
+                self.payload_error = None;
+            }
+            Err(e) => {
+                self.payload = None;
+                self.payload_error = Some(e.to_string());
+            }
+        }
         self
     }
 
@@ -204,12 +240,32 @@ impl SignalBuilder {
         self
     }
 
+    /// Sets a precomputed provenance signature (optional).
+    ///
+    /// The signature must cover the signal exactly as built, so this is
+    /// mainly useful when an ID and timestamp are also set. Prefer
+    /// `sign_with` (requires the `signing` feature).
+    pub fn signature(mut self, signature: Signature) -> Self {
+        self.signature = Some(signature);
+        self
+    }
+
+    /// Signs the signal with `key` when it is built (optional).
+    ///
+    /// Overrides any signature set with [`signature`](Self::signature).
+    #[cfg(feature = "signing")]
+    pub fn sign_with(mut self, key: &SigningKey) -> Self {
+        self.signing_key = Some(key.clone());
+        self
+    }
+
     /// Builds the Signal, returning an error if required fields are missing.
     ///
     /// # Returns
     ///
     /// - `Ok(Signal)` if all required fields are set
-    /// - `Err(BuilderError)` if required fields are missing
+    /// - `Err(BuilderError)` if required fields are missing or a typed
+    ///   payload failed to serialize
     ///
     /// # Example
     ///
@@ -226,6 +282,10 @@ impl SignalBuilder {
     ///     .expect("valid signal");
     /// ```
     pub fn build(self) -> Result<Signal, BuilderError> {
+        if let Some(reason) = self.payload_error {
+            return Err(BuilderError::InvalidPayload { reason });
+        }
+
         // Check for missing required fields
         let mut missing = Vec::new();
 
@@ -252,7 +312,7 @@ impl SignalBuilder {
         // Use current timestamp if not provided
         let timestamp = self.timestamp.unwrap_or_else(Utc::now);
 
-        Ok(Signal {
+        let signal = Signal {
             id,
             version,
             timestamp,
@@ -261,7 +321,18 @@ impl SignalBuilder {
             payload: self.payload.unwrap(),
             metadata: self.metadata,
             encrypted: self.encrypted,
-        })
+            signature: self.signature,
+        };
+
+        #[cfg(feature = "signing")]
+        if let Some(key) = &self.signing_key {
+            return signing::sign_signal(&signal, key)
+                .map_err(|e| BuilderError::InvalidPayload {
+                    reason: e.to_string(),
+                });
+        }
+
+        Ok(signal)
     }
 }
 
@@ -440,6 +511,35 @@ mod tests {
         assert!(msg.contains("payload"));
     }
 
+    #[test]
+    fn test_builder_typed_payload() {
+        let signal = SignalBuilder::new()
+            .source(test_source())
+            .topic(test_topic())
+            .typed_payload(&json!({"from": "alice@example.com"}))
+            .build()
+            .unwrap();
+
+        assert_eq!(signal.payload.content_type, "application/json");
+        assert!(signal.payload.size_bytes > 0);
+        assert_eq!(signal.payload.raw["from"], "alice@example.com");
+    }
+
+    #[test]
+    fn test_builder_typed_payload_serialization_failure() {
+        // JSON object keys must be strings
+        let mut unserializable = std::collections::HashMap::new();
+        unserializable.insert((1, 2), "value");
+
+        let result = SignalBuilder::new()
+            .source(test_source())
+            .topic(test_topic())
+            .typed_payload(&unserializable)
+            .build();
+
+        assert!(matches!(result, Err(BuilderError::InvalidPayload { .. })));
+    }
+
     #[test]
     fn test_signal_builder_method() {
         let signal = Signal::builder()
@@ -70,6 +70,9 @@ pub const METHOD_UNSUBSCRIBE: &str = "cauce.unsubscribe";
 /// Method name for Signal delivery notification
 pub const METHOD_SIGNAL: &str = "cauce.signal";
 
+/// Method name for Action delivery notification
+pub const METHOD_ACTION: &str = "cauce.action";
+
 /// Method name for Acknowledgment
 pub const METHOD_ACK: &str = "cauce.ack";
 
@@ -97,6 +100,16 @@ pub const METHOD_SCHEMAS_LIST: &str = "cauce.schemas.list";
 /// Method name for Schemas get
 pub const METHOD_SCHEMAS_GET: &str = "cauce.schemas.get";
 
+/// Method name for listing recipient encryption keys
+pub const METHOD_KEYS_LIST: &str = "cauce.keys.list";
+
+// =============================================================================
+// Encryption Constants
+// =============================================================================
+
+/// Content type of the placeholder payload left on an encrypted signal
+pub const ENCRYPTED_CONTENT_TYPE: &str = "application/vnd.cauce.encrypted";
+
 // =============================================================================
 // Size Limit Constants
 // =============================================================================
@@ -196,6 +209,7 @@ mod tests {
     #[test]
     fn test_method_signal_ack() {
         assert_eq!(METHOD_SIGNAL, "cauce.signal");
+        assert_eq!(METHOD_ACTION, "cauce.action");
         assert_eq!(METHOD_ACK, "cauce.ack");
     }
 
@@ -215,6 +229,11 @@ mod tests {
         assert_eq!(METHOD_SCHEMAS_GET, "cauce.schemas.get");
     }
 
+    #[test]
+    fn test_method_keys() {
+        assert_eq!(METHOD_KEYS_LIST, "cauce.keys.list");
+    }
+
     // ===== Size Limit Tests =====
 
     #[test]
@@ -0,0 +1,223 @@
+//! X25519 keys for end-to-end encryption.
+
+use std::fmt;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use rand_core::OsRng;
+use x25519_dalek::StaticSecret;
+
+use super::E2eError;
+
+/// Length of X25519 public and secret keys in bytes.
+pub const KEY_LENGTH: usize = 32;
+
+/// An X25519 public key that payloads are encrypted to.
+///
+/// Serialized as standard base64 wherever the protocol carries keys, such as
+/// [`E2eConfig::public_key`](crate::methods::E2eConfig::public_key) and
+/// [`Encrypted::recipient_public_key`](crate::types::Encrypted::recipient_public_key).
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PublicKey([u8; KEY_LENGTH]);
+
+impl PublicKey {
+    /// Creates a public key from raw bytes.
+    pub fn from_bytes(bytes: [u8; KEY_LENGTH]) -> Self {
+        Self(bytes)
+    }
+
+    /// Parses a base64-encoded public key.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`E2eError::InvalidKey`] if `encoded` is not base64 for
+    /// exactly 32 bytes.
+    pub fn from_base64(encoded: &str) -> Result<Self, E2eError> {
+        decode_key(encoded).map(Self)
+    }
+
+    /// Returns the raw key bytes.
+    pub fn as_bytes(&self) -> &[u8; KEY_LENGTH] {
+        &self.0
+    }
+
+    /// Returns the key as standard base64.
+    pub fn to_base64(&self) -> String {
+        STANDARD.encode(self.0)
+    }
+}
+
+impl fmt::Debug for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PublicKey").field(&self.to_base64()).finish()
+    }
+}
+
+impl fmt::Display for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_base64())
+    }
+}
+
+/// An X25519 secret key used to decrypt payloads.
+///
+/// The key material is zeroed when dropped and never printed by `Debug`.
+#[derive(Clone)]
+pub struct SecretKey(StaticSecret);
+
+impl SecretKey {
+    /// Generates a random secret key.
+    pub fn generate() -> Self {
+        Self(StaticSecret::random_from_rng(OsRng))
+    }
+
+    /// Creates a secret key from raw bytes.
+    pub fn from_bytes(bytes: [u8; KEY_LENGTH]) -> Self {
+        Self(StaticSecret::from(bytes))
+    }
+
+    /// Parses a base64-encoded secret key.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`E2eError::InvalidKey`] if `encoded` is not base64 for
+    /// exactly 32 bytes.
+    pub fn from_base64(encoded: &str) -> Result<Self, E2eError> {
+        decode_key(encoded).map(Self::from_bytes)
+    }
+
+    /// Returns the raw key bytes.
+    pub fn to_bytes(&self) -> [u8; KEY_LENGTH] {
+        self.0.to_bytes()
+    }
+
+    /// Returns the key as standard base64.
+    pub fn to_base64(&self) -> String {
+        STANDARD.encode(self.0.as_bytes())
+    }
+
+    /// Returns the public key matching this secret key.
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey(x25519_dalek::PublicKey::from(&self.0).to_bytes())
+    }
+
+    /// Performs X25519 with `public_key`.
+    ///
+    /// Rejects low-order public keys, which would make the shared secret
+    /// predictable.
+    pub(super) fn diffie_hellman(
+        &self,
+        public_key: &PublicKey,
+    ) -> Result<[u8; KEY_LENGTH], E2eError> {
+        let shared = self
+            .0
+            .diffie_hellman(&x25519_dalek::PublicKey::from(public_key.0));
+        if !shared.was_contributory() {
+            return Err(E2eError::InvalidKey {
+                reason: "public key has low order".to_string(),
+            });
+        }
+        Ok(shared.to_bytes())
+    }
+}
+
+impl fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretKey(..)")
+    }
+}
+
+/// An X25519 key pair.
+///
+/// # Example
+///
+/// ```
+/// use cauce_core::e2e::KeyPair;
+/// use cauce_core::methods::E2eConfig;
+///
+/// let keys = KeyPair::generate();
+/// let config = E2eConfig::enabled(keys.public_key().to_base64());
+/// assert!(config.enabled);
+/// ```
+#[derive(Debug, Clone)]
+pub struct KeyPair {
+    secret: SecretKey,
+    public: PublicKey,
+}
+
+impl KeyPair {
+    /// Generates a random key pair.
+    pub fn generate() -> Self {
+        Self::from_secret(SecretKey::generate())
+    }
+
+    /// Creates a key pair from an existing secret key.
+    pub fn from_secret(secret: SecretKey) -> Self {
+        let public = secret.public_key();
+        Self { secret, public }
+    }
+
+    /// Returns the public key.
+    pub fn public_key(&self) -> &PublicKey {
+        &self.public
+    }
+
+    /// Returns the secret key.
+    pub fn secret_key(&self) -> &SecretKey {
+        &self.secret
+    }
+}
+
+fn decode_key(encoded: &str) -> Result<[u8; KEY_LENGTH], E2eError> {
+    let bytes = STANDARD.decode(encoded).map_err(|e| E2eError::InvalidKey {
+        reason: format!("invalid base64: {}", e),
+    })?;
+    bytes
+        .try_into()
+        .map_err(|bytes: Vec<u8>| E2eError::InvalidKey {
+            reason: format!("expected {} bytes, got {}", KEY_LENGTH, bytes.len()),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_pair_round_trip() {
+        let keys = KeyPair::generate();
+        let secret = SecretKey::from_base64(&keys.secret_key().to_base64()).unwrap();
+        assert_eq!(secret.public_key(), *keys.public_key());
+
+        let public = PublicKey::from_base64(&keys.public_key().to_base64()).unwrap();
+        assert_eq!(public, *keys.public_key());
+        assert_ne!(KeyPair::generate().public_key(), keys.public_key());
+    }
+
+    #[test]
+    fn test_invalid_keys() {
+        assert!(matches!(
+            PublicKey::from_base64("not base64!"),
+            Err(E2eError::InvalidKey { .. })
+        ));
+        assert!(matches!(
+            SecretKey::from_base64(&STANDARD.encode([0u8; 16])),
+            Err(E2eError::InvalidKey { .. })
+        ));
+
+        // The identity point is rejected during key agreement
+        let secret = SecretKey::generate();
+        let err = secret
+            .diffie_hellman(&PublicKey::from_bytes([0u8; KEY_LENGTH]))
+            .unwrap_err();
+        assert!(err.to_string().contains("low order"));
+    }
+
+    #[test]
+    fn test_secret_key_debug_is_redacted() {
+        let keys = KeyPair::generate();
+        let debug = format!("{:?}", keys);
+        assert!(debug.contains("SecretKey(..)"));
+        assert!(!debug.contains(&keys.secret_key().to_base64()));
+    }
+}
@@ -0,0 +1,583 @@
+//! End-to-end payload encryption.
+//!
+//! This module encrypts a [`Payload`] to a recipient's X25519 public key,
+//! producing an [`Encrypted`] envelope that only the holder of the matching
+//! secret key can open. The hub routes envelopes without being able to read
+//! them. Requires the `e2e` feature.
+//!
+//! ## Wire format
+//!
+//! Every encryption uses a fresh ephemeral X25519 key pair, so the sender
+//! needs no long-term key of its own. All binary fields are standard base64
+//! with padding.
+//!
+//! - `recipient_public_key` - the recipient's 32-byte X25519 public key
+//! - `nonce` - 24 random bytes, or 12 for `a256gcm`
+//! - `ciphertext` - the sender's 32-byte ephemeral public key, followed by
+//!   the sealed JSON encoding of the [`Payload`]
+//!
+//! The payload is sealed according to the envelope's algorithm:
+//!
+//! - `x25519_xsalsa20_poly1305` - NaCl `crypto_box` between the ephemeral
+//!   secret key and the recipient public key; the 16-byte tag comes first
+//! - `a256gcm` / `xchacha20_poly1305` - the AEAD keyed with
+//!   HKDF-SHA256 over the X25519 shared secret, with the ephemeral and
+//!   recipient public keys (in that order) as salt and
+//!   `cauce-e2e-v1:<algorithm>` as info; the 16-byte tag comes last
+//!
+//! ## Signals
+//!
+//! [`encrypt_signal`] moves a signal's payload into its
+//! [`encrypted`](crate::types::Signal::encrypted) envelope and leaves a
+//! placeholder payload with content type [`ENCRYPTED_CONTENT_TYPE`] in its
+//! place; [`decrypt_signal`] reverses it. Everything else about the signal
+//! stays readable so the hub can route it.
+//!
+//! ## Example
+//!
+//! ```
+//! use cauce_core::e2e::{self, KeyPair};
+//! use cauce_core::types::{EncryptionAlgorithm, Payload};
+//! use serde_json::json;
+//!
+//! let recipient = KeyPair::generate();
+//! let payload = Payload::new(json!({"subject": "Hello"}), "application/json");
+//!
+//! let envelope = e2e::encrypt(
+//!     &payload,
+//!     recipient.public_key(),
+//!     EncryptionAlgorithm::XChaCha20Poly1305,
+//! )
+//! .unwrap();
+//! let decrypted = e2e::decrypt(&envelope, recipient.secret_key()).unwrap();
+//! assert_eq!(decrypted, payload);
+//! ```
+
+mod keys;
+
+pub use crate::constants::ENCRYPTED_CONTENT_TYPE;
+pub use keys::{KeyPair, PublicKey, SecretKey, KEY_LENGTH};
+
+use aes_gcm::Aes256Gcm;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::XChaCha20Poly1305;
+use crypto_box::SalsaBox;
+use hkdf::Hkdf;
+use rand_core::{OsRng, RngCore};
+use sha2::Sha256;
+use thiserror::Error;
+
+use crate::errors::CauceError;
+use crate::types::{Encrypted, EncryptionAlgorithm, Payload, Signal};
+
+/// Every algorithm this module can encrypt and decrypt, in order of preference.
+pub const SUPPORTED_ALGORITHMS: [EncryptionAlgorithm; 3] = [
+    EncryptionAlgorithm::X25519XSalsa20Poly1305,
+    EncryptionAlgorithm::XChaCha20Poly1305,
+    EncryptionAlgorithm::A256Gcm,
+];
+
+/// Errors from encrypting or decrypting payloads.
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum E2eError {
+    /// A key could not be parsed or used
+    #[error("invalid key: {reason}")]
+    InvalidKey {
+        /// The reason the key is invalid
+        reason: String,
+    },
+
+    /// The envelope is malformed
+    #[error("invalid envelope: {reason}")]
+    InvalidEnvelope {
+        /// The reason the envelope is invalid
+        reason: String,
+    },
+
+    /// The envelope was encrypted to a different public key
+    #[error("envelope is encrypted to a different recipient")]
+    WrongRecipient,
+
+    /// The ciphertext failed authentication
+    #[error("decryption failed: ciphertext was tampered with or the key is wrong")]
+    DecryptionFailed,
+
+    /// The payload could not be encoded or decoded
+    #[error("invalid payload: {reason}")]
+    InvalidPayload {
+        /// The reason the payload is invalid
+        reason: String,
+    },
+}
+
+impl From<E2eError> for CauceError {
+    fn from(err: E2eError) -> Self {
+        CauceError::InvalidEncryption {
+            reason: err.to_string(),
+        }
+    }
+}
+
+/// Encrypts `payload` to `recipient` with `algorithm`.
+///
+/// # Errors
+///
+/// Returns [`E2eError::InvalidKey`] if `recipient` is a low-order point, or
+/// [`E2eError::InvalidPayload`] if the payload cannot be serialized.
+pub fn encrypt(
+    payload: &Payload,
+    recipient: &PublicKey,
+    algorithm: EncryptionAlgorithm,
+) -> Result<Encrypted, E2eError> {
+    let mut nonce = vec![0u8; nonce_length(algorithm)];
+    OsRng.fill_bytes(&mut nonce);
+    seal(
+        payload,
+        recipient,
+        algorithm,
+        &SecretKey::generate(),
+        &nonce,
+    )
+}
+
+/// Decrypts an envelope with the recipient's secret key.
+///
+/// # Errors
+///
+/// Returns [`E2eError::WrongRecipient`] if the envelope was encrypted to
+/// another key, [`E2eError::InvalidEnvelope`] if it is malformed, and
+/// [`E2eError::DecryptionFailed`] if the ciphertext does not authenticate.
+pub fn decrypt(encrypted: &Encrypted, secret: &SecretKey) -> Result<Payload, E2eError> {
+    let recipient = PublicKey::from_base64(&encrypted.recipient_public_key)?;
+    if recipient != secret.public_key() {
+        return Err(E2eError::WrongRecipient);
+    }
+
+    let nonce = decode_field("nonce", &encrypted.nonce)?;
+    if nonce.len() != nonce_length(encrypted.algorithm) {
+        return Err(E2eError::InvalidEnvelope {
+            reason: format!(
+                "expected a {}-byte nonce, got {}",
+                nonce_length(encrypted.algorithm),
+                nonce.len()
+            ),
+        });
+    }
+
+    let ciphertext = decode_field("ciphertext", &encrypted.ciphertext)?;
+    if ciphertext.len() < KEY_LENGTH {
+        return Err(E2eError::InvalidEnvelope {
+            reason: "ciphertext is missing the ephemeral public key".to_string(),
+        });
+    }
+    let (ephemeral, sealed) = ciphertext.split_at(KEY_LENGTH);
+    let mut ephemeral_bytes = [0u8; KEY_LENGTH];
+    ephemeral_bytes.copy_from_slice(ephemeral);
+    let ephemeral = PublicKey::from_bytes(ephemeral_bytes);
+
+    let shared = secret.diffie_hellman(&ephemeral)?;
+    let plaintext = match encrypted.algorithm {
+        EncryptionAlgorithm::X25519XSalsa20Poly1305 => {
+            let cipher = SalsaBox::new(
+                &crypto_box::PublicKey::from(*ephemeral.as_bytes()),
+                &crypto_box::SecretKey::from(secret.to_bytes()),
+            );
+            cipher.decrypt(nonce.as_slice().into(), sealed)
+        }
+        EncryptionAlgorithm::A256Gcm => {
+            let key = derive_key(encrypted.algorithm, &shared, &ephemeral, &recipient);
+            Aes256Gcm::new(&key.into()).decrypt(nonce.as_slice().into(), sealed)
+        }
+        EncryptionAlgorithm::XChaCha20Poly1305 => {
+            let key = derive_key(encrypted.algorithm, &shared, &ephemeral, &recipient);
+            XChaCha20Poly1305::new(&key.into()).decrypt(nonce.as_slice().into(), sealed)
+        }
+    }
+    .map_err(|_| E2eError::DecryptionFailed)?;
+
+    serde_json::from_slice(&plaintext).map_err(|e| E2eError::InvalidPayload {
+        reason: e.to_string(),
+    })
+}
+
+/// Picks the algorithm to encrypt with for a recipient.
+///
+/// Returns the first of `accepted` this module supports, in the recipient's
+/// order of preference. A recipient that lists no algorithms accepts the
+/// protocol default, `x25519_xsalsa20_poly1305`.
+pub fn negotiate_algorithm(accepted: &[EncryptionAlgorithm]) -> Option<EncryptionAlgorithm> {
+    if accepted.is_empty() {
+        return Some(EncryptionAlgorithm::X25519XSalsa20Poly1305);
+    }
+    accepted
+        .iter()
+        .copied()
+        .find(|algorithm| SUPPORTED_ALGORITHMS.contains(algorithm))
+}
+
+/// Returns a copy of `signal` with its payload encrypted to `recipient`.
+///
+/// The payload is replaced by an empty placeholder with content type
+/// [`ENCRYPTED_CONTENT_TYPE`]; the ID, topic, source and metadata are kept.
+/// Any signature is dropped, since it covered the plaintext; sign the copy
+/// after encrypting it.
+///
+/// # Errors
+///
+/// Same as [`encrypt`].
+pub fn encrypt_signal(
+    signal: &Signal,
+    recipient: &PublicKey,
+    algorithm: EncryptionAlgorithm,
+) -> Result<Signal, E2eError> {
+    let encrypted = encrypt(&signal.payload, recipient, algorithm)?;
+    Ok(Signal {
+        payload: Payload::new(serde_json::Value::Null, ENCRYPTED_CONTENT_TYPE),
+        encrypted: Some(encrypted),
+        signature: None,
+        ..signal.clone()
+    })
+}
+
+/// Returns a copy of `signal` with its envelope decrypted back into the
+/// payload.
+///
+/// Signals without an envelope are returned unchanged.
+///
+/// # Errors
+///
+/// Same as [`decrypt`].
+pub fn decrypt_signal(signal: &Signal, secret: &SecretKey) -> Result<Signal, E2eError> {
+    let Some(encrypted) = &signal.encrypted else {
+        return Ok(signal.clone());
+    };
+    Ok(Signal {
+        payload: decrypt(encrypted, secret)?,
+        encrypted: None,
+        ..signal.clone()
+    })
+}
+
+/// Seals `payload` with a caller-chosen ephemeral key and nonce.
+fn seal(
+    payload: &Payload,
+    recipient: &PublicKey,
+    algorithm: EncryptionAlgorithm,
+    ephemeral: &SecretKey,
+    nonce: &[u8],
+) -> Result<Encrypted, E2eError> {
+    let plaintext = serde_json::to_vec(payload).map_err(|e| E2eError::InvalidPayload {
+        reason: e.to_string(),
+    })?;
+    let ephemeral_public = ephemeral.public_key();
+    let shared = ephemeral.diffie_hellman(recipient)?;
+
+    let sealed = match algorithm {
+        EncryptionAlgorithm::X25519XSalsa20Poly1305 => {
+            let cipher = SalsaBox::new(
+                &crypto_box::PublicKey::from(*recipient.as_bytes()),
+                &crypto_box::SecretKey::from(ephemeral.to_bytes()),
+            );
+            cipher.encrypt(nonce.into(), plaintext.as_slice())
+        }
+        EncryptionAlgorithm::A256Gcm => {
+            let key = derive_key(algorithm, &shared, &ephemeral_public, recipient);
+            Aes256Gcm::new(&key.into()).encrypt(nonce.into(), plaintext.as_slice())
+        }
+        EncryptionAlgorithm::XChaCha20Poly1305 => {
+            let key = derive_key(algorithm, &shared, &ephemeral_public, recipient);
+            XChaCha20Poly1305::new(&key.into()).encrypt(nonce.into(), plaintext.as_slice())
+        }
+    }
+    .map_err(|_| E2eError::InvalidPayload {
+        reason: "payload is too large to encrypt".to_string(),
+    })?;
+
+    let mut ciphertext = Vec::with_capacity(KEY_LENGTH + sealed.len());
+    ciphertext.extend_from_slice(ephemeral_public.as_bytes());
+    ciphertext.extend_from_slice(&sealed);
+
+    Ok(Encrypted::new(
+        algorithm,
+        recipient.to_base64(),
+        STANDARD.encode(nonce),
+        STANDARD.encode(ciphertext),
+    ))
+}
+
+/// Derives the AEAD key for `algorithm` from an X25519 shared secret.
+fn derive_key(
+    algorithm: EncryptionAlgorithm,
+    shared: &[u8; KEY_LENGTH],
+    ephemeral: &PublicKey,
+    recipient: &PublicKey,
+) -> [u8; 32] {
+    let mut salt = [0u8; 2 * KEY_LENGTH];
+    salt[..KEY_LENGTH].copy_from_slice(ephemeral.as_bytes());
+    salt[KEY_LENGTH..].copy_from_slice(recipient.as_bytes());
+
+    let info = format!("cauce-e2e-v1:{}", algorithm_name(algorithm));
+    let mut key = [0u8; 32];
+    Hkdf::<Sha256>::new(Some(&salt), shared)
+        .expand(info.as_bytes(), &mut key)
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    key
+}
+
+fn algorithm_name(algorithm: EncryptionAlgorithm) -> &'static str {
+    match algorithm {
+        EncryptionAlgorithm::X25519XSalsa20Poly1305 => "x25519_xsalsa20_poly1305",
+        EncryptionAlgorithm::A256Gcm => "a256gcm",
+        EncryptionAlgorithm::XChaCha20Poly1305 => "xchacha20_poly1305",
+    }
+}
+
+fn nonce_length(algorithm: EncryptionAlgorithm) -> usize {
+    match algorithm {
+        EncryptionAlgorithm::A256Gcm => 12,
+        EncryptionAlgorithm::X25519XSalsa20Poly1305 | EncryptionAlgorithm::XChaCha20Poly1305 => 24,
+    }
+}
+
+fn decode_field(field: &str, encoded: &str) -> Result<Vec<u8>, E2eError> {
+    STANDARD
+        .decode(encoded)
+        .map_err(|e| E2eError::InvalidEnvelope {
+            reason: format!("{} is not valid base64: {}", field, e),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Source, Topic};
+    use serde_json::json;
+
+    // Fixed inputs shared with other Cauce implementations. The expected
+    // envelopes were produced independently of this crate.
+    const RECIPIENT_SECRET: &str = "AQIDBAUGBwgJCgsMDQ4PEBESExQVFhcYGRobHB0eHyA=";
+    const RECIPIENT_PUBLIC: &str = "B6N8vBQgk8i3VdwbEOhstCY3StFqqFPtC9/AsrhtHHw=";
+    const EPHEMERAL_SECRET: [u8; 32] = [
+        101, 102, 103, 104, 105, 106, 107, 108, 109, 110, 111, 112, 113, 114, 115, 116, 117, 118,
+        119, 120, 121, 122, 123, 124, 125, 126, 127, 128, 129, 130, 131, 132,
+    ];
+    const NONCE_24: &str = "yMnKy8zNzs/Q0dLT1NXW19jZ2tvc3d7f";
+    const NONCE_12: &str = "yMnKy8zNzs/Q0dLT";
+
+    fn vector_payload() -> Payload {
+        Payload::new(json!({"subject": "hello"}), "application/json")
+    }
+
+    fn vectors() -> [(EncryptionAlgorithm, &'static str, &'static str); 3] {
+        [
+            (
+                EncryptionAlgorithm::X25519XSalsa20Poly1305,
+                NONCE_24,
+                concat!(
+                    "VxR2nRFr92Q2rnS8eT0sMK0ZA8WaxSc4BcfiaYtBDDa5fz8c6f/iQ7KU3IEanh15",
+                    "vZhANJmbIakRyldBiWseZ1DGhcLCXrqSTNiX764O3URNrLujkE6EtLyKslLpCXA8",
+                    "JJR5DT6/in3K+1sknWOWUsN+Wq8hBBdQKNIB8M8=",
+                ),
+            ),
+            (
+                EncryptionAlgorithm::A256Gcm,
+                NONCE_12,
+                concat!(
+                    "VxR2nRFr92Q2rnS8eT0sMK0ZA8WaxSc4BcfiaYtBDDZvj7oo+bVk8PMQiRp6Zt+/",
+                    "SWKw2eqMKsBGfUVouFVWazScwgEqG6K8Y6W6Yn6iOoN9tEjpmef/LC5+ZTXcVo23",
+                    "2KjwJOWQNCespUBWe/RiOPfTr3PeQXM2j1Gb34o=",
+                ),
+            ),
+            (
+                EncryptionAlgorithm::XChaCha20Poly1305,
+                NONCE_24,
+                concat!(
+                    "VxR2nRFr92Q2rnS8eT0sMK0ZA8WaxSc4BcfiaYtBDDYL1zKn8HrtcvIhCcuOqvkl",
+                    "KZSNVcCtqXAWT3hSQ1NC9VhUGQkRrH0HoMrR2C3CYA0Gw2qanvIir1omMC98XzWq",
+                    "qoDvWYk/6uXRlGzbKTgr05NJmZchxvgf7as9zcw=",
+                ),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_vectors_encrypt() {
+        let recipient = PublicKey::from_base64(RECIPIENT_PUBLIC).unwrap();
+        let ephemeral = SecretKey::from_bytes(EPHEMERAL_SECRET);
+
+        for (algorithm, nonce, ciphertext) in vectors() {
+            let nonce = STANDARD.decode(nonce).unwrap();
+            let envelope =
+                seal(&vector_payload(), &recipient, algorithm, &ephemeral, &nonce).unwrap();
+            assert_eq!(envelope.ciphertext, ciphertext, "{:?}", algorithm);
+            assert_eq!(envelope.recipient_public_key, RECIPIENT_PUBLIC);
+        }
+    }
+
+    #[test]
+    fn test_vectors_decrypt() {
+        let secret = SecretKey::from_base64(RECIPIENT_SECRET).unwrap();
+        assert_eq!(secret.public_key().to_base64(), RECIPIENT_PUBLIC);
+
+        for (algorithm, nonce, ciphertext) in vectors() {
+            let envelope = Encrypted::new(algorithm, RECIPIENT_PUBLIC, nonce, ciphertext);
+            let payload = decrypt(&envelope, &secret).unwrap();
+            assert_eq!(payload, vector_payload(), "{:?}", algorithm);
+        }
+    }
+
+    #[test]
+    fn test_round_trip_all_algorithms() {
+        let recipient = KeyPair::generate();
+        let payload = Payload::new(json!({"body": "x".repeat(4096)}), "application/json");
+
+        for algorithm in SUPPORTED_ALGORITHMS {
+            let envelope = encrypt(&payload, recipient.public_key(), algorithm).unwrap();
+            assert_eq!(envelope.algorithm, algorithm);
+            assert_eq!(
+                STANDARD.decode(&envelope.nonce).unwrap().len(),
+                nonce_length(algorithm)
+            );
+            assert_eq!(decrypt(&envelope, recipient.secret_key()).unwrap(), payload);
+
+            // Each encryption uses a fresh ephemeral key and nonce
+            let again = encrypt(&payload, recipient.public_key(), algorithm).unwrap();
+            assert_ne!(again.ciphertext, envelope.ciphertext);
+        }
+    }
+
+    #[test]
+    fn test_decrypt_rejects_tampering() {
+        let recipient = KeyPair::generate();
+
+        for algorithm in SUPPORTED_ALGORITHMS {
+            let mut envelope =
+                encrypt(&vector_payload(), recipient.public_key(), algorithm).unwrap();
+            let mut ciphertext = STANDARD.decode(&envelope.ciphertext).unwrap();
+            let last = ciphertext.len() - 1;
+            ciphertext[last] ^= 1;
+            envelope.ciphertext = STANDARD.encode(ciphertext);
+
+            let err = decrypt(&envelope, recipient.secret_key()).unwrap_err();
+            assert_eq!(err, E2eError::DecryptionFailed, "{:?}", algorithm);
+        }
+    }
+
+    #[test]
+    fn test_decrypt_rejects_wrong_recipient_and_malformed_envelopes() {
+        let recipient = KeyPair::generate();
+        let envelope = encrypt(
+            &vector_payload(),
+            recipient.public_key(),
+            EncryptionAlgorithm::A256Gcm,
+        )
+        .unwrap();
+
+        let other = KeyPair::generate();
+        assert_eq!(
+            decrypt(&envelope, other.secret_key()).unwrap_err(),
+            E2eError::WrongRecipient
+        );
+
+        // Claiming the other recipient's key doesn't help either
+        let mut redirected = envelope.clone();
+        redirected.recipient_public_key = other.public_key().to_base64();
+        assert_eq!(
+            decrypt(&redirected, other.secret_key()).unwrap_err(),
+            E2eError::DecryptionFailed
+        );
+
+        let mut bad_nonce = envelope.clone();
+        bad_nonce.nonce = NONCE_24.to_string();
+        assert!(matches!(
+            decrypt(&bad_nonce, recipient.secret_key()),
+            Err(E2eError::InvalidEnvelope { .. })
+        ));
+
+        let mut truncated = envelope;
+        truncated.ciphertext = STANDARD.encode([0u8; 8]);
+        assert!(matches!(
+            decrypt(&truncated, recipient.secret_key()),
+            Err(E2eError::InvalidEnvelope { .. })
+        ));
+    }
+
+    #[cfg(feature = "signing")]
+    #[test]
+    fn test_encrypt_signal_drops_signature() {
+        use crate::signing::{self, SigningKey};
+
+        let key = SigningKey::generate();
+        let signal = Signal::builder()
+            .source(Source::new("email", "adapter-1", "msg-1"))
+            .topic(Topic::new_unchecked("signal.email.received"))
+            .payload(vector_payload())
+            .sign_with(&key)
+            .build()
+            .unwrap();
+
+        let recipient = KeyPair::generate();
+        let algorithm = EncryptionAlgorithm::XChaCha20Poly1305;
+        let sealed = encrypt_signal(&signal, recipient.public_key(), algorithm).unwrap();
+        assert!(!sealed.is_signed());
+
+        // Signing after encrypting covers the envelope
+        let signed = signing::sign_signal(&sealed, &key).unwrap();
+        assert!(signing::verify_signal(&signed, &key.verifying_key()).is_ok());
+    }
+
+    #[test]
+    fn test_signal_round_trip() {
+        let recipient = KeyPair::generate();
+        let signal = Signal::builder()
+            .source(Source::new("email", "adapter-1", "msg-1"))
+            .topic(Topic::new_unchecked("signal.email.received"))
+            .payload(vector_payload())
+            .build()
+            .unwrap();
+
+        let sealed = encrypt_signal(
+            &signal,
+            recipient.public_key(),
+            EncryptionAlgorithm::XChaCha20Poly1305,
+        )
+        .unwrap();
+        assert_eq!(sealed.id, signal.id);
+        assert!(sealed.is_encrypted());
+        assert_eq!(sealed.payload.raw, serde_json::Value::Null);
+        assert_eq!(sealed.payload.content_type, ENCRYPTED_CONTENT_TYPE);
+
+        let opened = decrypt_signal(&sealed, recipient.secret_key()).unwrap();
+        assert_eq!(opened, signal);
+
+        // Plaintext signals pass through; other recipients cannot open it
+        assert_eq!(decrypt_signal(&signal, recipient.secret_key()).unwrap(), signal);
+        assert_eq!(
+            decrypt_signal(&sealed, KeyPair::generate().secret_key()),
+            Err(E2eError::WrongRecipient)
+        );
+    }
+
+    #[test]
+    fn test_negotiate_algorithm() {
+        assert_eq!(
+            negotiate_algorithm(&[]),
+            Some(EncryptionAlgorithm::X25519XSalsa20Poly1305)
+        );
+        assert_eq!(
+            negotiate_algorithm(&[
+                EncryptionAlgorithm::A256Gcm,
+                EncryptionAlgorithm::XChaCha20Poly1305
+            ]),
+            Some(EncryptionAlgorithm::A256Gcm)
+        );
+    }
+
+    #[test]
+    fn test_error_converts_to_invalid_encryption() {
+        let err: CauceError = E2eError::DecryptionFailed.into();
+        assert_eq!(err.code(), -32009);
+    }
+}
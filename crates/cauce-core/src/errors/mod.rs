@@ -115,6 +115,13 @@ pub enum BuilderError {
     /// Validation failed during build
     #[error("validation failed: {0}")]
     ValidationFailed(#[from] ValidationError),
+
+    /// A typed payload could not be serialized
+    #[error("invalid payload: {reason}")]
+    InvalidPayload {
+        /// Why serialization failed
+        reason: String,
+    },
 }
 
 /// Returns module information for testing purposes.
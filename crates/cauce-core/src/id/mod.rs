@@ -8,6 +8,7 @@
 //! - [`generate_subscription_id`] - Generate Subscription IDs (`sub_<uuid>`)
 //! - [`generate_session_id`] - Generate Session IDs (`sess_<uuid>`)
 //! - [`generate_message_id`] - Generate Message IDs (`msg_<uuid>`)
+//! - [`generate_correlation_id`] - Generate Correlation IDs (`corr_<uuid>`)
 
 use chrono::Utc;
 use uuid::Uuid;
@@ -106,6 +107,22 @@ pub fn generate_message_id() -> String {
     format!("msg_{}", Uuid::new_v4())
 }
 
+/// Generates a unique Correlation ID for request/reply exchanges.
+///
+/// Format: `corr_<uuid>`
+///
+/// # Example
+///
+/// ```
+/// use cauce_core::id::generate_correlation_id;
+///
+/// let id = generate_correlation_id();
+/// assert!(id.starts_with("corr_"));
+/// ```
+pub fn generate_correlation_id() -> String {
+    format!("corr_{}", Uuid::new_v4())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -203,4 +220,16 @@ mod tests {
         let id2 = generate_message_id();
         assert_ne!(id1, id2, "Message IDs should be unique");
     }
+
+    #[test]
+    fn test_generate_correlation_id_format() {
+        let id = generate_correlation_id();
+        let re = Regex::new(r"^corr_[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}$")
+            .unwrap();
+        assert!(
+            re.is_match(&id),
+            "Correlation ID '{}' doesn't match expected format",
+            id
+        );
+    }
 }
@@ -16,6 +16,8 @@
 //! - [`id`] - ID generation utilities
 //! - [`matching`] - Topic pattern matching
 //! - [`schemas`] - Embedded JSON schemas
+//! - `e2e` - End-to-end payload encryption (requires the `e2e` feature)
+//! - `signing` - Ed25519 provenance signatures (requires the `signing` feature)
 
 #![deny(missing_docs)]
 #![deny(rustdoc::broken_intra_doc_links)]
@@ -23,12 +25,16 @@
 // Module declarations
 pub mod builders;
 pub mod constants;
+#[cfg(feature = "e2e")]
+pub mod e2e;
 pub mod errors;
 pub mod id;
 pub mod jsonrpc;
 pub mod matching;
 pub mod methods;
 pub mod schemas;
+#[cfg(feature = "signing")]
+pub mod signing;
 pub mod types;
 pub mod validation;
 
@@ -40,7 +46,7 @@ pub mod validation;
 // Users can write `use cauce_core::Signal;` instead of `use cauce_core::types::Signal;`
 pub use types::{
     Action, ActionBody, ActionContext, ActionType, Encrypted, EncryptionAlgorithm, Metadata,
-    Payload, Priority, Signal, Source, Topic,
+    Payload, Priority, Signal, Signature, SignatureAlgorithm, Source, Topic,
 };
 
 // =============================================================================
@@ -89,14 +95,17 @@ pub use methods::{
 // Ping/Pong
 pub use methods::{PingParams, PongParams};
 
-// Signal Delivery
-pub use methods::SignalDelivery;
+// Signal and Action Delivery
+pub use methods::{ActionDelivery, SignalDelivery};
 
 // Schemas
 pub use methods::{
     SchemaInfo, SchemasGetRequest, SchemasGetResponse, SchemasListRequest, SchemasListResponse,
 };
 
+// Encryption keys
+pub use methods::{KeysListRequest, KeysListResponse, RecipientKey};
+
 // =============================================================================
 // Validation Re-exports
 // =============================================================================
@@ -121,13 +130,16 @@ pub use constants::{
 
 // Method name constants
 pub use constants::{
-    METHOD_ACK, METHOD_GOODBYE, METHOD_HELLO, METHOD_PING, METHOD_PONG, METHOD_PUBLISH,
-    METHOD_SCHEMAS_GET, METHOD_SCHEMAS_LIST, METHOD_SIGNAL, METHOD_SUBSCRIBE,
-    METHOD_SUBSCRIPTION_APPROVE, METHOD_SUBSCRIPTION_DENY, METHOD_SUBSCRIPTION_LIST,
-    METHOD_SUBSCRIPTION_REQUEST, METHOD_SUBSCRIPTION_REVOKE, METHOD_SUBSCRIPTION_STATUS,
-    METHOD_UNSUBSCRIBE,
+    METHOD_ACK, METHOD_ACTION, METHOD_GOODBYE, METHOD_HELLO, METHOD_KEYS_LIST, METHOD_PING,
+    METHOD_PONG, METHOD_PUBLISH, METHOD_SCHEMAS_GET, METHOD_SCHEMAS_LIST, METHOD_SIGNAL,
+    METHOD_SUBSCRIBE, METHOD_SUBSCRIPTION_APPROVE, METHOD_SUBSCRIPTION_DENY,
+    METHOD_SUBSCRIPTION_LIST, METHOD_SUBSCRIPTION_REQUEST, METHOD_SUBSCRIPTION_REVOKE,
+    METHOD_SUBSCRIPTION_STATUS, METHOD_UNSUBSCRIBE,
 };
 
+// Encryption constants
+pub use constants::ENCRYPTED_CONTENT_TYPE;
+
 // Size limit constants
 pub use constants::{
     MAX_SIGNALS_PER_BATCH, MAX_SIGNAL_PAYLOAD_SIZE, MAX_SUBSCRIPTIONS_PER_CLIENT,
@@ -139,8 +151,8 @@ pub use constants::{
 // =============================================================================
 
 pub use id::{
-    generate_action_id, generate_message_id, generate_session_id, generate_signal_id,
-    generate_subscription_id,
+    generate_action_id, generate_correlation_id, generate_message_id, generate_session_id,
+    generate_signal_id, generate_subscription_id,
 };
 
 // =============================================================================
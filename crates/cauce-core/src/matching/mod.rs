@@ -86,6 +86,38 @@ impl TopicMatcher {
     pub fn matches_any(topic: &str, patterns: &[&str]) -> bool {
         patterns.iter().any(|pattern| Self::matches(topic, pattern))
     }
+
+    /// Checks if every topic matched by `pattern` is matched by `allowed`.
+    ///
+    /// The check is conservative: for some patterns with `**` before their
+    /// last segment it may return `false` although `allowed` does cover
+    /// `pattern`, but it never returns `true` when it doesn't.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cauce_core::matching::TopicMatcher;
+    ///
+    /// assert!(TopicMatcher::pattern_covers("signal.**", "signal.email.*"));
+    /// assert!(!TopicMatcher::pattern_covers("signal.*", "signal.**"));
+    /// ```
+    pub fn pattern_covers(allowed: &str, pattern: &str) -> bool {
+        covers_segments(&pattern_segments(allowed), &pattern_segments(pattern))
+    }
+
+    /// Checks if at least one topic is matched by both `a` and `b`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cauce_core::matching::TopicMatcher;
+    ///
+    /// assert!(TopicMatcher::patterns_overlap("signal.health.*", "signal.*.heart"));
+    /// assert!(!TopicMatcher::patterns_overlap("signal.health.*", "signal.email.*"));
+    /// ```
+    pub fn patterns_overlap(a: &str, b: &str) -> bool {
+        overlap_segments(&pattern_segments(a), &pattern_segments(b))
+    }
 }
 
 /// Convenience function for topic pattern matching.
@@ -122,17 +154,13 @@ fn matches_segments_inner(topic: &[&str], pattern: &[&str], star_star_consumed:
         (Some(_), None) => false,
 
         // Topic is empty, pattern has `**` left
-        (None, Some(&"**")) => {
-            // ** matches 1+ segments. If we've consumed at least one, we can move past **.
-            if star_star_consumed {
-                matches_segments_inner(&[], &pattern[1..], false)
-            } else {
-                // ** hasn't consumed anything yet, can't match empty topic
-                false
-            }
+        // ** matches 1+ segments. If we've consumed at least one, we can move past **.
+        (None, Some(&"**")) if star_star_consumed => {
+            matches_segments_inner(&[], &pattern[1..], false)
         }
 
-        // Topic is empty, pattern has non-** segment - no match
+        // Topic is empty, pattern has a non-** segment (or a ** that hasn't
+        // consumed anything yet) - no match
         (None, Some(_)) => false,
 
         // Pattern has `**` - try matching 1 or more segments
@@ -174,6 +202,79 @@ fn matches_segments_inner(topic: &[&str], pattern: &[&str], star_star_consumed:
     }
 }
 
+/// A pattern segment, with `**` split into a `*` followed by [`Segment::Any`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Segment<'a> {
+    /// A literal segment.
+    Literal(&'a str),
+    /// `*`: exactly one segment.
+    One,
+    /// Zero or more segments.
+    Any,
+}
+
+impl Segment<'_> {
+    /// Returns true if some single segment matches both `self` and `other`.
+    fn compatible(self, other: Self) -> bool {
+        match (self, other) {
+            (Segment::Literal(a), Segment::Literal(b)) => a == b,
+            _ => true,
+        }
+    }
+}
+
+/// Splits a pattern into segments.
+fn pattern_segments(pattern: &str) -> Vec<Segment<'_>> {
+    pattern
+        .split('.')
+        .flat_map(|segment| match segment {
+            "**" => vec![Segment::One, Segment::Any],
+            "*" => vec![Segment::One],
+            literal => vec![Segment::Literal(literal)],
+        })
+        .collect()
+}
+
+/// Returns true if every topic `pattern` matches is matched by `allowed`.
+fn covers_segments(allowed: &[Segment], pattern: &[Segment]) -> bool {
+    match (allowed.split_first(), pattern.split_first()) {
+        (None, None) => true,
+        // Either `allowed` matches no more segments, or it absorbs the next
+        (Some((Segment::Any, allowed_rest)), _) => {
+            covers_segments(allowed_rest, pattern)
+                || (!pattern.is_empty() && covers_segments(allowed, &pattern[1..]))
+        }
+        // `pattern` may match no more segments here, or one and more to come
+        (Some((Segment::One, allowed_rest)), Some((Segment::Any, pattern_rest))) => {
+            covers_segments(allowed, pattern_rest) && covers_segments(allowed_rest, pattern)
+        }
+        (Some((Segment::One, allowed_rest)), Some((_, pattern_rest))) => {
+            covers_segments(allowed_rest, pattern_rest)
+        }
+        (Some((Segment::Literal(literal), allowed_rest)), Some((segment, pattern_rest))) => {
+            *segment == Segment::Literal(literal) && covers_segments(allowed_rest, pattern_rest)
+        }
+        _ => false,
+    }
+}
+
+/// Returns true if some topic is matched by both `a` and `b`.
+fn overlap_segments(a: &[Segment], b: &[Segment]) -> bool {
+    match (a.split_first(), b.split_first()) {
+        (None, None) => true,
+        (Some((Segment::Any, a_rest)), _) => {
+            overlap_segments(a_rest, b) || (!b.is_empty() && overlap_segments(a, &b[1..]))
+        }
+        (_, Some((Segment::Any, b_rest))) => {
+            overlap_segments(a, b_rest) || (!a.is_empty() && overlap_segments(&a[1..], b))
+        }
+        (Some((x, a_rest)), Some((y, b_rest))) => {
+            x.compatible(*y) && overlap_segments(a_rest, b_rest)
+        }
+        _ => false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -352,4 +453,55 @@ mod tests {
         assert!(topic_matches("signal.email", "**.**"));
         assert!(topic_matches("signal.email.received", "**.**"));
     }
+
+    // ===== Pattern comparison tests =====
+
+    #[test]
+    fn test_pattern_covers() {
+        assert!(TopicMatcher::pattern_covers("signal.**", "signal.email.*"));
+        assert!(TopicMatcher::pattern_covers("signal.**", "signal.**"));
+        assert!(TopicMatcher::pattern_covers("signal.*.received", "signal.email.received"));
+        assert!(TopicMatcher::pattern_covers("signal.*", "signal.*"));
+        assert!(!TopicMatcher::pattern_covers("signal.*", "signal.**"));
+        assert!(!TopicMatcher::pattern_covers("signal.email.*", "signal.**"));
+        assert!(!TopicMatcher::pattern_covers("signal.email.*", "signal.email"));
+        assert!(!TopicMatcher::pattern_covers("signal.**", "signal"));
+    }
+
+    #[test]
+    fn test_pattern_covers_inner_wildcard() {
+        assert!(TopicMatcher::pattern_covers(
+            "signal.**.important",
+            "signal.email.**.important"
+        ));
+        assert!(TopicMatcher::pattern_covers("signal.**", "signal.**.important"));
+        assert!(TopicMatcher::pattern_covers("**.important", "signal.*.important"));
+        assert!(!TopicMatcher::pattern_covers(
+            "signal.email.**.important",
+            "signal.**.important"
+        ));
+        assert!(!TopicMatcher::pattern_covers("signal.**.important", "signal.**"));
+        assert!(!TopicMatcher::pattern_covers("signal.*.*", "signal.**"));
+    }
+
+    #[test]
+    fn test_patterns_overlap() {
+        assert!(TopicMatcher::patterns_overlap("signal.health.*", "signal.*.heart"));
+        assert!(TopicMatcher::patterns_overlap("signal.**", "signal.health.heart"));
+        assert!(TopicMatcher::patterns_overlap("signal.health.heart", "*.**"));
+        assert!(!TopicMatcher::patterns_overlap("signal.health.*", "signal.health"));
+        assert!(!TopicMatcher::patterns_overlap("signal.health.*", "signal.email.*"));
+        assert!(!TopicMatcher::patterns_overlap("signal.**", "signal"));
+    }
+
+    #[test]
+    fn test_patterns_overlap_inner_wildcard() {
+        assert!(TopicMatcher::patterns_overlap("signal.**.heart", "signal.health.**"));
+        assert!(TopicMatcher::patterns_overlap("**.heart", "signal.*.*"));
+        assert!(TopicMatcher::patterns_overlap("signal.**.heart", "**.rate.heart"));
+        assert!(!TopicMatcher::patterns_overlap("signal.**.heart", "signal.**.rate"));
+        assert!(!TopicMatcher::patterns_overlap("signal.**.heart", "signal.heart"));
+        assert!(!TopicMatcher::patterns_overlap("signal.**.heart", "signal.*.rate"));
+        assert!(!TopicMatcher::patterns_overlap("**.heart", "signal"));
+    }
 }
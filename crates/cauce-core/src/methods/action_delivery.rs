@@ -0,0 +1,95 @@
+//! Action delivery notification type for the Cauce Protocol.
+//!
+//! Used to deliver actions to the adapters subscribed to their topics.
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::Action;
+
+/// Notification payload for action delivery.
+///
+/// Sent via the `cauce.action` notification method.
+///
+/// # Example
+///
+/// ```ignore
+/// use cauce_core::methods::ActionDelivery;
+/// use cauce_core::types::Action;
+///
+/// let delivery = ActionDelivery::new("action.email.send", action)
+///     .with_subscription_id("sub_abc123");
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActionDelivery {
+    /// The topic the action was published to
+    pub topic: String,
+
+    /// The action being delivered
+    pub action: Action,
+
+    /// The subscription this delivery is for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subscription_id: Option<String>,
+}
+
+impl ActionDelivery {
+    /// Creates a new ActionDelivery.
+    pub fn new(topic: impl Into<String>, action: Action) -> Self {
+        Self {
+            topic: topic.into(),
+            action,
+            subscription_id: None,
+        }
+    }
+
+    /// Sets the subscription this delivery is for.
+    pub fn with_subscription_id(mut self, subscription_id: impl Into<String>) -> Self {
+        self.subscription_id = Some(subscription_id.into());
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ActionBody, ActionType, Topic};
+    use chrono::{DateTime, Utc};
+    use serde_json::json;
+
+    fn create_test_action() -> Action {
+        Action {
+            id: "act_1704067200_abc123def456".to_string(),
+            version: "1.0".to_string(),
+            timestamp: DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc),
+            topic: Topic::new_unchecked("action.email.send"),
+            action: ActionBody {
+                type_: ActionType::Send,
+                target: Some("bob@example.com".to_string()),
+                payload: json!({"text": "hello"}),
+            },
+            context: None,
+            encrypted: None,
+            signature: None,
+        }
+    }
+
+    #[test]
+    fn test_action_delivery_round_trip() {
+        let delivery = ActionDelivery::new("action.email.send", create_test_action())
+            .with_subscription_id("sub_1");
+        let json = serde_json::to_string(&delivery).unwrap();
+        assert!(json.contains("\"subscription_id\":\"sub_1\""));
+
+        let parsed: ActionDelivery = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, delivery);
+    }
+
+    #[test]
+    fn test_action_delivery_without_subscription_id() {
+        let delivery = ActionDelivery::new("action.email.send", create_test_action());
+        let json = serde_json::to_string(&delivery).unwrap();
+        assert!(!json.contains("subscription_id"));
+    }
+}
@@ -100,6 +100,7 @@ impl HelloRequest {
 ///     server_version: "1.0".to_string(),
 ///     capabilities: vec![Capability::Subscribe, Capability::Publish],
 ///     session_expires_at: None,
+///     session_token: None,
 /// };
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -117,6 +118,10 @@ pub struct HelloResponse {
     /// When the session expires (optional)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub session_expires_at: Option<DateTime<Utc>>,
+
+    /// Signed token for authenticating HTTP transport requests (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_token: Option<String>,
 }
 
 impl HelloResponse {
@@ -127,6 +132,7 @@ impl HelloResponse {
             server_version: server_version.into(),
             capabilities: vec![],
             session_expires_at: None,
+            session_token: None,
         }
     }
 
@@ -141,6 +147,12 @@ impl HelloResponse {
         self.session_expires_at = Some(expires_at);
         self
     }
+
+    /// Sets the session token for HTTP transports.
+    pub fn with_session_token(mut self, token: impl Into<String>) -> Self {
+        self.session_token = Some(token.into());
+        self
+    }
 }
 
 #[cfg(test)]
@@ -279,6 +291,17 @@ mod tests {
         assert!(json.contains("\"session_id\":\"sess_abc\""));
         assert!(json.contains("\"server_version\":\"1.0\""));
         assert!(!json.contains("\"session_expires_at\"")); // Should be omitted
+        assert!(!json.contains("\"session_token\"")); // Should be omitted
+    }
+
+    #[test]
+    fn test_hello_response_with_session_token() {
+        let response = HelloResponse::new("sess_abc", "1.0").with_session_token("tok");
+        let json = serde_json::to_string(&response).unwrap();
+        assert!(json.contains("\"session_token\":\"tok\""));
+
+        let parsed: HelloResponse = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.session_token.as_deref(), Some("tok"));
     }
 
     #[test]
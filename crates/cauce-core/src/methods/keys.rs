@@ -0,0 +1,139 @@
+//! Key discovery method types for the Cauce Protocol.
+//!
+//! Publishers use `cauce.keys.list` to fetch the public keys of the
+//! subscribers a topic will be delivered to, so they can encrypt a copy of
+//! each signal for every recipient.
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::EncryptionAlgorithm;
+
+/// Request parameters for the `cauce.keys.list` method.
+///
+/// # Example
+///
+/// ```
+/// use cauce_core::methods::KeysListRequest;
+///
+/// let request = KeysListRequest::new("signal.email.received");
+/// assert_eq!(request.topic, "signal.email.received");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeysListRequest {
+    /// The topic a message is about to be published to
+    pub topic: String,
+}
+
+impl KeysListRequest {
+    /// Creates a new KeysListRequest.
+    pub fn new(topic: impl Into<String>) -> Self {
+        Self {
+            topic: topic.into(),
+        }
+    }
+}
+
+/// Response from the `cauce.keys.list` method.
+///
+/// Lists one entry per subscription that matches the topic and has
+/// end-to-end encryption enabled.
+///
+/// # Example
+///
+/// ```
+/// use cauce_core::methods::{KeysListResponse, RecipientKey};
+///
+/// let response = KeysListResponse::new(vec![RecipientKey::new("sub_1", "cHVibGlj")]);
+/// assert_eq!(response.recipients.len(), 1);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeysListResponse {
+    /// Recipients with encryption enabled
+    pub recipients: Vec<RecipientKey>,
+}
+
+impl KeysListResponse {
+    /// Creates a new KeysListResponse.
+    pub fn new(recipients: Vec<RecipientKey>) -> Self {
+        Self { recipients }
+    }
+
+    /// Creates an empty response.
+    pub fn empty() -> Self {
+        Self { recipients: vec![] }
+    }
+}
+
+/// A subscriber's public key.
+///
+/// # Example
+///
+/// ```
+/// use cauce_core::methods::RecipientKey;
+/// use cauce_core::types::EncryptionAlgorithm;
+///
+/// let key = RecipientKey::new("sub_1", "cHVibGlj")
+///     .with_algorithm(EncryptionAlgorithm::XChaCha20Poly1305);
+/// assert_eq!(key.supported_algorithms.len(), 1);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecipientKey {
+    /// The subscription the key was registered with
+    pub subscription_id: String,
+
+    /// The subscriber's public key (base64-encoded)
+    pub public_key: String,
+
+    /// Algorithms the subscriber can decrypt, in order of preference
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub supported_algorithms: Vec<EncryptionAlgorithm>,
+}
+
+impl RecipientKey {
+    /// Creates a new RecipientKey.
+    pub fn new(subscription_id: impl Into<String>, public_key: impl Into<String>) -> Self {
+        Self {
+            subscription_id: subscription_id.into(),
+            public_key: public_key.into(),
+            supported_algorithms: vec![],
+        }
+    }
+
+    /// Adds a supported algorithm.
+    pub fn with_algorithm(mut self, algorithm: EncryptionAlgorithm) -> Self {
+        self.supported_algorithms.push(algorithm);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keys_list_request_serialization() {
+        let request = KeysListRequest::new("signal.email.received");
+        let json = serde_json::to_string(&request).unwrap();
+        assert_eq!(json, r#"{"topic":"signal.email.received"}"#);
+    }
+
+    #[test]
+    fn test_keys_list_response_roundtrip() {
+        let response = KeysListResponse::new(vec![
+            RecipientKey::new("sub_1", "a2V5MQ=="),
+            RecipientKey::new("sub_2", "a2V5Mg==").with_algorithm(EncryptionAlgorithm::A256Gcm),
+        ]);
+        let json = serde_json::to_string(&response).unwrap();
+        assert!(json.contains(r#"{"subscription_id":"sub_1","public_key":"a2V5MQ=="}"#));
+        assert!(json.contains(r#""supported_algorithms":["a256gcm"]"#));
+
+        let parsed: KeysListResponse = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, response);
+    }
+
+    #[test]
+    fn test_keys_list_response_empty() {
+        let response: KeysListResponse = serde_json::from_str(r#"{"recipients":[]}"#).unwrap();
+        assert_eq!(response, KeysListResponse::empty());
+    }
+}
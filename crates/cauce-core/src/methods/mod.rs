@@ -6,8 +6,10 @@
 //! - Subscription management: [`SubscribeRequest`], [`UnsubscribeRequest`]
 //! - Publishing: [`PublishRequest`], [`PublishResponse`]
 //! - Acknowledgment: [`AckRequest`], [`AckResponse`]
+//! - Delivery: [`SignalDelivery`], [`ActionDelivery`]
 //! - Ping/Pong: [`PingParams`], [`PongParams`]
 //! - Schema discovery: [`SchemasListRequest`], [`SchemasGetRequest`]
+//! - Key discovery: [`KeysListRequest`], [`KeysListResponse`]
 
 // Foundational types (shared enums)
 mod auth;
@@ -17,7 +19,9 @@ mod transport;
 
 // Method-specific types
 mod ack;
+mod action_delivery;
 mod hello;
+mod keys;
 mod ping;
 mod publish;
 mod schemas;
@@ -34,7 +38,9 @@ pub use transport::{E2eConfig, Transport, WebhookConfig};
 
 // Re-export method types
 pub use ack::{AckFailure, AckRequest, AckResponse};
+pub use action_delivery::ActionDelivery;
 pub use hello::{HelloRequest, HelloResponse};
+pub use keys::{KeysListRequest, KeysListResponse, RecipientKey};
 pub use ping::{PingParams, PongParams};
 pub use publish::{PublishMessage, PublishRequest, PublishResponse};
 pub use schemas::{
@@ -137,6 +137,7 @@ mod tests {
             payload: Payload::new(json!({"text": "hello"}), "application/json"),
             metadata: None,
             encrypted: None,
+            signature: None,
         }
     }
 
@@ -151,6 +152,7 @@ mod tests {
             action: ActionBody::new(ActionType::Send, json!({"text": "Hello!"})),
             context: None,
             encrypted: None,
+            signature: None,
         }
     }
 
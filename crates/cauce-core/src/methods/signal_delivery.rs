@@ -16,10 +16,8 @@ use crate::types::Signal;
 /// use cauce_core::methods::SignalDelivery;
 /// use cauce_core::types::Signal;
 ///
-/// let delivery = SignalDelivery {
-///     topic: "signal.email.received".to_string(),
-///     signal,
-/// };
+/// let delivery = SignalDelivery::new("signal.email.received", signal)
+///     .with_subscription_id("sub_abc123");
 /// ```
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SignalDelivery {
@@ -28,6 +26,21 @@ pub struct SignalDelivery {
 
     /// The signal being delivered
     pub signal: Signal,
+
+    /// The subscription this delivery is for.
+    ///
+    /// Lets clients route deliveries to the right subscription without
+    /// re-matching topics. Omitted by hubs that predate this field.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subscription_id: Option<String>,
+
+    /// Whether the hub verified the signal's provenance signature against
+    /// a key registered for its source adapter.
+    ///
+    /// False for unsigned signals and signals from adapters the hub has no
+    /// key for. Omitted when false.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub verified: bool,
 }
 
 impl SignalDelivery {
@@ -36,8 +49,22 @@ impl SignalDelivery {
         Self {
             topic: topic.into(),
             signal,
+            subscription_id: None,
+            verified: false,
         }
     }
+
+    /// Sets the subscription this delivery is for.
+    pub fn with_subscription_id(mut self, subscription_id: impl Into<String>) -> Self {
+        self.subscription_id = Some(subscription_id.into());
+        self
+    }
+
+    /// Marks whether the signal's provenance was verified.
+    pub fn with_verified(mut self, verified: bool) -> Self {
+        self.verified = verified;
+        self
+    }
 }
 
 #[cfg(test)]
@@ -59,6 +86,7 @@ mod tests {
             payload: Payload::new(json!({"text": "hello"}), "application/json"),
             metadata: None,
             encrypted: None,
+            signature: None,
         }
     }
 
@@ -99,6 +127,34 @@ mod tests {
         let delivery: SignalDelivery = serde_json::from_str(json).unwrap();
         assert_eq!(delivery.topic, "signal.slack.message");
         assert_eq!(delivery.signal.id, "sig_1704067200_xyz789ghi012");
+        assert!(delivery.subscription_id.is_none());
+    }
+
+    #[test]
+    fn test_signal_delivery_with_subscription_id() {
+        let signal = create_test_signal();
+        let delivery = SignalDelivery::new("signal.email", signal).with_subscription_id("sub_123");
+        assert_eq!(delivery.subscription_id.as_deref(), Some("sub_123"));
+
+        let json = serde_json::to_string(&delivery).unwrap();
+        assert!(json.contains("\"subscription_id\":\"sub_123\""));
+
+        let without = SignalDelivery::new("signal.email", create_test_signal());
+        let json = serde_json::to_string(&without).unwrap();
+        assert!(!json.contains("subscription_id"));
+    }
+
+    #[test]
+    fn test_signal_delivery_with_verified() {
+        let unverified = SignalDelivery::new("signal.email", create_test_signal());
+        assert!(!unverified.verified);
+        assert!(!serde_json::to_string(&unverified).unwrap().contains("verified"));
+
+        let delivery = unverified.with_verified(true);
+        let json = serde_json::to_string(&delivery).unwrap();
+        assert!(json.contains("\"verified\":true"));
+        let restored: SignalDelivery = serde_json::from_str(&json).unwrap();
+        assert!(restored.verified);
     }
 
     #[test]
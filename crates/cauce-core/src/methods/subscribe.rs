@@ -93,6 +93,12 @@ impl SubscribeRequest {
         self.webhook = Some(webhook);
         self
     }
+
+    /// Sets end-to-end encryption configuration.
+    pub fn with_e2e(mut self, e2e: E2eConfig) -> Self {
+        self.e2e = Some(e2e);
+        self
+    }
 }
 
 /// Response from the `cauce.subscribe` method.
@@ -203,6 +209,12 @@ mod tests {
         assert!(request.webhook.is_some());
     }
 
+    #[test]
+    fn test_subscribe_request_with_e2e() {
+        let request = SubscribeRequest::single("topic").with_e2e(E2eConfig::enabled("pk"));
+        assert_eq!(request.e2e, Some(E2eConfig::enabled("pk")));
+    }
+
     #[test]
     fn test_subscribe_request_serialization() {
         let request = SubscribeRequest::single("signal.*");
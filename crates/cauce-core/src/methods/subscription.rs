@@ -5,7 +5,7 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use super::{SubscriptionStatus, Transport};
+use super::{E2eConfig, SubscriptionStatus, Transport};
 
 /// Request to approve a subscription.
 ///
@@ -271,6 +271,10 @@ pub struct SubscriptionInfo {
     /// When the subscription expires (if applicable)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub expires_at: Option<DateTime<Utc>>,
+
+    /// End-to-end encryption settings the subscriber registered
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub e2e: Option<E2eConfig>,
 }
 
 impl SubscriptionInfo {
@@ -292,8 +296,24 @@ impl SubscriptionInfo {
             transport,
             created_at: Utc::now(),
             expires_at: None,
+            e2e: None,
         }
     }
+
+    /// Sets the end-to-end encryption settings.
+    pub fn with_e2e(mut self, e2e: E2eConfig) -> Self {
+        self.e2e = Some(e2e);
+        self
+    }
+
+    /// Returns the subscriber's public key if end-to-end encryption is
+    /// enabled.
+    pub fn e2e_public_key(&self) -> Option<&str> {
+        self.e2e
+            .as_ref()
+            .filter(|e2e| e2e.enabled)
+            .and_then(|e2e| e2e.public_key.as_deref())
+    }
 }
 
 /// Notification about subscription status change.
@@ -520,6 +540,7 @@ mod tests {
                     .unwrap()
                     .with_timezone(&Utc),
             ),
+            e2e: Some(E2eConfig::enabled("cHVibGlj")),
         };
 
         let json = serde_json::to_string(&info).unwrap();
@@ -527,6 +548,27 @@ mod tests {
         assert_eq!(info, restored);
     }
 
+    #[test]
+    fn test_subscription_info_e2e_public_key() {
+        let info = SubscriptionInfo::new(
+            "sub_1",
+            "client_1",
+            "sess_1",
+            vec!["signal.**".to_string()],
+            SubscriptionStatus::Active,
+            Transport::WebSocket,
+        );
+        assert_eq!(info.e2e_public_key(), None);
+        assert!(!serde_json::to_string(&info).unwrap().contains("e2e"));
+
+        let info = info.with_e2e(E2eConfig::enabled("cHVibGlj"));
+        assert_eq!(info.e2e_public_key(), Some("cHVibGlj"));
+
+        let mut disabled = E2eConfig::enabled("cHVibGlj");
+        disabled.enabled = false;
+        assert_eq!(info.with_e2e(disabled).e2e_public_key(), None);
+    }
+
     // ===== SubscriptionStatusNotification Tests =====
 
     #[test]
@@ -27,7 +27,8 @@ pub const SIGNAL_SCHEMA: &str = r##"{
     "topic": { "type": "string" },
     "payload": { "$ref": "#/$defs/payload" },
     "metadata": { "$ref": "#/$defs/metadata" },
-    "encrypted": { "$ref": "#/$defs/encrypted" }
+    "encrypted": { "$ref": "#/$defs/encrypted" },
+    "signature": { "$ref": "#/$defs/signature" }
   },
   "$defs": {
     "source": {
@@ -53,7 +54,8 @@ pub const SIGNAL_SCHEMA: &str = r##"{
       "properties": {
         "thread_id": { "type": "string" },
         "priority": { "enum": ["low", "normal", "high", "urgent"] },
-        "tags": { "type": "array", "items": { "type": "string" } }
+        "tags": { "type": "array", "items": { "type": "string" } },
+        "correlation_id": { "type": "string" }
       }
     },
     "encrypted": {
@@ -65,6 +67,14 @@ pub const SIGNAL_SCHEMA: &str = r##"{
         "nonce": { "type": "string" },
         "ciphertext": { "type": "string" }
       }
+    },
+    "signature": {
+      "type": "object",
+      "required": ["algorithm", "value"],
+      "properties": {
+        "algorithm": { "enum": ["ed25519"] },
+        "value": { "type": "string" }
+      }
     }
   }
 }"##;
@@ -83,7 +93,8 @@ pub const ACTION_SCHEMA: &str = r##"{
     "target": { "$ref": "#/$defs/target" },
     "topic": { "type": "string" },
     "body": { "$ref": "#/$defs/body" },
-    "context": { "$ref": "#/$defs/context" }
+    "context": { "$ref": "#/$defs/context" },
+    "signature": { "$ref": "#/$defs/signature" }
   },
   "$defs": {
     "target": {
@@ -109,6 +120,14 @@ pub const ACTION_SCHEMA: &str = r##"{
         "reply_to_signal_id": { "type": "string" },
         "correlation_id": { "type": "string" }
       }
+    },
+    "signature": {
+      "type": "object",
+      "required": ["algorithm", "value"],
+      "properties": {
+        "algorithm": { "enum": ["ed25519"] },
+        "value": { "type": "string" }
+      }
     }
   }
 }"##;
@@ -0,0 +1,205 @@
+//! Ed25519 keys for provenance signatures.
+
+use std::fmt;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use ed25519_dalek::Signer;
+use rand_core::OsRng;
+
+use super::SigningError;
+
+/// Length of Ed25519 public and secret keys in bytes.
+pub const KEY_LENGTH: usize = 32;
+
+/// Length of an Ed25519 signature in bytes.
+pub const SIGNATURE_LENGTH: usize = 64;
+
+/// An Ed25519 secret key that signs signals and actions.
+///
+/// The key material is zeroed when dropped and never printed by `Debug`.
+///
+/// # Example
+///
+/// ```
+/// use cauce_core::signing::SigningKey;
+///
+/// let key = SigningKey::generate();
+/// let restored = SigningKey::from_base64(&key.to_base64()).unwrap();
+/// assert_eq!(restored.verifying_key(), key.verifying_key());
+/// ```
+#[derive(Clone)]
+pub struct SigningKey(ed25519_dalek::SigningKey);
+
+impl SigningKey {
+    /// Generates a random signing key.
+    pub fn generate() -> Self {
+        Self(ed25519_dalek::SigningKey::generate(&mut OsRng))
+    }
+
+    /// Creates a signing key from its 32-byte seed.
+    pub fn from_bytes(bytes: [u8; KEY_LENGTH]) -> Self {
+        Self(ed25519_dalek::SigningKey::from_bytes(&bytes))
+    }
+
+    /// Parses a base64-encoded signing key.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SigningError::InvalidKey`] if `encoded` is not base64 for
+    /// exactly 32 bytes.
+    pub fn from_base64(encoded: &str) -> Result<Self, SigningError> {
+        decode_key(encoded).map(Self::from_bytes)
+    }
+
+    /// Returns the 32-byte seed.
+    pub fn to_bytes(&self) -> [u8; KEY_LENGTH] {
+        self.0.to_bytes()
+    }
+
+    /// Returns the key as standard base64.
+    pub fn to_base64(&self) -> String {
+        STANDARD.encode(self.0.to_bytes())
+    }
+
+    /// Returns the public key matching this signing key.
+    pub fn verifying_key(&self) -> VerifyingKey {
+        VerifyingKey(self.0.verifying_key())
+    }
+
+    /// Signs `message`.
+    pub(super) fn sign(&self, message: &[u8]) -> [u8; SIGNATURE_LENGTH] {
+        self.0.sign(message).to_bytes()
+    }
+}
+
+impl fmt::Debug for SigningKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SigningKey(..)")
+    }
+}
+
+/// An Ed25519 public key that signatures are verified against.
+///
+/// Serialized as standard base64 wherever keys are configured, such as the
+/// adapter keys a hub verifies signals with.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct VerifyingKey(ed25519_dalek::VerifyingKey);
+
+impl VerifyingKey {
+    /// Creates a verifying key from raw bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SigningError::InvalidKey`] if `bytes` is not a valid point.
+    pub fn from_bytes(bytes: [u8; KEY_LENGTH]) -> Result<Self, SigningError> {
+        ed25519_dalek::VerifyingKey::from_bytes(&bytes)
+            .map(Self)
+            .map_err(|e| SigningError::InvalidKey {
+                reason: e.to_string(),
+            })
+    }
+
+    /// Parses a base64-encoded verifying key.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SigningError::InvalidKey`] if `encoded` is not base64 for
+    /// a valid 32-byte key.
+    pub fn from_base64(encoded: &str) -> Result<Self, SigningError> {
+        decode_key(encoded).and_then(Self::from_bytes)
+    }
+
+    /// Returns the raw key bytes.
+    pub fn as_bytes(&self) -> &[u8; KEY_LENGTH] {
+        self.0.as_bytes()
+    }
+
+    /// Returns the key as standard base64.
+    pub fn to_base64(&self) -> String {
+        STANDARD.encode(self.0.as_bytes())
+    }
+
+    /// Returns true if `signature` is a valid signature of `message`.
+    ///
+    /// Uses strict verification, which rejects weak keys and malleable
+    /// signatures.
+    pub(super) fn verify(&self, message: &[u8], signature: &[u8; SIGNATURE_LENGTH]) -> bool {
+        let signature = ed25519_dalek::Signature::from_bytes(signature);
+        self.0.verify_strict(message, &signature).is_ok()
+    }
+}
+
+impl fmt::Debug for VerifyingKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("VerifyingKey")
+            .field(&self.to_base64())
+            .finish()
+    }
+}
+
+impl fmt::Display for VerifyingKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_base64())
+    }
+}
+
+fn decode_key(encoded: &str) -> Result<[u8; KEY_LENGTH], SigningError> {
+    let bytes = STANDARD
+        .decode(encoded)
+        .map_err(|e| SigningError::InvalidKey {
+            reason: format!("invalid base64: {}", e),
+        })?;
+    bytes
+        .try_into()
+        .map_err(|bytes: Vec<u8>| SigningError::InvalidKey {
+            reason: format!("expected {} bytes, got {}", KEY_LENGTH, bytes.len()),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_round_trip() {
+        let key = SigningKey::generate();
+        let restored = SigningKey::from_base64(&key.to_base64()).unwrap();
+        assert_eq!(restored.to_bytes(), key.to_bytes());
+
+        let public = VerifyingKey::from_base64(&key.verifying_key().to_base64()).unwrap();
+        assert_eq!(public, key.verifying_key());
+        assert_ne!(SigningKey::generate().verifying_key(), public);
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let key = SigningKey::generate();
+        let signature = key.sign(b"hello");
+        assert!(key.verifying_key().verify(b"hello", &signature));
+        assert!(!key.verifying_key().verify(b"hullo", &signature));
+        assert!(!SigningKey::generate()
+            .verifying_key()
+            .verify(b"hello", &signature));
+    }
+
+    #[test]
+    fn test_invalid_keys() {
+        assert!(matches!(
+            VerifyingKey::from_base64("not base64!"),
+            Err(SigningError::InvalidKey { .. })
+        ));
+        assert!(matches!(
+            SigningKey::from_base64(&STANDARD.encode([0u8; 16])),
+            Err(SigningError::InvalidKey { .. })
+        ));
+    }
+
+    #[test]
+    fn test_signing_key_debug_is_redacted() {
+        let key = SigningKey::generate();
+        let debug = format!("{:?}", key);
+        assert_eq!(debug, "SigningKey(..)");
+        assert!(!debug.contains(&key.to_base64()));
+    }
+}
@@ -6,7 +6,7 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use super::{ActionType, Encrypted, Topic};
+use super::{ActionType, Encrypted, Signature, Topic};
 
 /// A command from an agent to be executed by an adapter.
 ///
@@ -22,6 +22,7 @@ use super::{ActionType, Encrypted, Topic};
 /// - `action` - Action details (type, target, payload)
 /// - `context` - Optional correlation and threading info
 /// - `encrypted` - Optional E2E encryption envelope
+/// - `signature` - Optional provenance signature
 ///
 /// # Example
 ///
@@ -42,6 +43,7 @@ use super::{ActionType, Encrypted, Topic};
 ///     },
 ///     context: None,
 ///     encrypted: None,
+///     signature: None,
 /// };
 /// ```
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -68,6 +70,10 @@ pub struct Action {
     /// End-to-end encryption envelope
     #[serde(skip_serializing_if = "Option::is_none")]
     pub encrypted: Option<Encrypted>,
+
+    /// Provenance signature by the issuing agent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<Signature>,
 }
 
 /// Details of the action to be performed.
@@ -19,6 +19,7 @@ use super::Priority;
 /// - `references` - Related Signal/Action IDs
 /// - `priority` - Message priority (defaults to Normal)
 /// - `tags` - User-defined labels
+/// - `correlation_id` - Correlates a reply with the request action
 ///
 /// # Example
 ///
@@ -31,6 +32,7 @@ use super::Priority;
 ///     references: None,
 ///     priority: Some(Priority::High),
 ///     tags: Some(vec!["important".to_string()]),
+///     correlation_id: None,
 /// };
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
@@ -54,6 +56,11 @@ pub struct Metadata {
     /// User-defined labels
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tags: Option<Vec<String>>,
+
+    /// Correlation ID copied from the action this signal answers
+    /// (see [`ActionContext::correlation_id`](super::ActionContext::correlation_id))
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
 }
 
 impl Metadata {
@@ -134,6 +141,21 @@ impl Metadata {
         self.tags = Some(tags);
         self
     }
+
+    /// Sets the correlation ID.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cauce_core::types::Metadata;
+    ///
+    /// let metadata = Metadata::reply_to("act_1234_abc").correlation_id("corr-123");
+    /// assert_eq!(metadata.correlation_id, Some("corr-123".to_string()));
+    /// ```
+    pub fn correlation_id(mut self, id: impl Into<String>) -> Self {
+        self.correlation_id = Some(id.into());
+        self
+    }
 }
 
 #[cfg(test)]
@@ -209,6 +231,7 @@ mod tests {
             references: Some(vec!["ref1".to_string(), "ref2".to_string()]),
             priority: Some(Priority::Low),
             tags: Some(vec!["tag1".to_string()]),
+            correlation_id: Some("corr-1".to_string()),
         };
 
         let json = serde_json::to_string(&metadata).unwrap();
@@ -14,6 +14,8 @@
 //! - [`ActionBody`] - Details of an action to be performed
 //! - [`ActionContext`] - Correlation and threading information for actions
 //! - [`EncryptionAlgorithm`] - Supported E2E encryption algorithms
+//! - [`Signature`] - Detached provenance signature
+//! - [`SignatureAlgorithm`] - Supported signature algorithms
 
 // Submodules
 mod action;
@@ -22,6 +24,7 @@ mod enums;
 mod metadata;
 mod payload;
 mod signal;
+mod signature;
 mod source;
 mod topic;
 
@@ -30,8 +33,9 @@ pub use action::{Action, ActionBody, ActionContext};
 pub use encrypted::{Encrypted, EncryptionAlgorithm};
 pub use enums::{ActionType, Priority};
 pub use metadata::Metadata;
-pub use payload::Payload;
+pub use payload::{Payload, JSON_CONTENT_TYPE};
 pub use signal::Signal;
+pub use signature::{Signature, SignatureAlgorithm};
 pub use source::Source;
 pub use topic::Topic;
 
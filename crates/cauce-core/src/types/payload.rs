@@ -2,9 +2,13 @@
 //!
 //! The [`Payload`] struct contains the actual message content.
 
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+/// Content type used for payloads built from serializable values.
+pub const JSON_CONTENT_TYPE: &str = "application/json";
+
 /// The actual message content with type information.
 ///
 /// Payload wraps the raw message content along with metadata
@@ -71,6 +75,60 @@ impl Payload {
         }
     }
 
+    /// Creates a JSON Payload from any serializable value.
+    ///
+    /// The content type is set to `application/json` and the size is
+    /// calculated from the serialized JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `value` cannot be represented as JSON.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cauce_core::types::Payload;
+    /// use serde::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Email {
+    ///     from: String,
+    /// }
+    ///
+    /// let payload = Payload::from_serializable(&Email { from: "alice@example.com".into() }).unwrap();
+    /// assert_eq!(payload.content_type, "application/json");
+    /// assert_eq!(payload.raw["from"], "alice@example.com");
+    /// ```
+    pub fn from_serializable<T: Serialize + ?Sized>(value: &T) -> Result<Self, serde_json::Error> {
+        Ok(Self::new(serde_json::to_value(value)?, JSON_CONTENT_TYPE))
+    }
+
+    /// Deserializes the raw content into a typed value.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the content does not match the shape of `T`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cauce_core::types::Payload;
+    /// use serde::Deserialize;
+    /// use serde_json::json;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Email {
+    ///     from: String,
+    /// }
+    ///
+    /// let payload = Payload::new(json!({"from": "alice@example.com"}), "application/json");
+    /// let email: Email = payload.decode().unwrap();
+    /// assert_eq!(email.from, "alice@example.com");
+    /// ```
+    pub fn decode<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        T::deserialize(&self.raw)
+    }
+
     /// Creates a Payload with explicit size.
     ///
     /// Use this when you have a pre-computed size or need exact control.
@@ -101,6 +159,30 @@ mod tests {
         assert!(payload.size_bytes > 0);
     }
 
+    #[test]
+    fn test_payload_typed_round_trip() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Email {
+            from: String,
+            unread: bool,
+        }
+
+        let email = Email {
+            from: "alice".to_string(),
+            unread: true,
+        };
+        let payload = Payload::from_serializable(&email).unwrap();
+        assert_eq!(payload.content_type, JSON_CONTENT_TYPE);
+        assert_eq!(
+            payload.size_bytes,
+            serde_json::to_string(&payload.raw).unwrap().len() as u64
+        );
+        assert_eq!(payload.decode::<Email>().unwrap(), email);
+
+        // Shape mismatches are reported, not panicked on
+        assert!(payload.decode::<Vec<String>>().is_err());
+    }
+
     #[test]
     fn test_payload_with_size() {
         let payload = Payload::with_size(json!(null), "text/plain", 100);
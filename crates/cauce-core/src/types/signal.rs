@@ -5,7 +5,7 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use super::{Encrypted, Metadata, Payload, Source, Topic};
+use super::{Encrypted, Metadata, Payload, Signature, Source, Topic};
 
 /// An inbound message from an adapter to the hub.
 ///
@@ -22,6 +22,7 @@ use super::{Encrypted, Metadata, Payload, Source, Topic};
 /// - `payload` - Message content with type information
 /// - `metadata` - Optional threading and priority info
 /// - `encrypted` - Optional E2E encryption envelope
+/// - `signature` - Optional provenance signature
 ///
 /// # Example
 ///
@@ -39,6 +40,7 @@ use super::{Encrypted, Metadata, Payload, Source, Topic};
 ///     payload: Payload::new(json!({"from": "alice@example.com"}), "application/json"),
 ///     metadata: None,
 ///     encrypted: None,
+///     signature: None,
 /// };
 /// ```
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -68,6 +70,10 @@ pub struct Signal {
     /// Optional end-to-end encryption envelope
     #[serde(skip_serializing_if = "Option::is_none")]
     pub encrypted: Option<Encrypted>,
+
+    /// Optional provenance signature by the source adapter
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<Signature>,
 }
 
 impl Signal {
@@ -101,6 +107,15 @@ impl Signal {
         &self.payload
     }
 
+    /// Deserializes the payload content into a typed value.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the payload does not match the shape of `T`.
+    pub fn decode_payload<T: serde::de::DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        self.payload.decode()
+    }
+
     /// Returns a reference to the metadata, if present.
     pub fn metadata(&self) -> Option<&Metadata> {
         self.metadata.as_ref()
@@ -111,6 +126,11 @@ impl Signal {
         self.encrypted.as_ref()
     }
 
+    /// Returns a reference to the provenance signature, if present.
+    pub fn signature(&self) -> Option<&Signature> {
+        self.signature.as_ref()
+    }
+
     /// Checks if the signal has metadata.
     pub fn has_metadata(&self) -> bool {
         self.metadata.is_some()
@@ -120,12 +140,17 @@ impl Signal {
     pub fn is_encrypted(&self) -> bool {
         self.encrypted.is_some()
     }
+
+    /// Checks if the signal is signed.
+    pub fn is_signed(&self) -> bool {
+        self.signature.is_some()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::Priority;
+    use crate::types::{Priority, SignatureAlgorithm};
     use serde_json::json;
 
     fn create_test_signal() -> Signal {
@@ -143,6 +168,7 @@ mod tests {
             ),
             metadata: None,
             encrypted: None,
+            signature: None,
         }
     }
 
@@ -184,6 +210,17 @@ mod tests {
         assert!(signal.encrypted().is_none());
     }
 
+    #[test]
+    fn test_signal_is_signed() {
+        let mut signal = create_test_signal();
+        assert!(!signal.is_signed());
+        assert!(!serde_json::to_string(&signal).unwrap().contains("signature"));
+
+        signal.signature = Some(Signature::new(SignatureAlgorithm::Ed25519, "c2ln"));
+        assert!(signal.is_signed());
+        assert_eq!(signal.signature().unwrap().value, "c2ln");
+    }
+
     #[test]
     fn test_signal_serialization() {
         let signal = create_test_signal();
@@ -246,6 +283,7 @@ mod tests {
                     .tags(vec!["bot".to_string(), "greeting".to_string()]),
             ),
             encrypted: None,
+            signature: None,
         };
 
         let json_str = serde_json::to_string(&signal).unwrap();
@@ -0,0 +1,96 @@
+//! Signature type for the Cauce Protocol.
+//!
+//! This module provides the [`Signature`] attached to signals and actions to
+//! prove who produced them, and the [`SignatureAlgorithm`] enum.
+
+use serde::{Deserialize, Serialize};
+
+/// Supported provenance signature algorithms.
+///
+/// # JSON Serialization
+///
+/// Serializes as lowercase strings:
+/// - `Ed25519` → `"ed25519"`
+///
+/// # Example
+///
+/// ```
+/// use cauce_core::types::SignatureAlgorithm;
+///
+/// let algo = SignatureAlgorithm::Ed25519;
+/// let json = serde_json::to_string(&algo).unwrap();
+/// assert_eq!(json, "\"ed25519\"");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SignatureAlgorithm {
+    /// Ed25519 (RFC 8032)
+    #[serde(rename = "ed25519")]
+    Ed25519,
+}
+
+/// Detached provenance signature.
+///
+/// Covers the canonical JSON encoding of the signal or action it is attached
+/// to, minus the signature itself. Signing and verification live in the
+/// `signing` module (requires the `signing` feature).
+///
+/// # Fields
+///
+/// - `algorithm` - The signature algorithm
+/// - `value` - Base64-encoded signature bytes
+///
+/// # Example
+///
+/// ```
+/// use cauce_core::types::{Signature, SignatureAlgorithm};
+///
+/// let signature = Signature::new(SignatureAlgorithm::Ed25519, "c2lnbmF0dXJl");
+///
+/// let json = serde_json::to_string(&signature).unwrap();
+/// assert!(json.contains("\"algorithm\":\"ed25519\""));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Signature {
+    /// Signature algorithm used
+    pub algorithm: SignatureAlgorithm,
+
+    /// Base64-encoded signature
+    pub value: String,
+}
+
+impl Signature {
+    /// Creates a new Signature.
+    ///
+    /// # Arguments
+    ///
+    /// * `algorithm` - The signature algorithm
+    /// * `value` - Base64-encoded signature bytes
+    pub fn new(algorithm: SignatureAlgorithm, value: impl Into<String>) -> Self {
+        Self {
+            algorithm,
+            value: value.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature_round_trip() {
+        let signature = Signature::new(SignatureAlgorithm::Ed25519, "c2lnbmF0dXJl");
+        let json = serde_json::to_string(&signature).unwrap();
+        assert_eq!(json, r#"{"algorithm":"ed25519","value":"c2lnbmF0dXJl"}"#);
+
+        let parsed: Signature = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, signature);
+    }
+
+    #[test]
+    fn test_unknown_algorithm_is_rejected() {
+        let result: Result<Signature, _> =
+            serde_json::from_str(r#"{"algorithm":"rsa","value":"c2ln"}"#);
+        assert!(result.is_err());
+    }
+}
@@ -5,7 +5,6 @@
 fn crate_compiles() {
     // This test passes if the crate compiles successfully.
     // The mere existence of this test file proves the crate is usable.
-    assert!(true, "cauce-core crate compiled successfully");
 }
 
 /// Verify the crate version is accessible.
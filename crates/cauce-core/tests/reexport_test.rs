@@ -2,7 +2,7 @@
 
 use cauce_core::{
     Action, ActionBody, ActionContext, ActionType, Encrypted, EncryptionAlgorithm, Metadata,
-    Payload, Priority, Signal, Source, Topic,
+    Payload, Priority, Signal, Signature, SignatureAlgorithm, Source, Topic,
 };
 use chrono::Utc;
 use serde_json::json;
@@ -19,6 +19,7 @@ fn signal_reexported() {
         payload: Payload::new(json!({}), "application/json"),
         metadata: None,
         encrypted: None,
+        signature: None,
     };
     assert_eq!(signal.id(), "sig_1704067200_abc123def456");
 }
@@ -34,6 +35,7 @@ fn action_reexported() {
         action: ActionBody::new(ActionType::Send, json!({"text": "hello"})),
         context: None,
         encrypted: None,
+        signature: None,
     };
     assert_eq!(action.id, "act_1704067200_xyz789abc123");
 }
@@ -58,6 +60,7 @@ fn all_types_usable() {
     let payload = Payload::new(json!({"key": "value"}), "application/json");
     let metadata = Metadata::new().priority(Priority::Normal);
     let encrypted = Encrypted::new(EncryptionAlgorithm::A256Gcm, "pk", "nonce", "ct");
+    let signature = Signature::new(SignatureAlgorithm::Ed25519, "sig");
     let topic = Topic::new("test.topic").unwrap();
     let action_body = ActionBody::new(ActionType::Reply, json!({}));
     let context = ActionContext::new().with_correlation_id("corr-1");
@@ -72,6 +75,7 @@ fn all_types_usable() {
         payload,
         metadata: Some(metadata),
         encrypted: Some(encrypted.clone()),
+        signature: Some(signature.clone()),
     };
 
     // Full Action
@@ -83,9 +87,10 @@ fn all_types_usable() {
         action: action_body,
         context: Some(context),
         encrypted: Some(encrypted),
+        signature: Some(signature),
     };
 
-    assert!(true, "All types can be used together");
+    // Reaching this point means all types can be used together.
 }
 
 /// Verify error types are re-exported.
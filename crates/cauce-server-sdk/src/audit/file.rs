@@ -0,0 +1,311 @@
+//! JSON-lines audit sink.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use tokio::fs::{self, File, OpenOptions};
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+use tokio::sync::Mutex;
+
+use super::{AuditRecord, AuditSink, AuditTail};
+use crate::config::AuditFileConfig;
+use crate::error::ServerResult;
+
+/// The audit file being appended to.
+struct OpenFile {
+    file: File,
+    len: u64,
+}
+
+/// Appends audit records to a file, one JSON object per line.
+///
+/// When the file would grow past `max_bytes` it is renamed to `<path>.1`,
+/// shifting older files up by one and deleting any beyond `max_files`. The
+/// hash chain runs across rotated files.
+///
+/// # Example
+///
+/// ```ignore
+/// let sink = JsonLinesSink::new(AuditFileConfig::new("audit.jsonl"));
+/// let audit = AuditLog::new().with_sink(sink);
+/// ```
+pub struct JsonLinesSink {
+    config: AuditFileConfig,
+    file: Mutex<Option<OpenFile>>,
+}
+
+impl JsonLinesSink {
+    /// Creates a sink writing to the file described by `config`.
+    ///
+    /// The file is opened on the first write.
+    pub fn new(config: AuditFileConfig) -> Self {
+        Self {
+            config,
+            file: Mutex::new(None),
+        }
+    }
+
+    /// Returns the path of the current audit file.
+    pub fn path(&self) -> &Path {
+        &self.config.path
+    }
+
+    /// Reads every record still on disk, oldest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a file cannot be read or holds an invalid line.
+    pub async fn read_records(&self) -> ServerResult<Vec<AuditRecord>> {
+        let mut records = Vec::new();
+        for index in (1..=self.config.max_files).rev() {
+            records.extend(read_file(&self.rotated_path(index)).await?);
+        }
+        records.extend(read_file(&self.config.path).await?);
+        Ok(records)
+    }
+
+    /// Returns the path of the `index`th most recent rotated file.
+    fn rotated_path(&self, index: u32) -> PathBuf {
+        let mut path = self.config.path.clone().into_os_string();
+        path.push(format!(".{}", index));
+        path.into()
+    }
+
+    async fn open(&self) -> ServerResult<OpenFile> {
+        if let Some(parent) = self.config.path.parent() {
+            if !parent.as_os_str().is_empty() {
+                fs::create_dir_all(parent).await?;
+            }
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&self.config.path)
+            .await?;
+        let mut len = file.metadata().await?.len();
+
+        // A line cut short by a crash is ended so it does not swallow the
+        // next record
+        if len > 0 {
+            let mut last = [0u8];
+            file.seek(io::SeekFrom::End(-1)).await?;
+            file.read_exact(&mut last).await?;
+            if last[0] != b'\n' {
+                file.write_all(b"\n").await?;
+                len += 1;
+            }
+        }
+        Ok(OpenFile { file, len })
+    }
+
+    async fn rotate(&self) -> ServerResult<()> {
+        let max_files = self.config.max_files;
+        if max_files == 0 {
+            ignore_missing(fs::remove_file(&self.config.path).await)?;
+            return Ok(());
+        }
+        ignore_missing(fs::remove_file(self.rotated_path(max_files)).await)?;
+        for index in (1..max_files).rev() {
+            let from = self.rotated_path(index);
+            ignore_missing(fs::rename(&from, self.rotated_path(index + 1)).await)?;
+        }
+        fs::rename(&self.config.path, self.rotated_path(1)).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl AuditSink for JsonLinesSink {
+    async fn write(&self, record: &AuditRecord) -> ServerResult<()> {
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+        let len = line.len() as u64;
+
+        let mut current = self.file.lock().await;
+        let mut open = match current.take() {
+            Some(open) => open,
+            None => self.open().await?,
+        };
+        if open.len > 0 && open.len + len > self.config.max_bytes {
+            drop(open);
+            self.rotate().await?;
+            open = self.open().await?;
+        }
+
+        open.file.write_all(line.as_bytes()).await?;
+        open.file.flush().await?;
+        open.len += len;
+        *current = Some(open);
+        Ok(())
+    }
+
+    /// Returns the last line that parses as a record, counting the lines
+    /// after it that do not.
+    async fn tail(&self) -> ServerResult<AuditTail> {
+        let tail = read_tail(&self.config.path).await?;
+        if tail.last.is_some() || self.config.max_files == 0 {
+            return Ok(tail);
+        }
+        let rotated = read_tail(&self.rotated_path(1)).await?;
+        Ok(AuditTail {
+            last: rotated.last,
+            unreadable: tail.unreadable + rotated.unreadable,
+        })
+    }
+}
+
+/// Reads the records in one file; a missing file holds none.
+async fn read_file(path: &Path) -> ServerResult<Vec<AuditRecord>> {
+    read_lines(path)
+        .await?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| serde_json::from_str(line).map_err(Into::into))
+        .collect()
+}
+
+/// Finds the last record in one file, skipping unreadable lines after it.
+async fn read_tail(path: &Path) -> ServerResult<AuditTail> {
+    let mut tail = AuditTail::default();
+    for line in read_lines(path).await?.lines().rev() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(line) {
+            Ok(record) => {
+                tail.last = Some(record);
+                break;
+            }
+            Err(_) => tail.unreadable += 1,
+        }
+    }
+    Ok(tail)
+}
+
+/// Reads a file as text, replacing invalid UTF-8; a missing file is empty.
+async fn read_lines(path: &Path) -> ServerResult<String> {
+    match fs::read(path).await {
+        Ok(bytes) => Ok(String::from_utf8_lossy(&bytes).into_owned()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn ignore_missing(result: io::Result<()>) -> io::Result<()> {
+    match result {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::{verify_chain, AuditAction, AuditEvent, AuditLog};
+
+    fn temp_path() -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("cauce-audit-{}", uuid::Uuid::new_v4()))
+            .join("audit.jsonl")
+    }
+
+    #[tokio::test]
+    async fn test_rotation_keeps_chain() {
+        let config = AuditFileConfig::new(temp_path())
+            .with_max_bytes(600)
+            .with_max_files(2);
+        let log = AuditLog::new().with_sink(JsonLinesSink::new(config.clone()));
+        for i in 0..12 {
+            log.record(
+                AuditEvent::success(AuditAction::Subscribe)
+                    .with_identity(format!("client-{}", i))
+                    .with_target("signal.email.*"),
+            )
+            .await;
+        }
+        log.flush().await;
+
+        let sink = JsonLinesSink::new(config.clone());
+        assert!(sink.rotated_path(2).exists());
+        assert!(!sink.rotated_path(3).exists());
+
+        // Older records were rotated away; the rest still chain together
+        let records = sink.read_records().await.unwrap();
+        assert!(records.len() < 12);
+        assert_eq!(records.last().unwrap().sequence, 12);
+        assert!(verify_chain(&records).is_ok());
+
+        let _ = std::fs::remove_dir_all(config.path.parent().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_chain_continues_after_restart() {
+        let config = AuditFileConfig::new(temp_path());
+        let first = AuditLog::new().with_sink(JsonLinesSink::new(config.clone()));
+        first.record(AuditEvent::success(AuditAction::Hello)).await;
+        let last = first.record(AuditEvent::admin("restart")).await.unwrap();
+        first.flush().await;
+
+        let second = AuditLog::new().with_sink(JsonLinesSink::new(config.clone()));
+        let next = second
+            .record(AuditEvent::success(AuditAction::Hello))
+            .await
+            .unwrap();
+        assert_eq!(next.sequence, 3);
+        assert_eq!(next.prev_hash, last.hash);
+        second.flush().await;
+
+        let records = JsonLinesSink::new(config.clone())
+            .read_records()
+            .await
+            .unwrap();
+        assert_eq!(records.len(), 3);
+        assert!(verify_chain(&records).is_ok());
+
+        let _ = std::fs::remove_dir_all(config.path.parent().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_corrupt_tail_is_marked_as_gap() {
+        let config = AuditFileConfig::new(temp_path());
+        let first = AuditLog::new().with_sink(JsonLinesSink::new(config.clone()));
+        let last = first
+            .record(AuditEvent::success(AuditAction::Hello))
+            .await
+            .unwrap();
+        first.flush().await;
+
+        // A crash leaves half a record at the end of the file
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&config.path)
+            .unwrap();
+        std::io::Write::write_all(&mut file, br#"{"sequence":2,"timest"#).unwrap();
+        drop(file);
+
+        let second = AuditLog::new().with_sink(JsonLinesSink::new(config.clone()));
+        let next = second.record(AuditEvent::admin("restart")).await.unwrap();
+        second.flush().await;
+
+        // The chain continues from the last readable record, after a marker
+        assert_eq!(next.sequence, 3);
+        let contents = std::fs::read_to_string(&config.path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 4);
+        let records: Vec<AuditRecord> = [lines[0], lines[2], lines[3]]
+            .iter()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records[0], last);
+        assert_eq!(records[1].event.action, AuditAction::Gap);
+        assert_eq!(
+            records[1].event.details,
+            Some(serde_json::json!({"unreadable": 1}))
+        );
+        assert!(verify_chain(&records).is_ok());
+
+        let _ = std::fs::remove_dir_all(config.path.parent().unwrap());
+    }
+}
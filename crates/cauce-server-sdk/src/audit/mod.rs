@@ -0,0 +1,859 @@
+//! Audit log of security-relevant hub events.
+//!
+//! [`AuditLog`] records authentication attempts, new sessions, subscriptions
+//! and their approval, denial and revocation, publishes to action topics,
+//! and administrative operations. Each [`AuditRecord`] names the identity
+//! and source address involved and the outcome. Records are written to
+//! pluggable [`AuditSink`]s:
+//!
+//! - [`JsonLinesSink`] - a JSON-lines file, rotated by size
+//! - `SqliteSink` - a SQLite table (requires the `sqlite` feature)
+//!
+//! and can be published to the [`AUDIT_TOPIC`] system topic from
+//! [`AuditLog::subscribe`].
+//!
+//! Sinks are written by a background task, so recording an event never
+//! waits on disk I/O. Use [`AuditLog::flush`] to wait until every record so
+//! far has been written. If the task falls too far behind, new events are
+//! dropped rather than queued without bound.
+//!
+//! ## Hash chain
+//!
+//! Each record carries the hash of the record before it and its own hash:
+//! SHA-256 over its canonical JSON encoding without the `hash` field, in
+//! lowercase hex. Editing, removing or reordering records breaks the chain,
+//! which [`verify_chain`] detects. The first record follows
+//! [`GENESIS_HASH`]; after a restart the chain continues from the last
+//! record held by a sink.
+//!
+//! Records that never made it into the chain - dropped because the writer
+//! fell behind, or unreadable at the end of a sink after a crash - are
+//! counted in an [`AuditAction::Gap`] record, so losses are visible in the
+//! chain itself.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let audit = AuditLog::new()
+//!     .with_sink(JsonLinesSink::new(AuditFileConfig::new("audit.jsonl")));
+//!
+//! audit
+//!     .record(
+//!         AuditEvent::admin("api_key.revoke")
+//!             .with_identity("operator@example.com")
+//!             .with_details(json!({"key_id": "key_1"})),
+//!     )
+//!     .await;
+//! audit.flush().await;
+//! ```
+
+mod file;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+
+pub use file::JsonLinesSink;
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteSink;
+
+use std::fmt;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use async_trait::async_trait;
+use cauce_core::signing::canonical_json;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use tokio::sync::{broadcast, mpsc, oneshot, OnceCell};
+use tracing::warn;
+
+use crate::config::AuditConfig;
+use crate::error::ServerResult;
+
+/// Topic audit records are published to.
+pub const AUDIT_TOPIC: &str = "system.audit";
+
+/// The `prev_hash` of the first record in a chain.
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Kinds of audited events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    /// A request authenticated with an API key or bearer token
+    AuthSuccess,
+    /// A request presented credentials that were rejected
+    AuthFailure,
+    /// A client opened a session with `cauce.hello`
+    Hello,
+    /// A client subscribed to topics
+    Subscribe,
+    /// A pending subscription was approved
+    Approve,
+    /// A pending subscription was denied
+    Deny,
+    /// An active subscription was revoked
+    Revoke,
+    /// A client published to an action topic
+    Publish,
+    /// An administrative operation, such as rotating keys
+    Admin,
+    /// Records were lost before this point in the chain
+    Gap,
+}
+
+impl AuditAction {
+    /// Returns the action as it appears in records.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::AuthSuccess => "auth_success",
+            Self::AuthFailure => "auth_failure",
+            Self::Hello => "hello",
+            Self::Subscribe => "subscribe",
+            Self::Approve => "approve",
+            Self::Deny => "deny",
+            Self::Revoke => "revoke",
+            Self::Publish => "publish",
+            Self::Admin => "admin",
+            Self::Gap => "gap",
+        }
+    }
+}
+
+impl fmt::Display for AuditAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Whether an audited operation went through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditOutcome {
+    /// The operation succeeded
+    Success,
+    /// The operation was rejected or failed
+    Failure,
+}
+
+impl AuditOutcome {
+    /// Returns the outcome as it appears in records.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::Failure => "failure",
+        }
+    }
+}
+
+/// An event to be audited.
+///
+/// # Fields
+///
+/// - `action` - What happened
+/// - `identity` - The client ID or operator responsible, if known
+/// - `source_address` - The address the request came from, if known
+/// - `session_id` - The session the request was made in, if any
+/// - `target` - The topics, subscription ID or operation acted on
+/// - `outcome` - Whether it went through
+/// - `reason` - Why it failed
+/// - `details` - Event-specific data
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEvent {
+    /// What happened
+    pub action: AuditAction,
+
+    /// Client ID or operator responsible
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub identity: Option<String>,
+
+    /// Address the request came from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_address: Option<String>,
+
+    /// Session the request was made in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+
+    /// Topics, subscription ID or operation acted on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+
+    /// Whether the operation went through
+    pub outcome: AuditOutcome,
+
+    /// Why the operation failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+
+    /// Event-specific data
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<Value>,
+}
+
+impl AuditEvent {
+    /// Creates an event with the given outcome.
+    pub fn new(action: AuditAction, outcome: AuditOutcome) -> Self {
+        Self {
+            action,
+            identity: None,
+            source_address: None,
+            session_id: None,
+            target: None,
+            outcome,
+            reason: None,
+            details: None,
+        }
+    }
+
+    /// Creates an event for an operation that succeeded.
+    pub fn success(action: AuditAction) -> Self {
+        Self::new(action, AuditOutcome::Success)
+    }
+
+    /// Creates an event for an operation that was rejected or failed.
+    pub fn failure(action: AuditAction, reason: impl Into<String>) -> Self {
+        Self {
+            reason: Some(reason.into()),
+            ..Self::new(action, AuditOutcome::Failure)
+        }
+    }
+
+    /// Creates an event for a successful administrative operation.
+    ///
+    /// `operation` names what was done, such as `"api_key.revoke"`.
+    pub fn admin(operation: impl Into<String>) -> Self {
+        Self::success(AuditAction::Admin).with_target(operation)
+    }
+
+    /// Creates an event from the result of an operation.
+    pub fn from_result<T, E: fmt::Display>(action: AuditAction, result: &Result<T, E>) -> Self {
+        match result {
+            Ok(_) => Self::success(action),
+            Err(e) => Self::failure(action, e.to_string()),
+        }
+    }
+
+    /// Sets the client ID or operator responsible.
+    pub fn with_identity(mut self, identity: impl Into<String>) -> Self {
+        self.identity = Some(identity.into());
+        self
+    }
+
+    /// Sets the address the request came from.
+    pub fn with_source_address(mut self, address: Option<String>) -> Self {
+        self.source_address = address;
+        self
+    }
+
+    /// Sets the session the request was made in.
+    pub fn with_session_id(mut self, session_id: impl Into<String>) -> Self {
+        self.session_id = Some(session_id.into());
+        self
+    }
+
+    /// Sets the topics, subscription ID or operation acted on.
+    pub fn with_target(mut self, target: impl Into<String>) -> Self {
+        self.target = Some(target.into());
+        self
+    }
+
+    /// Sets event-specific data.
+    pub fn with_details(mut self, details: Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+}
+
+/// An audited event, linked into the hash chain.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    /// Position in the chain, starting at 1
+    pub sequence: u64,
+
+    /// When the event was recorded
+    pub timestamp: DateTime<Utc>,
+
+    /// The audited event
+    #[serde(flatten)]
+    pub event: AuditEvent,
+
+    /// Hash of the previous record
+    pub prev_hash: String,
+
+    /// Hash of this record
+    pub hash: String,
+}
+
+impl AuditRecord {
+    /// Computes the hash this record should carry.
+    pub fn compute_hash(&self) -> String {
+        let mut value = serde_json::to_value(self).unwrap_or_default();
+        if let Value::Object(map) = &mut value {
+            map.remove("hash");
+        }
+        hex::encode(Sha256::digest(canonical_json(&value).as_bytes()))
+    }
+
+    /// Returns the copy of this record published to [`AUDIT_TOPIC`].
+    ///
+    /// The session ID and source address are removed: a subscriber holding
+    /// a session ID could act as that session's client. The hashes are kept
+    /// so the copy can be matched to the stored record, but it no longer
+    /// verifies on its own.
+    pub fn redacted(&self) -> AuditRecord {
+        let mut record = self.clone();
+        record.event.session_id = None;
+        record.event.source_address = None;
+        record
+    }
+}
+
+/// A break in an audit hash chain.
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+#[error("audit chain broken at record {sequence}: {reason}")]
+pub struct ChainError {
+    /// Sequence number of the first record that does not verify
+    pub sequence: u64,
+    /// What is wrong with it
+    pub reason: String,
+}
+
+/// Verifies that `records`, oldest first, form an unbroken hash chain.
+///
+/// The first record's `prev_hash` is not checked, so a chain whose oldest
+/// records were rotated away still verifies. Compare it against a hash
+/// kept elsewhere to anchor the chain.
+///
+/// # Errors
+///
+/// Returns a [`ChainError`] for the first record whose hash is wrong or
+/// that does not follow the record before it.
+pub fn verify_chain(records: &[AuditRecord]) -> Result<(), ChainError> {
+    let mut previous: Option<&AuditRecord> = None;
+    for record in records {
+        let fail = |reason: &str| ChainError {
+            sequence: record.sequence,
+            reason: reason.to_string(),
+        };
+        if record.hash != record.compute_hash() {
+            return Err(fail("hash does not match contents"));
+        }
+        if let Some(previous) = previous {
+            if record.sequence != previous.sequence + 1 {
+                return Err(fail("sequence does not follow the previous record"));
+            }
+            if record.prev_hash != previous.hash {
+                return Err(fail("prev_hash does not match the previous record"));
+            }
+        }
+        previous = Some(record);
+    }
+    Ok(())
+}
+
+/// Destination for audit records.
+#[async_trait]
+pub trait AuditSink: Send + Sync {
+    /// Writes a record.
+    async fn write(&self, record: &AuditRecord) -> ServerResult<()>;
+
+    /// Returns where the sink's copy of the chain ends, so the chain can
+    /// continue from it after a restart.
+    async fn tail(&self) -> ServerResult<AuditTail> {
+        Ok(AuditTail::default())
+    }
+}
+
+/// The end of a sink's copy of the chain.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AuditTail {
+    /// The most recent record that could be read
+    pub last: Option<AuditRecord>,
+    /// Records after it that could not be read, such as a line cut short
+    /// by a crash
+    pub unreadable: u64,
+}
+
+/// Default number of records waiting for the sinks before new events are
+/// dropped.
+pub const DEFAULT_QUEUE_CAPACITY: usize = 1024;
+
+/// Records lost since the last record in the chain.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+struct Gap {
+    /// Events dropped because the writer queue was full
+    #[serde(skip_serializing_if = "is_zero")]
+    dropped: u64,
+    /// Records at the end of a sink that could not be read
+    #[serde(skip_serializing_if = "is_zero")]
+    unreadable: u64,
+    /// Sinks whose end could not be read at all
+    #[serde(skip_serializing_if = "is_zero")]
+    unreadable_sinks: u64,
+}
+
+impl Gap {
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+fn is_zero(n: &u64) -> bool {
+    *n == 0
+}
+
+/// End of the chain records are appended to.
+#[derive(Clone)]
+struct Chain {
+    sequence: u64,
+    hash: String,
+    /// Lost records not yet marked in the chain
+    gap: Gap,
+}
+
+impl Chain {
+    /// Links `event` onto the chain.
+    fn append(&mut self, event: AuditEvent) -> AuditRecord {
+        let mut record = AuditRecord {
+            sequence: self.sequence + 1,
+            timestamp: Utc::now(),
+            event,
+            prev_hash: self.hash.clone(),
+            hash: String::new(),
+        };
+        record.hash = record.compute_hash();
+        self.sequence = record.sequence;
+        self.hash = record.hash.clone();
+        record
+    }
+}
+
+/// Work for the sink writer task.
+enum WriterMessage {
+    /// Write a record to every sink.
+    Record(Box<AuditRecord>),
+    /// Signal once every record sent before this has been written.
+    Flush(oneshot::Sender<()>),
+}
+
+/// A place for a record in the writer queue.
+enum Slot<'a> {
+    /// There are no sinks, so the record only needs publishing.
+    Unneeded,
+    Reserved(mpsc::Permit<'a, WriterMessage>),
+}
+
+/// Records audit events to a set of sinks.
+///
+/// Records are chained in the caller and handed to a writer task, which
+/// writes them one at a time, so sinks see them in sequence order. A sink
+/// that fails to write is logged and skipped; the operation being audited
+/// is never failed because of it.
+///
+/// At most [`with_queue_capacity`](Self::with_queue_capacity) records wait
+/// for the writer. Events recorded while the queue is full are dropped
+/// before they are chained, and the next record that fits is preceded by
+/// an [`AuditAction::Gap`] record counting them.
+pub struct AuditLog {
+    sinks: Vec<Arc<dyn AuditSink>>,
+    queue_capacity: usize,
+    /// Where the chain left off before this process, read from the sinks
+    /// on the first record.
+    start: OnceCell<Chain>,
+    chain: Mutex<Option<Chain>>,
+    writer: OnceLock<mpsc::Sender<WriterMessage>>,
+    records: broadcast::Sender<AuditRecord>,
+}
+
+impl AuditLog {
+    /// Creates an audit log with no sinks.
+    pub fn new() -> Self {
+        let (records, _) = broadcast::channel(256);
+        Self {
+            sinks: Vec::new(),
+            queue_capacity: DEFAULT_QUEUE_CAPACITY,
+            start: OnceCell::new(),
+            chain: Mutex::new(None),
+            writer: OnceLock::new(),
+            records,
+        }
+    }
+
+    /// Creates an audit log writing to the sinks in `config`.
+    ///
+    /// A SQLite database that cannot be opened is logged and skipped.
+    /// Publishing to [`AUDIT_TOPIC`] is set up by the server.
+    pub fn from_config(config: &AuditConfig) -> Self {
+        #[allow(unused_mut)]
+        let mut log = Self::new();
+        if let Some(file) = &config.file {
+            log = log.with_sink(JsonLinesSink::new(file.clone()));
+        }
+        #[cfg(feature = "sqlite")]
+        if let Some(path) = &config.sqlite_path {
+            match SqliteSink::open(path) {
+                Ok(sink) => log = log.with_sink(sink),
+                Err(e) => warn!("Failed to open audit database {}: {}", path.display(), e),
+            }
+        }
+        log
+    }
+
+    /// Adds a sink.
+    pub fn with_sink(mut self, sink: impl AuditSink + 'static) -> Self {
+        self.sinks.push(Arc::new(sink));
+        self
+    }
+
+    /// Sets how many records may wait for the sinks before new events are
+    /// dropped. Defaults to [`DEFAULT_QUEUE_CAPACITY`].
+    pub fn with_queue_capacity(mut self, capacity: usize) -> Self {
+        self.queue_capacity = capacity.max(1);
+        self
+    }
+
+    /// Returns true if records go anywhere: to a sink or a subscriber.
+    pub fn is_enabled(&self) -> bool {
+        !self.sinks.is_empty() || self.records.receiver_count() > 0
+    }
+
+    /// Subscribes to records as they are written.
+    pub fn subscribe(&self) -> broadcast::Receiver<AuditRecord> {
+        self.records.subscribe()
+    }
+
+    /// Records `event`, returning the chained record.
+    ///
+    /// The record is queued for the sinks; it has not necessarily been
+    /// written when this returns. Returns `None` if the queue is full and
+    /// the event was dropped.
+    pub async fn record(&self, event: AuditEvent) -> Option<AuditRecord> {
+        let start = self.start.get_or_init(|| self.load_chain()).await;
+
+        // Chained and queued under the lock so sinks receive records in
+        // sequence order
+        let mut chain = self.chain.lock().unwrap();
+        let chain = chain.get_or_insert_with(|| start.clone());
+        if !chain.gap.is_empty() {
+            let Some(slot) = self.reserve() else {
+                chain.gap.dropped += 1;
+                return None;
+            };
+            let gap = std::mem::take(&mut chain.gap);
+            let marker = AuditEvent::failure(AuditAction::Gap, "audit records were lost")
+                .with_details(serde_json::to_value(gap).unwrap_or_default());
+            self.dispatch(slot, chain.append(marker));
+        }
+
+        let Some(slot) = self.reserve() else {
+            chain.gap.dropped += 1;
+            return None;
+        };
+        let record = chain.append(event);
+        self.dispatch(slot, record.clone());
+        Some(record)
+    }
+
+    /// Waits until every record made so far has been written to the sinks.
+    pub async fn flush(&self) {
+        if self.sinks.is_empty() {
+            return;
+        }
+        let (done, written) = oneshot::channel();
+        let writer = self.writer();
+        if writer.send(WriterMessage::Flush(done)).await.is_err() {
+            warn!("Audit writer has stopped; nothing to flush");
+            return;
+        }
+        let _ = written.await;
+    }
+
+    /// Returns the writer task's queue, starting the task if needed.
+    fn writer(&self) -> &mpsc::Sender<WriterMessage> {
+        self.writer
+            .get_or_init(|| spawn_writer(self.sinks.clone(), self.queue_capacity))
+    }
+
+    /// Reserves a place in the writer queue, or returns `None` if it is
+    /// full.
+    fn reserve(&self) -> Option<Slot<'_>> {
+        if self.sinks.is_empty() {
+            return Some(Slot::Unneeded);
+        }
+        match self.writer().try_reserve() {
+            Ok(permit) => Some(Slot::Reserved(permit)),
+            Err(mpsc::error::TrySendError::Full(())) => None,
+            Err(mpsc::error::TrySendError::Closed(())) => {
+                warn!("Audit writer has stopped; record not written");
+                Some(Slot::Unneeded)
+            }
+        }
+    }
+
+    /// Queues `record` for the sinks and publishes it to subscribers.
+    fn dispatch(&self, slot: Slot<'_>, record: AuditRecord) {
+        if let Slot::Reserved(permit) = slot {
+            permit.send(WriterMessage::Record(Box::new(record.clone())));
+        }
+        let _ = self.records.send(record);
+    }
+
+    /// Finds where the chain left off: the latest record any sink holds,
+    /// and anything lost after it.
+    async fn load_chain(&self) -> Chain {
+        let mut chain = Chain {
+            sequence: 0,
+            hash: GENESIS_HASH.to_string(),
+            gap: Gap::default(),
+        };
+        for sink in &self.sinks {
+            match sink.tail().await {
+                Ok(tail) => {
+                    chain.gap.unreadable += tail.unreadable;
+                    match tail.last {
+                        Some(record) if record.sequence > chain.sequence => {
+                            chain.sequence = record.sequence;
+                            chain.hash = record.hash;
+                        }
+                        _ => {}
+                    }
+                }
+                Err(e) => {
+                    warn!("Failed to read last audit record: {}", e);
+                    chain.gap.unreadable_sinks += 1;
+                }
+            }
+        }
+        if !chain.gap.is_empty() {
+            warn!(
+                "Audit chain continues from record {} past lost records",
+                chain.sequence
+            );
+        }
+        chain
+    }
+}
+
+/// Starts the task that writes records to `sinks` in the order received,
+/// holding at most `capacity` waiting records.
+///
+/// The task ends when the [`AuditLog`] is dropped.
+fn spawn_writer(sinks: Vec<Arc<dyn AuditSink>>, capacity: usize) -> mpsc::Sender<WriterMessage> {
+    let (tx, mut rx) = mpsc::channel(capacity);
+    tokio::spawn(async move {
+        while let Some(message) = rx.recv().await {
+            match message {
+                WriterMessage::Record(record) => {
+                    for sink in &sinks {
+                        if let Err(e) = sink.write(&record).await {
+                            warn!("Failed to write audit record {}: {}", record.sequence, e);
+                        }
+                    }
+                }
+                WriterMessage::Flush(done) => {
+                    let _ = done.send(());
+                }
+            }
+        }
+    });
+    tx
+}
+
+impl Default for AuditLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for AuditLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuditLog")
+            .field("sinks", &self.sinks.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    async fn chain(log: &AuditLog) -> Vec<AuditRecord> {
+        vec![
+            log.record(
+                AuditEvent::success(AuditAction::Hello)
+                    .with_identity("client-1")
+                    .with_source_address(Some("10.0.0.7".to_string())),
+            )
+            .await
+            .unwrap(),
+            log.record(
+                AuditEvent::failure(AuditAction::Subscribe, "not authorized")
+                    .with_identity("client-1")
+                    .with_target("signal.email.*"),
+            )
+            .await
+            .unwrap(),
+            log.record(AuditEvent::admin("api_key.revoke").with_details(json!({"id": "key_1"})))
+                .await
+                .unwrap(),
+        ]
+    }
+
+    #[test]
+    fn test_action_names_match_serde() {
+        for action in [
+            AuditAction::AuthSuccess,
+            AuditAction::AuthFailure,
+            AuditAction::Hello,
+            AuditAction::Subscribe,
+            AuditAction::Approve,
+            AuditAction::Deny,
+            AuditAction::Revoke,
+            AuditAction::Publish,
+            AuditAction::Admin,
+            AuditAction::Gap,
+        ] {
+            assert_eq!(json!(action), json!(action.as_str()));
+        }
+        assert_eq!(json!(AuditOutcome::Failure), json!("failure"));
+    }
+
+    #[tokio::test]
+    async fn test_records_are_chained() {
+        let log = AuditLog::new();
+        assert!(!log.is_enabled());
+        let mut receiver = log.subscribe();
+        assert!(log.is_enabled());
+
+        let records = chain(&log).await;
+        assert_eq!(records[0].sequence, 1);
+        assert_eq!(records[0].prev_hash, GENESIS_HASH);
+        assert_eq!(records[1].prev_hash, records[0].hash);
+        assert_eq!(records[2].event.target.as_deref(), Some("api_key.revoke"));
+        assert!(verify_chain(&records).is_ok());
+
+        assert_eq!(receiver.recv().await.unwrap(), records[0]);
+
+        // Verification survives a trip through JSON
+        let json = serde_json::to_string(&records[1]).unwrap();
+        assert!(json.contains(r#""action":"subscribe""#));
+        assert!(!json.contains("session_id"));
+        let parsed: AuditRecord = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.compute_hash(), records[1].hash);
+    }
+
+    #[tokio::test]
+    async fn test_verify_chain_detects_tampering() {
+        let records = chain(&AuditLog::new()).await;
+
+        let mut edited = records.clone();
+        edited[1].event.outcome = AuditOutcome::Success;
+        let err = verify_chain(&edited).unwrap_err();
+        assert_eq!(err.sequence, 2);
+
+        let removed = vec![records[0].clone(), records[2].clone()];
+        assert_eq!(verify_chain(&removed).unwrap_err().sequence, 3);
+
+        // Recomputing the hash of an edited record breaks the link after it
+        let mut rehashed = records.clone();
+        rehashed[1].event.identity = Some("someone-else".to_string());
+        rehashed[1].hash = rehashed[1].compute_hash();
+        assert_eq!(verify_chain(&rehashed).unwrap_err().sequence, 3);
+
+        // A chain whose oldest records were rotated away still verifies
+        assert!(verify_chain(&records[1..]).is_ok());
+    }
+
+    /// A sink that holds every write until the test releases it.
+    struct GatedSink {
+        gate: Arc<tokio::sync::Semaphore>,
+        written: Arc<Mutex<Vec<u64>>>,
+    }
+
+    #[async_trait]
+    impl AuditSink for GatedSink {
+        async fn write(&self, record: &AuditRecord) -> ServerResult<()> {
+            self.gate.acquire().await.unwrap().forget();
+            self.written.lock().unwrap().push(record.sequence);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_record_does_not_wait_for_sinks() {
+        let gate = Arc::new(tokio::sync::Semaphore::new(0));
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let log = AuditLog::new().with_sink(GatedSink {
+            gate: Arc::clone(&gate),
+            written: Arc::clone(&written),
+        });
+
+        // Records are chained while the sink is still blocked on the first
+        let records = tokio::time::timeout(std::time::Duration::from_secs(1), chain(&log))
+            .await
+            .expect("record waited on a sink write");
+        assert!(verify_chain(&records).is_ok());
+        assert!(written.lock().unwrap().is_empty());
+
+        gate.add_permits(records.len());
+        log.flush().await;
+        assert_eq!(*written.lock().unwrap(), [1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_full_queue_drops_and_marks_gap() {
+        let gate = Arc::new(tokio::sync::Semaphore::new(0));
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let log = AuditLog::new()
+            .with_sink(GatedSink {
+                gate: Arc::clone(&gate),
+                written: Arc::clone(&written),
+            })
+            .with_queue_capacity(2);
+        let mut receiver = log.subscribe();
+
+        // The writer task does not run until the test yields
+        for _ in 0..4 {
+            log.record(AuditEvent::success(AuditAction::Hello)).await;
+        }
+        gate.add_permits(16);
+        log.flush().await;
+        assert_eq!(*written.lock().unwrap(), [1, 2]);
+
+        let next = log.record(AuditEvent::admin("restart")).await.unwrap();
+        assert_eq!(next.sequence, 4);
+        log.flush().await;
+        assert_eq!(*written.lock().unwrap(), [1, 2, 3, 4]);
+
+        let mut records = Vec::new();
+        while let Ok(record) = receiver.try_recv() {
+            records.push(record);
+        }
+        assert!(verify_chain(&records).is_ok());
+        let marker = &records[2].event;
+        assert_eq!(marker.action, AuditAction::Gap);
+        assert_eq!(marker.details, Some(json!({"dropped": 2})));
+    }
+
+    #[tokio::test]
+    async fn test_redacted_drops_session_and_address() {
+        let record = AuditLog::new()
+            .record(
+                AuditEvent::success(AuditAction::Hello)
+                    .with_identity("client-1")
+                    .with_source_address(Some("10.0.0.7".to_string()))
+                    .with_session_id("sess_1"),
+            )
+            .await
+            .unwrap();
+
+        let redacted = record.redacted();
+        assert_eq!(redacted.event.identity.as_deref(), Some("client-1"));
+        assert!(redacted.event.source_address.is_none());
+        assert!(redacted.event.session_id.is_none());
+        assert_eq!(redacted.hash, record.hash);
+    }
+}
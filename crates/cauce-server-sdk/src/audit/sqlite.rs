@@ -0,0 +1,209 @@
+//! SQLite audit sink.
+
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use rusqlite::{params, Connection, OptionalExtension};
+
+use super::{AuditRecord, AuditSink, AuditTail};
+use crate::error::{ServerError, ServerResult};
+
+const CREATE_TABLE: &str = "
+    CREATE TABLE IF NOT EXISTS audit_log (
+        sequence INTEGER PRIMARY KEY,
+        timestamp TEXT NOT NULL,
+        action TEXT NOT NULL,
+        identity TEXT,
+        source_address TEXT,
+        session_id TEXT,
+        target TEXT,
+        outcome TEXT NOT NULL,
+        hash TEXT NOT NULL,
+        record TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS audit_log_action ON audit_log (action, target);
+";
+
+/// Inserts audit records into an `audit_log` table.
+///
+/// Identity, source address, target and outcome get their own columns for
+/// querying, e.g. who subscribed to email topics:
+///
+/// ```sql
+/// SELECT timestamp, identity, source_address FROM audit_log
+/// WHERE action = 'subscribe' AND target LIKE 'signal.email.%';
+/// ```
+///
+/// The `record` column holds the full record as JSON, which is what the
+/// hash chain covers.
+///
+/// Queries run on Tokio's blocking thread pool.
+pub struct SqliteSink {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl SqliteSink {
+    /// Opens or creates the database at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be opened or the table
+    /// cannot be created.
+    pub fn open(path: impl AsRef<Path>) -> ServerResult<Self> {
+        Self::from_connection(Connection::open(path).map_err(sqlite_error)?)
+    }
+
+    /// Opens a database that lives only in memory.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`open`](Self::open).
+    pub fn in_memory() -> ServerResult<Self> {
+        Self::from_connection(Connection::open_in_memory().map_err(sqlite_error)?)
+    }
+
+    fn from_connection(conn: Connection) -> ServerResult<Self> {
+        conn.execute_batch(CREATE_TABLE).map_err(sqlite_error)?;
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    /// Runs `query` against the connection on the blocking thread pool.
+    async fn blocking<T, F>(&self, query: F) -> ServerResult<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Connection) -> ServerResult<T> + Send + 'static,
+    {
+        let conn = Arc::clone(&self.conn);
+        tokio::task::spawn_blocking(move || query(&conn.lock().unwrap()))
+            .await
+            .map_err(|e| ServerError::internal(format!("audit database task failed: {}", e)))?
+    }
+
+    /// Reads every record, oldest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the table cannot be read or holds an invalid
+    /// record.
+    pub fn read_records(&self) -> ServerResult<Vec<AuditRecord>> {
+        let conn = self.conn.lock().unwrap();
+        let mut statement = conn
+            .prepare("SELECT record FROM audit_log ORDER BY sequence")
+            .map_err(sqlite_error)?;
+        let rows = statement
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(sqlite_error)?;
+        rows.map(|row| Ok(serde_json::from_str(&row.map_err(sqlite_error)?)?))
+            .collect()
+    }
+}
+
+#[async_trait]
+impl AuditSink for SqliteSink {
+    async fn write(&self, record: &AuditRecord) -> ServerResult<()> {
+        let record = record.clone();
+        let json = serde_json::to_string(&record)?;
+        let timestamp = serde_json::to_value(record.timestamp)?;
+        self.blocking(move |conn| {
+            let event = &record.event;
+            conn.execute(
+                "INSERT INTO audit_log (sequence, timestamp, action, identity, source_address,
+                     session_id, target, outcome, hash, record)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                params![
+                    record.sequence as i64,
+                    timestamp.as_str(),
+                    event.action.as_str(),
+                    event.identity,
+                    event.source_address,
+                    event.session_id,
+                    event.target,
+                    event.outcome.as_str(),
+                    record.hash,
+                    json,
+                ],
+            )
+            .map_err(sqlite_error)?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn tail(&self) -> ServerResult<AuditTail> {
+        let json: Option<String> = self
+            .blocking(|conn| {
+                conn.query_row(
+                    "SELECT record FROM audit_log ORDER BY sequence DESC LIMIT 1",
+                    [],
+                    |row| row.get(0),
+                )
+                .optional()
+                .map_err(sqlite_error)
+            })
+            .await?;
+        Ok(AuditTail {
+            last: json.map(|json| serde_json::from_str(&json)).transpose()?,
+            unreadable: 0,
+        })
+    }
+}
+
+fn sqlite_error(e: rusqlite::Error) -> ServerError {
+    ServerError::internal(format!("audit database error: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::{verify_chain, AuditAction, AuditEvent, AuditLog};
+
+    #[tokio::test]
+    async fn test_records_are_queryable() {
+        let path = std::env::temp_dir().join(format!("cauce-audit-{}.db", uuid::Uuid::new_v4()));
+        let log = AuditLog::new().with_sink(SqliteSink::open(&path).unwrap());
+        log.record(
+            AuditEvent::success(AuditAction::Subscribe)
+                .with_identity("agent-1")
+                .with_source_address(Some("10.0.0.7".to_string()))
+                .with_target("signal.email.*"),
+        )
+        .await;
+        log.record(AuditEvent::success(AuditAction::Hello).with_identity("agent-2"))
+            .await;
+        log.flush().await;
+
+        let sink = SqliteSink::open(&path).unwrap();
+        let subscribers: Vec<(String, String)> = {
+            let conn = sink.conn.lock().unwrap();
+            let mut statement = conn
+                .prepare(
+                    "SELECT identity, source_address FROM audit_log
+                     WHERE action = 'subscribe' AND target LIKE 'signal.email.%'",
+                )
+                .unwrap();
+            let rows = statement
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+                .unwrap();
+            rows.map(Result::unwrap).collect()
+        };
+        assert_eq!(
+            subscribers,
+            [("agent-1".to_string(), "10.0.0.7".to_string())]
+        );
+
+        // The chain continues from the database
+        let log = AuditLog::new().with_sink(sink);
+        let restart = log.record(AuditEvent::admin("restart")).await.unwrap();
+        assert_eq!(restart.sequence, 3);
+        log.flush().await;
+
+        let records = SqliteSink::open(&path).unwrap().read_records().unwrap();
+        assert_eq!(records.len(), 3);
+        assert!(verify_chain(&records).is_ok());
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,303 @@
+//! Topic-level access control.
+//!
+//! [`TopicAcl`] decides which topics a [`Principal`] may publish and
+//! subscribe to, based on the rules in an [`AclConfig`].
+
+use cauce_core::TopicMatcher;
+use dashmap::DashMap;
+
+use super::{AuthInfo, AuthMethod, TokenClaims};
+use crate::config::{AclConfig, AclSubject};
+use crate::error::{ServerError, ServerResult};
+
+/// The identity access control decisions are made for.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Principal {
+    /// The client ID.
+    pub client_id: String,
+    /// Name of the stored API key the client authenticated with, if any.
+    pub api_key_name: Option<String>,
+    /// Scopes granted by the client's bearer token or API key.
+    pub scopes: Vec<String>,
+}
+
+impl Principal {
+    /// Creates a principal identified only by client ID.
+    pub fn new(client_id: impl Into<String>) -> Self {
+        Self {
+            client_id: client_id.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Creates a principal from the result of authentication.
+    ///
+    /// The key name is only kept if the client authenticated with an API key.
+    pub fn from_auth(info: &AuthInfo, claims: Option<&TokenClaims>) -> Self {
+        Self {
+            client_id: info.client_id.clone(),
+            api_key_name: claims
+                .and_then(|c| c.key_name.clone())
+                .filter(|_| info.method == AuthMethod::ApiKey),
+            scopes: claims.map(|c| c.scopes.clone()).unwrap_or_default(),
+        }
+    }
+
+    /// Returns true if a rule for `subject` applies to this principal.
+    fn is(&self, subject: &AclSubject) -> bool {
+        match subject {
+            AclSubject::ClientId(id) => *id == self.client_id,
+            AclSubject::ApiKeyName(name) => self.api_key_name.as_ref() == Some(name),
+            AclSubject::Scope(scope) => self.scopes.contains(scope),
+            AclSubject::Any => true,
+        }
+    }
+}
+
+/// Enforces topic access control rules.
+///
+/// With no rules configured every check passes.
+///
+/// # Example
+///
+/// ```ignore
+/// let acl = TopicAcl::new(config.acl.clone());
+///
+/// let principal = Principal::new("email-adapter");
+/// acl.check_publish(&principal, "signal.email.received")?;
+/// acl.check_subscribe(&principal, &["action.email.*".to_string()])?;
+/// ```
+#[derive(Debug, Default)]
+pub struct TopicAcl {
+    config: AclConfig,
+    /// The principal each client last connected as, used for approvals.
+    principals: DashMap<String, Principal>,
+}
+
+impl TopicAcl {
+    /// Creates an ACL enforcing `config`.
+    pub fn new(config: AclConfig) -> Self {
+        Self {
+            config,
+            principals: DashMap::new(),
+        }
+    }
+
+    /// Returns true if any rules are configured.
+    pub fn is_enabled(&self) -> bool {
+        self.config.is_enabled()
+    }
+
+    /// Checks that `principal` may publish to `topic`.
+    pub fn check_publish(&self, principal: &Principal, topic: &str) -> ServerResult<()> {
+        if !self.is_enabled() || self.allowed_publish(principal, topic) {
+            return Ok(());
+        }
+        Err(ServerError::not_authorized(format!(
+            "{} may not publish to {}",
+            principal.client_id, topic
+        )))
+    }
+
+    /// Checks that `principal` may subscribe to every pattern in `patterns`.
+    ///
+    /// A pattern is allowed only if every topic it can match is allowed, so
+    /// `signal.**` is denied to a client granted `signal.email.*`.
+    pub fn check_subscribe(&self, principal: &Principal, patterns: &[String]) -> ServerResult<()> {
+        if !self.is_enabled() {
+            return Ok(());
+        }
+        match patterns
+            .iter()
+            .find(|pattern| !self.allowed_subscribe(principal, pattern))
+        {
+            Some(denied) => Err(ServerError::not_authorized(format!(
+                "{} may not subscribe to {}",
+                principal.client_id, denied
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Records the principal a client authenticated as.
+    ///
+    /// Approvals happen outside the client's connection, so they are checked
+    /// against the principal most recently recorded for the subscription's
+    /// client.
+    pub fn record_principal(&self, principal: &Principal) {
+        if self.is_enabled() {
+            self.principals
+                .insert(principal.client_id.clone(), principal.clone());
+        }
+    }
+
+    /// Returns the principal recorded for `client_id`, or one identified
+    /// only by the client ID if none was recorded.
+    pub fn principal(&self, client_id: &str) -> Principal {
+        self.principals
+            .get(client_id)
+            .map(|p| p.clone())
+            .unwrap_or_else(|| Principal::new(client_id))
+    }
+
+    fn allowed_publish(&self, principal: &Principal, topic: &str) -> bool {
+        self.config
+            .rules
+            .iter()
+            .filter(|rule| principal.is(&rule.subject))
+            .flat_map(|rule| &rule.publish)
+            .any(|allowed| TopicMatcher::matches(topic, allowed))
+    }
+
+    fn allowed_subscribe(&self, principal: &Principal, pattern: &str) -> bool {
+        self.config
+            .rules
+            .iter()
+            .filter(|rule| principal.is(&rule.subject))
+            .flat_map(|rule| &rule.subscribe)
+            .any(|allowed| TopicMatcher::pattern_covers(allowed, pattern))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{ApiKeyStore, AuthValidator, NewApiKey};
+    use crate::config::AclRule;
+
+    fn acl() -> TopicAcl {
+        TopicAcl::new(
+            AclConfig::default()
+                .with_rule(
+                    AclRule::new(AclSubject::ClientId("email-adapter".to_string()))
+                        .with_publish("signal.email.*")
+                        .with_subscribe("action.email.**"),
+                )
+                .with_rule(
+                    AclRule::new(AclSubject::ApiKeyName("agent-key".to_string()))
+                        .with_publish("action.email.send"),
+                )
+                .with_rule(
+                    AclRule::new(AclSubject::Scope("signals:read".to_string()))
+                        .with_subscribe("signal.**"),
+                )
+                .with_rule(AclRule::new(AclSubject::Any).with_subscribe("signal.public.*")),
+        )
+    }
+
+    fn topics(patterns: &[&str]) -> Vec<String> {
+        patterns.iter().map(|p| p.to_string()).collect()
+    }
+
+    #[test]
+    fn test_disabled_allows_everything() {
+        let acl = TopicAcl::default();
+        let principal = Principal::new("anyone");
+        assert!(acl.check_publish(&principal, "action.email.send").is_ok());
+        assert!(acl.check_subscribe(&principal, &topics(&["**"])).is_ok());
+    }
+
+    #[test]
+    fn test_publish_by_client_id_and_api_key_name() {
+        let acl = acl();
+
+        let adapter = Principal::new("email-adapter");
+        assert!(acl.check_publish(&adapter, "signal.email.received").is_ok());
+        assert!(acl.check_publish(&adapter, "action.email.send").is_err());
+
+        let mut agent = Principal::new("agent-1");
+        assert!(acl.check_publish(&agent, "action.email.send").is_err());
+        agent.api_key_name = Some("agent-key".to_string());
+        assert!(acl.check_publish(&agent, "action.email.send").is_ok());
+    }
+
+    #[test]
+    fn test_subscribe_by_scope_and_any() {
+        let acl = acl();
+
+        let stranger = Principal::new("stranger");
+        assert!(acl
+            .check_subscribe(&stranger, &topics(&["signal.public.news"]))
+            .is_ok());
+        assert!(acl
+            .check_subscribe(&stranger, &topics(&["signal.**"]))
+            .is_err());
+
+        let reader = Principal {
+            scopes: vec!["signals:read".to_string()],
+            ..Principal::new("reader")
+        };
+        assert!(acl
+            .check_subscribe(&reader, &topics(&["signal.**"]))
+            .is_ok());
+        assert!(acl
+            .check_subscribe(&reader, &topics(&["action.**"]))
+            .is_err());
+    }
+
+    #[test]
+    fn test_denial_names_topic() {
+        let acl = acl();
+        let principal = Principal::new("email-adapter");
+
+        let err = acl
+            .check_subscribe(
+                &principal,
+                &topics(&["action.email.send", "signal.email.*"]),
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            ServerError::NotAuthorized { ref reason } if reason.ends_with("signal.email.*")
+        ));
+
+        let err = acl
+            .check_publish(&principal, "action.email.send")
+            .unwrap_err();
+        assert!(err.to_string().contains("action.email.send"));
+    }
+
+    #[test]
+    fn test_recorded_principal() {
+        let acl = acl();
+        assert_eq!(acl.principal("reader"), Principal::new("reader"));
+
+        let reader = Principal {
+            scopes: vec!["signals:read".to_string()],
+            ..Principal::new("reader")
+        };
+        acl.record_principal(&reader);
+        assert_eq!(acl.principal("reader"), reader);
+    }
+
+    #[tokio::test]
+    async fn test_from_auth() {
+        let info = AuthInfo {
+            client_id: "agent-1".to_string(),
+            method: AuthMethod::BearerToken,
+        };
+        let claims = TokenClaims {
+            scopes: vec!["signals:read".to_string()],
+            ..TokenClaims::new("agent-1")
+        };
+
+        let principal = Principal::from_auth(&info, Some(&claims));
+        assert_eq!(principal.client_id, "agent-1");
+        assert_eq!(principal.api_key_name, None);
+        assert_eq!(principal.scopes, ["signals:read"]);
+
+        let store = ApiKeyStore::new();
+        let issued = store.create(NewApiKey::new("agent-key", "agent-1"));
+        let claims = store.validate_api_key_claims(&issued.key).await.unwrap();
+        let info = AuthInfo {
+            method: AuthMethod::ApiKey,
+            ..info
+        };
+        let principal = Principal::from_auth(&info, claims.as_ref());
+        assert_eq!(principal.api_key_name.as_deref(), Some("agent-key"));
+        assert!(acl().check_publish(&principal, "action.email.send").is_ok());
+
+        // The secret itself is never part of the principal
+        assert!(!format!("{:?}", principal).contains(&issued.key));
+    }
+}
@@ -0,0 +1,419 @@
+//! Hashed API key storage.
+//!
+//! [`ApiKeyStore`] issues API keys and keeps only a salted hash of each, with
+//! the key's name, owning client, scopes and expiry. Keys have the form
+//! `ck_<id>_<secret>`: the ID locates the record, and the secret is checked
+//! against its hash in constant time.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use uuid::Uuid;
+
+use super::{AuthValidator, TokenClaims};
+use crate::error::{ServerError, ServerResult};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Prefix identifying keys issued by an [`ApiKeyStore`].
+const KEY_PREFIX: &str = "ck_";
+
+/// A stored API key. Holds a hash of the key, never the key itself.
+///
+/// Records serialize to the `auth.keys` section of the server
+/// configuration, so they can be written by key management tooling and
+/// loaded with [`ApiKeyStore::from_records`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiKeyRecord {
+    /// Public key identifier, embedded in the key.
+    pub id: String,
+    /// Human-readable name.
+    pub name: String,
+    /// The client ID the key authenticates as.
+    pub client_id: String,
+    /// Scopes granted to the key.
+    #[serde(default)]
+    pub scopes: Vec<String>,
+    /// When the key was created.
+    pub created_at: DateTime<Utc>,
+    /// When the key stops being accepted, if ever.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Hex-encoded random salt.
+    pub salt: String,
+    /// Hex-encoded HMAC-SHA256 of the key's secret, keyed by the salt.
+    pub hash: String,
+}
+
+impl ApiKeyRecord {
+    /// Checks if the key has expired.
+    pub fn is_expired(&self) -> bool {
+        self.expires_at.is_some_and(|expires| Utc::now() >= expires)
+    }
+
+    /// Checks `secret` against the stored hash in constant time.
+    fn verify_secret(&self, secret: &str) -> bool {
+        let (Ok(salt), Ok(hash)) = (hex::decode(&self.salt), hex::decode(&self.hash)) else {
+            return false;
+        };
+        let mut mac = HmacSha256::new_from_slice(&salt).expect("HMAC can take key of any size");
+        mac.update(secret.as_bytes());
+        mac.verify_slice(&hash).is_ok()
+    }
+}
+
+/// Parameters for a new API key.
+#[derive(Debug, Clone)]
+pub struct NewApiKey {
+    name: String,
+    client_id: String,
+    scopes: Vec<String>,
+    expires_at: Option<DateTime<Utc>>,
+}
+
+impl NewApiKey {
+    /// Describes a key named `name` that authenticates as `client_id`.
+    pub fn new(name: impl Into<String>, client_id: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            client_id: client_id.into(),
+            scopes: Vec::new(),
+            expires_at: None,
+        }
+    }
+
+    /// Grants a scope to the key.
+    pub fn with_scope(mut self, scope: impl Into<String>) -> Self {
+        self.scopes.push(scope.into());
+        self
+    }
+
+    /// Sets when the key expires.
+    pub fn with_expiry(mut self, expires_at: DateTime<Utc>) -> Self {
+        self.expires_at = Some(expires_at);
+        self
+    }
+}
+
+/// A newly issued API key.
+///
+/// The plaintext key is only available here; the store keeps its hash.
+#[derive(Debug, Clone)]
+pub struct IssuedApiKey {
+    /// The API key to hand to the client.
+    pub key: String,
+    /// The stored record for the key.
+    pub record: ApiKeyRecord,
+}
+
+/// In-memory store of hashed API keys.
+///
+/// Cloning the store shares the underlying keys.
+///
+/// # Example
+///
+/// ```ignore
+/// use cauce_server_sdk::auth::{ApiKeyStore, NewApiKey};
+///
+/// let store = ApiKeyStore::new();
+/// let issued = store.create(NewApiKey::new("ci", "ci-agent").with_scope("signals:read"));
+/// println!("API key: {}", issued.key);
+///
+/// // Later: replace the key, accepting the old one for another day
+/// let replacement = store.rotate(&issued.record.id, Duration::from_secs(86_400))?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ApiKeyStore {
+    /// Records indexed by key ID.
+    keys: Arc<DashMap<String, ApiKeyRecord>>,
+}
+
+impl ApiKeyStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a store holding previously issued keys.
+    pub fn from_records(records: impl IntoIterator<Item = ApiKeyRecord>) -> Self {
+        let store = Self::new();
+        for record in records {
+            store.keys.insert(record.id.clone(), record);
+        }
+        store
+    }
+
+    /// Issues a new key.
+    pub fn create(&self, spec: NewApiKey) -> IssuedApiKey {
+        let id = Uuid::new_v4().as_simple().to_string()[..12].to_string();
+        let secret = format!(
+            "{}{}",
+            Uuid::new_v4().as_simple(),
+            Uuid::new_v4().as_simple()
+        );
+        let salt = Uuid::new_v4().into_bytes();
+
+        let mut mac = HmacSha256::new_from_slice(&salt).expect("HMAC can take key of any size");
+        mac.update(secret.as_bytes());
+
+        let record = ApiKeyRecord {
+            id: id.clone(),
+            name: spec.name,
+            client_id: spec.client_id,
+            scopes: spec.scopes,
+            created_at: Utc::now(),
+            expires_at: spec.expires_at,
+            salt: hex::encode(salt),
+            hash: hex::encode(mac.finalize().into_bytes()),
+        };
+        self.keys.insert(id.clone(), record.clone());
+
+        IssuedApiKey {
+            key: format!("{}{}_{}", KEY_PREFIX, id, secret),
+            record,
+        }
+    }
+
+    /// Replaces a key with a new one for the same client and scopes.
+    ///
+    /// The old key keeps working for `grace`, so clients can switch over
+    /// without downtime. The new key is valid for as long as the old one
+    /// was when it was issued.
+    pub fn rotate(&self, id: &str, grace: Duration) -> ServerResult<IssuedApiKey> {
+        let old = self.get(id).ok_or_else(|| ServerError::InvalidParams {
+            message: format!("unknown API key: {}", id),
+        })?;
+
+        let mut spec = NewApiKey::new(old.name.clone(), old.client_id.clone());
+        spec.scopes = old.scopes.clone();
+        spec.expires_at = old
+            .expires_at
+            .map(|expires| Utc::now() + (expires - old.created_at));
+        let issued = self.create(spec);
+
+        // A grace period too long to represent leaves the old expiry as is
+        let grace_ends = chrono::Duration::from_std(grace)
+            .ok()
+            .and_then(|grace| Utc::now().checked_add_signed(grace));
+        if let (Some(grace_ends), Some(mut record)) = (grace_ends, self.keys.get_mut(id)) {
+            record.expires_at = Some(match record.expires_at {
+                Some(expires) => expires.min(grace_ends),
+                None => grace_ends,
+            });
+        }
+
+        Ok(issued)
+    }
+
+    /// Revokes a key immediately, returning its record.
+    pub fn revoke(&self, id: &str) -> Option<ApiKeyRecord> {
+        self.keys.remove(id).map(|(_, record)| record)
+    }
+
+    /// Returns the record for a key ID.
+    pub fn get(&self, id: &str) -> Option<ApiKeyRecord> {
+        self.keys.get(id).map(|record| record.clone())
+    }
+
+    /// Returns all records, oldest first.
+    pub fn list(&self) -> Vec<ApiKeyRecord> {
+        let mut records: Vec<_> = self.keys.iter().map(|r| r.value().clone()).collect();
+        records.sort_by_key(|record| record.created_at);
+        records
+    }
+
+    /// Removes expired keys, returning how many were removed.
+    pub fn cleanup_expired(&self) -> usize {
+        let before = self.keys.len();
+        self.keys.retain(|_, record| !record.is_expired());
+        before - self.keys.len()
+    }
+
+    /// Returns the number of stored keys.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns true if no keys are stored.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Looks up the record for an API key.
+    ///
+    /// Returns `None` for keys this store did not issue, and an
+    /// [`AuthenticationFailed`](ServerError::AuthenticationFailed) error for
+    /// expired keys.
+    pub fn verify(&self, key: &str) -> ServerResult<Option<ApiKeyRecord>> {
+        let Some((id, secret)) = split_key(key) else {
+            return Ok(None);
+        };
+        let Some(record) = self.get(id) else {
+            return Ok(None);
+        };
+        if !record.verify_secret(secret) {
+            return Ok(None);
+        }
+        if record.is_expired() {
+            return Err(ServerError::auth_failed("API key has expired"));
+        }
+        Ok(Some(record))
+    }
+}
+
+/// Splits an issued key into its ID and secret.
+fn split_key(key: &str) -> Option<(&str, &str)> {
+    key.strip_prefix(KEY_PREFIX)
+        .and_then(|rest| rest.split_once('_'))
+}
+
+#[async_trait]
+impl AuthValidator for ApiKeyStore {
+    async fn validate_api_key(&self, api_key: &str) -> ServerResult<Option<String>> {
+        Ok(self.verify(api_key)?.map(|record| record.client_id))
+    }
+
+    async fn validate_bearer_token(&self, _token: &str) -> ServerResult<Option<String>> {
+        Ok(None)
+    }
+
+    async fn validate_api_key_claims(&self, api_key: &str) -> ServerResult<Option<TokenClaims>> {
+        Ok(self.verify(api_key)?.map(|record| TokenClaims {
+            scopes: record.scopes,
+            key_name: Some(record.name),
+            ..TokenClaims::new(record.client_id)
+        }))
+    }
+
+    /// Returns the owner of the key whose ID is embedded in `api_key`, if
+    /// that key exists.
+    fn matched_client_id(
+        &self,
+        api_key: Option<&str>,
+        _bearer_token: Option<&str>,
+    ) -> Option<String> {
+        let (id, _) = split_key(api_key?)?;
+        self.get(id).map(|record| record.client_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_stores_only_hash() {
+        let store = ApiKeyStore::new();
+        let issued = store.create(NewApiKey::new("ci", "ci-agent").with_scope("signals:read"));
+
+        assert!(issued.key.starts_with("ck_"));
+        let record = store.get(&issued.record.id).unwrap();
+        assert_eq!(record.client_id, "ci-agent");
+        assert_eq!(record.scopes, ["signals:read"]);
+
+        let stored = serde_json::to_string(&store.list()).unwrap();
+        let secret = issued.key.rsplit('_').next().unwrap();
+        assert!(!stored.contains(secret));
+    }
+
+    #[tokio::test]
+    async fn test_verify() {
+        let store = ApiKeyStore::new();
+        let issued = store.create(NewApiKey::new("ci", "ci-agent").with_scope("signals:read"));
+
+        let result = store.validate_api_key(&issued.key).await.unwrap();
+        assert_eq!(result, Some("ci-agent".to_string()));
+
+        let claims = store.validate_api_key_claims(&issued.key).await.unwrap();
+        assert_eq!(claims.unwrap().scopes, ["signals:read"]);
+
+        let wrong_secret = format!("ck_{}_guess", issued.record.id);
+        let matched = store.matched_client_id(Some(&wrong_secret), None);
+        assert_eq!(matched.as_deref(), Some("ci-agent"));
+        assert_eq!(store.matched_client_id(Some("ck_unknown_guess"), None), None);
+        assert_eq!(store.matched_client_id(Some("plain-key"), None), None);
+
+        let mut tampered = issued.key.clone();
+        tampered.pop();
+        tampered.push('x');
+        for key in [tampered.as_str(), "ck_unknown_secret", "plain-key", ""] {
+            assert_eq!(store.validate_api_key(key).await.unwrap(), None);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_expired_key_rejected() {
+        let store = ApiKeyStore::new();
+        let issued = store.create(
+            NewApiKey::new("old", "client-1")
+                .with_expiry(Utc::now() - chrono::Duration::seconds(1)),
+        );
+
+        let result = store.validate_api_key(&issued.key).await;
+        assert!(matches!(
+            result,
+            Err(ServerError::AuthenticationFailed { .. })
+        ));
+
+        assert_eq!(store.cleanup_expired(), 1);
+        assert!(store.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_rotate_with_grace_period() {
+        let store = ApiKeyStore::new();
+        let old = store.create(
+            NewApiKey::new("ci", "ci-agent")
+                .with_scope("signals:read")
+                .with_expiry(Utc::now() + chrono::Duration::days(90)),
+        );
+
+        let new = store
+            .rotate(&old.record.id, Duration::from_secs(3600))
+            .unwrap();
+        assert_ne!(new.record.id, old.record.id);
+        assert_eq!(new.record.client_id, "ci-agent");
+        assert_eq!(new.record.scopes, ["signals:read"]);
+        assert!(new.record.expires_at.unwrap() > Utc::now() + chrono::Duration::days(89));
+
+        // Both keys work during the grace period
+        assert!(store.verify(&old.key).unwrap().is_some());
+        assert!(store.verify(&new.key).unwrap().is_some());
+        let old_expiry = store.get(&old.record.id).unwrap().expires_at.unwrap();
+        assert!(old_expiry <= Utc::now() + chrono::Duration::hours(1));
+
+        // With no grace period the old key stops working immediately
+        let newer = store.rotate(&new.record.id, Duration::ZERO).unwrap();
+        assert!(store.verify(&new.key).is_err());
+        assert!(store.verify(&newer.key).unwrap().is_some());
+
+        assert!(store.rotate("missing", Duration::ZERO).is_err());
+    }
+
+    #[test]
+    fn test_revoke_and_reload() {
+        let store = ApiKeyStore::new();
+        let first = store.create(NewApiKey::new("first", "client-1"));
+        let second = store.create(NewApiKey::new("second", "client-2"));
+
+        let names: Vec<_> = store.list().into_iter().map(|r| r.name).collect();
+        assert_eq!(names, ["first", "second"]);
+
+        assert_eq!(store.revoke(&first.record.id).unwrap().name, "first");
+        assert!(store.revoke(&first.record.id).is_none());
+        assert_eq!(store.len(), 1);
+
+        // Records survive a round trip through configuration
+        let json = serde_json::to_string(&store.list()).unwrap();
+        let records: Vec<ApiKeyRecord> = serde_json::from_str(&json).unwrap();
+        let reloaded = ApiKeyStore::from_records(records);
+        assert!(reloaded.verify(&second.key).unwrap().is_some());
+        assert!(reloaded.verify(&first.key).unwrap().is_none());
+    }
+}
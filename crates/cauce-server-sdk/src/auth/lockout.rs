@@ -0,0 +1,520 @@
+//! Brute-force protection for authentication.
+//!
+//! [`AuthLockout`] counts failed authentication attempts per source IP and,
+//! once a credential has been matched to a client, per client ID. A subject
+//! that fails too often within a window is locked out, for twice as long
+//! each time it happens again, and a [`LockoutAlert`] is broadcast for each
+//! new lockout.
+
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use axum::extract::ConnectInfo;
+use axum::http::{HeaderMap, Request};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use tracing::warn;
+
+use crate::error::ServerResult;
+use crate::rate_limit::{KeyExtractor, RateLimitResult, RateLimitState, RateLimiter};
+
+/// Topic lockout alerts are published to.
+pub const LOCKOUT_ALERT_TOPIC: &str = "system.auth.lockout";
+
+/// Lockout configuration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LockoutConfig {
+    /// Whether failed attempts are tracked.
+    pub enabled: bool,
+    /// Failed attempts allowed within the window before locking out.
+    pub max_failures: u32,
+    /// Window failed attempts are counted over, in seconds.
+    pub window_secs: u64,
+    /// Duration of the first lockout, in seconds.
+    pub lockout_secs: u64,
+    /// Upper bound for escalated lockouts, in seconds.
+    pub max_lockout_secs: u64,
+    /// Proxies trusted to report the client's address in `X-Forwarded-For`.
+    ///
+    /// Empty by default, so attempts are counted against the connection's
+    /// peer address and the header, which clients control, is ignored.
+    pub trusted_proxies: Vec<IpAddr>,
+}
+
+impl Default for LockoutConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_failures: 5,
+            window_secs: 300,
+            lockout_secs: 60,
+            max_lockout_secs: 3600,
+            trusted_proxies: Vec::new(),
+        }
+    }
+}
+
+impl LockoutConfig {
+    /// Disables lockout.
+    pub fn disabled() -> Self {
+        Self {
+            enabled: false,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the failed attempts allowed within the window.
+    pub fn with_max_failures(mut self, max_failures: u32) -> Self {
+        self.max_failures = max_failures;
+        self
+    }
+
+    /// Sets the window failed attempts are counted over.
+    pub fn with_window_secs(mut self, window_secs: u64) -> Self {
+        self.window_secs = window_secs;
+        self
+    }
+
+    /// Sets the duration of the first lockout.
+    pub fn with_lockout_secs(mut self, lockout_secs: u64) -> Self {
+        self.lockout_secs = lockout_secs;
+        self
+    }
+
+    /// Sets the upper bound for escalated lockouts.
+    pub fn with_max_lockout_secs(mut self, max_lockout_secs: u64) -> Self {
+        self.max_lockout_secs = max_lockout_secs;
+        self
+    }
+
+    /// Trusts `X-Forwarded-For` headers added by the proxy at `proxy`.
+    pub fn with_trusted_proxy(mut self, proxy: IpAddr) -> Self {
+        self.trusted_proxies.push(proxy);
+        self
+    }
+
+    /// Returns the duration of the `level`th consecutive lockout.
+    fn lockout_duration(&self, level: u32) -> Duration {
+        let factor = 1u64 << level.saturating_sub(1).min(32);
+        Duration::from_secs(
+            self.lockout_secs
+                .saturating_mul(factor)
+                .min(self.max_lockout_secs),
+        )
+    }
+}
+
+/// Who failed attempts are counted against.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LockoutSubject {
+    /// The address a request came from.
+    SourceIp(String),
+    /// The client a rejected credential was matched to.
+    ClientId(String),
+}
+
+/// Published when a subject is locked out.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockoutAlert {
+    /// The subject that was locked out.
+    pub subject: LockoutSubject,
+    /// Failed attempts that triggered the lockout.
+    pub failures: u32,
+    /// How many consecutive times the subject has been locked out.
+    pub level: u32,
+    /// When the lockout ends.
+    pub locked_until: DateTime<Utc>,
+}
+
+/// Failed attempts recorded for a subject.
+struct FailureRecord {
+    failures: u32,
+    window_start: Instant,
+    level: u32,
+    locked_until: Option<Instant>,
+}
+
+/// Tracks failed authentication attempts and locks out repeat offenders.
+///
+/// Attach it to [`AuthMiddleware`](super::AuthMiddleware) to have failures
+/// recorded and locked-out requests rejected with `429 Too Many Requests`.
+/// It also implements [`RateLimiter`], keyed by source IP, so a
+/// [`RateLimitMiddleware`](crate::rate_limit::RateLimitMiddleware) built on it
+/// with its [`key_extractor`](Self::key_extractor) turns locked-out
+/// addresses away before they reach any handler.
+///
+/// Records are only dropped by [`cleanup`](Self::cleanup), which the server
+/// runs periodically while it is serving.
+///
+/// # Example
+///
+/// ```ignore
+/// let lockout = Arc::new(AuthLockout::new(LockoutConfig::default()));
+/// let mut alerts = lockout.subscribe_alerts();
+///
+/// let auth = AuthMiddleware::new(validator).with_lockout(Arc::clone(&lockout));
+/// let app = Router::new()
+///     .route("/protected", get(handler))
+///     .layer(auth.layer())
+///     .layer(
+///         RateLimitMiddleware::with_shared(Arc::clone(&lockout))
+///             .with_key_extractor(lockout.key_extractor())
+///             .layer(),
+///     );
+/// ```
+pub struct AuthLockout {
+    config: LockoutConfig,
+    records: Arc<DashMap<LockoutSubject, FailureRecord>>,
+    alerts: broadcast::Sender<LockoutAlert>,
+}
+
+impl AuthLockout {
+    /// Creates a lockout tracker with the given config.
+    pub fn new(config: LockoutConfig) -> Self {
+        let (alerts, _) = broadcast::channel(64);
+        Self {
+            config,
+            records: Arc::new(DashMap::new()),
+            alerts,
+        }
+    }
+
+    /// Returns true if failed attempts are tracked.
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// Returns the configuration.
+    pub fn config(&self) -> &LockoutConfig {
+        &self.config
+    }
+
+    /// Returns the source IP attempts from `peer` are counted against.
+    ///
+    /// This is the peer's address, unless the peer is a trusted proxy. Then
+    /// `X-Forwarded-For` is read from the right, skipping trusted proxies,
+    /// and the first other address is used.
+    pub fn source_ip(&self, headers: &HeaderMap, peer: Option<SocketAddr>) -> Option<String> {
+        let peer = peer?.ip();
+        let trusted = &self.config.trusted_proxies;
+        if !trusted.contains(&peer) {
+            return Some(peer.to_string());
+        }
+
+        let forwarded: Vec<&str> = headers
+            .get_all("X-Forwarded-For")
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .map(str::trim)
+            .collect();
+        let client = forwarded
+            .into_iter()
+            .rev()
+            .map(|entry| entry.parse::<IpAddr>().ok())
+            .find(|ip| !ip.is_some_and(|ip| trusted.contains(&ip)))
+            .flatten();
+        // A malformed entry can't be trusted any more than the proxy itself
+        Some(client.unwrap_or(peer).to_string())
+    }
+
+    /// Returns the source IP of `request`, from its [`ConnectInfo`].
+    pub fn request_source_ip<B>(&self, request: &Request<B>) -> Option<String> {
+        let peer = request
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| *addr);
+        self.source_ip(request.headers(), peer)
+    }
+
+    /// Returns a rate limit key extractor producing the source IPs this
+    /// tracker counts attempts against.
+    pub fn key_extractor(self: &Arc<Self>) -> KeyExtractor {
+        let lockout = Arc::clone(self);
+        KeyExtractor::Custom(Arc::new(move |request| {
+            lockout
+                .request_source_ip(request)
+                .unwrap_or_else(|| "unknown".to_string())
+        }))
+    }
+
+    /// Returns the time left until `subject` may try again, if it is locked out.
+    pub fn retry_after(&self, subject: &LockoutSubject) -> Option<Duration> {
+        let record = self.records.get(subject)?;
+        let remaining = record
+            .locked_until?
+            .checked_duration_since(Instant::now())?;
+        (!remaining.is_zero()).then_some(remaining)
+    }
+
+    /// Records a failed attempt, returning an alert if it locked `subject` out.
+    pub fn record_failure(&self, subject: &LockoutSubject) -> Option<LockoutAlert> {
+        if !self.is_enabled() {
+            return None;
+        }
+
+        let now = Instant::now();
+        let window = Duration::from_secs(self.config.window_secs);
+        let mut record = self
+            .records
+            .entry(subject.clone())
+            .or_insert_with(|| FailureRecord {
+                failures: 0,
+                window_start: now,
+                level: 0,
+                locked_until: None,
+            });
+
+        // Escalation is forgotten once a subject stays out of trouble for
+        // as long as the longest lockout
+        if let Some(until) = record.locked_until {
+            if now.saturating_duration_since(until).as_secs() >= self.config.max_lockout_secs {
+                record.level = 0;
+                record.locked_until = None;
+            }
+        }
+        if now.duration_since(record.window_start) >= window {
+            record.failures = 0;
+            record.window_start = now;
+        }
+
+        record.failures += 1;
+        if record.failures < self.config.max_failures {
+            return None;
+        }
+
+        record.level += 1;
+        let duration = self.config.lockout_duration(record.level);
+        record.locked_until = Some(now + duration);
+        let alert = LockoutAlert {
+            subject: subject.clone(),
+            failures: record.failures,
+            level: record.level,
+            locked_until: Utc::now()
+                + chrono::Duration::from_std(duration).unwrap_or_else(|_| chrono::Duration::zero()),
+        };
+        record.failures = 0;
+        record.window_start = now;
+        drop(record);
+
+        warn!(
+            subject = ?alert.subject,
+            level = alert.level,
+            "Locked out after repeated authentication failures for {}s",
+            duration.as_secs()
+        );
+        // No receivers is fine
+        let _ = self.alerts.send(alert.clone());
+        Some(alert)
+    }
+
+    /// Records a successful attempt, clearing `subject`'s failure count.
+    ///
+    /// An active lockout and its escalation level are kept.
+    pub fn record_success(&self, subject: &LockoutSubject) {
+        if let Some(mut record) = self.records.get_mut(subject) {
+            record.failures = 0;
+        }
+    }
+
+    /// Lifts any lockout of `subject` and forgets its failures.
+    pub fn unlock(&self, subject: &LockoutSubject) {
+        self.records.remove(subject);
+    }
+
+    /// Subscribes to lockout alerts.
+    pub fn subscribe_alerts(&self) -> broadcast::Receiver<LockoutAlert> {
+        self.alerts.subscribe()
+    }
+
+    /// Removes records with no recent failures and no escalation to remember.
+    pub fn cleanup(&self) {
+        let now = Instant::now();
+        let window = Duration::from_secs(self.config.window_secs);
+        let memory = Duration::from_secs(self.config.max_lockout_secs);
+        self.records.retain(|_, record| {
+            let counting = now.duration_since(record.window_start) < window;
+            let remembered = record
+                .locked_until
+                .is_some_and(|until| now.saturating_duration_since(until) < memory);
+            counting || remembered
+        });
+    }
+}
+
+impl std::fmt::Debug for AuthLockout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuthLockout")
+            .field("config", &self.config)
+            .field("records", &self.records.len())
+            .finish()
+    }
+}
+
+/// Denies requests from locked-out source IPs.
+///
+/// Keys are source IP addresses, as produced by
+/// [`AuthLockout::key_extractor`].
+#[async_trait]
+impl RateLimiter for AuthLockout {
+    async fn check(&self, key: &str) -> ServerResult<RateLimitResult> {
+        Ok(
+            match self.retry_after(&LockoutSubject::SourceIp(key.to_string())) {
+                Some(remaining) => RateLimitResult::denied(remaining.as_millis() as u64),
+                None => RateLimitResult::allowed(u64::MAX, u64::MAX),
+            },
+        )
+    }
+
+    async fn consume(&self, key: &str) -> ServerResult<RateLimitResult> {
+        self.check(key).await
+    }
+
+    async fn reset(&self, key: &str) -> ServerResult<()> {
+        self.unlock(&LockoutSubject::SourceIp(key.to_string()));
+        Ok(())
+    }
+
+    /// Lockouts have no token bucket or request window; always `None`.
+    async fn get_state(&self, _key: &str) -> ServerResult<Option<RateLimitState>> {
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(addr: &str) -> LockoutSubject {
+        LockoutSubject::SourceIp(addr.to_string())
+    }
+
+    #[test]
+    fn test_locks_out_after_max_failures() {
+        let lockout = AuthLockout::new(LockoutConfig::default().with_max_failures(3));
+        let subject = ip("10.0.0.1");
+
+        assert!(lockout.record_failure(&subject).is_none());
+        assert!(lockout.record_failure(&subject).is_none());
+        assert!(lockout.retry_after(&subject).is_none());
+
+        let alert = lockout.record_failure(&subject).unwrap();
+        assert_eq!(alert.subject, subject);
+        assert_eq!(alert.failures, 3);
+        assert_eq!(alert.level, 1);
+
+        let remaining = lockout.retry_after(&subject).unwrap();
+        assert!(remaining <= Duration::from_secs(60));
+        assert!(remaining > Duration::from_secs(55));
+
+        // Other subjects are unaffected
+        assert!(lockout.retry_after(&ip("10.0.0.2")).is_none());
+    }
+
+    #[test]
+    fn test_lockouts_escalate() {
+        let config = LockoutConfig::default()
+            .with_max_failures(1)
+            .with_lockout_secs(10)
+            .with_max_lockout_secs(25);
+        assert_eq!(config.lockout_duration(1), Duration::from_secs(10));
+        assert_eq!(config.lockout_duration(2), Duration::from_secs(20));
+        assert_eq!(config.lockout_duration(3), Duration::from_secs(25));
+        assert_eq!(config.lockout_duration(100), Duration::from_secs(25));
+
+        let lockout = AuthLockout::new(config);
+        let subject = LockoutSubject::ClientId("agent-1".to_string());
+        assert_eq!(lockout.record_failure(&subject).unwrap().level, 1);
+        assert_eq!(lockout.record_failure(&subject).unwrap().level, 2);
+        assert!(lockout.retry_after(&subject).unwrap() > Duration::from_secs(15));
+    }
+
+    #[test]
+    fn test_success_and_unlock() {
+        let lockout = AuthLockout::new(LockoutConfig::default().with_max_failures(2));
+        let subject = ip("10.0.0.1");
+
+        lockout.record_failure(&subject);
+        lockout.record_success(&subject);
+        assert!(lockout.record_failure(&subject).is_none());
+
+        assert!(lockout.record_failure(&subject).is_some());
+        lockout.unlock(&subject);
+        assert!(lockout.retry_after(&subject).is_none());
+    }
+
+    #[test]
+    fn test_disabled() {
+        let lockout = AuthLockout::new(LockoutConfig::disabled().with_max_failures(1));
+        assert!(lockout.record_failure(&ip("10.0.0.1")).is_none());
+        assert!(lockout.retry_after(&ip("10.0.0.1")).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_alerts_broadcast() {
+        let lockout = AuthLockout::new(LockoutConfig::default().with_max_failures(1));
+        let mut alerts = lockout.subscribe_alerts();
+
+        lockout.record_failure(&ip("10.0.0.1"));
+        let alert = alerts.recv().await.unwrap();
+        assert_eq!(alert.subject, ip("10.0.0.1"));
+
+        let json = serde_json::to_value(&alert).unwrap();
+        assert_eq!(json["subject"]["source_ip"], "10.0.0.1");
+    }
+
+    #[test]
+    fn test_source_ip_ignores_untrusted_forwarded_for() {
+        let mut headers = HeaderMap::new();
+        headers.insert("X-Forwarded-For", "203.0.113.7, 10.0.0.2".parse().unwrap());
+        let peer: SocketAddr = "10.0.0.1:4000".parse().unwrap();
+
+        let lockout = AuthLockout::new(LockoutConfig::default());
+        assert_eq!(lockout.source_ip(&headers, Some(peer)).as_deref(), Some("10.0.0.1"));
+        assert_eq!(lockout.source_ip(&headers, None), None);
+
+        // Behind trusted proxies, the nearest untrusted hop is the client
+        let config = LockoutConfig::default()
+            .with_trusted_proxy("10.0.0.1".parse().unwrap())
+            .with_trusted_proxy("10.0.0.2".parse().unwrap());
+        let lockout = AuthLockout::new(config);
+        assert_eq!(
+            lockout.source_ip(&headers, Some(peer)).as_deref(),
+            Some("203.0.113.7")
+        );
+
+        // A client can't hide behind a spoofed entry further left
+        headers.insert(
+            "X-Forwarded-For",
+            "198.51.100.1, 203.0.113.7, 10.0.0.2".parse().unwrap(),
+        );
+        assert_eq!(
+            lockout.source_ip(&headers, Some(peer)).as_deref(),
+            Some("203.0.113.7")
+        );
+
+        headers.insert("X-Forwarded-For", "garbage".parse().unwrap());
+        assert_eq!(lockout.source_ip(&headers, Some(peer)).as_deref(), Some("10.0.0.1"));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_denies_locked_ip() {
+        let lockout = AuthLockout::new(LockoutConfig::default().with_max_failures(1));
+        assert!(lockout.consume("10.0.0.1").await.unwrap().allowed);
+
+        lockout.record_failure(&ip("10.0.0.1"));
+        let result = lockout.consume("10.0.0.1").await.unwrap();
+        assert!(!result.allowed);
+        assert!(result.retry_after_ms.unwrap() > 55_000);
+        assert!(lockout.check("10.0.0.2").await.unwrap().allowed);
+
+        lockout.reset("10.0.0.1").await.unwrap();
+        assert!(lockout.check("10.0.0.1").await.unwrap().allowed);
+    }
+}
@@ -1,6 +1,9 @@
 //! Authentication middleware for the Cauce server.
 //!
 //! This module provides authentication validation via API keys and Bearer tokens.
+//! Bearer tokens can be opaque strings ([`InMemoryAuthValidator`]) or signed
+//! JWTs ([`JwtAuthValidator`]). API keys can be stored hashed, with scopes
+//! and expiry, in an [`ApiKeyStore`].
 //!
 //! # Example
 //!
@@ -18,21 +21,36 @@
 //!     .layer(auth.layer());
 //! ```
 
+mod acl;
+mod jwt;
+mod keys;
+mod lockout;
+
+pub use acl::{Principal, TopicAcl};
+pub use jwt::JwtAuthValidator;
+pub use keys::{ApiKeyRecord, ApiKeyStore, IssuedApiKey, NewApiKey};
+pub use lockout::{AuthLockout, LockoutAlert, LockoutConfig, LockoutSubject, LOCKOUT_ALERT_TOPIC};
+
 use std::future::Future;
 use std::pin::Pin;
+use std::net::SocketAddr;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 
 use async_trait::async_trait;
 use axum::body::Body;
+use axum::extract::ConnectInfo;
 use axum::http::{Request, StatusCode};
 use axum::response::{IntoResponse, Response};
 use dashmap::DashMap;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use tower::{Layer, Service};
 use tracing::{debug, warn};
 
+use crate::audit::{AuditAction, AuditEvent, AuditLog};
 use crate::error::ServerResult;
+use crate::rate_limit::too_many_requests;
 
 /// Authentication method used.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -57,6 +75,8 @@ pub struct AuthResult {
     pub method: AuthMethod,
     /// Error message if authentication failed.
     pub error: Option<String>,
+    /// Claims carried by the validated credential, if any.
+    pub claims: Option<TokenClaims>,
 }
 
 impl AuthResult {
@@ -67,9 +87,16 @@ impl AuthResult {
             client_id: Some(client_id.into()),
             method,
             error: None,
+            claims: None,
         }
     }
 
+    /// Attaches the claims of the credential that was validated.
+    pub fn with_claims(mut self, claims: TokenClaims) -> Self {
+        self.claims = Some(claims);
+        self
+    }
+
     /// Creates a failed auth result.
     pub fn failure(method: AuthMethod, error: impl Into<String>) -> Self {
         Self {
@@ -77,6 +104,7 @@ impl AuthResult {
             client_id: None,
             method,
             error: Some(error.into()),
+            claims: None,
         }
     }
 
@@ -87,6 +115,7 @@ impl AuthResult {
             client_id: None,
             method: AuthMethod::None,
             error: Some("No authentication provided".to_string()),
+            claims: None,
         }
     }
 }
@@ -99,17 +128,54 @@ pub trait AuthValidator: Send + Sync + 'static {
 
     /// Validate a bearer token and return the associated client ID.
     async fn validate_bearer_token(&self, token: &str) -> ServerResult<Option<String>>;
+
+    /// Validate a bearer token and return the client ID and claims it carries.
+    ///
+    /// The default implementation wraps [`validate_bearer_token`](Self::validate_bearer_token)
+    /// with no additional claims. Validators for self-describing tokens, such as
+    /// [`JwtAuthValidator`], override this to expose scopes, tenant and other claims.
+    async fn validate_bearer_claims(&self, token: &str) -> ServerResult<Option<TokenClaims>> {
+        Ok(self.validate_bearer_token(token).await?.map(TokenClaims::new))
+    }
+
+    /// Validate an API key and return the client ID and claims attached to it.
+    ///
+    /// The default implementation wraps [`validate_api_key`](Self::validate_api_key)
+    /// with no additional claims. [`ApiKeyStore`] overrides this to expose the
+    /// key's scopes.
+    async fn validate_api_key_claims(&self, api_key: &str) -> ServerResult<Option<TokenClaims>> {
+        Ok(self.validate_api_key(api_key).await?.map(TokenClaims::new))
+    }
+
+    /// Returns the client rejected credentials were matched to.
+    ///
+    /// Used to count failed attempts against the targeted client, so it must
+    /// only return a client the credential has been verified to belong to,
+    /// such as the owner of a known API key ID presented with the wrong
+    /// secret. Anything the caller merely asserts, like the subject of a
+    /// token whose signature hasn't been checked, must be ignored, or anyone
+    /// could lock a client out. The default implementation returns `None`.
+    fn matched_client_id(
+        &self,
+        _api_key: Option<&str>,
+        _bearer_token: Option<&str>,
+    ) -> Option<String> {
+        None
+    }
 }
 
 /// In-memory authentication validator.
 ///
-/// Stores API keys and bearer tokens in memory for validation.
+/// Stores API keys and bearer tokens in memory for validation. API keys not
+/// registered in plaintext are looked up in the attached [`ApiKeyStore`], if any.
 #[derive(Debug, Clone, Default)]
 pub struct InMemoryAuthValidator {
     /// Map of API key -> client ID.
     api_keys: Arc<DashMap<String, String>>,
     /// Map of bearer token -> client ID.
     bearer_tokens: Arc<DashMap<String, String>>,
+    /// Hashed API keys.
+    key_store: Option<ApiKeyStore>,
 }
 
 impl InMemoryAuthValidator {
@@ -124,6 +190,17 @@ impl InMemoryAuthValidator {
         self
     }
 
+    /// Attaches a store of hashed API keys.
+    pub fn with_key_store(mut self, store: ApiKeyStore) -> Self {
+        self.key_store = Some(store);
+        self
+    }
+
+    /// Returns the attached store of hashed API keys.
+    pub fn key_store(&self) -> Option<&ApiKeyStore> {
+        self.key_store.as_ref()
+    }
+
     /// Adds a bearer token for a client.
     pub fn with_bearer_token(
         self,
@@ -168,12 +245,35 @@ impl InMemoryAuthValidator {
 #[async_trait]
 impl AuthValidator for InMemoryAuthValidator {
     async fn validate_api_key(&self, api_key: &str) -> ServerResult<Option<String>> {
-        Ok(self.api_keys.get(api_key).map(|v| v.clone()))
+        Ok(self
+            .validate_api_key_claims(api_key)
+            .await?
+            .map(|claims| claims.client_id))
     }
 
     async fn validate_bearer_token(&self, token: &str) -> ServerResult<Option<String>> {
         Ok(self.bearer_tokens.get(token).map(|v| v.clone()))
     }
+
+    async fn validate_api_key_claims(&self, api_key: &str) -> ServerResult<Option<TokenClaims>> {
+        if let Some(client_id) = self.api_keys.get(api_key) {
+            return Ok(Some(TokenClaims::new(client_id.clone())));
+        }
+        match &self.key_store {
+            Some(store) => store.validate_api_key_claims(api_key).await,
+            None => Ok(None),
+        }
+    }
+
+    fn matched_client_id(
+        &self,
+        api_key: Option<&str>,
+        bearer_token: Option<&str>,
+    ) -> Option<String> {
+        self.key_store
+            .as_ref()
+            .and_then(|store| store.matched_client_id(api_key, bearer_token))
+    }
 }
 
 /// Authentication middleware for axum.
@@ -181,6 +281,10 @@ pub struct AuthMiddleware<V: AuthValidator> {
     validator: Arc<V>,
     /// Whether to allow unauthenticated requests.
     allow_anonymous: bool,
+    /// Failed attempt tracking, if enabled.
+    lockout: Option<Arc<AuthLockout>>,
+    /// Audit log failed attempts are recorded to.
+    audit: Option<Arc<AuditLog>>,
 }
 
 impl<V: AuthValidator> AuthMiddleware<V> {
@@ -189,6 +293,8 @@ impl<V: AuthValidator> AuthMiddleware<V> {
         Self {
             validator: Arc::new(validator),
             allow_anonymous: false,
+            lockout: None,
+            audit: None,
         }
     }
 
@@ -197,6 +303,8 @@ impl<V: AuthValidator> AuthMiddleware<V> {
         Self {
             validator,
             allow_anonymous: false,
+            lockout: None,
+            audit: None,
         }
     }
 
@@ -206,11 +314,30 @@ impl<V: AuthValidator> AuthMiddleware<V> {
         self
     }
 
+    /// Tracks failed attempts, rejecting locked-out sources and clients with
+    /// `429 Too Many Requests`.
+    pub fn with_lockout(mut self, lockout: Arc<AuthLockout>) -> Self {
+        self.lockout = Some(lockout);
+        self
+    }
+
+    /// Records rejected credentials to an audit log.
+    ///
+    /// Successful authentication is not recorded here, since every polling
+    /// request authenticates; the WebSocket handler records it once per
+    /// connection.
+    pub fn with_audit(mut self, audit: Arc<AuditLog>) -> Self {
+        self.audit = Some(audit);
+        self
+    }
+
     /// Creates a tower Layer for this middleware.
     pub fn layer(self) -> AuthLayer<V> {
         AuthLayer {
             validator: self.validator,
             allow_anonymous: self.allow_anonymous,
+            lockout: self.lockout,
+            audit: self.audit,
         }
     }
 
@@ -222,10 +349,11 @@ impl<V: AuthValidator> AuthMiddleware<V> {
             .get("X-Cauce-API-Key")
             .and_then(|v| v.to_str().ok())
         {
-            match self.validator.validate_api_key(api_key).await {
-                Ok(Some(client_id)) => {
-                    debug!("API key authenticated for client: {}", client_id);
-                    return AuthResult::success(client_id, AuthMethod::ApiKey);
+            match self.validator.validate_api_key_claims(api_key).await {
+                Ok(Some(claims)) => {
+                    debug!("API key authenticated for client: {}", claims.client_id);
+                    let client_id = claims.client_id.clone();
+                    return AuthResult::success(client_id, AuthMethod::ApiKey).with_claims(claims);
                 }
                 Ok(None) => {
                     warn!("Invalid API key attempted");
@@ -245,10 +373,12 @@ impl<V: AuthValidator> AuthMiddleware<V> {
             .and_then(|v| v.to_str().ok())
         {
             if let Some(token) = auth_header.strip_prefix("Bearer ") {
-                match self.validator.validate_bearer_token(token).await {
-                    Ok(Some(client_id)) => {
-                        debug!("Bearer token authenticated for client: {}", client_id);
-                        return AuthResult::success(client_id, AuthMethod::BearerToken);
+                match self.validator.validate_bearer_claims(token).await {
+                    Ok(Some(claims)) => {
+                        debug!("Bearer token authenticated for client: {}", claims.client_id);
+                        let client_id = claims.client_id.clone();
+                        return AuthResult::success(client_id, AuthMethod::BearerToken)
+                            .with_claims(claims);
                     }
                     Ok(None) => {
                         warn!("Invalid bearer token attempted");
@@ -272,6 +402,8 @@ impl<V: AuthValidator> Clone for AuthMiddleware<V> {
         Self {
             validator: Arc::clone(&self.validator),
             allow_anonymous: self.allow_anonymous,
+            lockout: self.lockout.clone(),
+            audit: self.audit.clone(),
         }
     }
 }
@@ -280,6 +412,8 @@ impl<V: AuthValidator> Clone for AuthMiddleware<V> {
 pub struct AuthLayer<V: AuthValidator> {
     validator: Arc<V>,
     allow_anonymous: bool,
+    lockout: Option<Arc<AuthLockout>>,
+    audit: Option<Arc<AuditLog>>,
 }
 
 impl<V: AuthValidator> Clone for AuthLayer<V> {
@@ -287,6 +421,8 @@ impl<V: AuthValidator> Clone for AuthLayer<V> {
         Self {
             validator: Arc::clone(&self.validator),
             allow_anonymous: self.allow_anonymous,
+            lockout: self.lockout.clone(),
+            audit: self.audit.clone(),
         }
     }
 }
@@ -299,6 +435,8 @@ impl<V: AuthValidator, S> Layer<S> for AuthLayer<V> {
             inner,
             validator: Arc::clone(&self.validator),
             allow_anonymous: self.allow_anonymous,
+            lockout: self.lockout.clone(),
+            audit: self.audit.clone(),
         }
     }
 }
@@ -308,6 +446,8 @@ pub struct AuthService<V: AuthValidator, S> {
     inner: S,
     validator: Arc<V>,
     allow_anonymous: bool,
+    lockout: Option<Arc<AuthLockout>>,
+    audit: Option<Arc<AuditLog>>,
 }
 
 impl<V: AuthValidator, S: Clone> Clone for AuthService<V, S> {
@@ -316,6 +456,8 @@ impl<V: AuthValidator, S: Clone> Clone for AuthService<V, S> {
             inner: self.inner.clone(),
             validator: Arc::clone(&self.validator),
             allow_anonymous: self.allow_anonymous,
+            lockout: self.lockout.clone(),
+            audit: self.audit.clone(),
         }
     }
 }
@@ -334,7 +476,7 @@ where
         self.inner.poll_ready(cx)
     }
 
-    fn call(&mut self, request: Request<Body>) -> Self::Future {
+    fn call(&mut self, mut request: Request<Body>) -> Self::Future {
         let validator = Arc::clone(&self.validator);
         let allow_anonymous = self.allow_anonymous;
         let mut inner = self.inner.clone();
@@ -352,10 +494,74 @@ where
             .and_then(|v| v.to_str().ok())
             .and_then(|h| h.strip_prefix("Bearer ").map(String::from));
 
+        // The peer address, or the client behind a proxy the lockout trusts
+        let source_ip = match &self.lockout {
+            Some(lockout) => lockout.request_source_ip(&request),
+            None => request
+                .extensions()
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(addr)| addr.ip().to_string()),
+        };
+
+        // Subjects failed attempts are counted against: always the source,
+        // and the client only once the credential has been matched to one
+        let lockout = self.lockout.clone().filter(|lockout| lockout.is_enabled());
+        let mut subjects = Vec::new();
+        if lockout.is_some() {
+            if let Some(ref source_ip) = source_ip {
+                subjects.push(LockoutSubject::SourceIp(source_ip.clone()));
+            }
+            if let Some(client_id) =
+                validator.matched_client_id(api_key.as_deref(), bearer_token.as_deref())
+            {
+                subjects.push(LockoutSubject::ClientId(client_id));
+            }
+        }
+
+        // Who and where a rejected attempt is recorded against
+        let audit = self.audit.clone().filter(|audit| audit.is_enabled());
+        let attempt = audit.as_ref().map(|_| {
+            let event = AuditEvent::failure(AuditAction::AuthFailure, "Unauthorized")
+                .with_source_address(source_ip)
+                .with_target(request.uri().path());
+            match validator.matched_client_id(api_key.as_deref(), bearer_token.as_deref()) {
+                Some(client_id) => event.with_identity(client_id),
+                None => event,
+            }
+        });
+
         Box::pin(async move {
+            if let Some(ref lockout) = lockout {
+                let remaining = subjects.iter().filter_map(|s| lockout.retry_after(s)).max();
+                if let Some(remaining) = remaining {
+                    debug!("Rejected locked-out request: {:?}", subjects);
+                    return Ok(too_many_requests(remaining.as_millis() as u64));
+                }
+            }
+
             // Validate authentication
             let auth_result = validate_extracted(&api_key, &bearer_token, &*validator).await;
 
+            if let Some(ref lockout) = lockout {
+                if auth_result.authenticated {
+                    for subject in &subjects {
+                        lockout.record_success(subject);
+                    }
+                } else if auth_result.method != AuthMethod::None {
+                    for subject in &subjects {
+                        lockout.record_failure(subject);
+                    }
+                }
+            }
+
+            if let (Some(audit), Some(mut event)) = (audit, attempt) {
+                if !auth_result.authenticated && auth_result.method != AuthMethod::None {
+                    event.reason = auth_result.error.clone();
+                    event.details = Some(serde_json::json!({ "method": auth_result.method }));
+                    audit.record(event).await;
+                }
+            }
+
             if !auth_result.authenticated && !allow_anonymous {
                 // Return 401 Unauthorized
                 let error_body = serde_json::json!({
@@ -373,6 +579,17 @@ where
                     .into_response());
             }
 
+            // Expose the identity to handlers
+            if let Some(client_id) = auth_result.client_id {
+                request.extensions_mut().insert(AuthInfo {
+                    client_id,
+                    method: auth_result.method,
+                });
+            }
+            if let Some(claims) = auth_result.claims {
+                request.extensions_mut().insert(claims);
+            }
+
             // Continue to inner service
             inner.call(request).await
         })
@@ -387,10 +604,11 @@ async fn validate_extracted<V: AuthValidator>(
 ) -> AuthResult {
     // Try API key first
     if let Some(ref api_key) = api_key {
-        match validator.validate_api_key(api_key).await {
-            Ok(Some(client_id)) => {
-                debug!("API key authenticated for client: {}", client_id);
-                return AuthResult::success(client_id, AuthMethod::ApiKey);
+        match validator.validate_api_key_claims(api_key).await {
+            Ok(Some(claims)) => {
+                debug!("API key authenticated for client: {}", claims.client_id);
+                return AuthResult::success(claims.client_id.clone(), AuthMethod::ApiKey)
+                    .with_claims(claims);
             }
             Ok(None) => {
                 warn!("Invalid API key attempted");
@@ -405,10 +623,11 @@ async fn validate_extracted<V: AuthValidator>(
 
     // Try bearer token
     if let Some(ref token) = bearer_token {
-        match validator.validate_bearer_token(token).await {
-            Ok(Some(client_id)) => {
-                debug!("Bearer token authenticated for client: {}", client_id);
-                return AuthResult::success(client_id, AuthMethod::BearerToken);
+        match validator.validate_bearer_claims(token).await {
+            Ok(Some(claims)) => {
+                debug!("Bearer token authenticated for client: {}", claims.client_id);
+                return AuthResult::success(claims.client_id.clone(), AuthMethod::BearerToken)
+                    .with_claims(claims);
             }
             Ok(None) => {
                 warn!("Invalid bearer token attempted");
@@ -434,6 +653,44 @@ pub struct AuthInfo {
     pub method: AuthMethod,
 }
 
+/// Identity and claims carried by a validated bearer token or API key.
+///
+/// Stored in request extensions alongside [`AuthInfo`], so authorization
+/// decisions can take scopes, tenant and other claims into account.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TokenClaims {
+    /// The client ID the token was issued to.
+    pub client_id: String,
+    /// Scopes granted to the token.
+    pub scopes: Vec<String>,
+    /// Tenant the client belongs to, if the token names one.
+    pub tenant: Option<String>,
+    /// Name of the stored API key the client authenticated with, if any.
+    pub key_name: Option<String>,
+    /// All claims in the token, including the ones above.
+    pub claims: Map<String, Value>,
+}
+
+impl TokenClaims {
+    /// Creates claims for a client with no scopes, tenant or other claims.
+    pub fn new(client_id: impl Into<String>) -> Self {
+        Self {
+            client_id: client_id.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Returns true if the token grants `scope`.
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|s| s == scope)
+    }
+
+    /// Returns the value of a claim by name.
+    pub fn claim(&self, name: &str) -> Option<&Value> {
+        self.claims.get(name)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -754,6 +1011,8 @@ mod tests {
         let layer = AuthLayer {
             validator,
             allow_anonymous: false,
+            lockout: None,
+            audit: None,
         };
         let cloned = layer.clone();
         assert_eq!(cloned.allow_anonymous, layer.allow_anonymous);
@@ -878,4 +1137,170 @@ mod tests {
         assert_eq!(result.client_id, Some("client-api".to_string()));
         assert_eq!(result.method, AuthMethod::ApiKey);
     }
+
+    #[tokio::test]
+    async fn test_auth_service_exposes_identity_to_handlers() {
+        use axum::body::Body;
+        use axum::http::Request;
+        use tower::ServiceExt;
+
+        let validator = InMemoryAuthValidator::new()
+            .with_bearer_token("client-2", "token_abc");
+        let inner = tower::service_fn(|request: Request<Body>| async move {
+            let info = request.extensions().get::<AuthInfo>().unwrap();
+            assert_eq!(info.client_id, "client-2");
+            assert_eq!(info.method, AuthMethod::BearerToken);
+            let claims = request.extensions().get::<TokenClaims>().unwrap();
+            assert_eq!(*claims, TokenClaims::new("client-2"));
+            Ok::<_, std::convert::Infallible>(StatusCode::OK.into_response())
+        });
+        let service = AuthMiddleware::new(validator).layer().layer(inner);
+
+        let request = Request::builder()
+            .header("Authorization", "Bearer token_abc")
+            .body(Body::empty())
+            .unwrap();
+        let response = service.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_auth_service_locks_out_repeated_failures() {
+        use axum::body::Body;
+        use axum::http::Request;
+        use tower::ServiceExt;
+
+        let store = ApiKeyStore::new();
+        let issued = store.create(NewApiKey::new("ci", "ci-agent"));
+        let validator = InMemoryAuthValidator::new().with_key_store(store);
+        let lockout = Arc::new(AuthLockout::new(LockoutConfig::default().with_max_failures(2)));
+        let inner = tower::service_fn(|_: Request<Body>| async move {
+            Ok::<_, std::convert::Infallible>(StatusCode::OK.into_response())
+        });
+        let service = AuthMiddleware::new(validator)
+            .with_lockout(Arc::clone(&lockout))
+            .layer()
+            .layer(inner);
+
+        let request = |ip: &str, key: &str| {
+            Request::builder()
+                .extension(ConnectInfo(SocketAddr::new(ip.parse().unwrap(), 4000)))
+                .header("X-Cauce-API-Key", key)
+                .body(Body::empty())
+                .unwrap()
+        };
+        let guess = format!("ck_{}_guess", issued.record.id);
+
+        for _ in 0..2 {
+            let response = service.clone().oneshot(request("10.0.0.1", &guess)).await.unwrap();
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        }
+
+        // The source is locked out, even with the right key
+        let response = service.clone().oneshot(request("10.0.0.1", &issued.key)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.headers().contains_key("Retry-After"));
+
+        // So is the client the guesses targeted
+        let response = service.clone().oneshot(request("10.0.0.2", &issued.key)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        lockout.unlock(&LockoutSubject::ClientId("ci-agent".to_string()));
+        let response = service.clone().oneshot(request("10.0.0.2", &issued.key)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_auth_service_counts_forged_tokens_against_source_only() {
+        use axum::body::Body;
+        use axum::http::Request;
+        use jsonwebtoken::{encode, EncodingKey, Header};
+        use tower::ServiceExt;
+
+        let validator = JwtAuthValidator::new().with_hs256_secret(b"hub-secret");
+        let lockout = Arc::new(AuthLockout::new(LockoutConfig::default().with_max_failures(2)));
+        let inner = tower::service_fn(|_: Request<Body>| async move {
+            Ok::<_, std::convert::Infallible>(StatusCode::OK.into_response())
+        });
+        let service = AuthMiddleware::new(validator)
+            .with_lockout(Arc::clone(&lockout))
+            .layer()
+            .layer(inner);
+
+        let claims = serde_json::json!({
+            "sub": "victim",
+            "exp": chrono::Utc::now().timestamp() + 300,
+        });
+        let token = |secret: &[u8]| {
+            encode(&Header::default(), &claims, &EncodingKey::from_secret(secret)).unwrap()
+        };
+        let request = |ip: &str, token: &str| {
+            Request::builder()
+                .extension(ConnectInfo(SocketAddr::new(ip.parse().unwrap(), 4000)))
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let forged = token(b"attacker-secret");
+        for ip in ["10.0.0.1", "10.0.0.1", "10.0.0.3", "10.0.0.3"] {
+            let response = service.clone().oneshot(request(ip, &forged)).await.unwrap();
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        }
+
+        // The forging sources are locked out, but the named client isn't
+        let response = service.clone().oneshot(request("10.0.0.1", &forged)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(lockout
+            .retry_after(&LockoutSubject::ClientId("victim".to_string()))
+            .is_none());
+
+        let genuine = token(b"hub-secret");
+        let response = service.clone().oneshot(request("10.0.0.2", &genuine)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_auth_service_audits_failures() {
+        use axum::body::Body;
+        use axum::http::Request;
+        use tower::ServiceExt;
+
+        let store = ApiKeyStore::new();
+        let issued = store.create(NewApiKey::new("ci", "ci-agent"));
+        let validator = InMemoryAuthValidator::new().with_key_store(store);
+        let audit = Arc::new(AuditLog::new());
+        let mut records = audit.subscribe();
+        let inner = tower::service_fn(|_: Request<Body>| async move {
+            Ok::<_, std::convert::Infallible>(StatusCode::OK.into_response())
+        });
+        let service = AuthMiddleware::new(validator)
+            .with_audit(Arc::clone(&audit))
+            .layer()
+            .layer(inner);
+
+        let request = |key: &str| {
+            Request::builder()
+                .uri("/cauce/v1/ws")
+                .extension(ConnectInfo(SocketAddr::from(([10, 0, 0, 1], 4000))))
+                .header("X-Cauce-API-Key", key)
+                .body(Body::empty())
+                .unwrap()
+        };
+        let guess = format!("ck_{}_guess", issued.record.id);
+        let response = service.clone().oneshot(request(&guess)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let response = service.clone().oneshot(request(&issued.key)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let record = records.recv().await.unwrap();
+        assert_eq!(record.event.action, AuditAction::AuthFailure);
+        assert_eq!(record.event.identity.as_deref(), Some("ci-agent"));
+        assert_eq!(record.event.source_address.as_deref(), Some("10.0.0.1"));
+        assert_eq!(record.event.target.as_deref(), Some("/cauce/v1/ws"));
+        assert_eq!(record.event.details, Some(serde_json::json!({"method": "api_key"})));
+
+        // Successful requests are not recorded by the middleware
+        assert!(records.try_recv().is_err());
+    }
 }
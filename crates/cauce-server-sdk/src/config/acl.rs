@@ -0,0 +1,164 @@
+//! Topic access control configuration.
+//!
+//! This module defines which clients may publish and subscribe to which
+//! topics. Rules are enforced by [`TopicAcl`](crate::auth::TopicAcl).
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ServerError, ServerResult};
+use crate::subscription::TopicTrie;
+
+/// Who an [`AclRule`] applies to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AclSubject {
+    /// An authenticated client ID.
+    ClientId(String),
+    /// Clients that authenticated with a stored API key of this name.
+    ///
+    /// Names identify keys without exposing them, and carry over when a key
+    /// is rotated.
+    ApiKeyName(String),
+    /// Clients whose bearer token or API key grants this scope.
+    Scope(String),
+    /// Every client.
+    Any,
+}
+
+/// Topics a subject may publish and subscribe to.
+///
+/// Patterns use the same wildcards as subscriptions (`*` and `**`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AclRule {
+    /// Who the rule applies to.
+    pub subject: AclSubject,
+
+    /// Topic patterns the subject may publish to.
+    #[serde(default)]
+    pub publish: Vec<String>,
+
+    /// Topic patterns the subject may subscribe to.
+    #[serde(default)]
+    pub subscribe: Vec<String>,
+}
+
+impl AclRule {
+    /// Create a rule granting nothing to `subject`.
+    pub fn new(subject: AclSubject) -> Self {
+        Self {
+            subject,
+            publish: Vec::new(),
+            subscribe: Vec::new(),
+        }
+    }
+
+    /// Allow publishing to topics matching `pattern`.
+    pub fn with_publish(mut self, pattern: impl Into<String>) -> Self {
+        self.publish.push(pattern.into());
+        self
+    }
+
+    /// Allow subscribing to topics matching `pattern`.
+    pub fn with_subscribe(mut self, pattern: impl Into<String>) -> Self {
+        self.subscribe.push(pattern.into());
+        self
+    }
+}
+
+/// Topic access control configuration.
+///
+/// With no rules, every client may publish and subscribe to any topic. Once
+/// a rule is configured, access is denied unless some rule grants it.
+///
+/// # Example
+///
+/// ```
+/// use cauce_server_sdk::config::{AclConfig, AclRule, AclSubject};
+///
+/// let acl = AclConfig::default()
+///     .with_rule(
+///         AclRule::new(AclSubject::ClientId("email-adapter".to_string()))
+///             .with_publish("signal.email.**"),
+///     )
+///     .with_rule(
+///         AclRule::new(AclSubject::Scope("signals:read".to_string()))
+///             .with_subscribe("signal.**"),
+///     );
+///
+/// assert!(acl.is_enabled());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AclConfig {
+    /// Access rules; a client is granted the union of all rules that apply.
+    #[serde(default)]
+    pub rules: Vec<AclRule>,
+}
+
+impl AclConfig {
+    /// Add a rule.
+    pub fn with_rule(mut self, rule: AclRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Check if access control is enforced.
+    pub fn is_enabled(&self) -> bool {
+        !self.rules.is_empty()
+    }
+
+    /// Validate the topic patterns in every rule.
+    pub fn validate(&self) -> ServerResult<()> {
+        for pattern in self
+            .rules
+            .iter()
+            .flat_map(|rule| rule.publish.iter().chain(&rule.subscribe))
+        {
+            TopicTrie::validate_pattern(pattern).map_err(|msg| {
+                ServerError::config_error(format!("invalid ACL pattern '{}': {}", pattern, msg))
+            })?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_is_disabled() {
+        assert!(!AclConfig::default().is_enabled());
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let json = r#"{
+            "rules": [
+                { "subject": { "client_id": "email-adapter" }, "publish": ["signal.email.*"] },
+                { "subject": { "scope": "signals:read" }, "subscribe": ["signal.**"] },
+                { "subject": "any", "subscribe": ["signal.public.*"] }
+            ]
+        }"#;
+
+        let acl: AclConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(acl.rules.len(), 3);
+        assert_eq!(
+            acl.rules[0],
+            AclRule::new(AclSubject::ClientId("email-adapter".to_string()))
+                .with_publish("signal.email.*")
+        );
+        assert_eq!(
+            acl.rules[1].subject,
+            AclSubject::Scope("signals:read".to_string())
+        );
+        assert_eq!(acl.rules[2].subject, AclSubject::Any);
+        assert!(acl.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_pattern() {
+        let acl = AclConfig::default()
+            .with_rule(AclRule::new(AclSubject::Any).with_subscribe("signal..email"));
+        assert!(acl.validate().is_err());
+    }
+}
@@ -0,0 +1,225 @@
+//! Audit log configuration.
+//!
+//! This module defines where audit records are written. Records are
+//! produced by [`AuditLog`](crate::audit::AuditLog).
+
+use std::path::PathBuf;
+
+use cauce_core::matching::TopicMatcher;
+use serde::{Deserialize, Serialize};
+
+use super::{AclConfig, AclSubject};
+use crate::audit::AUDIT_TOPIC;
+use crate::error::{ServerError, ServerResult};
+
+/// Default size at which the audit file is rotated (10 MiB).
+const DEFAULT_MAX_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Default number of rotated audit files kept.
+const DEFAULT_MAX_FILES: u32 = 5;
+
+/// JSON-lines audit file settings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditFileConfig {
+    /// Path of the current audit file. Rotated files get a numeric suffix,
+    /// `audit.jsonl.1` being the most recent.
+    pub path: PathBuf,
+    /// Size in bytes at which the file is rotated.
+    #[serde(default = "default_max_bytes")]
+    pub max_bytes: u64,
+    /// Number of rotated files kept; older files are deleted.
+    #[serde(default = "default_max_files")]
+    pub max_files: u32,
+}
+
+fn default_max_bytes() -> u64 {
+    DEFAULT_MAX_BYTES
+}
+
+fn default_max_files() -> u32 {
+    DEFAULT_MAX_FILES
+}
+
+impl AuditFileConfig {
+    /// Creates settings for an audit file at `path`.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            max_bytes: DEFAULT_MAX_BYTES,
+            max_files: DEFAULT_MAX_FILES,
+        }
+    }
+
+    /// Sets the size at which the file is rotated.
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Sets the number of rotated files kept.
+    pub fn with_max_files(mut self, max_files: u32) -> Self {
+        self.max_files = max_files;
+        self
+    }
+}
+
+/// Audit log configuration.
+///
+/// Each configured destination receives every record. Nothing is audited
+/// unless at least one is set.
+///
+/// # Example
+///
+/// ```
+/// use cauce_server_sdk::config::{AclConfig, AclRule, AclSubject, AuditConfig, AuditFileConfig};
+///
+/// let audit = AuditConfig::default()
+///     .with_file(AuditFileConfig::new("/var/log/cauce/audit.jsonl").with_max_files(10))
+///     .with_publish(true);
+/// let acl = AclConfig::default().with_rule(
+///     AclRule::new(AclSubject::Scope("audit:read".to_string())).with_subscribe("system.audit"),
+/// );
+///
+/// assert!(audit.is_enabled());
+/// assert!(audit.validate(&acl).is_ok());
+/// assert!(audit.validate(&AclConfig::default()).is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditConfig {
+    /// JSON-lines file records are appended to.
+    #[serde(default)]
+    pub file: Option<AuditFileConfig>,
+    /// SQLite database records are inserted into (requires the `sqlite`
+    /// feature).
+    #[serde(default)]
+    pub sqlite_path: Option<PathBuf>,
+    /// Whether records are published to
+    /// [`AUDIT_TOPIC`](crate::audit::AUDIT_TOPIC).
+    ///
+    /// Requires ACL rules that keep the topic from every client.
+    #[serde(default)]
+    pub publish: bool,
+}
+
+impl AuditConfig {
+    /// Append records to a JSON-lines file.
+    pub fn with_file(mut self, file: AuditFileConfig) -> Self {
+        self.file = Some(file);
+        self
+    }
+
+    /// Insert records into a SQLite database at `path`.
+    pub fn with_sqlite(mut self, path: impl Into<PathBuf>) -> Self {
+        self.sqlite_path = Some(path.into());
+        self
+    }
+
+    /// Set whether records are published to the audit topic.
+    pub fn with_publish(mut self, publish: bool) -> Self {
+        self.publish = publish;
+        self
+    }
+
+    /// Check if any destination is configured.
+    pub fn is_enabled(&self) -> bool {
+        self.file.is_some() || self.sqlite_path.is_some() || self.publish
+    }
+
+    /// Validate the configured destinations.
+    ///
+    /// Publishing is rejected unless `acl` restricts who may subscribe to
+    /// the audit topic: records name clients and what they did.
+    pub fn validate(&self, acl: &AclConfig) -> ServerResult<()> {
+        if self.publish && !restricts_audit_topic(acl) {
+            return Err(ServerError::config_error(format!(
+                "audit publish requires ACL rules restricting who may subscribe to {}",
+                AUDIT_TOPIC
+            )));
+        }
+        if let Some(file) = &self.file {
+            if file.max_bytes == 0 {
+                return Err(ServerError::config_error(
+                    "audit file max_bytes must be greater than 0",
+                ));
+            }
+        }
+        if self.sqlite_path.is_some() && !cfg!(feature = "sqlite") {
+            return Err(ServerError::config_error(
+                "audit sqlite_path requires the `sqlite` feature",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Returns true if `acl` is enforced and does not let every client
+/// subscribe to the audit topic.
+fn restricts_audit_topic(acl: &AclConfig) -> bool {
+    acl.is_enabled()
+        && !acl
+            .rules
+            .iter()
+            .filter(|rule| rule.subject == AclSubject::Any)
+            .flat_map(|rule| &rule.subscribe)
+            .any(|pattern| TopicMatcher::patterns_overlap(pattern, AUDIT_TOPIC))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AclRule;
+
+    fn auditor_acl() -> AclConfig {
+        AclConfig::default().with_rule(
+            AclRule::new(AclSubject::ClientId("auditor".to_string())).with_subscribe(AUDIT_TOPIC),
+        )
+    }
+
+    #[test]
+    fn test_default_is_disabled() {
+        assert!(!AuditConfig::default().is_enabled());
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let json = r#"{ "file": { "path": "audit.jsonl", "max_files": 2 }, "publish": true }"#;
+        let config: AuditConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            config,
+            AuditConfig::default()
+                .with_file(AuditFileConfig::new("audit.jsonl").with_max_files(2))
+                .with_publish(true)
+        );
+        assert!(config.validate(&auditor_acl()).is_ok());
+    }
+
+    #[test]
+    fn test_validate() {
+        let config =
+            AuditConfig::default().with_file(AuditFileConfig::new("audit.jsonl").with_max_bytes(0));
+        assert!(config.validate(&AclConfig::default()).is_err());
+
+        let config = AuditConfig::default().with_sqlite("audit.db");
+        assert_eq!(
+            config.validate(&AclConfig::default()).is_ok(),
+            cfg!(feature = "sqlite")
+        );
+    }
+
+    #[test]
+    fn test_validate_publish_requires_restricted_topic() {
+        let config = AuditConfig::default().with_publish(true);
+        assert!(config.validate(&AclConfig::default()).is_err());
+        assert!(config.validate(&auditor_acl()).is_ok());
+
+        // A rule letting every client subscribe to the topic undoes it
+        for pattern in ["system.*", "**", "system.audit"] {
+            let acl =
+                auditor_acl().with_rule(AclRule::new(AclSubject::Any).with_subscribe(pattern));
+            assert!(config.validate(&acl).is_err(), "{}", pattern);
+        }
+        let acl =
+            auditor_acl().with_rule(AclRule::new(AclSubject::Any).with_subscribe("signal.**"));
+        assert!(config.validate(&acl).is_ok());
+    }
+}
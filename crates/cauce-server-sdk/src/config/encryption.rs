@@ -0,0 +1,90 @@
+//! End-to-end encryption policy configuration.
+//!
+//! This module defines which topics must carry end-to-end encrypted
+//! payloads. The policy is enforced by
+//! [`EncryptionPolicy`](crate::encryption::EncryptionPolicy).
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ServerError, ServerResult};
+use crate::subscription::TopicTrie;
+
+/// End-to-end encryption policy configuration.
+///
+/// Publishes to a topic matching one of `required_topics` must be
+/// encrypted, and subscriptions that can receive such topics must register
+/// a public key.
+///
+/// # Example
+///
+/// ```
+/// use cauce_server_sdk::config::EncryptionConfig;
+///
+/// let encryption = EncryptionConfig::default()
+///     .with_required_topic("signal.health.**")
+///     .with_required_topic("action.banking.*");
+///
+/// assert!(encryption.is_enabled());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncryptionConfig {
+    /// Topic patterns whose messages must be encrypted.
+    #[serde(default)]
+    pub required_topics: Vec<String>,
+}
+
+impl EncryptionConfig {
+    /// Require encryption on topics matching `pattern`.
+    pub fn with_required_topic(mut self, pattern: impl Into<String>) -> Self {
+        self.required_topics.push(pattern.into());
+        self
+    }
+
+    /// Check if any topic requires encryption.
+    pub fn is_enabled(&self) -> bool {
+        !self.required_topics.is_empty()
+    }
+
+    /// Validate the required topic patterns.
+    pub fn validate(&self) -> ServerResult<()> {
+        for pattern in &self.required_topics {
+            TopicTrie::validate_pattern(pattern).map_err(|msg| {
+                ServerError::config_error(format!(
+                    "invalid encryption pattern '{}': {}",
+                    pattern, msg
+                ))
+            })?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_is_disabled() {
+        assert!(!EncryptionConfig::default().is_enabled());
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let json = r#"{ "required_topics": ["signal.health.**"] }"#;
+        let config: EncryptionConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            config,
+            EncryptionConfig::default().with_required_topic("signal.health.**")
+        );
+        assert!(config.validate().is_ok());
+
+        let config: EncryptionConfig = serde_json::from_str("{}").unwrap();
+        assert!(!config.is_enabled());
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_pattern() {
+        let config = EncryptionConfig::default().with_required_topic("signal..health");
+        assert!(config.validate().is_err());
+    }
+}
@@ -16,14 +16,23 @@
 //! assert_eq!(config.address.port(), 8080);
 //! ```
 
+mod acl;
+mod audit;
+mod encryption;
 mod limits;
 mod redelivery;
+mod signing;
 mod transports;
 
+pub use acl::{AclConfig, AclRule, AclSubject};
+pub use audit::{AuditConfig, AuditFileConfig};
+pub use encryption::EncryptionConfig;
 pub use limits::LimitsConfig;
 pub use redelivery::RedeliveryConfig;
+pub use signing::{AdapterKey, SigningConfig};
 pub use transports::TransportsConfig;
 
+use crate::auth::{ApiKeyRecord, LockoutConfig};
 use crate::error::{ServerError, ServerResult};
 use serde::{Deserialize, Serialize};
 use std::net::SocketAddr;
@@ -97,20 +106,69 @@ impl TlsConfig {
     }
 }
 
+/// Minimum length of a session token secret, in bytes.
+const MIN_SESSION_TOKEN_SECRET_LEN: usize = 32;
+
 /// Authentication configuration for API access.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuthConfig {
     /// Whether authentication is required.
     #[serde(default)]
     pub required: bool,
 
     /// Static API keys (for simple deployments).
+    ///
+    /// These are kept in plaintext; prefer [`keys`](Self::keys) in production.
     #[serde(default)]
     pub api_keys: Vec<String>,
 
+    /// Hashed API keys with their owner, scopes and expiry.
+    #[serde(default)]
+    pub keys: Vec<ApiKeyRecord>,
+
     /// Whether to accept bearer tokens.
     #[serde(default)]
     pub accept_bearer: bool,
+
+    /// Lockout after repeated failed authentication attempts.
+    #[serde(default)]
+    pub lockout: LockoutConfig,
+
+    /// Whether SSE and polling requests must present the signed session
+    /// token from the hello response rather than a bare session ID.
+    ///
+    /// On by default. Turning it off lets anyone who learns a session ID
+    /// act as its client; the server logs a warning while SSE or polling is
+    /// enabled without it.
+    #[serde(default = "default_require_session_tokens")]
+    pub require_session_tokens: bool,
+
+    /// Secret session tokens are signed with (HMAC-SHA256), at least 32
+    /// bytes long.
+    ///
+    /// Servers sharing a session store must share it so tokens verify on any
+    /// of them. Without it, a random secret is used and tokens stop
+    /// verifying when the process restarts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_token_secret: Option<String>,
+}
+
+fn default_require_session_tokens() -> bool {
+    true
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        Self {
+            required: false,
+            api_keys: Vec::new(),
+            keys: Vec::new(),
+            accept_bearer: false,
+            lockout: LockoutConfig::default(),
+            require_session_tokens: default_require_session_tokens(),
+            session_token_secret: None,
+        }
+    }
 }
 
 impl AuthConfig {
@@ -124,7 +182,7 @@ impl AuthConfig {
         Self {
             required: true,
             api_keys: keys,
-            accept_bearer: false,
+            ..Self::default()
         }
     }
 
@@ -132,8 +190,8 @@ impl AuthConfig {
     pub fn accept_bearer() -> Self {
         Self {
             required: true,
-            api_keys: Vec::new(),
             accept_bearer: true,
+            ..Self::default()
         }
     }
 
@@ -143,15 +201,52 @@ impl AuthConfig {
         self
     }
 
+    /// Set the failed authentication lockout policy.
+    pub fn with_lockout(mut self, lockout: LockoutConfig) -> Self {
+        self.lockout = lockout;
+        self
+    }
+
+    /// Require signed session tokens on HTTP transports.
+    pub fn with_required_session_tokens(mut self, required: bool) -> Self {
+        self.require_session_tokens = required;
+        self
+    }
+
+    /// Sign session tokens with `secret`.
+    pub fn with_session_token_secret(mut self, secret: impl Into<String>) -> Self {
+        self.session_token_secret = Some(secret.into());
+        self
+    }
+
+    /// Add a hashed API key record.
+    pub fn with_key(mut self, record: ApiKeyRecord) -> Self {
+        self.keys.push(record);
+        self
+    }
+
     /// Check if authentication is required.
     pub fn is_required(&self) -> bool {
         self.required
     }
 
-    /// Validate an API key.
+    /// Validate an API key against the static [`api_keys`](Self::api_keys).
     pub fn validate_api_key(&self, key: &str) -> bool {
         self.api_keys.iter().any(|k| k == key)
     }
+
+    /// Validate the session token secret.
+    pub fn validate(&self) -> ServerResult<()> {
+        match &self.session_token_secret {
+            Some(secret) if secret.len() < MIN_SESSION_TOKEN_SECRET_LEN => {
+                Err(ServerError::config_error(format!(
+                    "session_token_secret must be at least {} bytes",
+                    MIN_SESSION_TOKEN_SECRET_LEN
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
 }
 
 /// Main server configuration.
@@ -178,6 +273,22 @@ pub struct ServerConfig {
     #[serde(default)]
     pub auth: AuthConfig,
 
+    /// Topic access control rules.
+    #[serde(default)]
+    pub acl: AclConfig,
+
+    /// Topics that require end-to-end encryption.
+    #[serde(default)]
+    pub encryption: EncryptionConfig,
+
+    /// Adapter keys signals are verified against.
+    #[serde(default)]
+    pub signing: SigningConfig,
+
+    /// Where audit records are written.
+    #[serde(default)]
+    pub audit: AuditConfig,
+
     /// Redelivery settings for unacked signals.
     #[serde(default)]
     pub redelivery: RedeliveryConfig,
@@ -212,6 +323,10 @@ impl ServerConfig {
             transports: TransportsConfig::all(),
             limits: LimitsConfig::development(),
             auth: AuthConfig::none(),
+            acl: AclConfig::default(),
+            encryption: EncryptionConfig::default(),
+            signing: SigningConfig::default(),
+            audit: AuditConfig::default(),
             redelivery: RedeliveryConfig::default(),
             server_name: "cauce-hub-dev".to_string(),
         }
@@ -235,6 +350,17 @@ impl ServerConfig {
             ));
         }
 
+        self.auth.validate()?;
+        self.acl.validate()?;
+        self.encryption.validate()?;
+        self.signing.validate()?;
+        self.audit.validate(&self.acl)?;
+        if self.audit.publish && !self.auth.required {
+            return Err(ServerError::config_error(
+                "audit publish requires auth, since ACL rules apply to authenticated clients",
+            ));
+        }
+
         Ok(())
     }
 
@@ -266,6 +392,10 @@ pub struct ServerConfigBuilder {
     transports: TransportsConfig,
     limits: LimitsConfig,
     auth: AuthConfig,
+    acl: AclConfig,
+    encryption: EncryptionConfig,
+    signing: SigningConfig,
+    audit: AuditConfig,
     redelivery: RedeliveryConfig,
     server_name: String,
 }
@@ -279,6 +409,10 @@ impl ServerConfigBuilder {
             transports: TransportsConfig::default(),
             limits: LimitsConfig::default(),
             auth: AuthConfig::default(),
+            acl: AclConfig::default(),
+            encryption: EncryptionConfig::default(),
+            signing: SigningConfig::default(),
+            audit: AuditConfig::default(),
             redelivery: RedeliveryConfig::default(),
             server_name: default_server_name(),
         }
@@ -308,6 +442,30 @@ impl ServerConfigBuilder {
         self
     }
 
+    /// Set topic access control configuration.
+    pub fn acl(mut self, config: AclConfig) -> Self {
+        self.acl = config;
+        self
+    }
+
+    /// Set end-to-end encryption policy configuration.
+    pub fn encryption(mut self, config: EncryptionConfig) -> Self {
+        self.encryption = config;
+        self
+    }
+
+    /// Set signal provenance configuration.
+    pub fn signing(mut self, config: SigningConfig) -> Self {
+        self.signing = config;
+        self
+    }
+
+    /// Set audit log configuration.
+    pub fn audit(mut self, config: AuditConfig) -> Self {
+        self.audit = config;
+        self
+    }
+
     /// Set redelivery configuration.
     pub fn redelivery(mut self, config: RedeliveryConfig) -> Self {
         self.redelivery = config;
@@ -328,6 +486,10 @@ impl ServerConfigBuilder {
             transports: self.transports,
             limits: self.limits,
             auth: self.auth,
+            acl: self.acl,
+            encryption: self.encryption,
+            signing: self.signing,
+            audit: self.audit,
             redelivery: self.redelivery,
             server_name: self.server_name,
         };
@@ -474,6 +636,19 @@ mod tests {
     fn test_auth_config_none() {
         let auth = AuthConfig::none();
         assert!(!auth.is_required());
+        assert!(auth.require_session_tokens);
+    }
+
+    #[test]
+    fn test_auth_config_session_tokens() {
+        let parsed: AuthConfig = serde_json::from_str("{}").unwrap();
+        assert!(parsed.require_session_tokens);
+        assert!(parsed.session_token_secret.is_none());
+
+        let auth = AuthConfig::none().with_session_token_secret("too short");
+        assert!(auth.validate().is_err());
+        let auth = AuthConfig::none().with_session_token_secret("a".repeat(32));
+        assert!(auth.validate().is_ok());
     }
 
     #[test]
@@ -490,6 +665,21 @@ mod tests {
         assert!(auth.api_keys.contains(&"key123".to_string()));
     }
 
+    #[test]
+    fn test_auth_config_hashed_keys() {
+        use crate::auth::{ApiKeyStore, NewApiKey};
+
+        let issued = ApiKeyStore::new().create(NewApiKey::new("ci", "ci-agent"));
+        let auth = AuthConfig::none().with_key(issued.record.clone());
+
+        let json = serde_json::to_string(&auth).unwrap();
+        assert!(!json.contains(&issued.key));
+
+        let parsed: AuthConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.keys, vec![issued.record]);
+        assert!(!parsed.validate_api_key(&issued.key));
+    }
+
     #[test]
     fn test_server_config_clone() {
         let config = ServerConfig::development();
@@ -504,6 +694,82 @@ mod tests {
         assert!(debug.contains("127.0.0.1"));
     }
 
+    #[test]
+    fn test_builder_with_acl() {
+        let addr: SocketAddr = "127.0.0.1:8080".parse().unwrap();
+        let acl = AclConfig::default()
+            .with_rule(AclRule::new(AclSubject::Any).with_subscribe("signal.public.*"));
+        let config = ServerConfig::builder(addr).acl(acl.clone()).build().unwrap();
+        assert_eq!(config.acl, acl);
+
+        let invalid = AclConfig::default()
+            .with_rule(AclRule::new(AclSubject::Any).with_publish("signal.**.email"));
+        assert!(ServerConfig::builder(addr).acl(invalid).build().is_err());
+    }
+
+    #[test]
+    fn test_builder_with_encryption() {
+        let addr: SocketAddr = "127.0.0.1:8080".parse().unwrap();
+        let encryption = EncryptionConfig::default().with_required_topic("signal.health.**");
+        let config = ServerConfig::builder(addr)
+            .encryption(encryption.clone())
+            .build()
+            .unwrap();
+        assert_eq!(config.encryption, encryption);
+
+        let invalid = EncryptionConfig::default().with_required_topic("signal.**.health");
+        assert!(ServerConfig::builder(addr)
+            .encryption(invalid)
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn test_builder_with_signing() {
+        let addr: SocketAddr = "127.0.0.1:8080".parse().unwrap();
+        let key = cauce_core::signing::SigningKey::generate();
+        let signing = SigningConfig::default()
+            .with_adapter_key("email-1", key.verifying_key().to_base64());
+        let config = ServerConfig::builder(addr)
+            .signing(signing.clone())
+            .build()
+            .unwrap();
+        assert_eq!(config.signing, signing);
+
+        let invalid = SigningConfig::default().with_adapter_key("email-1", "not a key");
+        assert!(ServerConfig::builder(addr).signing(invalid).build().is_err());
+    }
+
+    #[test]
+    fn test_builder_with_audit() {
+        let addr: SocketAddr = "127.0.0.1:8080".parse().unwrap();
+        let audit = AuditConfig::default().with_file(AuditFileConfig::new("audit.jsonl"));
+        let config = ServerConfig::builder(addr)
+            .audit(audit.clone())
+            .build()
+            .unwrap();
+        assert_eq!(config.audit, audit);
+
+        let invalid =
+            AuditConfig::default().with_file(AuditFileConfig::new("audit.jsonl").with_max_bytes(0));
+        assert!(ServerConfig::builder(addr).audit(invalid).build().is_err());
+
+        // Publishing needs ACL rules on the topic, which need authentication
+        let publish = AuditConfig::default().with_publish(true);
+        let acl = AclConfig::default().with_rule(
+            AclRule::new(AclSubject::ClientId("auditor".to_string()))
+                .with_subscribe("system.audit"),
+        );
+        let builder = || ServerConfig::builder(addr).audit(publish.clone());
+        assert!(builder().build().is_err());
+        assert!(builder().acl(acl.clone()).build().is_err());
+        assert!(builder()
+            .acl(acl)
+            .auth(AuthConfig::require_api_key(vec!["key".to_string()]))
+            .build()
+            .is_ok());
+    }
+
     #[test]
     fn test_builder_with_server_name() {
         let addr: SocketAddr = "127.0.0.1:8080".parse().unwrap();
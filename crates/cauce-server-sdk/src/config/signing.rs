@@ -0,0 +1,124 @@
+//! Signal provenance configuration.
+//!
+//! This module defines the adapter public keys that signals are verified
+//! against. Verification is done by
+//! [`AdapterKeys`](crate::signing::AdapterKeys).
+
+use cauce_core::signing::VerifyingKey;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ServerError, ServerResult};
+
+/// An adapter's Ed25519 public key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdapterKey {
+    /// The adapter ID signals name in `source.adapter_id`.
+    pub adapter_id: String,
+    /// Base64-encoded Ed25519 public key.
+    pub public_key: String,
+}
+
+impl AdapterKey {
+    /// Creates a key for `adapter_id`.
+    pub fn new(adapter_id: impl Into<String>, public_key: impl Into<String>) -> Self {
+        Self {
+            adapter_id: adapter_id.into(),
+            public_key: public_key.into(),
+        }
+    }
+}
+
+/// Signal provenance configuration.
+///
+/// Signals claiming to come from an adapter listed here must be signed by
+/// one of its keys. An adapter may be listed more than once to rotate keys.
+///
+/// # Example
+///
+/// ```
+/// use cauce_server_sdk::config::SigningConfig;
+///
+/// let signing = SigningConfig::default()
+///     .with_adapter_key("email-adapter-1", "11qYAYKxCrfVS/7TyWQHOg7hcvPapiMlrwIaaPcHURo=");
+///
+/// assert!(signing.is_enabled());
+/// assert!(signing.validate().is_ok());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SigningConfig {
+    /// Registered adapter public keys.
+    #[serde(default)]
+    pub adapter_keys: Vec<AdapterKey>,
+}
+
+impl SigningConfig {
+    /// Register `public_key` for `adapter_id`.
+    pub fn with_adapter_key(
+        mut self,
+        adapter_id: impl Into<String>,
+        public_key: impl Into<String>,
+    ) -> Self {
+        self.adapter_keys
+            .push(AdapterKey::new(adapter_id, public_key));
+        self
+    }
+
+    /// Check if any adapter keys are registered.
+    pub fn is_enabled(&self) -> bool {
+        !self.adapter_keys.is_empty()
+    }
+
+    /// Validate the registered keys.
+    pub fn validate(&self) -> ServerResult<()> {
+        for key in &self.adapter_keys {
+            if key.adapter_id.is_empty() {
+                return Err(ServerError::config_error(
+                    "adapter key with empty adapter_id",
+                ));
+            }
+            VerifyingKey::from_base64(&key.public_key).map_err(|e| {
+                ServerError::config_error(format!(
+                    "invalid public key for adapter '{}': {}",
+                    key.adapter_id, e
+                ))
+            })?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cauce_core::signing::SigningKey;
+
+    #[test]
+    fn test_default_is_disabled() {
+        assert!(!SigningConfig::default().is_enabled());
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let key = SigningKey::generate().verifying_key().to_base64();
+        let json = format!(
+            r#"{{ "adapter_keys": [{{ "adapter_id": "email-1", "public_key": "{}" }}] }}"#,
+            key
+        );
+        let config: SigningConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            config,
+            SigningConfig::default().with_adapter_key("email-1", key)
+        );
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_key() {
+        let config = SigningConfig::default().with_adapter_key("email-1", "c2hvcnQ=");
+        assert!(config.validate().is_err());
+
+        let key = SigningKey::generate().verifying_key().to_base64();
+        let config = SigningConfig::default().with_adapter_key("", key);
+        assert!(config.validate().is_err());
+    }
+}
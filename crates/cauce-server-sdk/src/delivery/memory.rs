@@ -238,6 +238,7 @@ mod tests {
             payload: Payload::new(json!({"text": "hello"}), "application/json"),
             metadata: None,
             encrypted: None,
+            signature: None,
         }
     }
 
@@ -203,6 +203,7 @@ mod tests {
             payload: Payload::new(json!({"text": "hello"}), "application/json"),
             metadata: None,
             encrypted: None,
+            signature: None,
         }
     }
 
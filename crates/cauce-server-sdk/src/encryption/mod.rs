@@ -0,0 +1,433 @@
+//! End-to-end encryption enforcement.
+//!
+//! The hub never decrypts payloads, but it can insist that they are
+//! encrypted. [`EncryptionPolicy`] rejects plaintext publishes to topics
+//! marked as encryption-required in [`EncryptionConfig`], and subscriptions
+//! that could receive such topics without registering a public key.
+//!
+//! [`KeyDirectory`] lists the public keys of a topic's current subscribers
+//! so publishers know who to encrypt to. It is answered over the
+//! `cauce.keys.list` method.
+
+use std::sync::Arc;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use cauce_core::methods::{PublishMessage, PublishRequest, RecipientKey, SubscribeRequest};
+use cauce_core::{
+    CauceError, Encrypted, EncryptionAlgorithm, TopicMatcher, ENCRYPTED_CONTENT_TYPE,
+};
+
+use crate::config::EncryptionConfig;
+use crate::error::{ServerError, ServerResult};
+use crate::subscription::SubscriptionManager;
+
+/// Length of an X25519 public key in bytes.
+const PUBLIC_KEY_LEN: usize = 32;
+
+/// Smallest possible ciphertext: an ephemeral public key and an
+/// authentication tag around an empty payload.
+const MIN_CIPHERTEXT_LEN: usize = PUBLIC_KEY_LEN + 16;
+
+/// Enforces which topics must carry encrypted payloads.
+///
+/// With no required topics configured, plaintext is accepted everywhere,
+/// but encryption envelopes are still checked for well-formedness.
+///
+/// # Example
+///
+/// ```ignore
+/// let policy = EncryptionPolicy::new(
+///     EncryptionConfig::default().with_required_topic("signal.health.**"),
+/// );
+///
+/// policy.check_publish(&request)?;
+/// policy.check_subscribe(&subscribe_request)?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct EncryptionPolicy {
+    config: EncryptionConfig,
+}
+
+impl EncryptionPolicy {
+    /// Creates a policy enforcing `config`.
+    pub fn new(config: EncryptionConfig) -> Self {
+        Self { config }
+    }
+
+    /// Returns true if any topic requires encryption.
+    pub fn is_enabled(&self) -> bool {
+        self.config.is_enabled()
+    }
+
+    /// Returns true if messages published to `topic` must be encrypted.
+    pub fn requires_encryption(&self, topic: &str) -> bool {
+        self.config
+            .required_topics
+            .iter()
+            .any(|pattern| TopicMatcher::matches(topic, pattern))
+    }
+
+    /// Checks that a publish request satisfies the policy.
+    ///
+    /// Fails with `EncryptionRequired` if the topic requires encryption and
+    /// the message is plaintext, or `InvalidEncryption` if the message
+    /// carries a malformed envelope or a plaintext payload alongside it.
+    ///
+    /// An encrypted signal's payload must be the `null` placeholder with
+    /// content type [`ENCRYPTED_CONTENT_TYPE`], and an encrypted action's
+    /// payload must be `null`, so the envelope can't vouch for data sent
+    /// in the clear.
+    pub fn check_publish(&self, request: &PublishRequest) -> ServerResult<()> {
+        let (encrypted, placeholder) = match &request.message {
+            PublishMessage::Signal(signal) => (
+                signal.encrypted.as_ref(),
+                signal.payload.raw.is_null()
+                    && signal.payload.content_type == ENCRYPTED_CONTENT_TYPE,
+            ),
+            PublishMessage::Action(action) => {
+                (action.encrypted.as_ref(), action.action.payload.is_null())
+            }
+        };
+        match encrypted {
+            Some(_) if !placeholder => Err(invalid_encryption(
+                "an encrypted message must not carry a plaintext payload",
+            )),
+            Some(envelope) => validate_envelope(envelope),
+            None if self.requires_encryption(&request.topic) => {
+                Err(encryption_required(&request.topic))
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Checks that a subscribe request satisfies the policy.
+    ///
+    /// A subscription with encryption enabled must register a valid public
+    /// key. One without must not include a pattern that can match an
+    /// encryption-required topic, so `signal.**` is rejected when
+    /// `signal.health.*` requires encryption.
+    pub fn check_subscribe(&self, request: &SubscribeRequest) -> ServerResult<()> {
+        if let Some(e2e) = request.e2e.as_ref().filter(|e2e| e2e.enabled) {
+            let key = e2e
+                .public_key
+                .as_deref()
+                .ok_or_else(|| invalid_encryption("missing public key"))?;
+            return validate_public_key(key);
+        }
+        match request.topics.iter().find(|pattern| {
+            self.config
+                .required_topics
+                .iter()
+                .any(|required| TopicMatcher::patterns_overlap(required, pattern))
+        }) {
+            Some(pattern) => Err(encryption_required(pattern)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Directory of the public keys subscribers registered for encryption.
+///
+/// Keys are read from the active subscriptions in the
+/// [`SubscriptionManager`], so a key leaves the directory as soon as its
+/// subscription is revoked or expires.
+pub struct KeyDirectory<S: SubscriptionManager> {
+    subscriptions: Arc<S>,
+}
+
+impl<S: SubscriptionManager> KeyDirectory<S> {
+    /// Creates a directory over `subscriptions`.
+    pub fn new(subscriptions: Arc<S>) -> Self {
+        Self { subscriptions }
+    }
+
+    /// Returns the keys of every active subscription matching `topic`
+    /// that has encryption enabled, ordered by subscription ID.
+    pub async fn recipients(&self, topic: &str) -> ServerResult<Vec<RecipientKey>> {
+        let mut recipients: Vec<RecipientKey> = self
+            .subscriptions
+            .get_subscriptions_for_topic(topic)
+            .await?
+            .into_iter()
+            .filter_map(|info| {
+                let key = info.e2e_public_key()?.to_string();
+                let algorithms = info
+                    .e2e
+                    .map(|e2e| e2e.supported_algorithms)
+                    .unwrap_or_default();
+                Some(
+                    algorithms
+                        .into_iter()
+                        .fold(RecipientKey::new(info.subscription_id, key), |r, alg| {
+                            r.with_algorithm(alg)
+                        }),
+                )
+            })
+            .collect();
+        recipients.sort_by(|a, b| a.subscription_id.cmp(&b.subscription_id));
+        Ok(recipients)
+    }
+}
+
+impl<S: SubscriptionManager> Clone for KeyDirectory<S> {
+    fn clone(&self) -> Self {
+        Self {
+            subscriptions: Arc::clone(&self.subscriptions),
+        }
+    }
+}
+
+fn encryption_required(topic: &str) -> ServerError {
+    CauceError::EncryptionRequired {
+        topic: topic.to_string(),
+    }
+    .into()
+}
+
+fn invalid_encryption(reason: impl Into<String>) -> ServerError {
+    CauceError::InvalidEncryption {
+        reason: reason.into(),
+    }
+    .into()
+}
+
+fn decode(field: &str, value: &str) -> ServerResult<Vec<u8>> {
+    STANDARD
+        .decode(value)
+        .map_err(|e| invalid_encryption(format!("{} is not valid base64: {}", field, e)))
+}
+
+fn validate_public_key(key: &str) -> ServerResult<()> {
+    let len = decode("public key", key)?.len();
+    if len != PUBLIC_KEY_LEN {
+        return Err(invalid_encryption(format!(
+            "public key must be {} bytes, got {}",
+            PUBLIC_KEY_LEN, len
+        )));
+    }
+    Ok(())
+}
+
+/// Checks the parts of an envelope the hub can see without the secret key.
+fn validate_envelope(envelope: &Encrypted) -> ServerResult<()> {
+    validate_public_key(&envelope.recipient_public_key)?;
+
+    let nonce_len = match envelope.algorithm {
+        EncryptionAlgorithm::A256Gcm => 12,
+        EncryptionAlgorithm::X25519XSalsa20Poly1305 | EncryptionAlgorithm::XChaCha20Poly1305 => 24,
+    };
+    let len = decode("nonce", &envelope.nonce)?.len();
+    if len != nonce_len {
+        return Err(invalid_encryption(format!(
+            "nonce must be {} bytes, got {}",
+            nonce_len, len
+        )));
+    }
+
+    let len = decode("ciphertext", &envelope.ciphertext)?.len();
+    if len < MIN_CIPHERTEXT_LEN {
+        return Err(invalid_encryption(format!(
+            "ciphertext must be at least {} bytes, got {}",
+            MIN_CIPHERTEXT_LEN, len
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::subscription::InMemorySubscriptionManager;
+    use cauce_core::methods::{E2eConfig, SubscribeRequest};
+    use cauce_core::{Payload, Signal, Source, Topic};
+    use chrono::Utc;
+    use serde_json::json;
+
+    fn key() -> String {
+        STANDARD.encode([7u8; 32])
+    }
+
+    fn envelope() -> Encrypted {
+        Encrypted::new(
+            EncryptionAlgorithm::X25519XSalsa20Poly1305,
+            key(),
+            STANDARD.encode([1u8; 24]),
+            STANDARD.encode([2u8; 64]),
+        )
+    }
+
+    fn signal(topic: &str, encrypted: Option<Encrypted>) -> PublishRequest {
+        let signal = Signal {
+            id: "sig_1".to_string(),
+            version: "1.0".to_string(),
+            timestamp: Utc::now(),
+            source: Source::new("email", "adapter-1", "msg-1"),
+            topic: Topic::new_unchecked(topic),
+            payload: Payload::new(json!(null), ENCRYPTED_CONTENT_TYPE),
+            metadata: None,
+            encrypted,
+            signature: None,
+        };
+        PublishRequest::signal(topic, signal)
+    }
+
+    fn policy() -> EncryptionPolicy {
+        EncryptionPolicy::new(EncryptionConfig::default().with_required_topic("signal.health.*"))
+    }
+
+    fn code(err: ServerError) -> i32 {
+        match err {
+            ServerError::ProtocolError(e) => e.code(),
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_check_publish() {
+        let policy = policy();
+        assert!(policy.is_enabled());
+        assert!(policy.requires_encryption("signal.health.heart"));
+        assert!(!policy.requires_encryption("signal.email.received"));
+
+        assert!(policy
+            .check_publish(&signal("signal.email.received", None))
+            .is_ok());
+        assert!(policy
+            .check_publish(&signal("signal.health.heart", Some(envelope())))
+            .is_ok());
+
+        let err = policy
+            .check_publish(&signal("signal.health.heart", None))
+            .unwrap_err();
+        assert_eq!(code(err), -32008);
+    }
+
+    #[test]
+    fn test_check_publish_rejects_envelope_with_plaintext_payload() {
+        let policy = policy();
+        let with_payload = |payload: Payload| {
+            let mut request = signal("signal.health.heart", Some(envelope()));
+            if let PublishMessage::Signal(signal) = &mut request.message {
+                signal.payload = payload;
+            }
+            request
+        };
+
+        // The plaintext rides along with a valid envelope
+        let leaky = with_payload(Payload::new(json!({"bpm": 72}), "application/json"));
+        assert_eq!(code(policy.check_publish(&leaky).unwrap_err()), -32009);
+
+        // Labelling it as encrypted doesn't help
+        let mislabelled = with_payload(Payload::new(json!({"bpm": 72}), ENCRYPTED_CONTENT_TYPE));
+        assert_eq!(code(policy.check_publish(&mislabelled).unwrap_err()), -32009);
+
+        let unlabelled = with_payload(Payload::new(json!(null), "application/json"));
+        assert_eq!(code(policy.check_publish(&unlabelled).unwrap_err()), -32009);
+
+        // Actions must leave their payload empty too
+        let mut action = cauce_core::Action::builder()
+            .topic(Topic::new_unchecked("action.health.notify"))
+            .action(cauce_core::ActionBody::new(
+                cauce_core::ActionType::Send,
+                json!({"text": "hi"}),
+            ))
+            .encrypted(envelope())
+            .build()
+            .unwrap();
+        let request = PublishRequest::action("action.health.notify", action.clone());
+        assert_eq!(code(policy.check_publish(&request).unwrap_err()), -32009);
+
+        action.action.payload = json!(null);
+        let request = PublishRequest::action("action.health.notify", action);
+        assert!(policy.check_publish(&request).is_ok());
+    }
+
+    #[test]
+    fn test_check_publish_rejects_malformed_envelope() {
+        let policy = EncryptionPolicy::default();
+        assert!(!policy.is_enabled());
+
+        let mut short_key = envelope();
+        short_key.recipient_public_key = STANDARD.encode([7u8; 16]);
+        let mut bad_nonce = envelope();
+        bad_nonce.algorithm = EncryptionAlgorithm::A256Gcm;
+        let mut short_ciphertext = envelope();
+        short_ciphertext.ciphertext = STANDARD.encode([2u8; 8]);
+        let mut not_base64 = envelope();
+        not_base64.nonce = "not base64!".to_string();
+
+        for envelope in [short_key, bad_nonce, short_ciphertext, not_base64] {
+            let err = policy
+                .check_publish(&signal("signal.email.received", Some(envelope)))
+                .unwrap_err();
+            assert_eq!(code(err), -32009);
+        }
+    }
+
+    #[test]
+    fn test_check_subscribe() {
+        let policy = policy();
+        let plaintext = |topic: &str| SubscribeRequest::new(vec![topic.to_string()]);
+
+        assert!(policy.check_subscribe(&plaintext("signal.email.*")).is_ok());
+        assert!(policy.check_subscribe(&plaintext("action.**")).is_ok());
+        for topic in ["signal.health.heart", "signal.*.heart", "signal.**", "**"] {
+            let err = policy.check_subscribe(&plaintext(topic)).unwrap_err();
+            assert_eq!(code(err), -32008, "{}", topic);
+        }
+
+        let encrypted = plaintext("signal.**").with_e2e(E2eConfig::enabled(key()));
+        assert!(policy.check_subscribe(&encrypted).is_ok());
+
+        let bad_key = plaintext("signal.**").with_e2e(E2eConfig::enabled("c2hvcnQ="));
+        assert_eq!(code(policy.check_subscribe(&bad_key).unwrap_err()), -32009);
+    }
+
+    #[tokio::test]
+    async fn test_key_directory() {
+        let manager = Arc::new(InMemorySubscriptionManager::default());
+        let e2e = E2eConfig::enabled(key()).with_algorithm(EncryptionAlgorithm::A256Gcm);
+        let encrypted = manager
+            .subscribe(
+                "client-a",
+                "sess_a",
+                SubscribeRequest::new(vec!["signal.health.*".to_string()]).with_e2e(e2e),
+            )
+            .await
+            .unwrap();
+        manager
+            .subscribe(
+                "client-b",
+                "sess_b",
+                SubscribeRequest::new(vec!["signal.**".to_string()]),
+            )
+            .await
+            .unwrap();
+
+        let directory = KeyDirectory::new(Arc::clone(&manager));
+        let recipients = directory.recipients("signal.health.heart").await.unwrap();
+        assert_eq!(recipients.len(), 1);
+        assert_eq!(recipients[0].subscription_id, encrypted.subscription_id);
+        assert_eq!(recipients[0].public_key, key());
+        assert_eq!(
+            recipients[0].supported_algorithms,
+            vec![EncryptionAlgorithm::A256Gcm]
+        );
+        assert!(directory
+            .recipients("signal.email.received")
+            .await
+            .unwrap()
+            .is_empty());
+
+        manager
+            .unsubscribe(&encrypted.subscription_id)
+            .await
+            .unwrap();
+        assert!(directory
+            .recipients("signal.health.heart")
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
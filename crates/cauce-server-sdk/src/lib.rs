@@ -63,19 +63,23 @@ pub mod config;
 pub mod error;
 
 // Manager modules (to be implemented)
+pub mod audit;
 pub mod auth;
 pub mod delivery;
+pub mod encryption;
 pub mod rate_limit;
 pub mod routing;
 pub mod server;
 pub mod session;
+pub mod signing;
 pub mod subscription;
 pub mod transport;
 
 // Re-export main types
 pub use config::{
-    AuthConfig, LimitsConfig, RedeliveryConfig, ServerConfig, ServerConfigBuilder,
-    TransportsConfig,
+    AclConfig, AclRule, AclSubject, AdapterKey, AuditConfig, AuditFileConfig, AuthConfig,
+    EncryptionConfig, LimitsConfig, RedeliveryConfig, ServerConfig, ServerConfigBuilder,
+    SigningConfig, TransportsConfig,
 };
 pub use error::{ServerError, ServerResult};
 
@@ -83,18 +87,42 @@ pub use error::{ServerError, ServerResult};
 pub use subscription::{InMemorySubscriptionManager, SubscriptionManager, TopicTrie};
 
 // Re-export routing types
-pub use routing::{DefaultMessageRouter, DeliveryResult, MessageRouter, RouteResult};
+pub use routing::{
+    is_addressed_to, DefaultMessageRouter, DeliveryResult, MessageRouter, RouteResult,
+};
 
 // Re-export delivery types
 pub use delivery::{
     DeliveryStatus, DeliveryTracker, InMemoryDeliveryTracker, PendingDelivery, RedeliveryScheduler,
 };
 
+// Re-export encryption types
+pub use encryption::{EncryptionPolicy, KeyDirectory};
+
+// Re-export signing types
+pub use signing::AdapterKeys;
+
+// Re-export audit types
+#[cfg(feature = "sqlite")]
+pub use audit::SqliteSink;
+pub use audit::{
+    verify_chain, AuditAction, AuditEvent, AuditLog, AuditOutcome, AuditRecord, AuditSink,
+    AuditTail, ChainError, JsonLinesSink, AUDIT_TOPIC,
+};
+
 // Re-export session types
-pub use session::{InMemorySessionManager, SessionInfo, SessionManager};
+pub use session::{
+    InMemorySessionManager, SessionInfo, SessionManager, SessionTokenClaims, SessionTokenSigner,
+    SESSION_TOKEN_HEADER,
+};
 
 // Re-export auth types
-pub use auth::{AuthInfo, AuthLayer, AuthMethod, AuthMiddleware, AuthResult, AuthValidator, InMemoryAuthValidator};
+pub use auth::{
+    ApiKeyRecord, ApiKeyStore, AuthInfo, AuthLayer, AuthLockout, AuthMethod, AuthMiddleware,
+    AuthResult, AuthValidator, InMemoryAuthValidator, IssuedApiKey, JwtAuthValidator,
+    LockoutAlert, LockoutConfig, LockoutSubject, NewApiKey, Principal, TokenClaims, TopicAcl,
+    LOCKOUT_ALERT_TOPIC,
+};
 
 // Re-export rate limiting types
 pub use rate_limit::{
@@ -18,6 +18,7 @@
 //! ```
 
 use std::future::Future;
+use std::net::SocketAddr;
 use std::pin::Pin;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
@@ -26,7 +27,8 @@ use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
 use axum::body::Body;
-use axum::http::{Request, StatusCode};
+use axum::extract::ConnectInfo;
+use axum::http::{HeaderMap, Request, StatusCode};
 use axum::response::{IntoResponse, Response};
 use dashmap::DashMap;
 use serde::{Deserialize, Serialize};
@@ -450,17 +452,25 @@ pub enum KeyExtractor {
     Custom(KeyExtractorFn),
 }
 
+/// Returns the client's IP address: the first `X-Forwarded-For` entry, or
+/// else the peer address of the connection.
+pub(crate) fn client_ip(headers: &HeaderMap, peer: Option<SocketAddr>) -> Option<String> {
+    headers
+        .get("X-Forwarded-For")
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.split(',').next().unwrap_or("").trim().to_string())
+        .or_else(|| peer.map(|addr| addr.ip().to_string()))
+}
+
 impl KeyExtractor {
-    fn extract(&self, request: &Request<Body>) -> String {
+    pub(crate) fn extract(&self, request: &Request<Body>) -> String {
         match self {
             Self::IpAddress => {
-                // Try X-Forwarded-For first, then fall back to connection info
-                request
-                    .headers()
-                    .get("X-Forwarded-For")
-                    .and_then(|v| v.to_str().ok())
-                    .map(|s| s.split(',').next().unwrap_or("").trim().to_string())
-                    .unwrap_or_else(|| "unknown".to_string())
+                let peer = request
+                    .extensions()
+                    .get::<ConnectInfo<SocketAddr>>()
+                    .map(|ConnectInfo(addr)| *addr);
+                client_ip(request.headers(), peer).unwrap_or_else(|| "unknown".to_string())
             }
             Self::Header(name) => request
                 .headers()
@@ -609,25 +619,7 @@ where
 
             if !result.allowed {
                 debug!("Rate limited: key={}", key);
-
-                let retry_after = result.retry_after_ms.unwrap_or(1000);
-                let error_body = serde_json::json!({
-                    "error": {
-                        "code": "rate_limited",
-                        "message": "Too many requests",
-                        "retry_after_ms": retry_after
-                    }
-                });
-
-                return Ok((
-                    StatusCode::TOO_MANY_REQUESTS,
-                    [
-                        ("Content-Type", "application/json"),
-                        ("Retry-After", &format!("{}", retry_after / 1000)),
-                    ],
-                    error_body.to_string(),
-                )
-                    .into_response());
+                return Ok(too_many_requests(result.retry_after_ms.unwrap_or(1000)));
             }
 
             // Continue to inner service
@@ -636,6 +628,27 @@ where
     }
 }
 
+/// Builds a `429 Too Many Requests` response with a `Retry-After` header.
+pub(crate) fn too_many_requests(retry_after_ms: u64) -> Response {
+    let error_body = serde_json::json!({
+        "error": {
+            "code": "rate_limited",
+            "message": "Too many requests",
+            "retry_after_ms": retry_after_ms
+        }
+    });
+
+    (
+        StatusCode::TOO_MANY_REQUESTS,
+        [
+            ("Content-Type", "application/json"),
+            ("Retry-After", &format!("{}", retry_after_ms / 1000)),
+        ],
+        error_body.to_string(),
+    )
+        .into_response()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -872,6 +885,16 @@ mod tests {
             .unwrap();
         let key = extractor.extract(&request);
         assert_eq!(key, "192.168.1.1");
+
+        // With connection info
+        let mut request = Request::builder()
+            .uri("/test")
+            .body(Body::empty())
+            .unwrap();
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from(([10, 0, 0, 7], 4321))));
+        assert_eq!(extractor.extract(&request), "10.0.0.7");
     }
 
     #[test]
@@ -1,11 +1,13 @@
 //! Default message router implementation.
 
 use async_trait::async_trait;
-use cauce_core::methods::{PublishMessage, PublishRequest, SignalDelivery, SubscriptionInfo};
-use cauce_core::Signal;
+use cauce_core::methods::{
+    ActionDelivery, PublishMessage, PublishRequest, SignalDelivery, SubscriptionInfo,
+};
+use cauce_core::{Action, Signal};
 use std::sync::Arc;
 
-use super::{MessageRouter, RouteResult};
+use super::{is_addressed_to, MessageRouter, RouteResult};
 use crate::error::{ServerError, ServerResult};
 use crate::subscription::SubscriptionManager;
 
@@ -45,6 +47,33 @@ impl<S: SubscriptionManager> DefaultMessageRouter<S> {
             }),
         }
     }
+
+    /// Fails if the message is encrypted to a key `subscription` did not
+    /// register.
+    fn check_addressed_to(
+        request: &PublishRequest,
+        subscription: &SubscriptionInfo,
+    ) -> ServerResult<()> {
+        if is_addressed_to(&request.message, subscription) {
+            return Ok(());
+        }
+        Err(ServerError::InvalidParams {
+            message: format!(
+                "message is encrypted for a recipient other than {}",
+                subscription.subscription_id
+            ),
+        })
+    }
+
+    /// Extracts the action from a publish message.
+    fn extract_action(message: &PublishMessage) -> ServerResult<Action> {
+        match message {
+            PublishMessage::Action(action) => Ok(action.clone()),
+            PublishMessage::Signal(_) => Err(ServerError::InvalidParams {
+                message: "signals cannot be delivered as actions".to_string(),
+            }),
+        }
+    }
 }
 
 #[async_trait]
@@ -63,6 +92,7 @@ impl<S: SubscriptionManager> MessageRouter for DefaultMessageRouter<S> {
         // Collect subscription IDs
         let subscription_ids: Vec<String> = subscriptions
             .iter()
+            .filter(|s| is_addressed_to(&request.message, s))
             .map(|s| s.subscription_id.clone())
             .collect();
 
@@ -78,10 +108,23 @@ impl<S: SubscriptionManager> MessageRouter for DefaultMessageRouter<S> {
     fn create_delivery(
         &self,
         request: &PublishRequest,
-        _subscription: &SubscriptionInfo,
+        subscription: &SubscriptionInfo,
     ) -> ServerResult<SignalDelivery> {
+        Self::check_addressed_to(request, subscription)?;
         let signal = Self::extract_signal(&request.message)?;
-        Ok(SignalDelivery::new(&request.topic, signal))
+        Ok(SignalDelivery::new(&request.topic, signal)
+            .with_subscription_id(&subscription.subscription_id))
+    }
+
+    fn create_action_delivery(
+        &self,
+        request: &PublishRequest,
+        subscription: &SubscriptionInfo,
+    ) -> ServerResult<ActionDelivery> {
+        Self::check_addressed_to(request, subscription)?;
+        let action = Self::extract_action(&request.message)?;
+        Ok(ActionDelivery::new(&request.topic, action)
+            .with_subscription_id(&subscription.subscription_id))
     }
 }
 
@@ -90,7 +133,7 @@ mod tests {
     use super::*;
     use crate::subscription::InMemorySubscriptionManager;
     use cauce_core::methods::SubscribeRequest;
-    use cauce_core::types::{ActionBody, ActionType, Payload, Source, Topic};
+    use cauce_core::types::{ActionBody, ActionType, Metadata, Payload, Source, Topic};
     use chrono::{DateTime, Utc};
     use serde_json::json;
 
@@ -106,6 +149,7 @@ mod tests {
             payload: Payload::new(json!({"text": "hello"}), "application/json"),
             metadata: None,
             encrypted: None,
+            signature: None,
         }
     }
 
@@ -120,6 +164,7 @@ mod tests {
             action: ActionBody::new(ActionType::Send, json!({"to": "test@example.com"})),
             context: None,
             encrypted: None,
+            signature: None,
         }
     }
 
@@ -252,6 +297,39 @@ mod tests {
         let delivery = router.create_delivery(&request, &subscription).unwrap();
         assert_eq!(delivery.topic, "signal.email.received");
         assert_eq!(delivery.signal.id, signal.id);
+        assert_eq!(
+            delivery.subscription_id.as_deref(),
+            Some(sub.subscription_id.as_str())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_delivery_preserves_correlation() {
+        let (router, manager) = setup_router().await;
+
+        let sub = manager
+            .subscribe(
+                "client_1",
+                "session_1",
+                SubscribeRequest::single("signal.email.*"),
+            )
+            .await
+            .unwrap();
+        let subscription = manager
+            .get_subscription(&sub.subscription_id)
+            .await
+            .unwrap()
+            .unwrap();
+
+        // An adapter's answer to a correlated request
+        let mut signal = create_test_signal();
+        signal.metadata = Some(Metadata::reply_to("act_test_123").correlation_id("corr-1"));
+        let request = PublishRequest::signal("signal.email.action_result", signal);
+
+        let delivery = router.create_delivery(&request, &subscription).unwrap();
+        let metadata = delivery.signal.metadata.unwrap();
+        assert_eq!(metadata.correlation_id.as_deref(), Some("corr-1"));
+        assert_eq!(metadata.in_reply_to.as_deref(), Some("act_test_123"));
     }
 
     #[tokio::test]
@@ -280,6 +358,99 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_create_action_delivery() {
+        let (router, manager) = setup_router().await;
+
+        let sub = manager
+            .subscribe(
+                "adapter_1",
+                "session_1",
+                SubscribeRequest::single("action.email.*"),
+            )
+            .await
+            .unwrap();
+        let subscription = manager
+            .get_subscription(&sub.subscription_id)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let action = create_test_action();
+        let request = PublishRequest::action("action.email.send", action.clone());
+        let delivery = router
+            .create_action_delivery(&request, &subscription)
+            .unwrap();
+        assert_eq!(delivery.topic, "action.email.send");
+        assert_eq!(delivery.action.id, action.id);
+        assert_eq!(
+            delivery.subscription_id.as_deref(),
+            Some(sub.subscription_id.as_str())
+        );
+
+        let signal = PublishRequest::signal("signal.email.received", create_test_signal());
+        assert!(router
+            .create_action_delivery(&signal, &subscription)
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_messages_only_reach_their_recipient() {
+        use cauce_core::{E2eConfig, Encrypted, EncryptionAlgorithm};
+
+        let (router, manager) = setup_router().await;
+        let recipient_key = "BwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwc=";
+        let recipient = manager
+            .subscribe(
+                "client_1",
+                "session_1",
+                SubscribeRequest::single("signal.email.*")
+                    .with_e2e(E2eConfig::enabled(recipient_key)),
+            )
+            .await
+            .unwrap();
+        let other = manager
+            .subscribe(
+                "client_2",
+                "session_2",
+                SubscribeRequest::single("signal.email.*")
+                    .with_e2e(E2eConfig::enabled("CAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAg=")),
+            )
+            .await
+            .unwrap();
+        let plaintext = manager
+            .subscribe(
+                "client_3",
+                "session_3",
+                SubscribeRequest::single("signal.email.*"),
+            )
+            .await
+            .unwrap();
+
+        let mut signal = create_test_signal();
+        signal.encrypted = Some(Encrypted::new(
+            EncryptionAlgorithm::X25519XSalsa20Poly1305,
+            recipient_key,
+            "AQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEB",
+            "AgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgI=",
+        ));
+        let request = PublishRequest::signal("signal.email.received", signal);
+
+        let result = router.route(&request).await.unwrap();
+        assert_eq!(result.subscription_ids.len(), 1);
+        assert_eq!(result.subscription_ids[0], recipient.subscription_id);
+
+        for (sub, addressed) in [(recipient, true), (other, false), (plaintext, false)] {
+            let subscription = manager
+                .get_subscription(&sub.subscription_id)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(is_addressed_to(&request.message, &subscription), addressed);
+            assert_eq!(router.create_delivery(&request, &subscription).is_ok(), addressed);
+        }
+    }
+
     #[test]
     fn test_route_result_new() {
         let result = RouteResult::new(vec!["sub_1".to_string(), "sub_2".to_string()]);
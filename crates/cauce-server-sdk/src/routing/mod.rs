@@ -8,10 +8,31 @@ mod default;
 pub use default::DefaultMessageRouter;
 
 use async_trait::async_trait;
-use cauce_core::methods::{PublishRequest, SignalDelivery, SubscriptionInfo};
+use cauce_core::methods::{
+    ActionDelivery, PublishMessage, PublishRequest, SignalDelivery, SubscriptionInfo,
+};
 
 use crate::error::ServerResult;
 
+/// Returns true if `message` may be delivered to `subscription`.
+///
+/// An encrypted message is addressed to the public key in its envelope, so
+/// it only goes to subscriptions that registered that key. Publishers send
+/// one copy per recipient key, and this keeps each copy away from everyone
+/// else. Plaintext messages go to every matching subscription.
+pub fn is_addressed_to(message: &PublishMessage, subscription: &SubscriptionInfo) -> bool {
+    let encrypted = match message {
+        PublishMessage::Signal(signal) => signal.encrypted.as_ref(),
+        PublishMessage::Action(action) => action.encrypted.as_ref(),
+    };
+    match encrypted {
+        Some(envelope) => {
+            subscription.e2e_public_key() == Some(envelope.recipient_public_key.as_str())
+        }
+        None => true,
+    }
+}
+
 /// Result of routing a message.
 #[derive(Debug, Clone)]
 pub struct RouteResult {
@@ -132,6 +153,22 @@ pub trait MessageRouter: Send + Sync + 'static {
     ///
     /// The signal delivery ready for transmission.
     fn create_delivery(&self, request: &PublishRequest, subscription: &SubscriptionInfo) -> ServerResult<SignalDelivery>;
+
+    /// Creates an action delivery from a publish request for a specific subscription.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The publish request; its message must be an action
+    /// * `subscription` - The target subscription
+    ///
+    /// # Returns
+    ///
+    /// The action delivery ready for transmission.
+    fn create_action_delivery(
+        &self,
+        request: &PublishRequest,
+        subscription: &SubscriptionInfo,
+    ) -> ServerResult<ActionDelivery>;
 }
 
 #[cfg(test)]
@@ -22,19 +22,36 @@ pub use state::SharedState;
 use std::future::Future;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 
+use axum::extract::{ConnectInfo, WebSocketUpgrade};
+use axum::http::HeaderMap;
 use axum::routing::{get, post};
-use axum::Router;
+use axum::{Extension, Router};
+use cauce_core::builders::SignalBuilder;
+use cauce_core::generate_message_id;
+use cauce_core::methods::PublishRequest;
+use cauce_core::types::{Source, Topic};
+use serde::Serialize;
 use tokio::net::TcpListener;
-use tracing::info;
-
-use crate::auth::{AuthMiddleware, AuthValidator, InMemoryAuthValidator};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{info, warn};
+
+use crate::audit::{AuditLog, AUDIT_TOPIC};
+use crate::auth::{
+    ApiKeyStore, AuthInfo, AuthLockout, AuthMiddleware, AuthValidator, InMemoryAuthValidator,
+    Principal, TokenClaims, TopicAcl, LOCKOUT_ALERT_TOPIC,
+};
 use crate::config::ServerConfig;
 use crate::delivery::{DeliveryTracker, InMemoryDeliveryTracker};
+use crate::encryption::{EncryptionPolicy, KeyDirectory};
 use crate::error::{ServerError, ServerResult};
-use crate::rate_limit::{InMemoryRateLimiter, RateLimitConfig, RateLimitMiddleware, RateLimiter};
+use crate::rate_limit::{
+    InMemoryRateLimiter, RateLimitConfig, RateLimitMiddleware, RateLimiter,
+};
 use crate::routing::{DefaultMessageRouter, MessageRouter};
-use crate::session::{InMemorySessionManager, SessionManager};
+use crate::session::{InMemorySessionManager, SessionManager, SessionTokenSigner};
+use crate::signing::AdapterKeys;
 use crate::subscription::{InMemorySubscriptionManager, SubscriptionManager};
 use crate::transport::{PollingHandler, SseHandler, WebSocketHandler, WebhookDelivery, WebhookDeliveryConfig};
 
@@ -64,6 +81,12 @@ where
     auth_validator: Arc<A>,
     rate_limiter: Arc<L>,
     webhook_delivery: Option<WebhookDelivery>,
+    acl: Arc<TopicAcl>,
+    lockout: Arc<AuthLockout>,
+    session_tokens: Arc<SessionTokenSigner>,
+    encryption: Arc<EncryptionPolicy>,
+    adapter_keys: Arc<AdapterKeys>,
+    audit: Arc<AuditLog>,
 }
 
 /// Type alias for a server with default components.
@@ -79,17 +102,34 @@ pub type DefaultCauceServer = CauceServer<
 impl DefaultCauceServer {
     /// Creates a new server with default in-memory components.
     pub fn new(config: ServerConfig) -> Self {
-        let subscription_manager = Arc::new(InMemorySubscriptionManager::default());
+        let acl = Arc::new(TopicAcl::new(config.acl.clone()));
+        let audit = Arc::new(AuditLog::from_config(&config.audit));
+        let subscription_manager = Arc::new(
+            InMemorySubscriptionManager::default()
+                .with_acl(Arc::clone(&acl))
+                .with_audit(Arc::clone(&audit)),
+        );
         let message_router = Arc::new(DefaultMessageRouter::new(Arc::clone(&subscription_manager)));
         let delivery_tracker = Arc::new(InMemoryDeliveryTracker::new(config.redelivery.clone()));
         let session_manager = Arc::new(InMemorySessionManager::default());
 
         // Set up auth validator with API keys from config
-        let auth_validator = InMemoryAuthValidator::new();
+        let mut auth_validator = InMemoryAuthValidator::new();
         for key in &config.auth.api_keys {
             auth_validator.add_api_key("default", key);
         }
+        if !config.auth.keys.is_empty() {
+            auth_validator = auth_validator
+                .with_key_store(ApiKeyStore::from_records(config.auth.keys.clone()));
+        }
         let auth_validator = Arc::new(auth_validator);
+        let lockout = Arc::new(AuthLockout::new(config.auth.lockout.clone()));
+        let encryption = Arc::new(EncryptionPolicy::new(config.encryption.clone()));
+        let adapter_keys = Arc::new(AdapterKeys::new(config.signing.clone()));
+        let session_tokens = match &config.auth.session_token_secret {
+            Some(secret) => SessionTokenSigner::hs256(secret),
+            None => SessionTokenSigner::random(),
+        };
 
         let rate_limiter = Arc::new(InMemoryRateLimiter::new(
             RateLimitConfig::default()
@@ -109,6 +149,12 @@ impl DefaultCauceServer {
             auth_validator,
             rate_limiter,
             webhook_delivery,
+            acl,
+            lockout,
+            session_tokens: Arc::new(session_tokens),
+            encryption,
+            adapter_keys,
+            audit,
         }
     }
 
@@ -141,6 +187,12 @@ where
             auth_validator: self.auth_validator,
             rate_limiter: self.rate_limiter,
             webhook_delivery: self.webhook_delivery,
+            acl: self.acl,
+            lockout: self.lockout,
+            session_tokens: self.session_tokens,
+            encryption: self.encryption,
+            adapter_keys: self.adapter_keys,
+            audit: self.audit,
         }
     }
 
@@ -158,6 +210,12 @@ where
             auth_validator: self.auth_validator,
             rate_limiter: self.rate_limiter,
             webhook_delivery: self.webhook_delivery,
+            acl: self.acl,
+            lockout: self.lockout,
+            session_tokens: self.session_tokens,
+            encryption: self.encryption,
+            adapter_keys: self.adapter_keys,
+            audit: self.audit,
         }
     }
 
@@ -175,6 +233,12 @@ where
             auth_validator: self.auth_validator,
             rate_limiter: self.rate_limiter,
             webhook_delivery: self.webhook_delivery,
+            acl: self.acl,
+            lockout: self.lockout,
+            session_tokens: self.session_tokens,
+            encryption: self.encryption,
+            adapter_keys: self.adapter_keys,
+            audit: self.audit,
         }
     }
 
@@ -192,6 +256,12 @@ where
             auth_validator: self.auth_validator,
             rate_limiter: self.rate_limiter,
             webhook_delivery: self.webhook_delivery,
+            acl: self.acl,
+            lockout: self.lockout,
+            session_tokens: self.session_tokens,
+            encryption: self.encryption,
+            adapter_keys: self.adapter_keys,
+            audit: self.audit,
         }
     }
 
@@ -209,6 +279,12 @@ where
             auth_validator: Arc::new(validator),
             rate_limiter: self.rate_limiter,
             webhook_delivery: self.webhook_delivery,
+            acl: self.acl,
+            lockout: self.lockout,
+            session_tokens: self.session_tokens,
+            encryption: self.encryption,
+            adapter_keys: self.adapter_keys,
+            audit: self.audit,
         }
     }
 
@@ -226,6 +302,12 @@ where
             auth_validator: self.auth_validator,
             rate_limiter: Arc::new(limiter),
             webhook_delivery: self.webhook_delivery,
+            acl: self.acl,
+            lockout: self.lockout,
+            session_tokens: self.session_tokens,
+            encryption: self.encryption,
+            adapter_keys: self.adapter_keys,
+            audit: self.audit,
         }
     }
 
@@ -254,13 +336,76 @@ where
         Arc::clone(&self.session_manager)
     }
 
+    /// Gets the topic access control built from the configuration.
+    ///
+    /// Custom subscription managers can use it to check approvals.
+    pub fn acl(&self) -> Arc<TopicAcl> {
+        Arc::clone(&self.acl)
+    }
+
+    /// Gets the failed authentication tracker built from the configuration.
+    pub fn lockout(&self) -> Arc<AuthLockout> {
+        Arc::clone(&self.lockout)
+    }
+
+    /// Sets the signer for session tokens issued in hello responses.
+    ///
+    /// Defaults to HMAC with
+    /// [`auth.session_token_secret`](crate::config::AuthConfig::session_token_secret),
+    /// or a random per-process secret if it is not set. Servers sharing a
+    /// session store should share a signer so tokens verify on any of them.
+    pub fn with_session_token_signer(mut self, signer: SessionTokenSigner) -> Self {
+        self.session_tokens = Arc::new(signer);
+        self
+    }
+
+    /// Gets the signer for session tokens.
+    pub fn session_token_signer(&self) -> Arc<SessionTokenSigner> {
+        Arc::clone(&self.session_tokens)
+    }
+
+    /// Gets the end-to-end encryption policy built from the configuration.
+    pub fn encryption(&self) -> Arc<EncryptionPolicy> {
+        Arc::clone(&self.encryption)
+    }
+
+    /// Gets the adapter keys signals are verified against.
+    ///
+    /// Keys registered at runtime take effect immediately.
+    pub fn adapter_keys(&self) -> Arc<AdapterKeys> {
+        Arc::clone(&self.adapter_keys)
+    }
+
+    /// Gets the audit log.
+    ///
+    /// Applications record their own administrative operations to it with
+    /// [`AuditEvent::admin`](crate::audit::AuditEvent::admin).
+    pub fn audit(&self) -> Arc<AuditLog> {
+        Arc::clone(&self.audit)
+    }
+
+    /// Gets the directory of subscriber public keys.
+    pub fn key_directory(&self) -> KeyDirectory<S> {
+        KeyDirectory::new(Arc::clone(&self.subscription_manager))
+    }
+
     /// Gets the webhook delivery handler.
     pub fn webhook_delivery(&self) -> Option<&WebhookDelivery> {
         self.webhook_delivery.as_ref()
     }
 
     /// Creates the axum Router for this server.
+    ///
+    /// Lockout alerts and audit records are published to their system
+    /// topics only while [`serve`](Self::serve) or
+    /// [`serve_with_shutdown`](Self::serve_with_shutdown) runs.
     pub fn router(&self) -> Router {
+        self.build_router().0
+    }
+
+    /// Creates the router, along with the WebSocket handler hub signals are
+    /// published through if WebSocket is enabled.
+    fn build_router(&self) -> RouterParts<S, R, D, M> {
         let transports = &self.config.transports;
 
         // Create shared state
@@ -272,43 +417,73 @@ where
         };
 
         let mut router = Router::new();
+        let mut websocket = None;
 
         // Add WebSocket handler
         if transports.websocket_enabled {
-            let ws_handler = Arc::new(WebSocketHandler::new(
-                Arc::clone(&self.subscription_manager),
-                Arc::clone(&self.message_router),
-                Arc::clone(&self.delivery_tracker),
-                Arc::clone(&self.session_manager),
-            ));
-
+            let ws_handler = Arc::new(
+                WebSocketHandler::new(
+                    Arc::clone(&self.subscription_manager),
+                    Arc::clone(&self.message_router),
+                    Arc::clone(&self.delivery_tracker),
+                    Arc::clone(&self.session_manager),
+                )
+                .with_acl(Arc::clone(&self.acl))
+                .with_session_tokens(Arc::clone(&self.session_tokens))
+                .with_encryption(Arc::clone(&self.encryption))
+                .with_adapter_keys(Arc::clone(&self.adapter_keys))
+                .with_audit(Arc::clone(&self.audit)),
+            );
             router = router.route(
                 "/cauce/v1/ws",
                 get({
                     let handler = Arc::clone(&ws_handler);
-                    move |ws| {
+                    let lockout = Arc::clone(&self.lockout);
+                    move |ws: WebSocketUpgrade,
+                          auth: Option<Extension<AuthInfo>>,
+                          claims: Option<Extension<TokenClaims>>,
+                          peer: Option<ConnectInfo<SocketAddr>>,
+                          headers: HeaderMap| {
                         let h = Arc::clone(&handler);
-                        async move { h.handle_upgrade(ws).await }
+                        // Set by the auth middleware when authentication is required
+                        let principal = auth.map(|Extension(info)| {
+                            Principal::from_auth(&info, claims.as_ref().map(|c| &c.0))
+                        });
+                        let peer = peer.map(|ConnectInfo(addr)| addr);
+                        let source = lockout.source_ip(&headers, peer);
+                        async move { h.handle_upgrade_from(ws, principal, source).await }
                     }
                 }),
             );
+            websocket = Some(ws_handler);
+        }
+
+        let require_session_tokens = self.config.auth.require_session_tokens;
+        if !require_session_tokens && (transports.sse_enabled || transports.polling_enabled) {
+            warn!(
+                "SSE and polling accept bare session IDs; \
+                 set auth.require_session_tokens to require signed session tokens"
+            );
         }
 
         // Add SSE handler
         if transports.sse_enabled {
-            let sse_handler = Arc::new(SseHandler::new(
-                Arc::clone(&self.subscription_manager),
-                Arc::clone(&self.delivery_tracker),
-                Arc::clone(&self.session_manager),
-            ));
+            let sse_handler = Arc::new(
+                SseHandler::new(
+                    Arc::clone(&self.subscription_manager),
+                    Arc::clone(&self.delivery_tracker),
+                    Arc::clone(&self.session_manager),
+                )
+                .with_session_tokens(Arc::clone(&self.session_tokens), require_session_tokens),
+            );
 
             router = router.route(
                 "/cauce/v1/sse",
                 get({
                     let handler = Arc::clone(&sse_handler);
-                    move |query| {
+                    move |headers, query| {
                         let h = Arc::clone(&handler);
-                        async move { h.handle_stream(query).await }
+                        async move { h.handle_stream(headers, query).await }
                     }
                 }),
             );
@@ -316,20 +491,23 @@ where
 
         // Add polling handlers
         if transports.polling_enabled {
-            let polling_handler = Arc::new(PollingHandler::new(
-                Arc::clone(&self.subscription_manager),
-                Arc::clone(&self.delivery_tracker),
-                Arc::clone(&self.session_manager),
-            ));
+            let polling_handler = Arc::new(
+                PollingHandler::new(
+                    Arc::clone(&self.subscription_manager),
+                    Arc::clone(&self.delivery_tracker),
+                    Arc::clone(&self.session_manager),
+                )
+                .with_session_tokens(Arc::clone(&self.session_tokens), require_session_tokens),
+            );
 
             router = router
                 .route(
                     "/cauce/v1/poll",
                     get({
                         let handler = Arc::clone(&polling_handler);
-                        move |query| {
+                        move |headers, query| {
                             let h = Arc::clone(&handler);
-                            async move { h.handle_poll(query).await }
+                            async move { h.handle_poll(headers, query).await }
                         }
                     }),
                 )
@@ -337,9 +515,9 @@ where
                     "/cauce/v1/ack",
                     post({
                         let handler = Arc::clone(&polling_handler);
-                        move |query, body| {
+                        move |headers, query, body| {
                             let h = Arc::clone(&handler);
-                            async move { h.handle_ack(query, body).await }
+                            async move { h.handle_ack(headers, query, body).await }
                         }
                     }),
                 );
@@ -354,7 +532,9 @@ where
 
         // Add auth middleware if enabled
         let auth_enabled = self.config.auth.required;
-        let auth_middleware = AuthMiddleware::with_shared(Arc::clone(&self.auth_validator));
+        let auth_middleware = AuthMiddleware::with_shared(Arc::clone(&self.auth_validator))
+            .with_lockout(Arc::clone(&self.lockout))
+            .with_audit(Arc::clone(&self.audit));
 
         if auth_enabled {
             router = router.layer(auth_middleware.layer());
@@ -363,13 +543,33 @@ where
         // Apply rate limiting
         router = router.layer(rate_limit_middleware.layer());
 
-        router.with_state(state)
+        // Turn locked-out addresses away before they use up rate limit tokens
+        if auth_enabled && self.lockout.is_enabled() {
+            let lockout_middleware = RateLimitMiddleware::with_shared(Arc::clone(&self.lockout))
+                .with_key_extractor(self.lockout.key_extractor());
+            router = router.layer(lockout_middleware.layer());
+        }
+
+        (router.with_state(state), websocket)
+    }
+
+    /// Starts publishing lockout alerts and, if configured, audit records
+    /// through `handler`.
+    fn spawn_publishers(&self, handler: &Arc<WebSocketHandler<S, R, D, M>>) {
+        spawn_lockout_alerts(&self.lockout, handler, &self.config.server_name);
+        if self.config.audit.publish {
+            spawn_audit_publisher(&self.audit, handler, &self.config.server_name);
+        }
     }
 
     /// Starts the server and begins accepting connections.
     pub async fn serve(self) -> ServerResult<()> {
         let addr = self.config.address;
-        let router = self.router();
+        let (router, websocket) = self.build_router();
+        if let Some(handler) = &websocket {
+            self.spawn_publishers(handler);
+        }
+        spawn_lockout_cleanup(&self.lockout);
 
         info!("Starting Cauce server on {}", addr);
 
@@ -377,11 +577,12 @@ where
             message: format!("Failed to bind to {}: {}", addr, e),
         })?;
 
-        axum::serve(listener, router)
+        axum::serve(listener, router.into_make_service_with_connect_info::<SocketAddr>())
             .await
             .map_err(|e| ServerError::ConfigError {
                 message: format!("Server error: {}", e),
             })?;
+        self.audit.flush().await;
 
         Ok(())
     }
@@ -392,7 +593,11 @@ where
         F: Future<Output = ()> + Send + 'static,
     {
         let addr = self.config.address;
-        let router = self.router();
+        let (router, websocket) = self.build_router();
+        if let Some(handler) = &websocket {
+            self.spawn_publishers(handler);
+        }
+        spawn_lockout_cleanup(&self.lockout);
 
         info!("Starting Cauce server on {} (with graceful shutdown)", addr);
 
@@ -400,12 +605,13 @@ where
             message: format!("Failed to bind to {}: {}", addr, e),
         })?;
 
-        axum::serve(listener, router)
+        axum::serve(listener, router.into_make_service_with_connect_info::<SocketAddr>())
             .with_graceful_shutdown(signal)
             .await
             .map_err(|e| ServerError::ConfigError {
                 message: format!("Server error: {}", e),
             })?;
+        self.audit.flush().await;
 
         info!("Server shut down gracefully");
         Ok(())
@@ -417,11 +623,157 @@ where
     }
 }
 
+/// A router and the WebSocket handler hub signals are published through, if
+/// WebSocket is enabled.
+type RouterParts<S, R, D, M> = (Router, Option<Arc<WebSocketHandler<S, R, D, M>>>);
+
 /// Simple health check handler.
 async fn health_handler() -> &'static str {
     "OK"
 }
 
+/// Publishes lockout alerts to [`LOCKOUT_ALERT_TOPIC`] for as long as both
+/// the tracker and `handler` are alive.
+///
+/// Does nothing outside a Tokio runtime.
+fn spawn_lockout_alerts<S, R, D, M>(
+    lockout: &AuthLockout,
+    handler: &Arc<WebSocketHandler<S, R, D, M>>,
+    server_name: &str,
+) where
+    S: SubscriptionManager + 'static,
+    R: MessageRouter + 'static,
+    D: DeliveryTracker + 'static,
+    M: SessionManager + 'static,
+{
+    let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+        return;
+    };
+    if !lockout.is_enabled() {
+        return;
+    }
+
+    let mut alerts = lockout.subscribe_alerts();
+    let handler = Arc::downgrade(handler);
+    let server_name = server_name.to_string();
+    runtime.spawn(async move {
+        loop {
+            let alert = match alerts.recv().await {
+                Ok(alert) => alert,
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("Dropped {} lockout alerts", skipped);
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+            let Some(handler) = handler.upgrade() else {
+                break;
+            };
+            let result = publish_hub_signal(&handler, &server_name, LOCKOUT_ALERT_TOPIC, &alert);
+            if let Err(e) = result.await {
+                warn!("Failed to publish lockout alert: {}", e);
+            }
+        }
+    });
+}
+
+/// Drops stale lockout records once per window, for as long as the tracker
+/// is alive.
+///
+/// Does nothing outside a Tokio runtime.
+fn spawn_lockout_cleanup(lockout: &Arc<AuthLockout>) {
+    let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+        return;
+    };
+    if !lockout.is_enabled() {
+        return;
+    }
+
+    let period = Duration::from_secs(lockout.config().window_secs.max(1));
+    let lockout = Arc::downgrade(lockout);
+    runtime.spawn(async move {
+        let mut ticks = tokio::time::interval(period);
+        // The first tick completes immediately
+        ticks.tick().await;
+        loop {
+            ticks.tick().await;
+            match lockout.upgrade() {
+                Some(lockout) => lockout.cleanup(),
+                None => break,
+            }
+        }
+    });
+}
+
+/// Publishes audit records to [`AUDIT_TOPIC`] for as long as both the log
+/// and `handler` are alive.
+///
+/// Records are [redacted](crate::audit::AuditRecord::redacted) before they
+/// are published.
+///
+/// Does nothing outside a Tokio runtime.
+fn spawn_audit_publisher<S, R, D, M>(
+    audit: &AuditLog,
+    handler: &Arc<WebSocketHandler<S, R, D, M>>,
+    server_name: &str,
+) where
+    S: SubscriptionManager + 'static,
+    R: MessageRouter + 'static,
+    D: DeliveryTracker + 'static,
+    M: SessionManager + 'static,
+{
+    let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+        return;
+    };
+
+    let mut records = audit.subscribe();
+    let handler = Arc::downgrade(handler);
+    let server_name = server_name.to_string();
+    runtime.spawn(async move {
+        loop {
+            let record = match records.recv().await {
+                Ok(record) => record.redacted(),
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("Dropped {} audit records", skipped);
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+            let Some(handler) = handler.upgrade() else {
+                break;
+            };
+            let result = publish_hub_signal(&handler, &server_name, AUDIT_TOPIC, &record);
+            if let Err(e) = result.await {
+                warn!("Failed to publish audit record {}: {}", record.sequence, e);
+            }
+        }
+    });
+}
+
+/// Publishes `payload` to a system topic as a signal from the hub.
+async fn publish_hub_signal<S, R, D, M, T>(
+    handler: &WebSocketHandler<S, R, D, M>,
+    server_name: &str,
+    topic: &str,
+    payload: &T,
+) -> ServerResult<()>
+where
+    S: SubscriptionManager,
+    R: MessageRouter,
+    D: DeliveryTracker,
+    M: SessionManager,
+    T: Serialize,
+{
+    let signal = SignalBuilder::new()
+        .source(Source::new("hub", server_name, generate_message_id()))
+        .topic(Topic::new_unchecked(topic))
+        .typed_payload(payload)
+        .build()
+        .map_err(|e| ServerError::internal(e.to_string()))?;
+    let request = PublishRequest::signal(topic, signal);
+    handler.publish_system(&request).await.map(|_| ())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -570,6 +922,222 @@ mod tests {
         let _router = server.router();
     }
 
+    #[tokio::test]
+    async fn test_hashed_api_keys_from_config() {
+        use crate::auth::NewApiKey;
+        use crate::config::AuthConfig;
+
+        let issued = ApiKeyStore::new()
+            .create(NewApiKey::new("ci", "ci-agent").with_scope("signals:read"));
+        let auth =
+            AuthConfig::require_api_key(vec!["test_key_123".to_string()]).with_key(issued.record);
+        let config = ServerConfig::builder("127.0.0.1:8080".parse().unwrap())
+            .auth(auth)
+            .build()
+            .unwrap();
+
+        let server = DefaultCauceServer::new(config);
+        let validator = &server.auth_validator;
+        let claims = validator
+            .validate_api_key_claims(&issued.key)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(claims.client_id, "ci-agent");
+        assert_eq!(claims.scopes, ["signals:read"]);
+        let client_id = validator.validate_api_key("test_key_123").await.unwrap();
+        assert_eq!(client_id.as_deref(), Some("default"));
+    }
+
+    #[tokio::test]
+    async fn test_lockout_rejects_and_publishes_alert() {
+        use crate::auth::LockoutConfig;
+        use crate::config::AuthConfig;
+        use axum::body::Body;
+        use axum::http::{Request, StatusCode};
+        use cauce_core::methods::SubscribeRequest;
+        use tower::ServiceExt;
+
+        let auth = AuthConfig::require_api_key(vec!["test_key_123".to_string()])
+            .with_lockout(LockoutConfig::default().with_max_failures(1));
+        let config = ServerConfig::builder("127.0.0.1:8080".parse().unwrap())
+            .auth(auth)
+            .build()
+            .unwrap();
+        let server = DefaultCauceServer::new(config);
+        let subscription = server
+            .subscription_manager()
+            .subscribe(
+                "monitor",
+                "sess_monitor",
+                SubscribeRequest::new(vec!["system.auth.*".to_string()]),
+            )
+            .await
+            .unwrap();
+        let (router, websocket) = server.build_router();
+        server.spawn_publishers(websocket.as_ref().unwrap());
+
+        let request = |key: &str| {
+            Request::builder()
+                .uri("/health")
+                .extension(ConnectInfo(SocketAddr::from(([10, 0, 0, 9], 4000))))
+                .header("X-Cauce-API-Key", key)
+                .body(Body::empty())
+                .unwrap()
+        };
+        let response = router.clone().oneshot(request("wrong")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let response = router.clone().oneshot(request("test_key_123")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.headers().contains_key("Retry-After"));
+
+        // Alerts are published in the background
+        let mut delivered = Vec::new();
+        for _ in 0..50 {
+            delivered = server
+                .delivery_tracker()
+                .get_unacked(&subscription.subscription_id)
+                .await
+                .unwrap();
+            if !delivered.is_empty() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(delivered.len(), 1);
+        assert_eq!(delivered[0].topic, LOCKOUT_ALERT_TOPIC);
+        assert_eq!(
+            delivered[0].signal.payload.raw["subject"]["source_ip"],
+            "10.0.0.9"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_audit_records_published_to_topic() {
+        use crate::audit::{AuditAction, AuditRecord};
+        use crate::config::{AclConfig, AclRule, AclSubject, AuditConfig, AuthConfig};
+        use axum::body::Body;
+        use axum::http::{Request, StatusCode};
+        use cauce_core::methods::SubscribeRequest;
+        use tower::ServiceExt;
+
+        let auditors = AclRule::new(AclSubject::ClientId("auditor".to_string()))
+            .with_subscribe(AUDIT_TOPIC);
+        let config = ServerConfig::builder("127.0.0.1:8080".parse().unwrap())
+            .auth(AuthConfig::require_api_key(vec!["test_key_123".to_string()]))
+            .acl(AclConfig::default().with_rule(auditors))
+            .audit(AuditConfig::default().with_publish(true))
+            .build()
+            .unwrap();
+        let server = DefaultCauceServer::new(config);
+        let subscription = server
+            .subscription_manager()
+            .subscribe(
+                "auditor",
+                "sess_auditor",
+                SubscribeRequest::new(vec![AUDIT_TOPIC.to_string()]),
+            )
+            .await
+            .unwrap();
+        let (router, websocket) = server.build_router();
+        server.spawn_publishers(websocket.as_ref().unwrap());
+        assert!(server.audit().is_enabled());
+
+        // Routers built outside the serve paths do not publish again
+        let _ = server.router();
+
+        let request = Request::builder()
+            .uri("/health")
+            .extension(ConnectInfo(SocketAddr::from(([10, 0, 0, 9], 4000))))
+            .header("X-Forwarded-For", "192.0.2.1")
+            .header("X-Cauce-API-Key", "wrong")
+            .body(Body::empty())
+            .unwrap();
+        let response = router.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        // Records are published in the background
+        let mut delivered = Vec::new();
+        for _ in 0..50 {
+            delivered = server
+                .delivery_tracker()
+                .get_unacked(&subscription.subscription_id)
+                .await
+                .unwrap();
+            if !delivered.is_empty() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(delivered.len(), 1);
+        let record: AuditRecord =
+            serde_json::from_value(delivered[0].signal.payload.raw.clone()).unwrap();
+        assert_eq!(record.event.action, AuditAction::AuthFailure);
+        assert!(record.event.source_address.is_none());
+        assert!(record.event.session_id.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_encryption_policy_from_config() {
+        use crate::config::EncryptionConfig;
+
+        let config = ServerConfig::builder("127.0.0.1:8080".parse().unwrap())
+            .encryption(EncryptionConfig::default().with_required_topic("signal.health.*"))
+            .build()
+            .unwrap();
+        let server = DefaultCauceServer::new(config);
+
+        assert!(server.encryption().requires_encryption("signal.health.heart"));
+        assert!(!server.encryption().requires_encryption("signal.email.received"));
+        assert!(server
+            .key_directory()
+            .recipients("signal.health.heart")
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_required_session_tokens() {
+        use crate::config::AuthConfig;
+        use crate::session::SessionInfo;
+        use axum::body::Body;
+        use axum::http::{Request, StatusCode};
+        use cauce_core::methods::Transport;
+        use tower::ServiceExt;
+
+        let config = ServerConfig::builder("127.0.0.1:8080".parse().unwrap())
+            .auth(AuthConfig::none().with_required_session_tokens(true))
+            .build()
+            .unwrap();
+        let server = DefaultCauceServer::new(config);
+        let info = SessionInfo::new("sess_1", "agent-1", "agent", "1.0", Transport::Polling, 3600);
+        server.session_manager().create_session(info).await.unwrap();
+        let token = server
+            .session_token_signer()
+            .issue("sess_1", "agent-1", None)
+            .unwrap();
+        let router = server.router();
+
+        let poll = |query: String| {
+            Request::builder()
+                .uri(format!("/cauce/v1/poll?{}", query))
+                .body(Body::empty())
+                .unwrap()
+        };
+        let response = router
+            .clone()
+            .oneshot(poll(format!("session_token={}", token)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = router
+            .oneshot(poll("session_id=sess_1".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
     #[tokio::test]
     async fn test_health_handler() {
         let result = health_handler().await;
@@ -1,7 +1,7 @@
 //! In-memory session manager implementation.
 
 use async_trait::async_trait;
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use dashmap::DashMap;
 
 use super::{SessionInfo, SessionManager};
@@ -25,6 +25,8 @@ pub struct InMemorySessionManager {
     sessions: DashMap<String, SessionInfo>,
     /// Sessions indexed by client ID
     client_sessions: DashMap<String, Vec<String>>,
+    /// Revoked session token IDs and when each token expires
+    revoked_tokens: DashMap<String, DateTime<Utc>>,
     /// Default session TTL in seconds
     session_ttl_secs: i64,
 }
@@ -39,6 +41,7 @@ impl InMemorySessionManager {
         Self {
             sessions: DashMap::new(),
             client_sessions: DashMap::new(),
+            revoked_tokens: DashMap::new(),
             session_ttl_secs,
         }
     }
@@ -179,8 +182,21 @@ impl SessionManager for InMemorySessionManager {
             let _ = self.remove_session(id).await;
         }
 
+        // Forget revocations of tokens that have expired on their own
+        let now = Utc::now();
+        self.revoked_tokens.retain(|_, expires_at| *expires_at > now);
+
         Ok(expired_ids.len())
     }
+
+    async fn revoke_token(&self, token_id: &str, expires_at: DateTime<Utc>) -> ServerResult<()> {
+        self.revoked_tokens.insert(token_id.to_string(), expires_at);
+        Ok(())
+    }
+
+    async fn is_token_revoked(&self, token_id: &str) -> ServerResult<bool> {
+        Ok(self.revoked_tokens.contains_key(token_id))
+    }
 }
 
 #[cfg(test)]
@@ -360,6 +376,28 @@ mod tests {
         assert!(manager.sessions.get("sess_valid").is_some());
     }
 
+    #[tokio::test]
+    async fn test_revoke_token() {
+        let manager = InMemorySessionManager::new(3600);
+        assert!(!manager.is_token_revoked("tok_1").await.unwrap());
+
+        manager
+            .revoke_token("tok_1", Utc::now() + Duration::seconds(60))
+            .await
+            .unwrap();
+        manager
+            .revoke_token("tok_2", Utc::now() - Duration::seconds(1))
+            .await
+            .unwrap();
+        assert!(manager.is_token_revoked("tok_1").await.unwrap());
+        assert!(manager.is_token_revoked("tok_2").await.unwrap());
+
+        // Cleanup forgets revocations of tokens that have expired
+        manager.cleanup_expired().await.unwrap();
+        assert!(manager.is_token_revoked("tok_1").await.unwrap());
+        assert!(!manager.is_token_revoked("tok_2").await.unwrap());
+    }
+
     #[test]
     fn test_session_info_is_expired() {
         let mut info = create_test_info("sess_1", "client_1");
@@ -4,15 +4,18 @@
 //! for managing client sessions and authentication state.
 
 mod memory;
+mod token;
 
 pub use memory::InMemorySessionManager;
+pub(crate) use token::{header_session_token, resolve_session, ResolvedSession};
+pub use token::{SessionTokenClaims, SessionTokenSigner, SESSION_TOKEN_HEADER};
 
 use async_trait::async_trait;
 use cauce_core::methods::Transport;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::error::ServerResult;
+use crate::error::{ServerError, ServerResult};
 
 /// Information about a client session.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,6 +24,12 @@ pub struct SessionInfo {
     pub session_id: String,
     /// Client identifier.
     pub client_id: String,
+    /// Client ID the session's connection authenticated as, if it did.
+    ///
+    /// Unlike [`client_id`](Self::client_id), which the client states in
+    /// `cauce.hello`, this comes from its credentials.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authenticated_id: Option<String>,
     /// Client type (adapter, agent, etc.).
     pub client_type: String,
     /// Protocol version negotiated.
@@ -52,6 +61,7 @@ impl SessionInfo {
         Self {
             session_id: session_id.into(),
             client_id: client_id.into(),
+            authenticated_id: None,
             client_type: client_type.into(),
             protocol_version: protocol_version.into(),
             transport,
@@ -62,6 +72,18 @@ impl SessionInfo {
         }
     }
 
+    /// Sets the client ID the session authenticated as.
+    pub fn with_authenticated_id(mut self, client_id: impl Into<String>) -> Self {
+        self.authenticated_id = Some(client_id.into());
+        self
+    }
+
+    /// Returns who the session belongs to: the client ID it authenticated
+    /// as, or the one it stated if it did not authenticate.
+    pub fn identity(&self) -> &str {
+        self.authenticated_id.as_deref().unwrap_or(&self.client_id)
+    }
+
     /// Checks if the session has expired.
     pub fn is_expired(&self) -> bool {
         Utc::now() > self.expires_at
@@ -170,4 +192,31 @@ pub trait SessionManager: Send + Sync + 'static {
     ///
     /// Number of sessions removed.
     async fn cleanup_expired(&self) -> ServerResult<usize>;
+
+    /// Revokes a session token before it expires.
+    ///
+    /// The revocation only needs to be remembered until `expires_at`, after
+    /// which the token no longer verifies anyway. Removing a session already
+    /// invalidates all of its tokens.
+    ///
+    /// # Arguments
+    ///
+    /// * `token_id` - The token's `jti` claim
+    /// * `expires_at` - When the token expires
+    async fn revoke_token(&self, token_id: &str, expires_at: DateTime<Utc>) -> ServerResult<()> {
+        let _ = (token_id, expires_at);
+        Err(ServerError::internal(
+            "session token revocation is not supported by this session manager",
+        ))
+    }
+
+    /// Checks if a session token has been revoked.
+    ///
+    /// # Arguments
+    ///
+    /// * `token_id` - The token's `jti` claim
+    async fn is_token_revoked(&self, token_id: &str) -> ServerResult<bool> {
+        let _ = token_id;
+        Ok(false)
+    }
 }
@@ -0,0 +1,216 @@
+//! Signal provenance verification.
+//!
+//! [`AdapterKeys`] holds the Ed25519 public keys of registered adapters and
+//! checks the signatures on signals published in their name, so a client
+//! cannot forge a signal from an adapter it is not. Deliveries of signals
+//! that verified are marked with
+//! [`SignalDelivery::verified`](cauce_core::SignalDelivery::verified).
+
+use cauce_core::methods::{PublishMessage, PublishRequest};
+use cauce_core::signing::{self, VerifyingKey};
+use cauce_core::Signal;
+use dashmap::DashMap;
+
+use crate::config::SigningConfig;
+use crate::error::{ServerError, ServerResult};
+
+/// Registry of adapter public keys that signals are verified against.
+///
+/// A signal whose `source.adapter_id` names a registered adapter must carry
+/// a valid signature by one of that adapter's keys, or it is rejected.
+/// Signals from unregistered adapters are accepted but never verified.
+///
+/// # Example
+///
+/// ```ignore
+/// let keys = AdapterKeys::new(config.signing.clone());
+/// keys.register("email-adapter-1", verifying_key);
+///
+/// let verified = keys.verify_signal(&signal)?;
+/// ```
+#[derive(Debug, Default)]
+pub struct AdapterKeys {
+    keys: DashMap<String, Vec<VerifyingKey>>,
+}
+
+impl AdapterKeys {
+    /// Creates a registry holding the keys in `config`.
+    ///
+    /// Keys that fail to parse are skipped; [`SigningConfig::validate`]
+    /// rejects them when the server configuration is built.
+    pub fn new(config: SigningConfig) -> Self {
+        let registry = Self::default();
+        for key in config.adapter_keys {
+            if let Ok(public_key) = VerifyingKey::from_base64(&key.public_key) {
+                registry.register(key.adapter_id, public_key);
+            }
+        }
+        registry
+    }
+
+    /// Returns true if any adapter keys are registered.
+    pub fn is_enabled(&self) -> bool {
+        !self.keys.is_empty()
+    }
+
+    /// Registers `key` for `adapter_id`, alongside any existing keys.
+    pub fn register(&self, adapter_id: impl Into<String>, key: VerifyingKey) {
+        let mut keys = self.keys.entry(adapter_id.into()).or_default();
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+
+    /// Removes every key registered for `adapter_id`.
+    ///
+    /// Returns true if the adapter had keys.
+    pub fn revoke(&self, adapter_id: &str) -> bool {
+        self.keys.remove(adapter_id).is_some()
+    }
+
+    /// Returns the keys registered for `adapter_id`.
+    pub fn keys(&self, adapter_id: &str) -> Vec<VerifyingKey> {
+        self.keys
+            .get(adapter_id)
+            .map(|keys| keys.clone())
+            .unwrap_or_default()
+    }
+
+    /// Verifies the provenance of `signal`.
+    ///
+    /// Returns `Ok(true)` if it is signed by a key registered for its
+    /// adapter, and `Ok(false)` if its adapter has no registered keys.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ServerError::NotAuthorized`] if the adapter has registered
+    /// keys and the signal is unsigned or its signature does not verify.
+    pub fn verify_signal(&self, signal: &Signal) -> ServerResult<bool> {
+        let adapter_id = &signal.source.adapter_id;
+        let Some(keys) = self.keys.get(adapter_id) else {
+            return Ok(false);
+        };
+        if !signal.is_signed() {
+            return Err(ServerError::not_authorized(format!(
+                "signals from adapter {} must be signed",
+                adapter_id
+            )));
+        }
+        if keys
+            .iter()
+            .any(|key| signing::verify_signal(signal, key).is_ok())
+        {
+            return Ok(true);
+        }
+        Err(ServerError::not_authorized(format!(
+            "invalid signature for adapter {}",
+            adapter_id
+        )))
+    }
+
+    /// Verifies the provenance of a published message.
+    ///
+    /// Actions name no adapter and are never verified by the hub.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ServerError::InvalidParams`] if a signal is published to a
+    /// topic other than its own, since its signature only covers its own
+    /// topic, and the errors of [`verify_signal`](Self::verify_signal).
+    pub fn check_publish(&self, request: &PublishRequest) -> ServerResult<bool> {
+        match &request.message {
+            PublishMessage::Signal(signal) if signal.topic.as_str() != request.topic => {
+                Err(ServerError::invalid_params(format!(
+                    "signal topic {} does not match publish topic {}",
+                    signal.topic, request.topic
+                )))
+            }
+            PublishMessage::Signal(signal) => self.verify_signal(signal),
+            PublishMessage::Action(_) => Ok(false),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cauce_core::signing::SigningKey;
+    use cauce_core::types::{Payload, Source, Topic};
+    use serde_json::json;
+
+    fn signal(adapter_id: &str, key: Option<&SigningKey>) -> Signal {
+        let builder = Signal::builder()
+            .source(Source::new("email", adapter_id, "msg-1"))
+            .topic(Topic::new_unchecked("signal.email.received"))
+            .payload(Payload::new(
+                json!({"from": "bank@example.com"}),
+                "application/json",
+            ));
+        match key {
+            Some(key) => builder.sign_with(key).build().unwrap(),
+            None => builder.build().unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_verify_signal() {
+        let key = SigningKey::generate();
+        let keys = AdapterKeys::new(
+            SigningConfig::default().with_adapter_key("email-1", key.verifying_key().to_base64()),
+        );
+        assert!(keys.is_enabled());
+
+        assert!(keys.verify_signal(&signal("email-1", Some(&key))).unwrap());
+
+        // Forged and unsigned signals from a registered adapter are rejected
+        let forger = SigningKey::generate();
+        let err = keys
+            .verify_signal(&signal("email-1", Some(&forger)))
+            .unwrap_err();
+        assert!(matches!(err, ServerError::NotAuthorized { .. }));
+        assert!(keys.verify_signal(&signal("email-1", None)).is_err());
+
+        // Signals from unregistered adapters pass unverified
+        assert!(!keys.verify_signal(&signal("slack-1", None)).unwrap());
+        assert!(!keys
+            .verify_signal(&signal("slack-1", Some(&forger)))
+            .unwrap());
+    }
+
+    #[test]
+    fn test_key_rotation() {
+        let old = SigningKey::generate();
+        let new = SigningKey::generate();
+        let keys = AdapterKeys::default();
+        assert!(!keys.is_enabled());
+
+        keys.register("email-1", old.verifying_key());
+        keys.register("email-1", new.verifying_key());
+        keys.register("email-1", new.verifying_key());
+        assert_eq!(keys.keys("email-1").len(), 2);
+        assert!(keys.verify_signal(&signal("email-1", Some(&old))).unwrap());
+        assert!(keys.verify_signal(&signal("email-1", Some(&new))).unwrap());
+
+        assert!(keys.revoke("email-1"));
+        assert!(!keys.revoke("email-1"));
+        assert!(!keys.verify_signal(&signal("email-1", Some(&old))).unwrap());
+    }
+
+    #[test]
+    fn test_check_publish_rejects_mismatched_topic() {
+        let key = SigningKey::generate();
+        let keys = AdapterKeys::default();
+        keys.register("email-1", key.verifying_key());
+        let signal = signal("email-1", Some(&key));
+
+        let request = PublishRequest::signal("signal.email.received", signal.clone());
+        assert!(keys.check_publish(&request).unwrap());
+
+        // Checked before the signature, and for unregistered adapters too
+        let request = PublishRequest::signal("signal.email.sent", signal);
+        let err = keys.check_publish(&request).unwrap_err();
+        assert!(matches!(err, ServerError::InvalidParams { .. }));
+        let request = PublishRequest::signal("signal.email.sent", self::signal("slack-1", None));
+        assert!(keys.check_publish(&request).is_err());
+    }
+}
@@ -7,10 +7,13 @@ use cauce_core::methods::{
 };
 use chrono::Utc;
 use dashmap::DashMap;
-use std::sync::RwLock;
+use serde_json::json;
+use std::sync::{Arc, RwLock};
 use uuid::Uuid;
 
 use super::{SubscriptionManager, TopicTrie};
+use crate::audit::{AuditAction, AuditEvent, AuditLog};
+use crate::auth::TopicAcl;
 use crate::config::LimitsConfig;
 use crate::error::{ServerError, ServerResult};
 
@@ -54,6 +57,10 @@ pub struct InMemorySubscriptionManager {
     limits: LimitsConfig,
     /// Default approval type when not specified
     default_approval: ApprovalType,
+    /// Access control checked when subscriptions are approved
+    acl: Option<Arc<TopicAcl>>,
+    /// Audit log approvals, denials and revocations are recorded to
+    audit: Option<Arc<AuditLog>>,
 }
 
 impl InMemorySubscriptionManager {
@@ -65,6 +72,8 @@ impl InMemorySubscriptionManager {
             topic_trie: RwLock::new(TopicTrie::new()),
             limits: LimitsConfig::default(),
             default_approval: ApprovalType::Automatic,
+            acl: None,
+            audit: None,
         }
     }
 
@@ -82,6 +91,52 @@ impl InMemorySubscriptionManager {
         self
     }
 
+    /// Checks approvals against topic access control.
+    ///
+    /// A pending subscription is only approved if its owner may subscribe
+    /// to all of its topics.
+    pub fn with_acl(mut self, acl: Arc<TopicAcl>) -> Self {
+        self.acl = Some(acl);
+        self
+    }
+
+    /// Records approvals, denials and revocations to an audit log.
+    ///
+    /// Records name the actor passed to
+    /// [`approve_by`](SubscriptionManager::approve_by) and friends as the
+    /// identity, and carry the subscription's owner in their details.
+    /// Changes made without naming an actor are recorded without one.
+    pub fn with_audit(mut self, audit: Arc<AuditLog>) -> Self {
+        self.audit = Some(audit);
+        self
+    }
+
+    /// Records a change to a subscription's status.
+    async fn audit(
+        &self,
+        action: AuditAction,
+        actor: Option<&str>,
+        subscription_id: &str,
+        reason: Option<String>,
+        result: &ServerResult<()>,
+    ) {
+        let Some(audit) = self.audit.as_ref().filter(|audit| audit.is_enabled()) else {
+            return;
+        };
+        let mut event = AuditEvent::from_result(action, result).with_target(subscription_id);
+        event.identity = actor.map(str::to_string);
+        if result.is_ok() {
+            event.reason = reason;
+        }
+        if let Some(stored) = self.subscriptions.get(subscription_id) {
+            event = event.with_details(json!({
+                "owner": stored.info.client_id,
+                "topics": stored.info.topics,
+            }));
+        }
+        audit.record(event).await;
+    }
+
     /// Generates a new subscription ID.
     fn generate_subscription_id() -> String {
         format!("sub_{}", Uuid::new_v4().as_simple())
@@ -151,6 +206,105 @@ impl InMemorySubscriptionManager {
             trie.remove(topic, subscription_id);
         }
     }
+
+    /// Moves a pending subscription to active.
+    fn set_approved(
+        &self,
+        subscription_id: &str,
+        restrictions: Option<SubscriptionRestrictions>,
+    ) -> ServerResult<()> {
+        let mut stored = self
+            .subscriptions
+            .get_mut(subscription_id)
+            .ok_or_else(|| ServerError::SubscriptionNotFound {
+                id: subscription_id.to_string(),
+            })?;
+
+        if stored.info.status != SubscriptionStatus::Pending {
+            return Err(ServerError::InvalidSessionState {
+                message: format!(
+                    "cannot approve subscription in {} state",
+                    status_str(&stored.info.status)
+                ),
+            });
+        }
+
+        if let Some(ref acl) = self.acl {
+            let principal = acl.principal(&stored.info.client_id);
+            acl.check_subscribe(&principal, &stored.info.topics)?;
+        }
+
+        // Update status and restrictions
+        stored.info.status = SubscriptionStatus::Active;
+        stored.restrictions = restrictions.clone();
+
+        // Update expiry if specified in restrictions
+        if let Some(ref r) = restrictions {
+            if let Some(expires) = r.expires_at {
+                stored.info.expires_at = Some(expires);
+            }
+        }
+
+        // Add to topic trie now that it's active
+        let topics = stored.info.topics.clone();
+        drop(stored); // Release the lock before modifying trie
+        self.add_to_trie(subscription_id, &topics);
+
+        Ok(())
+    }
+
+    /// Moves a pending subscription to denied.
+    fn set_denied(&self, subscription_id: &str, reason: Option<String>) -> ServerResult<()> {
+        let mut stored = self
+            .subscriptions
+            .get_mut(subscription_id)
+            .ok_or_else(|| ServerError::SubscriptionNotFound {
+                id: subscription_id.to_string(),
+            })?;
+
+        if stored.info.status != SubscriptionStatus::Pending {
+            return Err(ServerError::InvalidSessionState {
+                message: format!(
+                    "cannot deny subscription in {} state",
+                    status_str(&stored.info.status)
+                ),
+            });
+        }
+
+        stored.info.status = SubscriptionStatus::Denied;
+        stored.denial_reason = reason;
+
+        Ok(())
+    }
+
+    /// Moves an active subscription to revoked.
+    fn set_revoked(&self, subscription_id: &str, reason: Option<String>) -> ServerResult<()> {
+        let mut stored = self
+            .subscriptions
+            .get_mut(subscription_id)
+            .ok_or_else(|| ServerError::SubscriptionNotFound {
+                id: subscription_id.to_string(),
+            })?;
+
+        if stored.info.status != SubscriptionStatus::Active {
+            return Err(ServerError::InvalidSessionState {
+                message: format!(
+                    "cannot revoke subscription in {} state",
+                    status_str(&stored.info.status)
+                ),
+            });
+        }
+
+        // Remove from topic trie first
+        let topics = stored.info.topics.clone();
+        stored.info.status = SubscriptionStatus::Revoked;
+        stored.revocation_reason = reason;
+        drop(stored); // Release lock before modifying trie
+
+        self.remove_from_trie(subscription_id, &topics);
+
+        Ok(())
+    }
 }
 
 impl Default for InMemorySubscriptionManager {
@@ -176,14 +330,17 @@ impl SubscriptionManager for InMemorySubscriptionManager {
         let topics = request.topics.clone();
 
         // Create subscription info
-        let info = SubscriptionInfo::new(
-            subscription_id.clone(),
-            client_id,
-            session_id,
-            topics.clone(),
-            status,
-            transport,
-        );
+        let info = SubscriptionInfo {
+            e2e: request.e2e.clone(),
+            ..SubscriptionInfo::new(
+                subscription_id.clone(),
+                client_id,
+                session_id,
+                topics.clone(),
+                status,
+                transport,
+            )
+        };
 
         // Store the subscription
         let stored = StoredSubscription {
@@ -287,90 +444,60 @@ impl SubscriptionManager for InMemorySubscriptionManager {
         subscription_id: &str,
         restrictions: Option<SubscriptionRestrictions>,
     ) -> ServerResult<()> {
-        let mut stored = self
-            .subscriptions
-            .get_mut(subscription_id)
-            .ok_or_else(|| ServerError::SubscriptionNotFound {
-                id: subscription_id.to_string(),
-            })?;
-
-        if stored.info.status != SubscriptionStatus::Pending {
-            return Err(ServerError::InvalidSessionState {
-                message: format!(
-                    "cannot approve subscription in {} state",
-                    status_str(&stored.info.status)
-                ),
-            });
-        }
-
-        // Update status and restrictions
-        stored.info.status = SubscriptionStatus::Active;
-        stored.restrictions = restrictions.clone();
-
-        // Update expiry if specified in restrictions
-        if let Some(ref r) = restrictions {
-            if let Some(expires) = r.expires_at {
-                stored.info.expires_at = Some(expires);
-            }
-        }
-
-        // Add to topic trie now that it's active
-        let topics = stored.info.topics.clone();
-        drop(stored); // Release the lock before modifying trie
-        self.add_to_trie(subscription_id, &topics);
-
-        Ok(())
+        let result = self.set_approved(subscription_id, restrictions);
+        self.audit(AuditAction::Approve, None, subscription_id, None, &result)
+            .await;
+        result
     }
 
     async fn deny(&self, subscription_id: &str, reason: Option<String>) -> ServerResult<()> {
-        let mut stored = self
-            .subscriptions
-            .get_mut(subscription_id)
-            .ok_or_else(|| ServerError::SubscriptionNotFound {
-                id: subscription_id.to_string(),
-            })?;
-
-        if stored.info.status != SubscriptionStatus::Pending {
-            return Err(ServerError::InvalidSessionState {
-                message: format!(
-                    "cannot deny subscription in {} state",
-                    status_str(&stored.info.status)
-                ),
-            });
-        }
-
-        stored.info.status = SubscriptionStatus::Denied;
-        stored.denial_reason = reason;
-
-        Ok(())
+        let result = self.set_denied(subscription_id, reason.clone());
+        self.audit(AuditAction::Deny, None, subscription_id, reason, &result)
+            .await;
+        result
     }
 
     async fn revoke(&self, subscription_id: &str, reason: Option<String>) -> ServerResult<()> {
-        let mut stored = self
-            .subscriptions
-            .get_mut(subscription_id)
-            .ok_or_else(|| ServerError::SubscriptionNotFound {
-                id: subscription_id.to_string(),
-            })?;
-
-        if stored.info.status != SubscriptionStatus::Active {
-            return Err(ServerError::InvalidSessionState {
-                message: format!(
-                    "cannot revoke subscription in {} state",
-                    status_str(&stored.info.status)
-                ),
-            });
-        }
+        let result = self.set_revoked(subscription_id, reason.clone());
+        self.audit(AuditAction::Revoke, None, subscription_id, reason, &result)
+            .await;
+        result
+    }
 
-        // Remove from topic trie first
-        let topics = stored.info.topics.clone();
-        stored.info.status = SubscriptionStatus::Revoked;
-        stored.revocation_reason = reason;
-        drop(stored); // Release lock before modifying trie
+    async fn approve_by(
+        &self,
+        actor: &str,
+        subscription_id: &str,
+        restrictions: Option<SubscriptionRestrictions>,
+    ) -> ServerResult<()> {
+        let result = self.set_approved(subscription_id, restrictions);
+        self.audit(AuditAction::Approve, Some(actor), subscription_id, None, &result)
+            .await;
+        result
+    }
 
-        self.remove_from_trie(subscription_id, &topics);
+    async fn deny_by(
+        &self,
+        actor: &str,
+        subscription_id: &str,
+        reason: Option<String>,
+    ) -> ServerResult<()> {
+        let result = self.set_denied(subscription_id, reason.clone());
+        self.audit(AuditAction::Deny, Some(actor), subscription_id, reason, &result)
+            .await;
+        result
+    }
 
-        Ok(())
+    async fn revoke_by(
+        &self,
+        actor: &str,
+        subscription_id: &str,
+        reason: Option<String>,
+    ) -> ServerResult<()> {
+        let result = self.set_revoked(subscription_id, reason.clone());
+        self.audit(AuditAction::Revoke, Some(actor), subscription_id, reason, &result)
+            .await;
+        result
     }
 
     async fn cleanup_expired(&self) -> ServerResult<usize> {
@@ -455,6 +582,25 @@ mod tests {
         assert_eq!(response.status, SubscriptionStatus::Active);
     }
 
+    #[tokio::test]
+    async fn test_subscribe_keeps_e2e_config() {
+        let manager = InMemorySubscriptionManager::new();
+        let e2e = cauce_core::E2eConfig::enabled("cHVibGlj");
+        let request = SubscribeRequest::single("signal.email.*").with_e2e(e2e.clone());
+
+        let response = manager
+            .subscribe("client_1", "session_1", request)
+            .await
+            .unwrap();
+
+        let info = manager
+            .get_subscription(&response.subscription_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(info.e2e, Some(e2e));
+    }
+
     #[tokio::test]
     async fn test_unsubscribe() {
         let manager = InMemorySubscriptionManager::new();
@@ -561,6 +707,35 @@ mod tests {
         assert_eq!(matches.len(), 1);
     }
 
+    #[tokio::test]
+    async fn test_approve_checks_acl() {
+        use crate::auth::Principal;
+        use crate::config::{AclConfig, AclRule, AclSubject};
+
+        let acl = Arc::new(TopicAcl::new(AclConfig::default().with_rule(
+            AclRule::new(AclSubject::Scope("email:read".to_string()))
+                .with_subscribe("signal.email.*"),
+        )));
+        let manager = InMemorySubscriptionManager::new()
+            .with_default_approval(ApprovalType::UserApproved)
+            .with_acl(Arc::clone(&acl));
+
+        let response = manager
+            .subscribe("client_1", "session_1", SubscribeRequest::single("signal.email.*"))
+            .await
+            .unwrap();
+
+        let result = manager.approve(&response.subscription_id, None).await;
+        assert!(matches!(result, Err(ServerError::NotAuthorized { .. })));
+
+        // Approved once the client has connected with the granting scope
+        acl.record_principal(&Principal {
+            scopes: vec!["email:read".to_string()],
+            ..Principal::new("client_1")
+        });
+        manager.approve(&response.subscription_id, None).await.unwrap();
+    }
+
     #[tokio::test]
     async fn test_approve_with_restrictions() {
         let manager = InMemorySubscriptionManager::new()
@@ -619,6 +794,50 @@ mod tests {
         assert_eq!(info.status, SubscriptionStatus::Denied);
     }
 
+    #[tokio::test]
+    async fn test_status_changes_are_audited() {
+        use crate::audit::AuditOutcome;
+
+        let audit = Arc::new(AuditLog::new());
+        let mut records = audit.subscribe();
+        let manager = InMemorySubscriptionManager::new()
+            .with_default_approval(ApprovalType::UserApproved)
+            .with_audit(Arc::clone(&audit));
+
+        let pending = manager
+            .subscribe("agent_1", "session_1", SubscribeRequest::single("signal.email.*"))
+            .await
+            .unwrap();
+        manager
+            .approve_by("operator@example.com", &pending.subscription_id, None)
+            .await
+            .unwrap();
+        manager
+            .revoke(&pending.subscription_id, Some("Access removed".to_string()))
+            .await
+            .unwrap();
+        assert!(manager.deny(&pending.subscription_id, None).await.is_err());
+
+        // The actor, not the subscription's owner, is the identity
+        let approved = records.recv().await.unwrap().event;
+        assert_eq!(approved.action, AuditAction::Approve);
+        assert_eq!(approved.identity.as_deref(), Some("operator@example.com"));
+        assert_eq!(approved.target.as_deref(), Some(pending.subscription_id.as_str()));
+        assert_eq!(
+            approved.details,
+            Some(json!({"owner": "agent_1", "topics": ["signal.email.*"]}))
+        );
+
+        let revoked = records.recv().await.unwrap().event;
+        assert_eq!(revoked.action, AuditAction::Revoke);
+        assert_eq!(revoked.identity, None);
+        assert_eq!(revoked.reason.as_deref(), Some("Access removed"));
+
+        let denied = records.recv().await.unwrap().event;
+        assert_eq!(denied.action, AuditAction::Deny);
+        assert_eq!(denied.outcome, AuditOutcome::Failure);
+    }
+
     #[tokio::test]
     async fn test_revoke_subscription() {
         let manager = InMemorySubscriptionManager::new();
@@ -33,8 +33,10 @@ use crate::error::ServerResult;
 /// let request = SubscribeRequest::single("signal.email.*");
 /// let response = manager.subscribe("client_1", "session_1", request).await?;
 ///
-/// // Approve it
-/// manager.approve(&response.subscription_id, None).await?;
+/// // Approve it, naming who did so in the audit log
+/// manager
+///     .approve_by("operator@example.com", &response.subscription_id, None)
+///     .await?;
 /// ```
 #[async_trait]
 pub trait SubscriptionManager: Send + Sync + 'static {
@@ -130,6 +132,44 @@ pub trait SubscriptionManager: Send + Sync + 'static {
     /// * `reason` - Optional reason for the revocation
     async fn revoke(&self, subscription_id: &str, reason: Option<String>) -> ServerResult<()>;
 
+    /// Approves a pending subscription on behalf of `actor`.
+    ///
+    /// Like [`approve`](Self::approve), but names the operator or client
+    /// that made the decision, so it can be audited. The default
+    /// implementation ignores `actor`.
+    async fn approve_by(
+        &self,
+        _actor: &str,
+        subscription_id: &str,
+        restrictions: Option<SubscriptionRestrictions>,
+    ) -> ServerResult<()> {
+        self.approve(subscription_id, restrictions).await
+    }
+
+    /// Denies a pending subscription on behalf of `actor`.
+    ///
+    /// See [`approve_by`](Self::approve_by).
+    async fn deny_by(
+        &self,
+        _actor: &str,
+        subscription_id: &str,
+        reason: Option<String>,
+    ) -> ServerResult<()> {
+        self.deny(subscription_id, reason).await
+    }
+
+    /// Revokes an active subscription on behalf of `actor`.
+    ///
+    /// See [`approve_by`](Self::approve_by).
+    async fn revoke_by(
+        &self,
+        _actor: &str,
+        subscription_id: &str,
+        reason: Option<String>,
+    ) -> ServerResult<()> {
+        self.revoke(subscription_id, reason).await
+    }
+
     /// Cleans up expired subscriptions.
     ///
     /// Called periodically to remove subscriptions that have passed
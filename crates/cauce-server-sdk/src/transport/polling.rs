@@ -19,8 +19,8 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use axum::extract::Query;
-use axum::http::StatusCode;
-use axum::response::IntoResponse;
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
 use axum::Json;
 use serde::{Deserialize, Serialize};
 use tokio::time::timeout;
@@ -28,15 +28,26 @@ use tracing::{debug, error, warn};
 
 use crate::delivery::DeliveryTracker;
 use crate::error::ServerResult;
-use crate::session::SessionManager;
+use crate::session::{
+    header_session_token, resolve_session, ResolvedSession, SessionManager, SessionTokenSigner,
+};
 use crate::subscription::SubscriptionManager;
+use cauce_core::methods::Transport;
 use cauce_core::{AckRequest, SignalDelivery};
 
 /// Query parameters for poll endpoint.
 #[derive(Debug, Clone, Deserialize)]
 pub struct PollQuery {
     /// The session ID to authenticate.
+    ///
+    /// Only accepted when session tokens are not required.
+    #[serde(default)]
     pub session_id: String,
+    /// Signed session token from the hello response.
+    ///
+    /// Prefer sending it in a header, which is not written to access logs.
+    #[serde(default)]
+    pub session_token: Option<String>,
     /// Optional subscription ID to filter signals.
     pub subscription_id: Option<String>,
     /// Timeout for long polling in seconds (0 for short polling).
@@ -58,6 +69,10 @@ pub struct PollResponse {
     pub signals: Vec<PollSignal>,
     /// Whether there are more signals available.
     pub has_more: bool,
+    /// A fresh session token to use from now on, if the request presented
+    /// one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_token: Option<String>,
 }
 
 /// A signal in the poll response.
@@ -112,6 +127,8 @@ where
     delivery_tracker: Arc<D>,
     session_manager: Arc<M>,
     max_long_poll_timeout: Duration,
+    session_tokens: Option<Arc<SessionTokenSigner>>,
+    require_session_tokens: bool,
 }
 
 impl<S, D, M> PollingHandler<S, D, M>
@@ -131,6 +148,8 @@ where
             delivery_tracker,
             session_manager,
             max_long_poll_timeout: Duration::from_secs(60),
+            session_tokens: None,
+            require_session_tokens: false,
         }
     }
 
@@ -140,34 +159,96 @@ where
         self
     }
 
-    /// Handle a poll request.
-    pub async fn handle_poll(self: Arc<Self>, query: Query<PollQuery>) -> impl IntoResponse {
-        // Validate session
-        let session_valid = match self.session_manager.is_valid(&query.session_id).await {
-            Ok(valid) => valid,
+    /// Accepts signed session tokens verified by `signer`.
+    ///
+    /// When `required` is true, a bare `session_id` is no longer accepted.
+    pub fn with_session_tokens(mut self, signer: Arc<SessionTokenSigner>, required: bool) -> Self {
+        self.session_tokens = Some(signer);
+        self.require_session_tokens = required;
+        self
+    }
+
+    /// Resolves and touches the session a request acts for.
+    ///
+    /// A session token in `headers` takes precedence over one in the query.
+    async fn authorize(
+        &self,
+        headers: &HeaderMap,
+        session_id: &str,
+        session_token: Option<&str>,
+        transports: &[Transport],
+    ) -> Result<ResolvedSession, Response> {
+        let session_token = header_session_token(headers).or(session_token);
+        let session = resolve_session(
+            self.session_manager.as_ref(),
+            self.session_tokens.as_deref(),
+            self.require_session_tokens,
+            session_id,
+            session_token,
+            transports,
+        )
+        .await;
+
+        let session = match session {
+            Ok(Some(session)) => session,
+            Ok(None) => {
+                return Err((
+                    StatusCode::UNAUTHORIZED,
+                    Json(ErrorResponse::invalid_session()),
+                )
+                    .into_response());
+            }
             Err(e) => {
                 error!("Failed to validate session: {}", e);
-                return (
+                return Err((
                     StatusCode::INTERNAL_SERVER_ERROR,
                     Json(ErrorResponse::internal(e.to_string())),
                 )
-                    .into_response();
+                    .into_response());
             }
         };
 
-        if !session_valid {
-            return (
-                StatusCode::UNAUTHORIZED,
-                Json(ErrorResponse::invalid_session()),
-            )
-                .into_response();
-        }
-
         // Touch session
-        if let Err(e) = self.session_manager.touch_session(&query.session_id).await {
+        if let Err(e) = self
+            .session_manager
+            .touch_session(&session.session_id)
+            .await
+        {
             warn!("Failed to touch session: {}", e);
         }
 
+        Ok(session)
+    }
+
+    /// Issues a fresh token for a session that presented one.
+    fn renew_token(&self, session: &ResolvedSession) -> Option<String> {
+        let signer = self.session_tokens.as_ref()?;
+        match signer.renew(session.claims.as_ref()?) {
+            Ok(token) => Some(token),
+            Err(e) => {
+                warn!("Failed to renew session token: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Handle a poll request.
+    pub async fn handle_poll(
+        self: Arc<Self>,
+        headers: HeaderMap,
+        query: Query<PollQuery>,
+    ) -> impl IntoResponse {
+        // Validate session
+        let transports = [Transport::Polling, Transport::LongPolling];
+        let session_token = query.session_token.as_deref();
+        let session = match self
+            .authorize(&headers, &query.session_id, session_token, &transports)
+            .await
+        {
+            Ok(session) => session,
+            Err(response) => return response,
+        };
+
         // Determine timeout
         let poll_timeout = if query.timeout_secs > 0 {
             Duration::from_secs(query.timeout_secs.min(self.max_long_poll_timeout.as_secs()))
@@ -231,10 +312,14 @@ where
         debug!(
             "Poll returning {} signals for session {}",
             signals.len(),
-            query.session_id
+            session.session_id
         );
 
-        let response = PollResponse { signals, has_more };
+        let response = PollResponse {
+            signals,
+            has_more,
+            session_token: self.renew_token(&session),
+        };
 
         (StatusCode::OK, Json(response)).into_response()
     }
@@ -242,33 +327,18 @@ where
     /// Handle an acknowledgment request.
     pub async fn handle_ack(
         self: Arc<Self>,
+        headers: HeaderMap,
         query: Query<AckQuery>,
         Json(request): Json<AckRequest>,
     ) -> impl IntoResponse {
-        // Validate session
-        let session_valid = match self.session_manager.is_valid(&query.session_id).await {
-            Ok(valid) => valid,
-            Err(e) => {
-                error!("Failed to validate session: {}", e);
-                return (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ErrorResponse::internal(e.to_string())),
-                )
-                    .into_response();
-            }
-        };
-
-        if !session_valid {
-            return (
-                StatusCode::UNAUTHORIZED,
-                Json(ErrorResponse::invalid_session()),
-            )
-                .into_response();
-        }
-
-        // Touch session
-        if let Err(e) = self.session_manager.touch_session(&query.session_id).await {
-            warn!("Failed to touch session: {}", e);
+        // Validate session; SSE clients acknowledge through this endpoint too
+        let transports = [Transport::Polling, Transport::LongPolling, Transport::Sse];
+        let session_token = query.session_token.as_deref();
+        if let Err(response) = self
+            .authorize(&headers, &query.session_id, session_token, &transports)
+            .await
+        {
+            return response;
         }
 
         // Acknowledge signals
@@ -327,7 +397,15 @@ where
 #[derive(Debug, Clone, Deserialize)]
 pub struct AckQuery {
     /// The session ID to authenticate.
+    ///
+    /// Only accepted when session tokens are not required.
+    #[serde(default)]
     pub session_id: String,
+    /// Signed session token from the hello response.
+    ///
+    /// Prefer sending it in a header, which is not written to access logs.
+    #[serde(default)]
+    pub session_token: Option<String>,
 }
 
 impl<S, D, M> Clone for PollingHandler<S, D, M>
@@ -342,6 +420,8 @@ where
             delivery_tracker: Arc::clone(&self.delivery_tracker),
             session_manager: Arc::clone(&self.session_manager),
             max_long_poll_timeout: self.max_long_poll_timeout,
+            session_tokens: self.session_tokens.clone(),
+            require_session_tokens: self.require_session_tokens,
         }
     }
 }
@@ -351,7 +431,7 @@ mod tests {
     use super::*;
     use crate::config::RedeliveryConfig;
     use crate::delivery::{DeliveryTracker, InMemoryDeliveryTracker};
-    use crate::session::InMemorySessionManager;
+    use crate::session::{InMemorySessionManager, SESSION_TOKEN_HEADER};
     use crate::subscription::InMemorySubscriptionManager;
 
     fn create_test_handler() -> PollingHandler<
@@ -381,6 +461,7 @@ mod tests {
             payload: Payload::new(json!({"test": true}), "application/json"),
             metadata: None,
             encrypted: None,
+            signature: None,
         }
     }
 
@@ -432,6 +513,7 @@ mod tests {
     fn test_poll_query_clone() {
         let query = PollQuery {
             session_id: "sess_123".to_string(),
+            session_token: None,
             subscription_id: Some("sub_456".to_string()),
             timeout_secs: 30,
             max_signals: 50,
@@ -448,6 +530,7 @@ mod tests {
     fn test_poll_query_debug() {
         let query = PollQuery {
             session_id: "sess_123".to_string(),
+            session_token: None,
             subscription_id: None,
             timeout_secs: 0,
             max_signals: 100,
@@ -475,6 +558,7 @@ mod tests {
             payload: Payload::new(json!({"text": "hello"}), "application/json"),
             metadata: None,
             encrypted: None,
+            signature: None,
         };
 
         let delivery = SignalDelivery::new("signal.email.*", signal);
@@ -486,6 +570,8 @@ mod tests {
         let response = PollResponse {
             signals: vec![poll_signal],
             has_more: false,
+
+            session_token: None,
         };
 
         let json = serde_json::to_string(&response).unwrap();
@@ -499,6 +585,8 @@ mod tests {
         let response = PollResponse {
             signals: vec![],
             has_more: true,
+
+            session_token: None,
         };
 
         let cloned = response.clone();
@@ -511,6 +599,8 @@ mod tests {
         let response = PollResponse {
             signals: vec![],
             has_more: false,
+
+            session_token: None,
         };
 
         let debug_str = format!("{:?}", response);
@@ -589,6 +679,7 @@ mod tests {
     fn test_ack_query_clone() {
         let query = AckQuery {
             session_id: "sess_123".to_string(),
+            session_token: None,
         };
 
         let cloned = query.clone();
@@ -599,6 +690,7 @@ mod tests {
     fn test_ack_query_debug() {
         let query = AckQuery {
             session_id: "sess_123".to_string(),
+            session_token: None,
         };
 
         let debug_str = format!("{:?}", query);
@@ -676,16 +768,111 @@ mod tests {
         let handler = Arc::new(create_test_handler());
         let query = Query(PollQuery {
             session_id: "nonexistent".to_string(),
+            session_token: None,
             subscription_id: None,
             timeout_secs: 0,
             max_signals: 100,
         });
 
-        let response = handler.handle_poll(query).await;
+        let response = handler.handle_poll(HeaderMap::new(), query).await;
         let response = response.into_response();
         assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
     }
 
+    #[tokio::test]
+    async fn test_handle_poll_with_session_token() {
+        let subscription_manager = Arc::new(InMemorySubscriptionManager::default());
+        let delivery_tracker = Arc::new(InMemoryDeliveryTracker::new(RedeliveryConfig::default()));
+        let session_manager = Arc::new(InMemorySessionManager::default());
+        let session_info = crate::session::SessionInfo::new(
+            "sess_poll_token",
+            "client-1",
+            "agent",
+            "1.0",
+            cauce_core::Transport::Polling,
+            3600,
+        );
+        session_manager.create_session(session_info).await.unwrap();
+
+        let signer = Arc::new(SessionTokenSigner::hs256(b"secret"));
+        let token = signer.issue("sess_poll_token", "client-1", None).unwrap();
+        let handler = Arc::new(
+            PollingHandler::new(subscription_manager, delivery_tracker, session_manager)
+                .with_session_tokens(Arc::clone(&signer), true),
+        );
+        let query = |session_id: &str, session_token: Option<String>| {
+            Query(PollQuery {
+                session_id: session_id.to_string(),
+                session_token,
+                subscription_id: None,
+                timeout_secs: 0,
+                max_signals: 100,
+            })
+        };
+
+        let response = Arc::clone(&handler)
+            .handle_poll(HeaderMap::new(), query("", Some(token.clone())))
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // The response carries a fresh token for the same session
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let renewed = body["session_token"].as_str().unwrap();
+        assert_ne!(renewed, token);
+        assert_eq!(
+            signer.verify(renewed).unwrap().session_id,
+            "sess_poll_token"
+        );
+
+        // The bare session ID is refused once tokens are required
+        let response = Arc::clone(&handler)
+            .handle_poll(HeaderMap::new(), query("sess_poll_token", None))
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        // So is a token bound to another transport
+        let sse_token = signer
+            .issue("sess_poll_token", "client-1", Some(Transport::Sse))
+            .unwrap();
+        let response = Arc::clone(&handler)
+            .handle_poll(HeaderMap::new(), query("", Some(sse_token)))
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        // Tokens can be sent in a header instead of the query
+        let token = signer.issue("sess_poll_token", "client-1", None).unwrap();
+        for (name, value) in [
+            ("Authorization", format!("Session {}", token)),
+            (SESSION_TOKEN_HEADER, token.clone()),
+        ] {
+            let mut headers = HeaderMap::new();
+            headers.insert(name, value.parse().unwrap());
+            let response = Arc::clone(&handler)
+                .handle_poll(headers, query("", None))
+                .await
+                .into_response();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+
+        // A bearer token is not mistaken for a session token
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "Authorization",
+            format!("Bearer {}", token).parse().unwrap(),
+        );
+        let response = handler
+            .handle_poll(headers, query("", None))
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
     #[tokio::test]
     async fn test_handle_poll_short_poll_valid_session() {
         let subscription_manager = Arc::new(InMemorySubscriptionManager::default());
@@ -712,12 +899,13 @@ mod tests {
 
         let query = Query(PollQuery {
             session_id: "sess_poll_test".to_string(),
+            session_token: None,
             subscription_id: None,
             timeout_secs: 0, // Short polling
             max_signals: 100,
         });
 
-        let response = handler.handle_poll(query).await;
+        let response = handler.handle_poll(HeaderMap::new(), query).await;
         let response = response.into_response();
         assert_eq!(response.status(), StatusCode::OK);
     }
@@ -753,12 +941,13 @@ mod tests {
 
         let query = Query(PollQuery {
             session_id: "sess_poll_signals".to_string(),
+            session_token: None,
             subscription_id: Some("sub_poll".to_string()),
             timeout_secs: 0,
             max_signals: 100,
         });
 
-        let response = handler.handle_poll(query).await;
+        let response = handler.handle_poll(HeaderMap::new(), query).await;
         let response = response.into_response();
         assert_eq!(response.status(), StatusCode::OK);
     }
@@ -789,13 +978,14 @@ mod tests {
 
         let query = Query(PollQuery {
             session_id: "sess_long_poll".to_string(),
+            session_token: None,
             subscription_id: Some("sub_none".to_string()),
             timeout_secs: 1, // 1 second timeout
             max_signals: 100,
         });
 
         // This should timeout and return empty
-        let response = handler.handle_poll(query).await;
+        let response = handler.handle_poll(HeaderMap::new(), query).await;
         let response = response.into_response();
         assert_eq!(response.status(), StatusCode::OK);
     }
@@ -805,13 +995,14 @@ mod tests {
         let handler = Arc::new(create_test_handler());
         let query = Query(AckQuery {
             session_id: "nonexistent".to_string(),
+            session_token: None,
         });
         let request = Json(cauce_core::AckRequest {
             subscription_id: "sub_123".to_string(),
             signal_ids: vec!["sig_1".to_string()],
         });
 
-        let response = handler.handle_ack(query, request).await;
+        let response = handler.handle_ack(HeaderMap::new(), query, request).await;
         let response = response.into_response();
         assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
     }
@@ -848,13 +1039,14 @@ mod tests {
 
         let query = Query(AckQuery {
             session_id: "sess_ack_test".to_string(),
+            session_token: None,
         });
         let request = Json(cauce_core::AckRequest {
             subscription_id: "sub_ack".to_string(),
             signal_ids: vec![signal_id],
         });
 
-        let response = handler.handle_ack(query, request).await;
+        let response = handler.handle_ack(HeaderMap::new(), query, request).await;
         let response = response.into_response();
         assert_eq!(response.status(), StatusCode::OK);
     }
@@ -887,13 +1079,14 @@ mod tests {
         // Request 60 second timeout, should be capped to 1 second
         let query = Query(PollQuery {
             session_id: "sess_cap_test".to_string(),
+            session_token: None,
             subscription_id: Some("sub_none".to_string()),
             timeout_secs: 60,
             max_signals: 100,
         });
 
         let start = std::time::Instant::now();
-        let response = handler.handle_poll(query).await;
+        let response = handler.handle_poll(HeaderMap::new(), query).await;
         let elapsed = start.elapsed();
 
         // Should complete in about 1 second (capped), not 60 seconds
@@ -919,6 +1112,8 @@ mod tests {
         let response = PollResponse {
             signals,
             has_more: true,
+
+            session_token: None,
         };
 
         let json = serde_json::to_string(&response).unwrap();
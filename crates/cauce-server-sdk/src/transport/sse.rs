@@ -19,6 +19,7 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use axum::extract::Query;
+use axum::http::HeaderMap;
 use axum::response::sse::{Event, KeepAlive, Sse};
 use axum::response::IntoResponse;
 use serde::{Deserialize, Serialize};
@@ -27,15 +28,27 @@ use tokio_stream::wrappers::ReceiverStream;
 use tracing::{debug, error, info, warn};
 
 use crate::delivery::DeliveryTracker;
-use crate::session::SessionManager;
+use crate::session::{
+    header_session_token, resolve_session, SessionManager, SessionTokenClaims, SessionTokenSigner,
+};
 use crate::subscription::SubscriptionManager;
+use cauce_core::methods::Transport;
 use cauce_core::SignalDelivery;
 
 /// Query parameters for SSE stream endpoint.
 #[derive(Debug, Clone, Deserialize)]
 pub struct SseQuery {
     /// The session ID to authenticate.
+    ///
+    /// Only accepted when session tokens are not required.
+    #[serde(default)]
     pub session_id: String,
+    /// Signed session token from the hello response.
+    ///
+    /// Clients that can set request headers should send it there instead,
+    /// where it is not written to access logs.
+    #[serde(default)]
+    pub session_token: Option<String>,
     /// Optional subscription ID to filter signals.
     pub subscription_id: Option<String>,
     /// Last event ID received (for resumption).
@@ -66,6 +79,8 @@ where
     session_manager: Arc<M>,
     signal_tx: broadcast::Sender<(String, SignalDelivery)>,
     keepalive_interval: Duration,
+    session_tokens: Option<Arc<SessionTokenSigner>>,
+    require_session_tokens: bool,
 }
 
 impl<S, D, M> SseHandler<S, D, M>
@@ -87,6 +102,8 @@ where
             session_manager,
             signal_tx,
             keepalive_interval: Duration::from_secs(30),
+            session_tokens: None,
+            require_session_tokens: false,
         }
     }
 
@@ -96,6 +113,15 @@ where
         self
     }
 
+    /// Accepts signed session tokens verified by `signer`.
+    ///
+    /// When `required` is true, a bare `session_id` is no longer accepted.
+    pub fn with_session_tokens(mut self, signer: Arc<SessionTokenSigner>, required: bool) -> Self {
+        self.session_tokens = Some(signer);
+        self.require_session_tokens = required;
+        self
+    }
+
     /// Gets a sender for broadcasting signals to SSE clients.
     pub fn signal_sender(&self) -> broadcast::Sender<(String, SignalDelivery)> {
         self.signal_tx.clone()
@@ -107,24 +133,35 @@ where
     }
 
     /// Handle an SSE stream request.
+    ///
+    /// A session token in `headers` takes precedence over one in the query.
     pub async fn handle_stream(
         self: Arc<Self>,
+        headers: HeaderMap,
         query: Query<SseQuery>,
     ) -> impl IntoResponse {
-        let session_id = query.session_id.clone();
         let subscription_filter = query.subscription_id.clone();
         let last_event_id = query.last_event_id.clone();
 
         // Validate session
-        let session_valid = match self.session_manager.is_valid(&session_id).await {
-            Ok(valid) => valid,
+        let session = resolve_session(
+            self.session_manager.as_ref(),
+            self.session_tokens.as_deref(),
+            self.require_session_tokens,
+            &query.session_id,
+            header_session_token(&headers).or(query.session_token.as_deref()),
+            &[Transport::Sse],
+        )
+        .await;
+        let session = match session {
+            Ok(session) => session,
             Err(e) => {
                 error!("Failed to validate session: {}", e);
-                false
+                None
             }
         };
 
-        if !session_valid {
+        let Some(session) = session else {
             // Return error event stream
             let (tx, rx) = mpsc::channel::<Result<Event, Infallible>>(1);
             let _ = tx
@@ -135,7 +172,8 @@ where
             drop(tx);
 
             return Sse::new(ReceiverStream::new(rx)).keep_alive(KeepAlive::default());
-        }
+        };
+        let session_id = session.session_id;
 
         info!(
             "SSE stream opened for session {} (subscription filter: {:?})",
@@ -180,12 +218,31 @@ where
             }
         }
 
+        // Hand out fresh tokens well before the one presented expires; the
+        // first tick is immediate
+        let renewal = session.claims;
+        let renew_every = self
+            .session_tokens
+            .as_ref()
+            .map_or(Duration::from_secs(3600), |signer| {
+                (signer.ttl() / 2).max(Duration::from_secs(1))
+            });
+        let mut renewals = tokio::time::interval(renew_every);
+
         // Spawn task to forward signals
         let handler = Arc::clone(&self);
         let session_id_clone = session_id.clone();
         tokio::spawn(async move {
             loop {
                 tokio::select! {
+                    _ = renewals.tick(), if renewal.is_some() => {
+                        let event = renewal.as_ref().and_then(|claims| handler.token_event(claims));
+                        if let Some(event) = event {
+                            if tx.send(Ok(event)).await.is_err() {
+                                break;
+                            }
+                        }
+                    }
                     result = signal_rx.recv() => {
                         match result {
                             Ok((sub_id, delivery)) => {
@@ -237,6 +294,23 @@ where
                     .text("keepalive")
             )
     }
+
+    /// Builds a `session_token` event carrying a fresh token for the
+    /// session `claims` were issued for.
+    fn token_event(&self, claims: &SessionTokenClaims) -> Option<Event> {
+        let signer = self.session_tokens.as_ref()?;
+        match signer.renew(claims) {
+            Ok(token) => Some(
+                Event::default()
+                    .event("session_token")
+                    .data(serde_json::json!({ "session_token": token }).to_string()),
+            ),
+            Err(e) => {
+                warn!("Failed to renew session token: {}", e);
+                None
+            }
+        }
+    }
 }
 
 impl<S, D, M> Clone for SseHandler<S, D, M>
@@ -252,6 +326,8 @@ where
             session_manager: Arc::clone(&self.session_manager),
             signal_tx: self.signal_tx.clone(),
             keepalive_interval: self.keepalive_interval,
+            session_tokens: self.session_tokens.clone(),
+            require_session_tokens: self.require_session_tokens,
         }
     }
 }
@@ -291,6 +367,7 @@ mod tests {
             payload: Payload::new(json!({"test": true}), "application/json"),
             metadata: None,
             encrypted: None,
+            signature: None,
         }
     }
 
@@ -388,6 +465,7 @@ mod tests {
             payload: Payload::new(json!({"text": "hello"}), "application/json"),
             metadata: None,
             encrypted: None,
+            signature: None,
         };
 
         let delivery = SignalDelivery::new("signal.email.*", signal);
@@ -432,6 +510,7 @@ mod tests {
     fn test_sse_query_debug() {
         let query = SseQuery {
             session_id: "sess_123".to_string(),
+            session_token: None,
             subscription_id: Some("sub_456".to_string()),
             last_event_id: None,
         };
@@ -444,6 +523,7 @@ mod tests {
     fn test_sse_query_clone() {
         let query = SseQuery {
             session_id: "sess_123".to_string(),
+            session_token: None,
             subscription_id: Some("sub_456".to_string()),
             last_event_id: Some("sig_789".to_string()),
         };
@@ -461,12 +541,13 @@ mod tests {
         let handler = Arc::new(create_test_handler());
         let query = Query(SseQuery {
             session_id: "nonexistent".to_string(),
+            session_token: None,
             subscription_id: None,
             last_event_id: None,
         });
 
         // Should return an error stream for invalid session
-        let _response = handler.handle_stream(query).await;
+        let _response = handler.handle_stream(HeaderMap::new(), query).await;
         // The response is an Sse stream - we can't easily test the content
         // but we verify it doesn't panic
     }
@@ -499,14 +580,71 @@ mod tests {
 
         let query = Query(SseQuery {
             session_id: "sess_sse_test".to_string(),
+            session_token: None,
             subscription_id: None,
             last_event_id: None,
         });
 
-        let _response = handler.handle_stream(query).await;
+        let _response = handler.handle_stream(HeaderMap::new(), query).await;
         // Verify it doesn't panic for valid session
     }
 
+    #[tokio::test]
+    async fn test_handle_stream_sends_renewed_token() {
+        use axum::extract::Query;
+        use http_body_util::BodyExt;
+
+        let session_manager = Arc::new(InMemorySessionManager::default());
+        let session_info = crate::session::SessionInfo::new(
+            "sess_sse_token",
+            "client-1",
+            "agent",
+            "1.0",
+            cauce_core::Transport::Sse,
+            3600,
+        );
+        session_manager.create_session(session_info).await.unwrap();
+
+        let signer = Arc::new(SessionTokenSigner::hs256(b"secret"));
+        let token = signer.issue("sess_sse_token", "client-1", None).unwrap();
+        let handler = Arc::new(
+            SseHandler::new(
+                Arc::new(InMemorySubscriptionManager::default()),
+                Arc::new(InMemoryDeliveryTracker::new(RedeliveryConfig::default())),
+                session_manager,
+            )
+            .with_session_tokens(Arc::clone(&signer), true),
+        );
+        let query = Query(SseQuery {
+            session_id: String::new(),
+            session_token: Some(token.clone()),
+            subscription_id: None,
+            last_event_id: None,
+        });
+
+        let mut body = handler
+            .handle_stream(HeaderMap::new(), query)
+            .await
+            .into_response()
+            .into_body();
+        let frame = tokio::time::timeout(Duration::from_secs(1), body.frame())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        let text = String::from_utf8(frame.into_data().unwrap().to_vec()).unwrap();
+        assert!(text.starts_with("event: session_token\n"), "{}", text);
+
+        let data = text
+            .lines()
+            .find_map(|line| line.strip_prefix("data: "))
+            .unwrap();
+        let data: serde_json::Value = serde_json::from_str(data).unwrap();
+        let renewed = data["session_token"].as_str().unwrap();
+        assert_ne!(renewed, token);
+        assert_eq!(signer.verify(renewed).unwrap().session_id, "sess_sse_token");
+    }
+
     #[tokio::test]
     async fn test_handle_stream_with_subscription_filter() {
         use axum::extract::Query;
@@ -540,11 +678,12 @@ mod tests {
 
         let query = Query(SseQuery {
             session_id: "sess_sse_filter".to_string(),
+            session_token: None,
             subscription_id: Some("sub_filter".to_string()),
             last_event_id: None,
         });
 
-        let _response = handler.handle_stream(query).await;
+        let _response = handler.handle_stream(HeaderMap::new(), query).await;
         // Verify it doesn't panic and processes unacked signals
     }
 
@@ -576,11 +715,12 @@ mod tests {
 
         let query = Query(SseQuery {
             session_id: "sess_sse_resume".to_string(),
+            session_token: None,
             subscription_id: Some("sub_resume".to_string()),
             last_event_id: Some("sig_last".to_string()),
         });
 
-        let _response = handler.handle_stream(query).await;
+        let _response = handler.handle_stream(HeaderMap::new(), query).await;
         // Verify it handles the resumption request without panicking
     }
 
@@ -39,23 +39,29 @@ use axum::extract::WebSocketUpgrade;
 use axum::response::IntoResponse;
 use futures::stream::SplitSink;
 use futures::{SinkExt, StreamExt};
-use serde_json::json;
+use serde_json::{json, Value};
 use tokio::sync::{broadcast, mpsc, Mutex, RwLock};
 use tracing::{debug, error, info, warn};
 
 use super::message::JsonRpcMessage;
 use super::SignalSender;
+use crate::audit::{AuditAction, AuditEvent, AuditLog};
+use crate::auth::{Principal, TopicAcl};
 use crate::delivery::DeliveryTracker;
+use crate::encryption::{EncryptionPolicy, KeyDirectory};
 use crate::error::{ServerError, ServerResult};
-use crate::routing::MessageRouter;
-use crate::session::{SessionInfo, SessionManager};
+use crate::routing::{is_addressed_to, MessageRouter};
+use crate::session::{SessionInfo, SessionManager, SessionTokenSigner};
+use crate::signing::AdapterKeys;
 use crate::subscription::SubscriptionManager;
 use cauce_core::methods::Transport;
 use cauce_core::{
-    AckRequest, HelloRequest, HelloResponse, JsonRpcError, JsonRpcNotification, JsonRpcRequest,
-    JsonRpcResponse, PublishRequest, PublishResponse, RequestId, SignalDelivery, SubscribeRequest,
-    UnsubscribeRequest, UnsubscribeResponse, METHOD_ACK, METHOD_GOODBYE, METHOD_HELLO, METHOD_PING,
-    METHOD_PUBLISH, METHOD_SIGNAL, METHOD_SUBSCRIBE, METHOD_UNSUBSCRIBE, PROTOCOL_VERSION,
+    AckRequest, Action, ActionDelivery, HelloRequest, HelloResponse, JsonRpcError,
+    JsonRpcNotification, JsonRpcRequest, JsonRpcResponse, KeysListRequest, KeysListResponse,
+    PublishMessage, PublishRequest, PublishResponse, RequestId, SignalDelivery, SubscribeRequest,
+    SubscriptionInfo, UnsubscribeRequest, UnsubscribeResponse, METHOD_ACK, METHOD_ACTION,
+    METHOD_GOODBYE, METHOD_HELLO, METHOD_KEYS_LIST, METHOD_PING, METHOD_PUBLISH, METHOD_SIGNAL,
+    METHOD_SUBSCRIBE, METHOD_UNSUBSCRIBE, PROTOCOL_VERSION,
 };
 
 /// WebSocket transport handler.
@@ -76,6 +82,24 @@ where
     /// Registry mapping session IDs to their signal delivery channels.
     /// Used to push signals to connected clients in real-time.
     connections: Arc<RwLock<HashMap<String, mpsc::Sender<SignalDelivery>>>>,
+    /// Registry mapping session IDs to their action delivery channels.
+    action_connections: Arc<RwLock<HashMap<String, mpsc::Sender<ActionDelivery>>>>,
+    /// Topic access control for publish and subscribe requests.
+    acl: Arc<TopicAcl>,
+    /// Principal each session authenticated as, keyed by session ID.
+    principals: Arc<RwLock<HashMap<String, Principal>>>,
+    /// Issues session tokens for HTTP transports in hello responses.
+    session_tokens: Option<Arc<SessionTokenSigner>>,
+    /// Topics that must carry end-to-end encrypted payloads.
+    encryption: Arc<EncryptionPolicy>,
+    /// Subscriber public keys, answered over `cauce.keys.list`.
+    keys: KeyDirectory<S>,
+    /// Adapter keys that published signals are verified against.
+    adapter_keys: Arc<AdapterKeys>,
+    /// Audit log security-relevant requests are recorded to.
+    audit: Arc<AuditLog>,
+    /// Address each session connected from, keyed by session ID.
+    addresses: Arc<RwLock<HashMap<String, String>>>,
 }
 
 impl<S, R, D, M> WebSocketHandler<S, R, D, M>
@@ -94,19 +118,66 @@ where
     ) -> Self {
         let (shutdown_tx, _) = broadcast::channel(1);
         Self {
+            keys: KeyDirectory::new(Arc::clone(&subscription_manager)),
             subscription_manager,
             message_router,
             delivery_tracker,
             session_manager,
             shutdown_tx,
             connections: Arc::new(RwLock::new(HashMap::new())),
+            action_connections: Arc::new(RwLock::new(HashMap::new())),
+            acl: Arc::new(TopicAcl::default()),
+            principals: Arc::new(RwLock::new(HashMap::new())),
+            session_tokens: None,
+            encryption: Arc::new(EncryptionPolicy::default()),
+            adapter_keys: Arc::new(AdapterKeys::default()),
+            audit: Arc::new(AuditLog::new()),
+            addresses: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
-    /// Register a connection's signal sender for a session.
-    async fn register_connection(&self, session_id: &str, signal_tx: mpsc::Sender<SignalDelivery>) {
+    /// Enforces topic access control on publish and subscribe requests.
+    pub fn with_acl(mut self, acl: Arc<TopicAcl>) -> Self {
+        self.acl = acl;
+        self
+    }
+
+    /// Includes a signed session token in hello responses.
+    pub fn with_session_tokens(mut self, signer: Arc<SessionTokenSigner>) -> Self {
+        self.session_tokens = Some(signer);
+        self
+    }
+
+    /// Enforces an end-to-end encryption policy on publish and subscribe
+    /// requests.
+    pub fn with_encryption(mut self, policy: Arc<EncryptionPolicy>) -> Self {
+        self.encryption = policy;
+        self
+    }
+
+    /// Verifies published signals against registered adapter keys.
+    pub fn with_adapter_keys(mut self, keys: Arc<AdapterKeys>) -> Self {
+        self.adapter_keys = keys;
+        self
+    }
+
+    /// Records authenticated connections, hellos, subscriptions and
+    /// publishes to action topics to an audit log.
+    pub fn with_audit(mut self, audit: Arc<AuditLog>) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// Register a connection's signal and action senders for a session.
+    async fn register_connection(&self, session_id: &str, connection: &WebSocketConnection) {
         let mut conns = self.connections.write().await;
-        conns.insert(session_id.to_string(), signal_tx);
+        conns.insert(session_id.to_string(), connection.signal_sender());
+        if let Some(action_tx) = connection.action_sender() {
+            self.action_connections
+                .write()
+                .await
+                .insert(session_id.to_string(), action_tx);
+        }
         debug!("Registered connection for session {}", session_id);
     }
 
@@ -114,6 +185,9 @@ where
     async fn unregister_connection(&self, session_id: &str) {
         let mut conns = self.connections.write().await;
         conns.remove(session_id);
+        self.action_connections.write().await.remove(session_id);
+        self.principals.write().await.remove(session_id);
+        self.addresses.write().await.remove(session_id);
         debug!("Unregistered connection for session {}", session_id);
     }
 
@@ -131,6 +205,99 @@ where
         }
     }
 
+    /// Routes a message to matching subscriptions, tracking and pushing a
+    /// delivery for each. Deliveries are marked `verified` as given.
+    /// Returns the message ID and the delivery count.
+    async fn deliver(
+        &self,
+        publish_request: &PublishRequest,
+        verified: bool,
+    ) -> ServerResult<(String, u32)> {
+        // Route the message to find matching subscriptions
+        let _route_result = self.message_router.route(publish_request).await?;
+
+        // Get matching subscriptions and create deliveries; encrypted
+        // messages only go to the subscription holding their recipient key
+        let matching_subs: Vec<SubscriptionInfo> = self
+            .message_router
+            .get_matching_subscriptions(&publish_request.topic)
+            .await?
+            .into_iter()
+            .filter(|sub| is_addressed_to(&publish_request.message, sub))
+            .collect();
+
+        if let PublishMessage::Action(action) = &publish_request.message {
+            let count = self
+                .deliver_action(publish_request, action, &matching_subs)
+                .await;
+            return Ok((action.id.clone(), count));
+        }
+
+        // Create and track deliveries for each subscription, and push to connected clients
+        let mut message_id = format!("msg_{}", uuid::Uuid::new_v4());
+        let mut delivered_count = 0u32;
+        for sub in &matching_subs {
+            if let Ok(delivery) = self.message_router.create_delivery(publish_request, sub) {
+                let delivery = delivery.with_verified(verified);
+                message_id = delivery.signal.id.clone();
+
+                // Track the delivery
+                if let Err(e) = self.delivery_tracker.track(&sub.subscription_id, &delivery).await {
+                    warn!("Failed to track delivery for {}: {}", sub.subscription_id, e);
+                }
+
+                // Push to connected client in real-time
+                self.push_signal_to_subscribers(std::slice::from_ref(&sub.session_id), &delivery).await;
+                delivered_count += 1;
+            }
+        }
+
+        Ok((message_id, delivered_count))
+    }
+
+    /// Pushes an action to the connected sessions of matching subscriptions
+    /// as `cauce.action` notifications. Returns the delivery count.
+    ///
+    /// Actions are commands for adapters that are online; they are not
+    /// tracked for redelivery.
+    async fn deliver_action(
+        &self,
+        publish_request: &PublishRequest,
+        action: &Action,
+        matching_subs: &[SubscriptionInfo],
+    ) -> u32 {
+        let conns = self.action_connections.read().await;
+        let mut delivered_count = 0u32;
+        for sub in matching_subs {
+            let delivery = match self.message_router.create_action_delivery(publish_request, sub) {
+                Ok(delivery) => delivery,
+                Err(e) => {
+                    warn!("Failed to create action delivery for {}: {}", sub.subscription_id, e);
+                    continue;
+                }
+            };
+            if let Some(tx) = conns.get(&sub.session_id) {
+                if let Err(e) = tx.send(delivery).await {
+                    warn!("Failed to push action to session {}: {}", sub.session_id, e);
+                    continue;
+                }
+                debug!("Pushed action {} to session {}", action.id, sub.session_id);
+                delivered_count += 1;
+            }
+        }
+        delivered_count
+    }
+
+    /// Publishes a message on behalf of the hub itself.
+    ///
+    /// Used for system topics such as lockout alerts. No access control is
+    /// applied. Returns the number of subscriptions the message was delivered to.
+    pub async fn publish_system(&self, publish_request: &PublishRequest) -> ServerResult<u32> {
+        self.deliver(publish_request, false)
+            .await
+            .map(|(_, count)| count)
+    }
+
     /// Signal shutdown to all active connections.
     pub fn shutdown(&self) {
         let _ = self.shutdown_tx.send(());
@@ -138,24 +305,70 @@ where
 
     /// Handle a WebSocket upgrade request.
     pub async fn handle_upgrade(self: Arc<Self>, ws: WebSocketUpgrade) -> impl IntoResponse {
+        self.handle_upgrade_as(ws, None).await
+    }
+
+    /// Handle a WebSocket upgrade request from an authenticated client.
+    ///
+    /// Access control decisions for the connection are made for `principal`.
+    /// Without one, they are made for the client ID given in `cauce.hello`.
+    pub async fn handle_upgrade_as(
+        self: Arc<Self>,
+        ws: WebSocketUpgrade,
+        principal: Option<Principal>,
+    ) -> impl IntoResponse {
+        self.handle_upgrade_from(ws, principal, None).await
+    }
+
+    /// Handle a WebSocket upgrade request from `source_address`.
+    ///
+    /// The address is recorded in the audit log for requests made over the
+    /// connection.
+    pub async fn handle_upgrade_from(
+        self: Arc<Self>,
+        ws: WebSocketUpgrade,
+        principal: Option<Principal>,
+        source_address: Option<String>,
+    ) -> impl IntoResponse {
         let handler = Arc::clone(&self);
         ws.on_upgrade(move |socket| async move {
-            if let Err(e) = handler.handle_connection(socket).await {
+            let result = handler
+                .handle_connection(socket, principal, source_address)
+                .await;
+            if let Err(e) = result {
                 error!("WebSocket connection error: {}", e);
             }
         })
     }
 
     /// Handle a WebSocket connection.
-    async fn handle_connection(self: Arc<Self>, socket: WebSocket) -> ServerResult<()> {
+    async fn handle_connection(
+        self: Arc<Self>,
+        socket: WebSocket,
+        principal: Option<Principal>,
+        source_address: Option<String>,
+    ) -> ServerResult<()> {
         let (ws_sender, mut ws_receiver) = socket.split();
 
         // Create channels for signal delivery
         let (signal_tx, mut signal_rx) = mpsc::channel::<SignalDelivery>(100);
+        let (action_tx, mut action_rx) = mpsc::channel::<ActionDelivery>(100);
         let mut shutdown_rx = self.shutdown_tx.subscribe();
 
         // Connection state
-        let connection = Arc::new(WebSocketConnection::new(ws_sender, signal_tx));
+        let mut connection = WebSocketConnection::new(ws_sender, signal_tx)
+            .with_action_sender(action_tx)
+            .with_source_address(source_address.clone());
+        if let Some(principal) = principal {
+            if self.audit.is_enabled() {
+                let event = AuditEvent::success(AuditAction::AuthSuccess)
+                    .with_identity(&principal.client_id)
+                    .with_source_address(source_address);
+                self.audit.record(event).await;
+            }
+            connection = connection.with_principal(principal);
+        }
+        let connection = Arc::new(connection);
         let session_id: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
 
         info!("New WebSocket connection established");
@@ -176,6 +389,14 @@ where
                     }
                 }
 
+                // Incoming action to deliver
+                Some(action) = action_rx.recv() => {
+                    if let Err(e) = connection.send_action_notification(&action).await {
+                        warn!("Failed to send action notification: {}", e);
+                        break;
+                    }
+                }
+
                 // Incoming WebSocket message
                 msg = ws_receiver.next() => {
                     match msg {
@@ -274,7 +495,24 @@ where
         debug!("Processing request: {} (id: {:?})", method, id);
 
         match method {
-            METHOD_HELLO => self.handle_hello(&request, connection, session_id).await,
+            METHOD_HELLO => {
+                let response = self.handle_hello(&request, connection, session_id).await;
+                if self.audit.is_enabled() {
+                    let param = |key: &str| {
+                        request
+                            .params()
+                            .and_then(|params| params.get(key))
+                            .cloned()
+                    };
+                    let mut event = response_event(AuditAction::Hello, &response, &[]);
+                    event.identity = param("client_id").and_then(|v| v.as_str().map(String::from));
+                    event.source_address = connection.source_address().map(String::from);
+                    event.details = param("client_type").map(|v| json!({ "client_type": v }));
+                    let sid = session_id.lock().await.clone();
+                    self.audit(event, sid.as_deref()).await;
+                }
+                response
+            }
             METHOD_SUBSCRIBE => {
                 self.handle_subscribe(&request, session_id)
                     .await
@@ -295,6 +533,11 @@ where
                     .await
                     .unwrap_or_else(|e| e)
             }
+            METHOD_KEYS_LIST => {
+                self.handle_keys_list(&request, session_id)
+                    .await
+                    .unwrap_or_else(|e| e)
+            }
             METHOD_PING => self.handle_ping(&request),
             METHOD_GOODBYE => self.handle_goodbye(&request, session_id).await,
             _ => JsonRpcResponse::error(
@@ -349,8 +592,9 @@ where
         // Get client type as string
         let client_type_str = format!("{:?}", hello_request.client_type).to_lowercase();
 
-        // Create session info
-        let session_info = SessionInfo::new(
+        // Create session info, bound to the identity the connection
+        // authenticated as rather than the client ID it states
+        let mut session_info = SessionInfo::new(
             &new_session_id,
             &hello_request.client_id,
             &client_type_str,
@@ -358,6 +602,10 @@ where
             Transport::WebSocket,
             3600, // 1 hour default TTL
         );
+        if let Some(principal) = connection.principal() {
+            session_info = session_info.with_authenticated_id(&principal.client_id);
+        }
+        let identity = session_info.identity().to_string();
 
         // Create session
         if let Err(e) = self.session_manager.create_session(session_info).await {
@@ -376,7 +624,24 @@ where
 
         // Register the connection for signal delivery
         connection.set_session_id(&new_session_id);
-        self.register_connection(&new_session_id, connection.signal_sender()).await;
+        self.register_connection(&new_session_id, connection).await;
+
+        // Remember who the session acts as for access control
+        let principal = connection
+            .principal()
+            .cloned()
+            .unwrap_or_else(|| Principal::new(&hello_request.client_id));
+        self.acl.record_principal(&principal);
+        self.principals
+            .write()
+            .await
+            .insert(new_session_id.clone(), principal);
+        if let Some(address) = connection.source_address() {
+            self.addresses
+                .write()
+                .await
+                .insert(new_session_id.clone(), address.to_string());
+        }
 
         info!(
             "Client {} authenticated with session {}",
@@ -384,7 +649,13 @@ where
         );
 
         // Build response
-        let response = HelloResponse::new(&new_session_id, PROTOCOL_VERSION);
+        let mut response = HelloResponse::new(&new_session_id, PROTOCOL_VERSION);
+        if let Some(signer) = &self.session_tokens {
+            match signer.issue(&new_session_id, &identity, None) {
+                Ok(token) => response = response.with_session_token(token),
+                Err(e) => warn!("Failed to issue session token: {}", e),
+            }
+        }
 
         match serde_json::to_value(&response) {
             Ok(result) => JsonRpcResponse::success(id, result),
@@ -395,11 +666,37 @@ where
         }
     }
 
-    /// Handle cauce.subscribe request.
+    /// Handle cauce.subscribe request, recording it to the audit log.
     async fn handle_subscribe(
         &self,
         request: &JsonRpcRequest,
         session_id: &Arc<Mutex<Option<String>>>,
+    ) -> Result<JsonRpcResponse, JsonRpcResponse> {
+        let result = self.subscribe(request, session_id).await;
+        if self.audit.is_enabled() {
+            let topics = request
+                .params()
+                .and_then(|params| params.get("topics"))
+                .and_then(Value::as_array)
+                .map(|topics| {
+                    let topics: Vec<_> = topics.iter().filter_map(Value::as_str).collect();
+                    topics.join(",")
+                });
+            let response = result.as_ref().unwrap_or_else(|e| e);
+            let mut event =
+                response_event(AuditAction::Subscribe, response, &["subscription_id", "status"]);
+            event.target = topics;
+            let sid = session_id.lock().await.clone();
+            self.audit(event, sid.as_deref()).await;
+        }
+        result
+    }
+
+    /// Create a subscription for a cauce.subscribe request.
+    async fn subscribe(
+        &self,
+        request: &JsonRpcRequest,
+        session_id: &Arc<Mutex<Option<String>>>,
     ) -> Result<JsonRpcResponse, JsonRpcResponse> {
         let id = request.id().clone();
 
@@ -427,6 +724,17 @@ where
         // Parse subscribe request
         let subscribe_request: SubscribeRequest = self.parse_params(request.params(), &id)?;
 
+        // Check topic access
+        let principal = self.principal(&sid, &session_info.client_id).await;
+        self.acl
+            .check_subscribe(&principal, &subscribe_request.topics)
+            .map_err(|e| JsonRpcResponse::error(Some(id.clone()), e.into()))?;
+
+        // Check encryption policy
+        self.encryption
+            .check_subscribe(&subscribe_request)
+            .map_err(|e| JsonRpcResponse::error(Some(id.clone()), e.into()))?;
+
         // Create subscription
         let response = self
             .subscription_manager
@@ -486,58 +794,84 @@ where
             })
     }
 
-    /// Handle cauce.publish request.
+    /// Handle cauce.publish request, recording publishes to action topics
+    /// to the audit log.
     async fn handle_publish(
         &self,
         request: &JsonRpcRequest,
         session_id: &Arc<Mutex<Option<String>>>,
+    ) -> Result<JsonRpcResponse, JsonRpcResponse> {
+        let result = self.publish(request, session_id).await;
+        let topic = request
+            .params()
+            .and_then(|params| params.get("topic"))
+            .and_then(Value::as_str)
+            .filter(|topic| topic.starts_with("action."));
+        if let (Some(topic), true) = (topic, self.audit.is_enabled()) {
+            let response = result.as_ref().unwrap_or_else(|e| e);
+            let keys = ["message_id", "delivered_to"];
+            let event = response_event(AuditAction::Publish, response, &keys).with_target(topic);
+            let sid = session_id.lock().await.clone();
+            self.audit(event, sid.as_deref()).await;
+        }
+        result
+    }
+
+    /// Route and deliver the message in a cauce.publish request.
+    async fn publish(
+        &self,
+        request: &JsonRpcRequest,
+        session_id: &Arc<Mutex<Option<String>>>,
     ) -> Result<JsonRpcResponse, JsonRpcResponse> {
         let id = request.id().clone();
 
         // Check session
-        self.require_session(session_id, &id).await?;
+        let sid = self.require_session(session_id, &id).await?;
 
         // Parse publish request
         let publish_request: PublishRequest = self.parse_params(request.params(), &id)?;
 
-        // Route the message to find matching subscriptions
-        let _route_result = self.message_router.route(&publish_request).await.map_err(|e| {
-            JsonRpcResponse::error(
-                Some(id.clone()),
-                JsonRpcError::with_data(-32603, "Internal error", json!({"details": e.to_string()})),
-            )
-        })?;
+        // Check topic access
+        if self.acl.is_enabled() {
+            let client_id = self
+                .session_manager
+                .get_session(&sid)
+                .await
+                .ok()
+                .flatten()
+                .map(|session| session.client_id)
+                .unwrap_or_default();
+            let principal = self.principal(&sid, &client_id).await;
+            self.acl
+                .check_publish(&principal, &publish_request.topic)
+                .map_err(|e| JsonRpcResponse::error(Some(id.clone()), e.into()))?;
+        }
 
-        // Get matching subscriptions and create deliveries
-        let matching_subs = self
-            .message_router
-            .get_matching_subscriptions(&publish_request.topic)
+        // Check encryption policy
+        self.encryption
+            .check_publish(&publish_request)
+            .map_err(|e| JsonRpcResponse::error(Some(id.clone()), e.into()))?;
+
+        // Check signal provenance
+        let verified = self
+            .adapter_keys
+            .check_publish(&publish_request)
+            .map_err(|e| JsonRpcResponse::error(Some(id.clone()), e.into()))?;
+
+        let (message_id, delivered_count) = self
+            .deliver(&publish_request, verified)
             .await
             .map_err(|e| {
                 JsonRpcResponse::error(
                     Some(id.clone()),
-                    JsonRpcError::with_data(-32603, "Internal error", json!({"details": e.to_string()})),
+                    JsonRpcError::with_data(
+                        -32603,
+                        "Internal error",
+                        json!({"details": e.to_string()}),
+                    ),
                 )
             })?;
 
-        // Create and track deliveries for each subscription, and push to connected clients
-        let mut message_id = format!("msg_{}", uuid::Uuid::new_v4());
-        let mut delivered_count = 0u32;
-        for sub in &matching_subs {
-            if let Ok(delivery) = self.message_router.create_delivery(&publish_request, sub) {
-                message_id = delivery.signal.id.clone();
-
-                // Track the delivery
-                if let Err(e) = self.delivery_tracker.track(&sub.subscription_id, &delivery).await {
-                    warn!("Failed to track delivery for {}: {}", sub.subscription_id, e);
-                }
-
-                // Push to connected client in real-time
-                self.push_signal_to_subscribers(std::slice::from_ref(&sub.session_id), &delivery).await;
-                delivered_count += 1;
-            }
-        }
-
         let response = PublishResponse::new(
             message_id,
             delivered_count,
@@ -554,6 +888,55 @@ where
             })
     }
 
+    /// Handle cauce.keys.list request.
+    ///
+    /// Only clients allowed to publish to the topic may list its recipients.
+    async fn handle_keys_list(
+        &self,
+        request: &JsonRpcRequest,
+        session_id: &Arc<Mutex<Option<String>>>,
+    ) -> Result<JsonRpcResponse, JsonRpcResponse> {
+        let id = request.id().clone();
+
+        // Check session
+        let sid = self.require_session(session_id, &id).await?;
+
+        // Parse keys list request
+        let keys_request: KeysListRequest = self.parse_params(request.params(), &id)?;
+
+        // Check topic access
+        if self.acl.is_enabled() {
+            let client_id = self
+                .session_manager
+                .get_session(&sid)
+                .await
+                .ok()
+                .flatten()
+                .map(|session| session.client_id)
+                .unwrap_or_default();
+            let principal = self.principal(&sid, &client_id).await;
+            self.acl
+                .check_publish(&principal, &keys_request.topic)
+                .map_err(|e| JsonRpcResponse::error(Some(id.clone()), e.into()))?;
+        }
+
+        let recipients = self.keys.recipients(&keys_request.topic).await.map_err(|e| {
+            JsonRpcResponse::error(
+                Some(id.clone()),
+                JsonRpcError::with_data(-32603, "Internal error", json!({"details": e.to_string()})),
+            )
+        })?;
+
+        serde_json::to_value(KeysListResponse::new(recipients))
+            .map(|result| JsonRpcResponse::success(id.clone(), result))
+            .map_err(|e| {
+                JsonRpcResponse::error(
+                    Some(id),
+                    JsonRpcError::with_data(-32603, "Internal error", json!({"details": e.to_string()})),
+                )
+            })
+    }
+
     /// Handle cauce.ack request.
     async fn handle_ack(
         &self,
@@ -618,6 +1001,7 @@ where
 
         // Remove session if exists
         if let Some(ref session) = sid {
+            self.principals.write().await.remove(session);
             if let Err(e) = self.session_manager.remove_session(session).await {
                 warn!("Failed to remove session on goodbye: {}", e);
             }
@@ -641,6 +1025,43 @@ where
         }
     }
 
+    /// Records `event` for a request made in `session_id`, filling in the
+    /// session's identity and source address where the event has none.
+    async fn audit(&self, mut event: AuditEvent, session_id: Option<&str>) {
+        if let Some(sid) = session_id {
+            if event.identity.is_none() {
+                let principal = self.principals.read().await.get(sid).cloned();
+                event.identity = match principal {
+                    Some(principal) => Some(principal.client_id),
+                    None => self
+                        .session_manager
+                        .get_session(sid)
+                        .await
+                        .ok()
+                        .flatten()
+                        .map(|session| session.client_id),
+                };
+            }
+            if event.source_address.is_none() {
+                event.source_address = self.addresses.read().await.get(sid).cloned();
+            }
+            event.session_id = Some(sid.to_string());
+        }
+        self.audit.record(event).await;
+    }
+
+    /// Returns the principal a session acts as.
+    ///
+    /// Sessions not created by this handler act as their client ID.
+    async fn principal(&self, session_id: &str, client_id: &str) -> Principal {
+        self.principals
+            .read()
+            .await
+            .get(session_id)
+            .cloned()
+            .unwrap_or_else(|| Principal::new(client_id))
+    }
+
     /// Require an active session, returning an error response if not authenticated.
     async fn require_session(
         &self,
@@ -693,18 +1114,60 @@ where
             session_manager: Arc::clone(&self.session_manager),
             shutdown_tx: self.shutdown_tx.clone(),
             connections: Arc::clone(&self.connections),
+            action_connections: Arc::clone(&self.action_connections),
+            acl: Arc::clone(&self.acl),
+            principals: Arc::clone(&self.principals),
+            session_tokens: self.session_tokens.clone(),
+            encryption: Arc::clone(&self.encryption),
+            keys: self.keys.clone(),
+            adapter_keys: Arc::clone(&self.adapter_keys),
+            audit: Arc::clone(&self.audit),
+            addresses: Arc::clone(&self.addresses),
         }
     }
 }
 
+/// Builds an audit event from the response to a request.
+///
+/// Failures carry the error as their reason; successes carry the `keys`
+/// of the result as details.
+fn response_event(action: AuditAction, response: &JsonRpcResponse, keys: &[&str]) -> AuditEvent {
+    if let Some(error) = response.error_obj() {
+        let reason = error
+            .data
+            .as_ref()
+            .and_then(|data| data.get("reason").or_else(|| data.get("details")))
+            .and_then(Value::as_str);
+        return match reason {
+            Some(reason) => AuditEvent::failure(action, format!("{}: {}", error.message, reason)),
+            None => AuditEvent::failure(action, &error.message),
+        };
+    }
+
+    let mut event = AuditEvent::success(action);
+    if let Some(result) = response.result() {
+        let details: serde_json::Map<String, Value> = keys
+            .iter()
+            .filter_map(|key| Some((key.to_string(), result.get(*key)?.clone())))
+            .collect();
+        if !details.is_empty() {
+            event.details = Some(Value::Object(details));
+        }
+    }
+    event
+}
+
 /// Represents an active WebSocket connection.
 ///
 /// Provides methods for sending messages and managing connection state.
 pub struct WebSocketConnection {
     sender: Mutex<SplitSink<WebSocket, Message>>,
     signal_tx: mpsc::Sender<SignalDelivery>,
+    action_tx: Option<mpsc::Sender<ActionDelivery>>,
     session_id: Mutex<Option<String>>,
     connected: AtomicBool,
+    principal: Option<Principal>,
+    source_address: Option<String>,
 }
 
 impl WebSocketConnection {
@@ -713,11 +1176,44 @@ impl WebSocketConnection {
         Self {
             sender: Mutex::new(sender),
             signal_tx,
+            action_tx: None,
             session_id: Mutex::new(None),
             connected: AtomicBool::new(true),
+            principal: None,
+            source_address: None,
         }
     }
 
+    /// Sets the channel actions for this connection are queued on.
+    ///
+    /// Without one, the connection receives no actions.
+    pub fn with_action_sender(mut self, action_tx: mpsc::Sender<ActionDelivery>) -> Self {
+        self.action_tx = Some(action_tx);
+        self
+    }
+
+    /// Sets the principal the client authenticated as.
+    pub fn with_principal(mut self, principal: Principal) -> Self {
+        self.principal = Some(principal);
+        self
+    }
+
+    /// Returns the principal the client authenticated as, if any.
+    pub fn principal(&self) -> Option<&Principal> {
+        self.principal.as_ref()
+    }
+
+    /// Sets the address the client connected from.
+    pub fn with_source_address(mut self, address: Option<String>) -> Self {
+        self.source_address = address;
+        self
+    }
+
+    /// Returns the address the client connected from, if known.
+    pub fn source_address(&self) -> Option<&str> {
+        self.source_address.as_deref()
+    }
+
     /// Sets the session ID for this connection.
     pub fn set_session_id(&self, id: &str) {
         if let Ok(mut sid) = self.session_id.try_lock() {
@@ -731,6 +1227,11 @@ impl WebSocketConnection {
         self.signal_tx.clone()
     }
 
+    /// Returns a clone of the action sender channel, if any.
+    pub fn action_sender(&self) -> Option<mpsc::Sender<ActionDelivery>> {
+        self.action_tx.clone()
+    }
+
     /// Send a JSON-RPC message.
     pub async fn send_message(&self, message: &JsonRpcMessage) -> ServerResult<()> {
         if !self.connected.load(Ordering::SeqCst) {
@@ -778,6 +1279,19 @@ impl WebSocketConnection {
             .await
     }
 
+    /// Send an action notification to the client.
+    pub async fn send_action_notification(&self, delivery: &ActionDelivery) -> ServerResult<()> {
+        let notification = JsonRpcNotification::new(
+            METHOD_ACTION.to_string(),
+            Some(serde_json::to_value(delivery).map_err(|e| ServerError::Serialization {
+                message: e.to_string(),
+            })?),
+        );
+
+        self.send_message(&JsonRpcMessage::Notification(notification))
+            .await
+    }
+
     /// Queue a signal for delivery to this connection.
     pub async fn queue_signal(&self, delivery: SignalDelivery) -> ServerResult<()> {
         self.signal_tx.send(delivery).await.map_err(|_| ServerError::TransportError {
@@ -845,6 +1359,7 @@ mod tests {
             payload: Payload::new(json!({"test": true}), "application/json"),
             metadata: None,
             encrypted: None,
+            signature: None,
         }
     }
 
@@ -1386,4 +1901,319 @@ mod tests {
         // Should have delivered to at least one subscription
         assert!(result_value.get("delivered_to").is_some());
     }
+
+    #[tokio::test]
+    async fn test_acl_enforced_on_subscribe_and_publish() {
+        use crate::config::{AclConfig, AclRule, AclSubject};
+
+        let acl = AclConfig::default().with_rule(
+            AclRule::new(AclSubject::Scope("email".to_string()))
+                .with_subscribe("signal.email.*")
+                .with_publish("signal.test"),
+        );
+        let handler = create_test_handler().with_acl(Arc::new(TopicAcl::new(acl)));
+
+        let session_info = crate::session::SessionInfo::new(
+            "sess_acl_test",
+            "client-1",
+            "agent",
+            "1.0",
+            cauce_core::Transport::WebSocket,
+            3600,
+        );
+        handler.session_manager.create_session(session_info).await.unwrap();
+        let session_id: Arc<Mutex<Option<String>>> =
+            Arc::new(Mutex::new(Some("sess_acl_test".to_string())));
+
+        let subscribe = JsonRpcRequest::new(
+            RequestId::Number(1),
+            METHOD_SUBSCRIBE.to_string(),
+            Some(json!({"topics": ["signal.email.*"]})),
+        );
+        let publish = JsonRpcRequest::new(
+            RequestId::Number(2),
+            METHOD_PUBLISH.to_string(),
+            Some(json!({"topic": "signal.test", "message": create_test_signal()})),
+        );
+
+        // Without the scope, both are denied with the topic named
+        let response = handler.handle_subscribe(&subscribe, &session_id).await.unwrap_err();
+        let error = response.error_obj().unwrap();
+        assert_eq!(error.code, -32003);
+        assert!(error.data.as_ref().unwrap()["reason"]
+            .as_str()
+            .unwrap()
+            .contains("signal.email.*"));
+        let response = handler.handle_publish(&publish, &session_id).await.unwrap_err();
+        assert_eq!(response.error_obj().unwrap().code, -32003);
+
+        // With it, both are allowed
+        handler.principals.write().await.insert(
+            "sess_acl_test".to_string(),
+            Principal {
+                scopes: vec!["email".to_string()],
+                ..Principal::new("client-1")
+            },
+        );
+        assert!(handler.handle_subscribe(&subscribe, &session_id).await.is_ok());
+        assert!(handler.handle_publish(&publish, &session_id).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_and_action_publish_are_audited() {
+        use crate::audit::AuditOutcome;
+        use crate::config::{AclConfig, AclRule, AclSubject};
+        use cauce_core::types::{ActionBody, ActionType};
+        use cauce_core::Action;
+
+        let acl = AclConfig::default().with_rule(
+            AclRule::new(AclSubject::Scope("email".to_string()))
+                .with_subscribe("signal.email.*")
+                .with_publish("action.email.*")
+                .with_publish("signal.test"),
+        );
+        let audit = Arc::new(AuditLog::new());
+        let mut records = audit.subscribe();
+        let handler = create_test_handler()
+            .with_acl(Arc::new(TopicAcl::new(acl)))
+            .with_audit(Arc::clone(&audit));
+
+        let session_info = crate::session::SessionInfo::new(
+            "sess_audit_test",
+            "client-1",
+            "agent",
+            "1.0",
+            cauce_core::Transport::WebSocket,
+            3600,
+        );
+        handler.session_manager.create_session(session_info).await.unwrap();
+        let session_id: Arc<Mutex<Option<String>>> =
+            Arc::new(Mutex::new(Some("sess_audit_test".to_string())));
+
+        let subscribe = JsonRpcRequest::new(
+            RequestId::Number(1),
+            METHOD_SUBSCRIBE.to_string(),
+            Some(json!({"topics": ["signal.email.*", "signal.email.sent"]})),
+        );
+        assert!(handler.handle_subscribe(&subscribe, &session_id).await.is_err());
+        let denied = records.recv().await.unwrap().event;
+        assert_eq!(denied.action, AuditAction::Subscribe);
+        assert_eq!(denied.outcome, AuditOutcome::Failure);
+        assert_eq!(denied.identity.as_deref(), Some("client-1"));
+        assert_eq!(denied.target.as_deref(), Some("signal.email.*,signal.email.sent"));
+        assert!(denied.reason.unwrap().contains("signal.email.*"));
+
+        handler.principals.write().await.insert(
+            "sess_audit_test".to_string(),
+            Principal {
+                scopes: vec!["email".to_string()],
+                ..Principal::new("client-1")
+            },
+        );
+        handler
+            .addresses
+            .write()
+            .await
+            .insert("sess_audit_test".to_string(), "10.0.0.7".to_string());
+        let response = handler.handle_subscribe(&subscribe, &session_id).await.unwrap();
+        let subscribed = records.recv().await.unwrap().event;
+        assert_eq!(subscribed.outcome, AuditOutcome::Success);
+        assert_eq!(subscribed.source_address.as_deref(), Some("10.0.0.7"));
+        assert_eq!(subscribed.session_id.as_deref(), Some("sess_audit_test"));
+        assert_eq!(
+            subscribed.details.unwrap()["subscription_id"],
+            response.result().unwrap()["subscription_id"]
+        );
+
+        // Publishes to signal topics are not audited; those to action topics are
+        let publish = |id: i64, topic: &str, message: Value| {
+            JsonRpcRequest::new(
+                RequestId::Number(id),
+                METHOD_PUBLISH.to_string(),
+                Some(json!({"topic": topic, "message": message})),
+            )
+        };
+        let signal = json!(create_test_signal());
+        assert!(handler
+            .handle_publish(&publish(2, "signal.test", signal), &session_id)
+            .await
+            .is_ok());
+        let action = Action::builder()
+            .topic(Topic::new_unchecked("action.email.send"))
+            .action(ActionBody::new(ActionType::Send, json!({"to": "bob@example.com"})))
+            .build()
+            .unwrap();
+        assert!(handler
+            .handle_publish(&publish(3, "action.email.send", json!(action)), &session_id)
+            .await
+            .is_ok());
+        let published = records.recv().await.unwrap().event;
+        assert_eq!(published.action, AuditAction::Publish);
+        assert_eq!(published.target.as_deref(), Some("action.email.send"));
+        assert_eq!(published.details.unwrap()["delivered_to"], 0);
+        assert!(records.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_encryption_policy_and_key_directory() {
+        use crate::config::EncryptionConfig;
+        use base64::engine::general_purpose::STANDARD;
+        use base64::Engine;
+
+        let policy = EncryptionConfig::default().with_required_topic("signal.test");
+        let handler =
+            create_test_handler().with_encryption(Arc::new(EncryptionPolicy::new(policy)));
+
+        let session_info = crate::session::SessionInfo::new(
+            "sess_e2e_test",
+            "client-1",
+            "agent",
+            "1.0",
+            cauce_core::Transport::WebSocket,
+            3600,
+        );
+        handler.session_manager.create_session(session_info).await.unwrap();
+        let session_id: Arc<Mutex<Option<String>>> =
+            Arc::new(Mutex::new(Some("sess_e2e_test".to_string())));
+
+        // Plaintext subscriptions and publishes are rejected
+        let subscribe = JsonRpcRequest::new(
+            RequestId::Number(1),
+            METHOD_SUBSCRIBE.to_string(),
+            Some(json!({"topics": ["signal.*"]})),
+        );
+        let response = handler.handle_subscribe(&subscribe, &session_id).await.unwrap_err();
+        assert_eq!(response.error_obj().unwrap().code, -32008);
+
+        let publish = JsonRpcRequest::new(
+            RequestId::Number(2),
+            METHOD_PUBLISH.to_string(),
+            Some(json!({"topic": "signal.test", "message": create_test_signal()})),
+        );
+        let response = handler.handle_publish(&publish, &session_id).await.unwrap_err();
+        assert_eq!(response.error_obj().unwrap().code, -32008);
+
+        // A subscription registering a key is accepted and listed
+        let key = STANDARD.encode([7u8; 32]);
+        let subscribe = JsonRpcRequest::new(
+            RequestId::Number(3),
+            METHOD_SUBSCRIBE.to_string(),
+            Some(json!({"topics": ["signal.*"], "e2e": {"enabled": true, "public_key": key}})),
+        );
+        handler.handle_subscribe(&subscribe, &session_id).await.unwrap();
+
+        let keys_list = JsonRpcRequest::new(
+            RequestId::Number(4),
+            METHOD_KEYS_LIST.to_string(),
+            Some(json!({"topic": "signal.test"})),
+        );
+        let response = handler.handle_keys_list(&keys_list, &session_id).await.unwrap();
+        let keys: KeysListResponse = serde_json::from_value(response.result().unwrap().clone())
+            .unwrap();
+        assert_eq!(keys.recipients.len(), 1);
+        assert_eq!(keys.recipients[0].public_key, key);
+
+        // An encrypted publish is accepted, a malformed one is not
+        let mut signal = create_test_signal();
+        signal.payload =
+            cauce_core::Payload::new(json!(null), cauce_core::ENCRYPTED_CONTENT_TYPE);
+        signal.encrypted = Some(cauce_core::Encrypted::new(
+            cauce_core::EncryptionAlgorithm::X25519XSalsa20Poly1305,
+            key,
+            STANDARD.encode([1u8; 24]),
+            STANDARD.encode([2u8; 64]),
+        ));
+        let publish = JsonRpcRequest::new(
+            RequestId::Number(5),
+            METHOD_PUBLISH.to_string(),
+            Some(json!({"topic": "signal.test", "message": signal})),
+        );
+        assert!(handler.handle_publish(&publish, &session_id).await.is_ok());
+
+        signal.encrypted.as_mut().unwrap().nonce = STANDARD.encode([1u8; 4]);
+        let publish = JsonRpcRequest::new(
+            RequestId::Number(6),
+            METHOD_PUBLISH.to_string(),
+            Some(json!({"topic": "signal.test", "message": signal})),
+        );
+        let response = handler.handle_publish(&publish, &session_id).await.unwrap_err();
+        assert_eq!(response.error_obj().unwrap().code, -32009);
+    }
+
+    #[tokio::test]
+    async fn test_signal_provenance_verified() {
+        use cauce_core::signing::{self, SigningKey};
+        use cauce_core::SubscribeResponse;
+
+        let key = SigningKey::generate();
+        let adapter_keys = AdapterKeys::default();
+        adapter_keys.register("adapter-1", key.verifying_key());
+        let handler = create_test_handler().with_adapter_keys(Arc::new(adapter_keys));
+
+        let session_info = crate::session::SessionInfo::new(
+            "sess_sig_test",
+            "client-1",
+            "agent",
+            "1.0",
+            cauce_core::Transport::WebSocket,
+            3600,
+        );
+        handler.session_manager.create_session(session_info).await.unwrap();
+        let session_id: Arc<Mutex<Option<String>>> =
+            Arc::new(Mutex::new(Some("sess_sig_test".to_string())));
+
+        let subscribe = JsonRpcRequest::new(
+            RequestId::Number(1),
+            METHOD_SUBSCRIBE.to_string(),
+            Some(json!({"topics": ["signal.test"]})),
+        );
+        let response = handler.handle_subscribe(&subscribe, &session_id).await.unwrap();
+        let subscription: SubscribeResponse =
+            serde_json::from_value(response.result().unwrap().clone()).unwrap();
+
+        // Unsigned and forged signals from a registered adapter are rejected
+        let publish = |id: i64, signal: &Signal| {
+            JsonRpcRequest::new(
+                RequestId::Number(id),
+                METHOD_PUBLISH.to_string(),
+                Some(json!({"topic": "signal.test", "message": signal})),
+            )
+        };
+        let unsigned = create_test_signal();
+        let response = handler
+            .handle_publish(&publish(2, &unsigned), &session_id)
+            .await
+            .unwrap_err();
+        assert_eq!(response.error_obj().unwrap().code, -32003);
+
+        let forged = signing::sign_signal(&unsigned, &SigningKey::generate()).unwrap();
+        let response = handler
+            .handle_publish(&publish(3, &forged), &session_id)
+            .await
+            .unwrap_err();
+        assert_eq!(response.error_obj().unwrap().code, -32003);
+
+        // A signature doesn't vouch for a topic other than the signal's own
+        let signed = signing::sign_signal(&unsigned, &key).unwrap();
+        let retopiced = JsonRpcRequest::new(
+            RequestId::Number(4),
+            METHOD_PUBLISH.to_string(),
+            Some(json!({"topic": "signal.other", "message": signed})),
+        );
+        let response = handler
+            .handle_publish(&retopiced, &session_id)
+            .await
+            .unwrap_err();
+        assert_eq!(response.error_obj().unwrap().code, -32602);
+
+        // A signed signal is delivered marked as verified
+        handler.handle_publish(&publish(5, &signed), &session_id).await.unwrap();
+        let deliveries = handler
+            .delivery_tracker
+            .get_unacked(&subscription.subscription_id)
+            .await
+            .unwrap();
+        assert_eq!(deliveries.len(), 1);
+        assert!(deliveries[0].verified);
+    }
 }
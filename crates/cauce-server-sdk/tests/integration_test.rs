@@ -29,6 +29,7 @@ fn create_test_signal(topic: &str) -> Signal {
         payload: Payload::new(json!({"test": "data"}), "application/json"),
         metadata: None,
         encrypted: None,
+        signature: None,
     }
 }
 
@@ -494,11 +495,13 @@ async fn test_session_not_found() {
 
 /// Creates a test server with relaxed rate limiting for HTTP tests.
 fn create_http_test_server() -> DefaultCauceServer {
-    use cauce_server_sdk::config::{LimitsConfig, ServerConfig};
+    use cauce_server_sdk::config::{AuthConfig, LimitsConfig, ServerConfig};
 
-    // Use a config with high rate limit to avoid 429s in tests
+    // Use a config with high rate limit to avoid 429s in tests, accepting
+    // the bare session IDs these tests poll with
     let config = ServerConfig::builder("127.0.0.1:8080".parse().unwrap())
         .limits(LimitsConfig::default().with_rate_limit(10000, 10000))
+        .auth(AuthConfig::none().with_required_session_tokens(false))
         .build()
         .unwrap();
     DefaultCauceServer::new(config)
@@ -842,6 +845,111 @@ async fn test_websocket_hello_flow() {
     server_handle.abort();
 }
 
+#[tokio::test]
+async fn test_session_token_bound_to_authenticated_client_and_renewed() {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use cauce_server_sdk::config::{AuthConfig, LimitsConfig};
+    use futures::{SinkExt, StreamExt};
+    use http_body_util::BodyExt;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+    use tokio_tungstenite::{connect_async, tungstenite::Message};
+    use tower::ServiceExt;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let config = ServerConfig::builder(addr)
+        .limits(LimitsConfig::default().with_rate_limit(10000, 10000))
+        .auth(
+            AuthConfig::require_api_key(vec!["test_key_123".to_string()])
+                .with_session_token_secret("an integration test secret of 32+ bytes"),
+        )
+        .build()
+        .unwrap();
+    let server = DefaultCauceServer::new(config);
+    let signer = server.session_token_signer();
+    let router = server.router();
+    let server_handle = tokio::spawn({
+        let router = router.clone();
+        async move { axum::serve(listener, router).await.unwrap() }
+    });
+
+    let mut request = format!("ws://{}/cauce/v1/ws", addr)
+        .into_client_request()
+        .unwrap();
+    request
+        .headers_mut()
+        .insert("X-Cauce-API-Key", "test_key_123".parse().unwrap());
+    let (mut ws_stream, _) = connect_async(request).await.expect("Failed to connect");
+    let hello = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "cauce.hello",
+        "params": {
+            "protocol_version": "1.0",
+            "client_id": "claimed-client",
+            "client_type": "agent"
+        },
+        "id": 1
+    });
+    ws_stream
+        .send(Message::Text(hello.to_string()))
+        .await
+        .unwrap();
+    let Some(Ok(Message::Text(text))) = ws_stream.next().await else {
+        panic!("Expected a hello response");
+    };
+    let response: serde_json::Value = serde_json::from_str(&text).unwrap();
+    let session_id = response["result"]["session_id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let token = response["result"]["session_token"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    // The token names the key's client, not the one claimed in hello
+    let claims = signer.verify(&token).unwrap();
+    assert_eq!(claims.session_id, session_id);
+    assert_eq!(claims.client_id, "default");
+
+    let poll = |query: String| {
+        Request::builder()
+            .uri(format!("/cauce/v1/poll?{}", query))
+            .header("X-Cauce-API-Key", "test_key_123")
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    // Bare session IDs are refused by default
+    let response = router
+        .clone()
+        .oneshot(poll(format!("session_id={}", session_id)))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // A poll with the token returns a fresh one that works in turn
+    let mut token = token;
+    for _ in 0..2 {
+        let response = router
+            .clone()
+            .oneshot(poll(format!("session_token={}", token)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let renewed = body["session_token"].as_str().unwrap().to_string();
+        assert_ne!(renewed, token);
+        token = renewed;
+    }
+
+    ws_stream.close(None).await.ok();
+    server_handle.abort();
+}
+
 #[tokio::test]
 async fn test_websocket_ping_pong() {
     use futures::{SinkExt, StreamExt};
@@ -1556,3 +1664,218 @@ async fn test_websocket_publish_flow() {
     ws_stream.close(None).await.ok();
     server_handle.abort();
 }
+
+// ============================================================================
+// Request/Reply Integration Tests
+// ============================================================================
+
+/// Adapter that answers `action.email.send` with a message ID.
+struct EchoEmailAdapter;
+
+#[async_trait::async_trait]
+impl cauce_client_sdk::Adapter for EchoEmailAdapter {
+    fn adapter_type(&self) -> &str {
+        "email"
+    }
+
+    fn action_topics(&self) -> Vec<String> {
+        vec!["action.email.*".to_string()]
+    }
+
+    async fn handle_action(
+        &self,
+        _ctx: &cauce_client_sdk::AdapterContext,
+        action: &cauce_core::Action,
+    ) -> Result<Option<serde_json::Value>, cauce_client_sdk::HandlerError> {
+        Ok(Some(json!({ "message_id": format!("sent-{}", action.id) })))
+    }
+}
+
+#[tokio::test]
+async fn test_request_reply_round_trip() {
+    use cauce_client_sdk::{ActionResult, AdapterRuntime, CauceClient, ClientConfig, ClientType};
+    use cauce_core::types::{ActionBody, ActionType};
+    use cauce_core::Action;
+    use tokio::net::TcpListener;
+
+    let (addr, server) = start_test_server().await;
+    let router = server.router();
+    let listener = TcpListener::bind(addr).await.unwrap();
+    let server_handle = tokio::spawn(async move {
+        axum::serve(listener, router).await.unwrap();
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let url = format!("ws://{}/cauce/v1/ws", addr);
+
+    // Adapter executing email actions
+    let config = ClientConfig::builder(&url, "email-adapter")
+        .client_type(ClientType::Adapter)
+        .build()
+        .unwrap();
+    let adapter_client = Arc::new(CauceClient::connect(config).await.unwrap());
+    let (stop_tx, stop_rx) = tokio::sync::oneshot::channel::<()>();
+    let adapter = tokio::spawn(
+        AdapterRuntime::new(adapter_client, EchoEmailAdapter).run_until(async {
+            stop_rx.await.ok();
+        }),
+    );
+    for _ in 0..50 {
+        let subscribed = server
+            .subscription_manager()
+            .get_subscriptions_for_topic("action.email.send")
+            .await
+            .unwrap();
+        if !subscribed.is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    // Agent sending the action and waiting for the result
+    let config = ClientConfig::builder(&url, "agent").build().unwrap();
+    let agent = CauceClient::connect(config).await.unwrap();
+    let _results = agent.subscribe(&["signal.email.action_result"]).await.unwrap();
+
+    let action = Action::builder()
+        .topic(Topic::new_unchecked("action.email.send"))
+        .action(ActionBody::with_target(
+            ActionType::Send,
+            "bob@example.com",
+            json!({"text": "hi"}),
+        ))
+        .build()
+        .unwrap();
+    let action_id = action.id.clone();
+    let reply = agent
+        .request(action, Duration::from_secs(5))
+        .await
+        .expect("adapter should answer the request");
+
+    let metadata = reply.metadata.as_ref().unwrap();
+    assert_eq!(metadata.in_reply_to.as_deref(), Some(action_id.as_str()));
+    let result: ActionResult = serde_json::from_value(reply.payload.raw.clone()).unwrap();
+    assert!(result.success);
+    assert_eq!(result.output.unwrap()["message_id"], format!("sent-{}", action_id));
+
+    stop_tx.send(()).ok();
+    let stats = adapter.await.unwrap().unwrap();
+    assert_eq!(stats.actions_succeeded, 1);
+    server_handle.abort();
+}
+
+// ============================================================================
+// End-to-End Encryption Integration Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_encrypted_publish_reaches_only_its_recipients() {
+    use cauce_client_sdk::{CauceClient, ClientConfig, E2eOptions};
+    use tokio::net::TcpListener;
+
+    let (addr, server) = start_test_server().await;
+    let router = server.router();
+    let listener = TcpListener::bind(addr).await.unwrap();
+    let server_handle = tokio::spawn(async move {
+        axum::serve(listener, router).await.unwrap();
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let url = format!("ws://{}/cauce/v1/ws", addr);
+
+    let connect = |client_id: &str, e2e: Option<E2eOptions>| {
+        let mut builder = ClientConfig::builder(&url, client_id);
+        if let Some(e2e) = e2e {
+            builder = builder.e2e(e2e);
+        }
+        CauceClient::connect(builder.build().unwrap())
+    };
+    let alice = connect("alice", Some(E2eOptions::generate())).await.unwrap();
+    let bob = connect("bob", Some(E2eOptions::generate())).await.unwrap();
+    let eve = connect("eve", None).await.unwrap();
+    let publisher = connect("publisher", None).await.unwrap();
+
+    let mut alice_signals = alice.subscribe(&["signal.secure.*"]).await.unwrap();
+    let mut bob_signals = bob.subscribe(&["signal.secure.*"]).await.unwrap();
+    let mut eve_signals = eve.subscribe(&["signal.secure.*"]).await.unwrap();
+
+    let signal = Signal {
+        payload: Payload::new(json!({"text": "for your eyes only"}), "application/json"),
+        ..create_test_signal("signal.secure.message")
+    };
+    let responses = publisher
+        .publish_encrypted("signal.secure.message", signal.clone())
+        .await
+        .unwrap();
+
+    // One copy per recipient, each delivered only to its recipient
+    assert_eq!(responses.len(), 2);
+    assert!(responses.iter().all(|r| r.delivered_to == 1));
+    for signals in [&mut alice_signals, &mut bob_signals] {
+        let received = tokio::time::timeout(Duration::from_secs(5), signals.next())
+            .await
+            .expect("recipient should receive its copy")
+            .unwrap()
+            .unwrap();
+        assert_eq!(received.payload, signal.payload);
+        let again = tokio::time::timeout(Duration::from_millis(200), signals.next()).await;
+        assert!(again.is_err(), "recipient received another copy");
+    }
+    let leaked = tokio::time::timeout(Duration::from_millis(200), eve_signals.next()).await;
+    assert!(leaked.is_err(), "plaintext subscriber received an envelope");
+
+    server_handle.abort();
+}
+
+#[tokio::test]
+async fn test_subscribers_see_signature_verification() {
+    use cauce_client_sdk::{CauceClient, ClientConfig};
+    use cauce_core::signing::{self, SigningKey};
+    use cauce_core::PublishMessage;
+    use cauce_server_sdk::config::{LimitsConfig, SigningConfig};
+    use tokio::net::TcpListener;
+
+    let key = SigningKey::generate();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let config = ServerConfig::builder(addr)
+        .limits(LimitsConfig::default().with_rate_limit(10000, 10000))
+        .signing(
+            SigningConfig::default()
+                .with_adapter_key("adapter-1", key.verifying_key().to_base64()),
+        )
+        .build()
+        .unwrap();
+    let router = DefaultCauceServer::new(config).router();
+    let server_handle = tokio::spawn(async move {
+        axum::serve(listener, router).await.unwrap();
+    });
+    let url = format!("ws://{}/cauce/v1/ws", addr);
+
+    let connect = |client_id: &str| {
+        CauceClient::connect(ClientConfig::builder(&url, client_id).build().unwrap())
+    };
+    let subscriber = connect("subscriber").await.unwrap();
+    let publisher = connect("publisher").await.unwrap();
+    let mut subscription = subscriber.subscribe(&["signal.provenance.*"]).await.unwrap();
+
+    let mut unsigned = create_test_signal("signal.provenance.test");
+    unsigned.source = Source::new("test", "adapter-2", "msg-1");
+    let signed = signing::sign_signal(&create_test_signal("signal.provenance.test"), &key).unwrap();
+    for signal in [signed.clone(), unsigned.clone()] {
+        publisher
+            .publish("signal.provenance.test", PublishMessage::Signal(signal))
+            .await
+            .unwrap();
+    }
+
+    for (expected, verified) in [(&signed, true), (&unsigned, false)] {
+        let delivery = tokio::time::timeout(Duration::from_secs(5), subscription.next_delivery())
+            .await
+            .expect("subscriber should receive the signal")
+            .unwrap()
+            .unwrap();
+        assert_eq!(delivery.signal.id, expected.id);
+        assert_eq!(delivery.verified, verified);
+    }
+
+    server_handle.abort();
+}
@@ -121,5 +121,6 @@ fn create_sample_signal(counter: u64) -> Signal {
         ),
         metadata: None,
         encrypted: None,
+        signature: None,
     }
 }
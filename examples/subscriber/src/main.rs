@@ -82,7 +82,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
             signal_opt = subscription.next() => {
                 match signal_opt {
-                    Some(signal) => {
+                    Some(Err(e)) => {
+                        warn!("Subscription error: {}", e);
+                    }
+                    Some(Ok(signal)) => {
                         info!("Received signal:");
                         info!("  ID: {}", signal.id);
                         info!("  Topic: {}", signal.topic.as_str());